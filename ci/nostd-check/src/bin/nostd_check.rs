@@ -18,7 +18,7 @@ use core::panic::PanicInfo;
 use getrandom::{register_custom_getrandom, Error};
 use linked_list_allocator::LockedHeap;
 #[allow(unused_imports)]
-use {zenoh_buffers, zenoh_codec, zenoh_protocol};
+use {zenoh_buffers, zenoh_codec, zenoh_keyexpr, zenoh_protocol};
 
 #[panic_handler]
 fn dummy_panic_handler(_: &PanicInfo) -> ! {
@@ -11,15 +11,18 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{common::extension, RCodec, WCodec, Zenoh080, Zenoh080Condition, Zenoh080Header};
-use alloc::string::String;
+use crate::{
+    common::extension, LCodec, RCodec, WCodec, Zenoh080, Zenoh080Bounded, Zenoh080Condition,
+    Zenoh080Header,
+};
+use alloc::{string::String, vec::Vec};
 use zenoh_buffers::{
     reader::{DidntRead, Reader},
     writer::{DidntWrite, HasWriter, Writer},
     ZBuf,
 };
 use zenoh_protocol::{
-    common::{iext, imsg, ZExtZ64},
+    common::{iext, imsg, ZExtZ64, ZExtZBufHeader},
     core::{ExprId, ExprLen, WireExpr},
     network::{
         declare::{
@@ -324,6 +327,67 @@ where
 // SubscriberInfo
 crate::impl_zextz64!(subscriber::ext::SubscriberInfo, subscriber::ext::Info::ID);
 
+// PayloadFilter
+impl LCodec<&subscriber::ext::PayloadFilterType> for Zenoh080 {
+    fn w_len(self, x: &subscriber::ext::PayloadFilterType) -> usize {
+        let subscriber::ext::PayloadFilterType { prefixes } = x;
+
+        self.w_len(prefixes.len())
+            + prefixes
+                .iter()
+                .map(|prefix| self.w_len(prefix.offset) + self.w_len(&prefix.bytes[..]))
+                .sum::<usize>()
+    }
+}
+
+impl<W> WCodec<(&subscriber::ext::PayloadFilterType, bool), &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: (&subscriber::ext::PayloadFilterType, bool)) -> Self::Output {
+        let (x, more) = x;
+        let header: ZExtZBufHeader<{ subscriber::ext::PayloadFilter::ID }> =
+            ZExtZBufHeader::new(self.w_len(x));
+        self.write(&mut *writer, (&header, more))?;
+
+        let zodec = Zenoh080Bounded::<u32>::new();
+        zodec.write(&mut *writer, x.prefixes.len())?;
+        for prefix in x.prefixes.iter() {
+            self.write(&mut *writer, prefix.offset)?;
+            zodec.write(&mut *writer, &prefix.bytes[..])?;
+        }
+        Ok(())
+    }
+}
+
+impl<R> RCodec<(subscriber::ext::PayloadFilterType, bool), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(
+        self,
+        reader: &mut R,
+    ) -> Result<(subscriber::ext::PayloadFilterType, bool), Self::Error> {
+        let (_, more): (ZExtZBufHeader<{ subscriber::ext::PayloadFilter::ID }>, bool) =
+            self.read(&mut *reader)?;
+
+        let zodec = Zenoh080Bounded::<u32>::new();
+        let count: usize = zodec.read(&mut *reader)?;
+        let mut prefixes = Vec::new();
+        for _ in 0..count {
+            let offset: u64 = self.codec.read(&mut *reader)?;
+            let bytes: Vec<u8> = zodec.read(&mut *reader)?;
+            prefixes.push(subscriber::ext::PayloadPrefix { offset, bytes });
+        }
+
+        Ok((subscriber::ext::PayloadFilterType { prefixes }, more))
+    }
+}
+
 // DeclareSubscriber
 impl<W> WCodec<&subscriber::DeclareSubscriber, &mut W> for Zenoh080
 where
@@ -336,11 +400,13 @@ where
             id,
             wire_expr,
             ext_info,
+            ext_payload_filter,
         } = x;
 
         // Header
         let mut header = declare::id::D_SUBSCRIBER;
-        let mut n_exts = (ext_info != &subscriber::ext::SubscriberInfo::default()) as u8;
+        let mut n_exts = (ext_info != &subscriber::ext::SubscriberInfo::default()) as u8
+            + (ext_payload_filter.is_some() as u8);
         if n_exts != 0 {
             header |= subscriber::flag::Z;
         }
@@ -361,6 +427,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (*ext_info, n_exts != 0))?;
         }
+        if let Some(filter) = ext_payload_filter.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (filter, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -403,6 +473,7 @@ where
 
         // Extensions
         let mut ext_info = subscriber::ext::SubscriberInfo::default();
+        let mut ext_payload_filter = None;
 
         let mut has_ext = imsg::has_flag(self.header, subscriber::flag::Z);
         while has_ext {
@@ -415,6 +486,12 @@ where
                     ext_info = i;
                     has_ext = ext;
                 }
+                subscriber::ext::PayloadFilter::ID => {
+                    let (f, ext): (subscriber::ext::PayloadFilterType, bool) =
+                        eodec.read(&mut *reader)?;
+                    ext_payload_filter = Some(f);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "DeclareSubscriber", ext)?;
                 }
@@ -425,6 +502,7 @@ where
             id,
             wire_expr,
             ext_info,
+            ext_payload_filter,
         })
     }
 }
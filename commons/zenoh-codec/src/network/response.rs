@@ -179,12 +179,14 @@ where
             rid,
             ext_qos,
             ext_tstamp,
+            ext_served,
         } = x;
 
         // Header
         let mut header = id::RESPONSE_FINAL;
-        let mut n_exts =
-            ((ext_qos != &ext::QoSType::default()) as u8) + (ext_tstamp.is_some() as u8);
+        let mut n_exts = ((ext_qos != &ext::QoSType::default()) as u8)
+            + (ext_tstamp.is_some() as u8)
+            + (ext_served.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -202,6 +204,11 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (ts, n_exts != 0))?;
         }
+        if let Some(served) = ext_served {
+            n_exts -= 1;
+            let e = ext::Served::new(*served as u64);
+            self.write(&mut *writer, (&e, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -238,6 +245,7 @@ where
         // Extensions
         let mut ext_qos = ext::QoSType::default();
         let mut ext_tstamp = None;
+        let mut ext_served = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -254,6 +262,11 @@ where
                     ext_tstamp = Some(t);
                     has_ext = ext;
                 }
+                ext::Served::ID => {
+                    let (s, ext): (ext::Served, bool) = eodec.read(&mut *reader)?;
+                    ext_served = Some(s.value as ext::ServedType);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "ResponseFinal", ext)?;
                 }
@@ -264,6 +277,7 @@ where
             rid,
             ext_qos,
             ext_tstamp,
+            ext_served,
         })
     }
 }
@@ -24,7 +24,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{iext, imsg},
-    core::Encoding,
+    core::{Encoding, SampleKind},
     zenoh::{
         id,
         reply::{ext, flag, Reply},
@@ -46,6 +46,7 @@ where
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_kind,
             ext_unknown,
             payload,
         } = x;
@@ -61,6 +62,7 @@ where
         let mut n_exts = (ext_sinfo.is_some()) as u8
             + ((ext_consolidation != &ext::ConsolidationType::default()) as u8)
             + (ext_attachment.is_some()) as u8
+            + ((ext_kind != &SampleKind::default()) as u8)
             + (ext_unknown.len() as u8);
         #[cfg(feature = "shared-memory")]
         {
@@ -97,6 +99,11 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (att, n_exts != 0))?;
         }
+        if ext_kind != &SampleKind::default() {
+            n_exts -= 1;
+            let kind = ext::Kind::new(*ext_kind as u64);
+            self.write(&mut *writer, (&kind, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -160,6 +167,7 @@ where
         #[cfg(feature = "shared-memory")]
         let mut ext_shm: Option<ext::ShmType> = None;
         let mut ext_attachment: Option<ext::AttachmentType> = None;
+        let mut ext_kind = SampleKind::default();
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -188,6 +196,11 @@ where
                     ext_attachment = Some(a);
                     has_ext = ext;
                 }
+                ext::Kind::ID => {
+                    let (k, ext): (ext::Kind, bool) = eodec.read(&mut *reader)?;
+                    ext_kind = SampleKind::try_from(k.value).map_err(|_| DidntRead)?;
+                    has_ext = ext;
+                }
                 _ => {
                     let (u, ext) = extension::read(reader, "Reply", ext)?;
                     ext_unknown.push(u);
@@ -219,6 +232,7 @@ where
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_kind,
             ext_unknown,
             payload,
         })
@@ -0,0 +1,67 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[macro_use]
+extern crate criterion;
+
+use criterion::{black_box, Criterion};
+use zenoh_collections::Properties;
+
+const N: usize = 1000;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let keys: Vec<String> = (0..N).map(|i| format!("transport.p{i}")).collect();
+
+    // Sequential inserts, with a lookup after each of them
+    c.bench_function("Properties 1k inserts", |b| {
+        b.iter(|| {
+            let mut props = Properties::default();
+            for key in &keys {
+                props.insert(key.clone(), "value".into());
+                black_box(props.get(key));
+            }
+            props
+        })
+    });
+
+    // Sequential inserts then removes
+    c.bench_function("Properties 1k inserts and removes", |b| {
+        b.iter(|| {
+            let mut props = Properties::default();
+            for key in &keys {
+                props.insert(key.clone(), "value".into());
+            }
+            for key in &keys {
+                black_box(props.remove(key));
+            }
+            props
+        })
+    });
+
+    // Formatting and parsing back
+    let props: Properties = keys
+        .iter()
+        .map(|k| (k.clone(), "value".to_string()))
+        .collect::<std::collections::HashMap<_, _>>()
+        .into();
+    c.bench_function("Properties 1k to_string", |b| {
+        b.iter(|| black_box(&props).to_string())
+    });
+    let s = props.to_string();
+    c.bench_function("Properties 1k from &str", |b| {
+        b.iter(|| Properties::from(black_box(s.as_str())))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -12,23 +12,47 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
     fmt,
+    hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
+    str::FromStr,
+    time::Duration,
 };
 
-const PROP_SEPS: &[&str] = &["\r\n", "\n", ";"];
 const DEFAULT_PROP_SEP: char = ';';
 const KV_SEP: char = '=';
 const COMMENT_PREFIX: char = '#';
+const ESCAPE: char = '\\';
+/// The separator between the values of a multi-value property.
+const LIST_SEP: char = '|';
+/// The characters standing for themselves when preceded by [`ESCAPE`].
+const ESCAPABLE: &[char] = &[ESCAPE, ';', KV_SEP, COMMENT_PREFIX, '\n', '\r'];
+/// The characters standing for themselves when preceded by [`ESCAPE`] in the values of a
+/// multi-value property.
+const LIST_ESCAPABLE: &[char] = &[ESCAPE, LIST_SEP];
 
 /// A map of key/value (String,String) properties.
 /// It can be parsed from a String, using `;` or `<newline>` as separator between each properties
 /// and `=` as separator between a key and its value. Keys and values are trimmed.
+///
+/// The separators, `#` and `\` are escaped with a `\` in the keys and values formatted
+/// by [`Display`](fmt::Display), and unescaped when parsed, so that any key and value survive
+/// a round-trip, barring their leading and trailing whitespaces. A `\` followed by another
+/// character stands for itself, so that the formatted strings are unchanged for the keys and
+/// values without any of these characters.
+///
+/// Likewise, the `|` and `\` of the values of a multi-value property are escaped with a `\`
+/// when the values are set, e.g. with [`Properties::set_values`], and unescaped when they are
+/// read, e.g. with [`Properties::values`].
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, Default)]
 pub struct Properties(HashMap<String, String>);
 
+/// A view into a single property of a [`Properties`], as returned by [`Properties::entry`].
+pub type PropertyEntry<'a> = std::collections::hash_map::Entry<'a, String, String>;
+
 impl Deref for Properties {
     type Target = HashMap<String, String>;
 
@@ -45,53 +69,1023 @@ impl DerefMut for Properties {
 
 impl fmt::Display for Properties {
     /// Format the Properties as a string, using `'='` for key/value separator
-    /// and `';'` for separator between each keys/values.
+    /// and `';'` for separator between each keys/values, escaping them in the keys and values.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut it = self.0.iter();
-        if let Some((k, v)) = it.next() {
-            if v.is_empty() {
-                write!(f, "{k}")?
-            } else {
-                write!(f, "{k}{KV_SEP}{v}")?
+        for (i, (k, v)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{DEFAULT_PROP_SEP}")?
             }
-            for (k, v) in it {
-                if v.is_empty() {
-                    write!(f, "{DEFAULT_PROP_SEP}{k}")?
-                } else {
-                    write!(f, "{DEFAULT_PROP_SEP}{k}{KV_SEP}{v}")?
-                }
+            write!(f, "{}", escape(k, true))?;
+            if !v.is_empty() {
+                write!(f, "{KV_SEP}{}", escape(v, false))?
             }
         }
         Ok(())
     }
 }
 
+/// Hashes the properties in the order of their keys, consistently with their equality.
+impl Hash for Properties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let sorted: BTreeMap<_, _> = self.0.iter().collect();
+        sorted.hash(state);
+    }
+}
+
+/// Escapes the characters of `s` that would otherwise be parsed as separators, or as
+/// reserved characters for a key.
+fn escape(s: &str, key: bool) -> Cow<'_, str> {
+    escape_by(s, ESCAPABLE, |c| match c {
+        ';' | '\n' | '\r' => true,
+        KV_SEP | COMMENT_PREFIX => key,
+        _ => false,
+    })
+}
+
+/// Escapes the [`LIST_SEP`] of `value`, so that it's a single value of a multi-value property.
+fn escape_value(value: &str) -> Cow<'_, str> {
+    escape_by(value, LIST_ESCAPABLE, |c| c == LIST_SEP)
+}
+
+/// Escapes the characters of `s` for which `reserved` returns `true`, and the [`ESCAPE`]
+/// that would otherwise escape the next character, one of `escapable`.
+fn escape_by<'a>(s: &'a str, escapable: &[char], reserved: impl Fn(char) -> bool) -> Cow<'a, str> {
+    let needs_escape = |i: usize, c: char| match c {
+        // A backslash is only escaped where it would escape the next character
+        ESCAPE => s[i + 1..]
+            .chars()
+            .next()
+            .map_or(true, |next| escapable.contains(&next)),
+        c => reserved(c),
+    };
+    if !s.char_indices().any(|(i, c)| needs_escape(i, c)) {
+        return Cow::Borrowed(s);
+    }
+    let mut escaped = String::with_capacity(s.len() + 1);
+    for (i, c) in s.char_indices() {
+        if needs_escape(i, c) {
+            escaped.push(ESCAPE);
+        }
+        escaped.push(c);
+    }
+    Cow::Owned(escaped)
+}
+
+/// Unescapes the characters of `s` escaped by [`escape_by`] among `escapable`.
+fn unescape(s: &str, escapable: &[char]) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(next) if c == ESCAPE && escapable.contains(next) => {
+                unescaped.push(*next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Returns the byte offsets of the characters of `s` that are not escaped, among `escapable`.
+fn unescaped_indices<'a>(
+    s: &'a str,
+    escapable: &'a [char],
+) -> impl Iterator<Item = (usize, char)> + 'a {
+    let mut chars = s.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (i, c) = chars.next()?;
+        if c == ESCAPE {
+            if let Some((_, next)) = chars.peek() {
+                if escapable.contains(next) {
+                    chars.next();
+                }
+            }
+        }
+        Some((i, c))
+    })
+}
+
+/// Percent-encodes all the bytes of `s` but the unreserved characters of RFC 3986.
+fn percent_encode(s: &str) -> Cow<'_, str> {
+    let unreserved = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~');
+    if s.bytes().all(unreserved) {
+        return Cow::Borrowed(s);
+    }
+    let mut encoded = String::with_capacity(s.len() * 3);
+    for b in s.bytes() {
+        if unreserved(b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{b:02X}"));
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Decodes the `%XX` sequences and the `+` of `s`. Malformed sequences are kept as is, and
+/// invalid UTF-8 replaced with `U+FFFD`.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => match s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(b) => {
+                    decoded.push(b);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            b'+' => decoded.push(b' '),
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Splits a multi-value property at its unescaped [`LIST_SEP`], unescaping the values and
+/// skipping the empty ones.
+fn split_values(value: &str) -> SplitValues<'_> {
+    SplitValues { rest: Some(value) }
+}
+
+/// The iterator returned by [`split_values`].
+#[derive(Debug, Clone)]
+struct SplitValues<'a> {
+    rest: Option<&'a str>,
+}
+
+impl<'a> SplitValues<'a> {
+    /// Returns the next non-empty value, still escaped.
+    fn next_escaped(&mut self) -> Option<&'a str> {
+        loop {
+            let rest = self.rest?;
+            let (value, next) =
+                match unescaped_indices(rest, LIST_ESCAPABLE).find(|(_, c)| *c == LIST_SEP) {
+                    Some((idx, _)) => (&rest[..idx], Some(&rest[idx + LIST_SEP.len_utf8()..])),
+                    None => (rest, None),
+                };
+            self.rest = next;
+            if !value.is_empty() {
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for SplitValues<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.next_escaped()?;
+        Some(match value.contains(ESCAPE) {
+            true => Cow::Owned(unescape(value, LIST_ESCAPABLE)),
+            false => Cow::Borrowed(value),
+        })
+    }
+}
+
+/// The `|`-separated values of a property, see [`Properties::values_vec`].
+#[derive(Debug, Clone)]
+pub struct PropertyValues<'a> {
+    values: SplitValues<'a>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for PropertyValues<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.values.next()?;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for PropertyValues<'_> {}
+
+/// Joins `values` into a multi-value property, escaping their [`LIST_SEP`].
+fn join_values<'a>(values: impl IntoIterator<Item = &'a str>) -> String {
+    let mut joined = String::new();
+    for v in values {
+        if !joined.is_empty() {
+            joined.push(LIST_SEP);
+        }
+        joined.push_str(&escape_value(v));
+    }
+    joined
+}
+
+/// Splits a property at its first unescaped [`KV_SEP`].
+fn split_property(prop: &str) -> (&str, &str) {
+    match unescaped_indices(prop, ESCAPABLE).find(|(_, c)| *c == KV_SEP) {
+        Some((idx, _)) => (&prop[..idx], &prop[idx + 1..]),
+        None => (prop, ""),
+    }
+}
+
 impl fmt::Debug for Properties {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{self}")
     }
 }
 
+/// A malformed segment reported by [`Properties::parse_strict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertiesParseIssue {
+    /// The byte offset of the issue in the parsed string.
+    pub offset: usize,
+    pub kind: PropertiesParseIssueKind,
+}
+
+/// The kinds of [`PropertiesParseIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertiesParseIssueKind {
+    /// A value without key, e.g. `=value`.
+    EmptyKey,
+    /// A separator without property before it, e.g. the second `;` of `p1=v1;;p2=v2`.
+    BareSeparator,
+    /// A reserved character where it can't be told apart from a separator,
+    /// e.g. the `#` of `p#1=v1`.
+    ReservedCharacter(char),
+}
+
+impl fmt::Display for PropertiesParseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            PropertiesParseIssueKind::EmptyKey => write!(f, "empty key at byte {}", self.offset),
+            PropertiesParseIssueKind::BareSeparator => {
+                write!(f, "bare separator at byte {}", self.offset)
+            }
+            PropertiesParseIssueKind::ReservedCharacter(c) => {
+                write!(f, "reserved character '{c}' at byte {}", self.offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PropertiesParseIssue {}
+
+/// A property whose value can't be read as the requested type, reported by the typed getters
+/// of [`Properties`] such as [`Properties::get_parsed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyValueError {
+    /// The key of the property.
+    pub key: String,
+    /// The raw value of the property.
+    pub value: String,
+    /// Why the value is invalid.
+    pub reason: String,
+}
+
+impl PropertyValueError {
+    fn new(key: &str, value: &str, reason: impl fmt::Display) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PropertyValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid value '{}' for property '{}': {}",
+            self.value, self.key, self.reason
+        )
+    }
+}
+
+impl std::error::Error for PropertyValueError {}
+
+/// The case of the keys rewritten by [`Properties::normalize_keys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Lower,
+    Upper,
+}
+
+/// How [`Properties::merge`] resolves a property set on both sides with different values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the value of the merged-into Properties.
+    PreferSelf,
+    /// Take the value of the merged Properties.
+    PreferOther,
+    /// Take the union of the `|`-separated values of both sides, without duplicates:
+    /// the values of the merged-into Properties first, then the new values of the merged
+    /// Properties, in their original order.
+    Combine,
+}
+
+/// The differences between two [`Properties`], as returned by [`Properties::diff`].
+/// Each list is sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertiesDiff {
+    /// The `(key, value)` of the properties only set in the new Properties.
+    pub added: Vec<(String, String)>,
+    /// The `(key, value)` of the properties only set in the old Properties.
+    pub removed: Vec<(String, String)>,
+    /// The `(key, old value, new value)` of the properties set in both with different values.
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl PropertiesDiff {
+    /// Returns whether both Properties are equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for PropertiesDiff {
+    /// Formats the differences one per line, as `+ key=value` for the added properties,
+    /// `- key=value` for the removed ones, and `~ key: old -> new` for the changed ones.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sep = "";
+        for (k, v) in &self.added {
+            write!(f, "{sep}+ {k}={v}")?;
+            sep = "\n";
+        }
+        for (k, v) in &self.removed {
+            write!(f, "{sep}- {k}={v}")?;
+            sep = "\n";
+        }
+        for (k, old, new) in &self.changed {
+            write!(f, "{sep}~ {k}: {old} -> {new}")?;
+            sep = "\n";
+        }
+        Ok(())
+    }
+}
+
+/// A JSON value that can't be converted from or to [`Properties`], reported by
+/// [`Properties::from_json`] and [`Properties::to_json`].
+#[cfg(feature = "serde_json")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertiesJsonError {
+    /// The dotted key of the offending property.
+    pub key: String,
+    /// Why it can't be converted.
+    pub reason: String,
+}
+
+#[cfg(feature = "serde_json")]
+impl PropertiesJsonError {
+    fn new(key: &str, reason: impl fmt::Display) -> Self {
+        Self {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl fmt::Display for PropertiesJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid property '{}': {}", self.key, self.reason)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl std::error::Error for PropertiesJsonError {}
+
+/// The units accepted by [`Properties::get_duration`], in seconds, longest suffixes first.
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("ns", 1e-9),
+    ("us", 1e-6),
+    ("ms", 1e-3),
+    ("s", 1.),
+    ("m", 60.),
+    ("h", 3600.),
+];
+
+impl Properties {
+    /// Returns the value of the property `key` as a boolean, or `None` if it is not set.
+    ///
+    /// An empty value, e.g. `p1` in `p1;p2=v2`, is `true`: otherwise the value must be `true`
+    /// or `false`.
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, PropertyValueError> {
+        match self.0.get(key).map(String::as_str) {
+            None => Ok(None),
+            Some("") => Ok(Some(true)),
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|e| PropertyValueError::new(key, value, e)),
+        }
+    }
+
+    /// Returns the value of the property `key` parsed as a `T`, or `None` if it is not set.
+    ///
+    /// An empty value is an error.
+    pub fn get_parsed<T>(&self, key: &str) -> Result<Option<T>, PropertyValueError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        match self.0.get(key) {
+            None => Ok(None),
+            Some(value) if value.is_empty() => {
+                Err(PropertyValueError::new(key, value, "empty value"))
+            }
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|e| PropertyValueError::new(key, value, e)),
+        }
+    }
+
+    /// Returns the value of the property `key` as a [`Duration`], or `None` if it is not set.
+    ///
+    /// The value is a number followed by its unit: `ns`, `us`, `ms`, `s`, `m` (minutes) or
+    /// `h`, e.g. `10ms`, `2s`, `1.5m`. An empty value is an error.
+    pub fn get_duration(&self, key: &str) -> Result<Option<Duration>, PropertyValueError> {
+        let Some(value) = self.0.get(key) else {
+            return Ok(None);
+        };
+        if value.is_empty() {
+            return Err(PropertyValueError::new(key, value, "empty value"));
+        }
+        let Some((number, scale)) = DURATION_UNITS.iter().find_map(|(unit, scale)| {
+            value
+                .strip_suffix(unit)
+                .filter(|number| number.ends_with(|c: char| c.is_ascii_digit() || c == '.'))
+                .map(|number| (number, scale))
+        }) else {
+            return Err(PropertyValueError::new(
+                key,
+                value,
+                "missing or unknown unit, expected one of ns, us, ms, s, m, h",
+            ));
+        };
+        let number: f64 = number
+            .parse()
+            .map_err(|e| PropertyValueError::new(key, value, e))?;
+        Duration::try_from_secs_f64(number * scale)
+            .map(Some)
+            .map_err(|e| PropertyValueError::new(key, value, e))
+    }
+
+    /// Returns the `|`-separated values of the property `key`, e.g. the endpoints of
+    /// `connect=tcp/a:7447|tcp/b:7447`, unescaped. An unset property and an empty value have
+    /// no values.
+    pub fn values<'a>(&'a self, key: &str) -> impl Iterator<Item = Cow<'a, str>> {
+        split_values(self.0.get(key).map_or("", String::as_str))
+    }
+
+    /// Returns the `|`-separated values of the property `key`, like [`Properties::values`],
+    /// with their number known up front.
+    pub fn values_vec<'a>(&'a self, key: &str) -> PropertyValues<'a> {
+        let values = split_values(self.0.get(key).map_or("", String::as_str));
+        let mut counted = values.clone();
+        let remaining = std::iter::from_fn(|| counted.next_escaped()).count();
+        PropertyValues { values, remaining }
+    }
+
+    /// Appends `value` to the `|`-separated values of the property `key`, setting it if absent.
+    /// With `dedup`, `value` is not appended if it is already one of the values.
+    ///
+    /// Returns whether `value` was appended. A `|` in `value` is escaped.
+    pub fn append_value<K: Into<String>>(&mut self, key: K, value: &str, dedup: bool) -> bool {
+        let current = self.0.entry(key.into()).or_default();
+        if dedup && split_values(current).any(|v| v == value) {
+            return false;
+        }
+        if !current.is_empty() {
+            current.push(LIST_SEP);
+        }
+        current.push_str(&escape_value(value));
+        true
+    }
+
+    /// Removes the first occurrence of `value` from the `|`-separated values of the property
+    /// `key`, and removes the property if it has no values left.
+    ///
+    /// Returns whether `value` was removed.
+    pub fn remove_value(&mut self, key: &str, value: &str) -> bool {
+        let Some(current) = self.0.get_mut(key) else {
+            return false;
+        };
+        let mut values: Vec<Cow<str>> = split_values(current).collect();
+        let Some(idx) = values.iter().position(|v| *v == value) else {
+            return false;
+        };
+        values.remove(idx);
+        if values.is_empty() {
+            self.0.remove(key);
+        } else {
+            *current = join_values(values.iter().map(|v| v.as_ref()));
+        }
+        true
+    }
+
+    /// Sets the `|`-separated values of the property `key`, replacing the previous ones.
+    /// A `|` in the values is escaped.
+    pub fn set_values<'a, K, I>(&mut self, key: K, values: I)
+    where
+        K: Into<String>,
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.0.insert(key.into(), join_values(values));
+    }
+
+    /// Returns the value of the property `key`, comparing the keys ASCII case-insensitively,
+    /// e.g. to accept both `MODE` from an environment variable and `mode` from a file.
+    ///
+    /// A key matching the case of `key` exactly is preferred, then the smallest of the other
+    /// matching keys.
+    pub fn get_ci(&self, key: &str) -> Option<&String> {
+        if let Some(value) = self.0.get(key) {
+            return Some(value);
+        }
+        self.0
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case(key))
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, v)| v)
+    }
+
+    /// Returns whether the property `key` is set, comparing the keys ASCII case-insensitively.
+    pub fn contains_key_ci(&self, key: &str) -> bool {
+        self.get_ci(key).is_some()
+    }
+
+    /// Rewrites all the keys to the ASCII `case`. The keys are otherwise kept as they were set
+    /// or parsed, including in the [`Display`](fmt::Display) form.
+    ///
+    /// When several keys fold to the same key, the one already in `case` is kept, then the
+    /// smallest of the others.
+    pub fn normalize_keys(&mut self, case: Case) {
+        let normalize = |k: &str| match case {
+            Case::Lower => k.to_ascii_lowercase(),
+            Case::Upper => k.to_ascii_uppercase(),
+        };
+        let mut props: Vec<(String, String, String)> = std::mem::take(&mut self.0)
+            .into_iter()
+            .map(|(k, v)| (normalize(&k), k, v))
+            .collect();
+        props.sort_unstable_by(|(na, a, _), (nb, b, _)| (a != na, a).cmp(&(b != nb, b)));
+        for (normalized, _, value) in props {
+            self.0.entry(normalized).or_insert(value);
+        }
+    }
+
+    /// Returns the properties under the `prefix` namespace, e.g. `transport.link.tx` for
+    /// `transport.link.tx.batch_size`, with `prefix` and its `.` stripped from their keys.
+    /// The property `prefix` itself, if set, gets the empty key. The empty `prefix` is the
+    /// root namespace, whose scope is all the properties.
+    pub fn scope(&self, prefix: &str) -> Properties {
+        if prefix.is_empty() {
+            return self.clone();
+        }
+        let inner = self
+            .0
+            .iter()
+            .filter_map(|(k, v)| {
+                let scoped = k.strip_prefix(prefix)?;
+                if scoped.is_empty() {
+                    Some((String::new(), v.clone()))
+                } else {
+                    Some((scoped.strip_prefix('.')?.to_string(), v.clone()))
+                }
+            })
+            .collect();
+        Self(inner)
+    }
+
+    /// Sets the properties of `scoped` under the `prefix` namespace, the reverse of
+    /// [`Properties::scope`]. The existing properties under `prefix` are overridden but not
+    /// removed. With the empty `prefix`, the keys of `scoped` are kept as they are.
+    pub fn merge_scoped(&mut self, prefix: &str, scoped: &Properties) {
+        for (k, v) in scoped.iter() {
+            let key = if k.is_empty() || prefix.is_empty() {
+                format!("{prefix}{k}")
+            } else {
+                format!("{prefix}.{k}")
+            };
+            self.0.insert(key, v.clone());
+        }
+    }
+
+    /// Returns the differences from these Properties to `other`, e.g. between the old and new
+    /// versions of a reloaded configuration.
+    ///
+    /// With `values_as_sets`, the `|`-separated values are compared regardless of their order
+    /// and duplicates, so that `a|b` and `b|a` are equal.
+    pub fn diff(&self, other: &Properties, values_as_sets: bool) -> PropertiesDiff {
+        let equal = |a: &str, b: &str| {
+            a == b
+                || (values_as_sets
+                    && split_values(a).all(|v| split_values(b).any(|w| v == w))
+                    && split_values(b).all(|w| split_values(a).any(|v| v == w)))
+        };
+        let mut diff = PropertiesDiff::default();
+        for (k, old) in self.iter() {
+            match other.get(k) {
+                None => diff.removed.push((k.clone(), old.clone())),
+                Some(new) if !equal(old, new) => {
+                    diff.changed.push((k.clone(), old.clone(), new.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (k, new) in other.iter() {
+            if !self.contains_key(k) {
+                diff.added.push((k.clone(), new.clone()));
+            }
+        }
+        diff.added.sort_unstable();
+        diff.removed.sort_unstable();
+        diff.changed.sort_unstable();
+        diff
+    }
+
+    /// Returns the canonical form of the Properties: their keys and values trimmed like when
+    /// parsed, without the properties with an empty key, and without the empty values of the
+    /// `|`-separated lists. The keys colliding once trimmed keep the value of the first of them
+    /// in key order.
+    ///
+    /// Properties that parse the same have the same canonical form, which formats and parses
+    /// back unchanged.
+    pub fn canonicalize(&self) -> Properties {
+        let sorted: BTreeMap<_, _> = self.0.iter().collect();
+        let mut canonical = HashMap::with_capacity(sorted.len());
+        for (k, v) in sorted {
+            let k = k.trim();
+            if k.is_empty() {
+                continue;
+            }
+            canonical.entry(k.to_string()).or_insert_with(|| {
+                let values: Vec<Cow<str>> = split_values(v.trim()).collect();
+                join_values(values.iter().map(|v| v.as_ref()))
+            });
+        }
+        Self(canonical)
+    }
+
+    /// Formats the [canonical form](Properties::canonicalize) of the Properties like
+    /// [`Display`](fmt::Display), but in the order of their keys, so that equivalent
+    /// Properties always give the same string, e.g. to hash or sign it.
+    pub fn to_canonical_string(&self) -> String {
+        let canonical = self.canonicalize();
+        let sorted: BTreeMap<_, _> = canonical.0.iter().collect();
+        let mut s = String::new();
+        for (k, v) in sorted {
+            if !s.is_empty() {
+                s.push(DEFAULT_PROP_SEP);
+            }
+            s.push_str(&escape(k, true));
+            if !v.is_empty() {
+                s.push(KV_SEP);
+                s.push_str(&escape(v, false));
+            }
+        }
+        s
+    }
+
+    /// Parses the Properties from a URL query string such as `?a=hello%20world&x=1&x=2`,
+    /// percent-decoding the keys and values. The values of a repeated key are `|`-separated,
+    /// e.g. `x=1|2`.
+    pub fn from_query_string(s: &str) -> Self {
+        let mut props = Self::default();
+        for param in s.strip_prefix('?').unwrap_or(s).split('&') {
+            if param.is_empty() {
+                continue;
+            }
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let (key, value) = (percent_decode(key), percent_decode(value));
+            if value.is_empty() {
+                props.0.entry(key).or_default();
+            } else {
+                props.append_value(key, &value, false);
+            }
+        }
+        props
+    }
+
+    /// Formats the Properties as a URL query string, without the leading `?`, in the order of
+    /// their keys. Each of the `|`-separated values gets its own parameter, and the keys and
+    /// values are percent-encoded.
+    pub fn to_query_string(&self) -> String {
+        let mut s = String::new();
+        for (k, v) in BTreeMap::from(self) {
+            let key = percent_encode(&k);
+            let mut values = split_values(&v).peekable();
+            if values.peek().is_none() {
+                if !s.is_empty() {
+                    s.push('&');
+                }
+                s.push_str(&key);
+            }
+            for value in values {
+                if !s.is_empty() {
+                    s.push('&');
+                }
+                s.push_str(&key);
+                s.push('=');
+                s.push_str(&percent_encode(&value));
+            }
+        }
+        s
+    }
+
+    /// Flattens a JSON object into Properties: the keys of the nested objects are joined with
+    /// `.`, the arrays become `|`-separated values, the other scalars are formatted as strings
+    /// and `null` is an empty value. E.g. `{"transport": {"link": {"tx": {"batch_size": 8192}}},
+    /// "connect": ["tcp/a:7447", "tcp/b:7447"]}` gives
+    /// `transport.link.tx.batch_size=8192;connect=tcp/a:7447|tcp/b:7447`.
+    ///
+    /// The arrays may only contain non-empty strings, numbers and booleans, whose `|` are
+    /// escaped.
+    #[cfg(feature = "serde_json")]
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, PropertiesJsonError> {
+        fn scalar(key: &str, value: &serde_json::Value) -> Result<String, PropertiesJsonError> {
+            match value {
+                serde_json::Value::Null => Ok(String::new()),
+                serde_json::Value::Bool(b) => Ok(b.to_string()),
+                serde_json::Value::Number(n) => Ok(n.to_string()),
+                serde_json::Value::String(s) => Ok(s.clone()),
+                _ => Err(PropertiesJsonError::new(key, "nested value in an array")),
+            }
+        }
+
+        fn flatten(
+            props: &mut Properties,
+            key: String,
+            json: &serde_json::Value,
+        ) -> Result<(), PropertiesJsonError> {
+            let value = match json {
+                serde_json::Value::Object(map) => {
+                    for (k, v) in map {
+                        let key = if key.is_empty() {
+                            k.clone()
+                        } else {
+                            format!("{key}.{k}")
+                        };
+                        flatten(props, key, v)?;
+                    }
+                    return Ok(());
+                }
+                serde_json::Value::Array(values) => {
+                    let mut list = vec![];
+                    for v in values {
+                        let v = scalar(&key, v)?;
+                        if v.is_empty() {
+                            return Err(PropertiesJsonError::new(&key, "empty array value"));
+                        }
+                        list.push(v);
+                    }
+                    join_values(list.iter().map(String::as_str))
+                }
+                value => scalar(&key, value)?,
+            };
+            if key.is_empty() {
+                return Err(PropertiesJsonError::new(&key, "not a JSON object"));
+            }
+            match props.0.entry(key) {
+                std::collections::hash_map::Entry::Occupied(entry) => {
+                    Err(PropertiesJsonError::new(entry.key(), "set more than once"))
+                }
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    entry.insert(value);
+                    Ok(())
+                }
+            }
+        }
+
+        let mut props = Properties::default();
+        flatten(&mut props, String::new(), json)?;
+        Ok(props)
+    }
+
+    /// Nests the Properties into a JSON object, the reverse of [`Properties::from_json`]: the
+    /// keys are split on `.`, the values with several `|`-separated values, or with a single
+    /// one containing an escaped `|`, become arrays of unescaped strings, and the other values
+    /// strings.
+    ///
+    /// A key that is also the prefix of another key, e.g. `a` and `a.b`, is an error.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> Result<serde_json::Value, PropertiesJsonError> {
+        let mut root = serde_json::Map::new();
+        for (key, value) in BTreeMap::from(self) {
+            let mut values: Vec<String> = split_values(&value).map(Cow::into_owned).collect();
+            let value = match values.len() {
+                0 => serde_json::Value::String(value),
+                1 if !values[0].contains(LIST_SEP) => serde_json::Value::String(values.remove(0)),
+                _ => serde_json::Value::Array(
+                    values.into_iter().map(serde_json::Value::String).collect(),
+                ),
+            };
+            let mut object = &mut root;
+            let mut chunks = key.split('.').peekable();
+            while let Some(chunk) = chunks.next() {
+                if chunks.peek().is_none() {
+                    if object.insert(chunk.to_string(), value).is_some() {
+                        return Err(PropertiesJsonError::new(
+                            &key,
+                            "both a value and the prefix of other properties",
+                        ));
+                    }
+                    break;
+                }
+                let nested = object
+                    .entry(chunk)
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+                object = match nested {
+                    serde_json::Value::Object(nested) => nested,
+                    _ => {
+                        return Err(PropertiesJsonError::new(
+                            &key,
+                            "the prefix of other properties is also a value",
+                        ))
+                    }
+                };
+            }
+        }
+        Ok(serde_json::Value::Object(root))
+    }
+
+    /// Gets the entry of the property `key` for in-place manipulation, e.g. to fill in a
+    /// default value with [`or_insert`](PropertyEntry::or_insert) without looking `key` up twice.
+    pub fn entry<K: Into<String>>(&mut self, key: K) -> PropertyEntry<'_> {
+        self.0.entry(key.into())
+    }
+
+    /// Retains only the properties for which `f(key, value)` returns `true`.
+    pub fn retain<F: FnMut(&str, &str) -> bool>(&mut self, mut f: F) {
+        self.0.retain(|k, v| f(k, v));
+    }
+
+    /// Removes the properties whose key starts with `prefix`, and returns them with their
+    /// keys unchanged.
+    pub fn remove_prefix(&mut self, prefix: &str) -> Properties {
+        let (removed, retained) = std::mem::take(&mut self.0)
+            .into_iter()
+            .partition(|(k, _)| k.starts_with(prefix));
+        self.0 = retained;
+        Self(removed)
+    }
+
+    /// Merges `other` into these Properties, resolving the properties set on both sides with
+    /// different values according to `policy`.
+    ///
+    /// Returns the `(key, overridden value, resulting value)` of each such property whose
+    /// overridden value was not kept as is, sorted by key: the value of `other` with
+    /// [`MergePolicy::PreferSelf`], and the value of `self` otherwise.
+    pub fn merge(
+        &mut self,
+        other: &Properties,
+        policy: MergePolicy,
+    ) -> Vec<(String, String, String)> {
+        let mut overridden = vec![];
+        for (key, value) in other.iter() {
+            let Some(current) = self.0.get_mut(key) else {
+                self.0.insert(key.clone(), value.clone());
+                continue;
+            };
+            if current == value {
+                continue;
+            }
+            match policy {
+                MergePolicy::PreferSelf => {
+                    overridden.push((key.clone(), value.clone(), current.clone()));
+                }
+                MergePolicy::PreferOther => {
+                    let old = std::mem::replace(current, value.clone());
+                    overridden.push((key.clone(), old, value.clone()));
+                }
+                MergePolicy::Combine => {
+                    let mut values: Vec<Cow<str>> = vec![];
+                    for v in split_values(current).chain(split_values(value)) {
+                        if !values.contains(&v) {
+                            values.push(v);
+                        }
+                    }
+                    let combined = join_values(values.iter().map(|v| v.as_ref()));
+                    if combined != *current {
+                        let old = std::mem::replace(current, combined.clone());
+                        overridden.push((key.clone(), old, combined));
+                    }
+                }
+            }
+        }
+        overridden.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        overridden
+    }
+
+    /// Parses the Properties like [`From<&str>`](Properties::from), but reports the malformed
+    /// segments that the lenient parsing silently skips or mis-splits instead of ignoring them.
+    /// An empty string and a trailing separator are accepted.
+    pub fn parse_strict(s: &str) -> Result<Self, Vec<PropertiesParseIssue>> {
+        Self::parse_strict_by(s, |c| c == DEFAULT_PROP_SEP || c == '\n')
+    }
+
+    /// Parses the Properties like [`Properties::parse_strict`], with `separator` instead of `;`
+    /// and newlines between the properties, e.g. `&` for the parameters of a selector.
+    pub fn parse_strict_with(s: &str, separator: char) -> Result<Self, Vec<PropertiesParseIssue>> {
+        Self::parse_strict_by(s, |c| c == separator)
+    }
+
+    fn parse_strict_by(
+        s: &str,
+        is_separator: impl Fn(char) -> bool,
+    ) -> Result<Self, Vec<PropertiesParseIssue>> {
+        // The segments between the separators, with their offsets
+        let mut segments = vec![];
+        let mut start = 0;
+        for (i, c) in unescaped_indices(s, ESCAPABLE) {
+            if is_separator(c) {
+                let end = if c == '\n' && s[..i].ends_with('\r') {
+                    i - 1
+                } else {
+                    i
+                };
+                segments.push((start, &s[start..end], Some(end)));
+                start = i + 1;
+            }
+        }
+        segments.push((start, &s[start..], None));
+
+        let mut inner = HashMap::new();
+        let mut issues = vec![];
+        for (offset, segment, separator) in segments {
+            let prop = segment.trim_start();
+            let offset = offset + segment.len() - prop.len();
+            if prop.trim_end().is_empty() {
+                // Only the last segment has no separator after it: a trailing separator is accepted
+                if let Some(separator) = separator {
+                    issues.push(PropertiesParseIssue {
+                        offset: separator,
+                        kind: PropertiesParseIssueKind::BareSeparator,
+                    });
+                }
+                continue;
+            }
+            if prop.starts_with(COMMENT_PREFIX) {
+                continue;
+            }
+            let (key, value) = split_property(prop);
+            let key = key.trim_end();
+            if key.is_empty() {
+                issues.push(PropertiesParseIssue {
+                    offset,
+                    kind: PropertiesParseIssueKind::EmptyKey,
+                });
+                continue;
+            }
+            if let Some((idx, _)) =
+                unescaped_indices(key, ESCAPABLE).find(|(_, c)| *c == COMMENT_PREFIX)
+            {
+                issues.push(PropertiesParseIssue {
+                    offset: offset + idx,
+                    kind: PropertiesParseIssueKind::ReservedCharacter(COMMENT_PREFIX),
+                });
+                continue;
+            }
+            inner.insert(unescape(key, ESCAPABLE), unescape(value.trim(), ESCAPABLE));
+        }
+        if issues.is_empty() {
+            Ok(Self(inner))
+        } else {
+            Err(issues)
+        }
+    }
+}
+
 impl From<&str> for Properties {
     fn from(s: &str) -> Self {
-        let mut props = vec![s];
-        for sep in PROP_SEPS {
-            props = props
-                .into_iter()
-                .flat_map(|s| s.split(sep))
-                .collect::<Vec<&str>>();
+        let mut props = vec![];
+        let mut start = 0;
+        for (i, c) in unescaped_indices(s, ESCAPABLE) {
+            if c == ';' || c == '\n' {
+                props.push(&s[start..i]);
+                start = i + 1;
+            }
         }
-        props = props.into_iter().map(str::trim).collect::<Vec<&str>>();
+        props.push(&s[start..]);
         let inner = props
-            .iter()
+            .into_iter()
+            .map(str::trim)
             .filter_map(|prop| {
                 if prop.is_empty() || prop.starts_with(COMMENT_PREFIX) {
                     None
                 } else {
-                    let mut it = prop.splitn(2, KV_SEP);
+                    let (key, value) = split_property(prop);
                     Some((
-                        it.next().unwrap().trim().to_string(),
-                        it.next().unwrap_or("").trim().to_string(),
+                        unescape(key.trim(), ESCAPABLE),
+                        unescape(value.trim(), ESCAPABLE),
                     ))
                 }
             })
@@ -112,6 +1106,13 @@ impl From<HashMap<String, String>> for Properties {
     }
 }
 
+impl<K: Into<String>, V: Into<String>> From<BTreeMap<K, V>> for Properties {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        let inner = map.into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+        Self(inner)
+    }
+}
+
 impl From<&[(&str, &str)]> for Properties {
     fn from(kvs: &[(&str, &str)]) -> Self {
         let inner = kvs
@@ -136,6 +1137,59 @@ impl From<Properties> for HashMap<String, String> {
     }
 }
 
+impl From<&Properties> for BTreeMap<String, String> {
+    fn from(props: &Properties) -> Self {
+        props
+            .0
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+/// Serializes the Properties as a map, sorted by key.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Properties {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let sorted: BTreeMap<_, _> = self.0.iter().collect();
+        serializer.collect_map(sorted)
+    }
+}
+
+/// Deserializes the Properties from a map, or from a string parsed like
+/// [`From<&str>`](Properties::from).
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Properties {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PropertiesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for PropertiesVisitor {
+            type Value = Properties;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of strings, or a string of `key=value` separated by `;`")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                Ok(Properties::from(s))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut inner = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some((key, value)) = map.next_entry::<String, String>()? {
+                    inner.insert(key, value);
+                }
+                Ok(Properties(inner))
+            }
+        }
+
+        deserializer.deserialize_any(PropertiesVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,4 +1225,679 @@ mod tests {
             Properties::from(&[("p1", "x=y"), ("p2", "a==b")][..])
         );
     }
+
+    #[test]
+    fn test_properties_typed() {
+        let props = Properties::from("flag;on=true;off=false;bad=yes;n=42;neg=-1;empty=");
+        assert_eq!(props.get_bool("flag"), Ok(Some(true)));
+        assert_eq!(props.get_bool("on"), Ok(Some(true)));
+        assert_eq!(props.get_bool("off"), Ok(Some(false)));
+        assert_eq!(props.get_bool("missing"), Ok(None));
+        assert_eq!(
+            props.get_bool("bad").unwrap_err().to_string(),
+            "invalid value 'yes' for property 'bad': provided string was not `true` or `false`"
+        );
+
+        assert_eq!(props.get_parsed::<u64>("n"), Ok(Some(42)));
+        assert_eq!(props.get_parsed::<i32>("neg"), Ok(Some(-1)));
+        assert_eq!(props.get_parsed::<u64>("missing"), Ok(None));
+        assert_eq!(
+            props.get_parsed::<u64>("neg").unwrap_err(),
+            PropertyValueError {
+                key: "neg".into(),
+                value: "-1".into(),
+                reason: "invalid digit found in string".into(),
+            }
+        );
+        assert_eq!(
+            props.get_parsed::<u64>("empty").unwrap_err().to_string(),
+            "invalid value '' for property 'empty': empty value"
+        );
+        assert_eq!(
+            props.get_parsed::<u64>("flag").unwrap_err().to_string(),
+            "invalid value '' for property 'flag': empty value"
+        );
+    }
+
+    #[test]
+    fn test_properties_duration() {
+        fn duration(value: &str) -> Result<Option<Duration>, PropertyValueError> {
+            Properties::from(&[("d", value)][..]).get_duration("d")
+        }
+
+        assert_eq!(duration("10ns"), Ok(Some(Duration::from_nanos(10))));
+        assert_eq!(duration("10us"), Ok(Some(Duration::from_micros(10))));
+        assert_eq!(duration("10ms"), Ok(Some(Duration::from_millis(10))));
+        assert_eq!(duration("2s"), Ok(Some(Duration::from_secs(2))));
+        assert_eq!(duration("1m"), Ok(Some(Duration::from_secs(60))));
+        assert_eq!(duration("1h"), Ok(Some(Duration::from_secs(3600))));
+        assert_eq!(duration("1.5s"), Ok(Some(Duration::from_millis(1500))));
+        assert_eq!(duration("0ms"), Ok(Some(Duration::ZERO)));
+        assert_eq!(Properties::default().get_duration("d"), Ok(None));
+
+        for (value, reason) in [
+            ("", "empty value"),
+            (
+                "10",
+                "missing or unknown unit, expected one of ns, us, ms, s, m, h",
+            ),
+            (
+                "10d",
+                "missing or unknown unit, expected one of ns, us, ms, s, m, h",
+            ),
+            (
+                "ms",
+                "missing or unknown unit, expected one of ns, us, ms, s, m, h",
+            ),
+            ("1x5ms", "invalid float literal"),
+        ] {
+            assert_eq!(
+                duration(value).unwrap_err().to_string(),
+                format!("invalid value '{value}' for property 'd': {reason}")
+            );
+        }
+        // The out of range durations are reported like Duration::try_from_secs_f64
+        for (value, secs) in [("-1s", -1.), ("1e30h", 3600e30)] {
+            let reason = Duration::try_from_secs_f64(secs).unwrap_err();
+            assert_eq!(
+                duration(value).unwrap_err().to_string(),
+                format!("invalid value '{value}' for property 'd': {reason}")
+            );
+        }
+    }
+
+    #[test]
+    fn test_properties_entry() {
+        let mut props = Properties::from("p1=user;p4=;p7=v7");
+        let defaults: Vec<(String, String)> = (0..10)
+            .map(|i| (format!("p{i}"), format!("default{i}")))
+            .collect();
+        for (key, value) in &defaults {
+            props.entry(key.as_str()).or_insert_with(|| value.clone());
+        }
+        assert_eq!(props.len(), 10);
+        for (key, value) in &defaults {
+            let expected = match key.as_str() {
+                "p1" => "user",
+                "p4" => "",
+                "p7" => "v7",
+                _ => value,
+            };
+            assert_eq!(props.get(key).unwrap(), expected);
+        }
+
+        props
+            .entry("p1")
+            .and_modify(|v| v.push_str("-modified"))
+            .or_insert_with(|| unreachable!());
+        assert_eq!(props.get("p1").unwrap(), "user-modified");
+        assert_eq!(
+            *props
+                .entry("p10")
+                .and_modify(|_| unreachable!())
+                .or_insert("v10".into()),
+            "v10"
+        );
+        assert_eq!(props.len(), 11);
+        assert_eq!(Properties::from(props.to_string()), props);
+    }
+
+    #[test]
+    fn test_properties_retain() {
+        let mut props =
+            Properties::from("internal.a=1;p1=v1;internal.b;p2=internal.c;internalp3=v3");
+        let mut internal = props.clone();
+        internal.retain(|k, _| k.starts_with("internal."));
+        assert_eq!(internal, Properties::from("internal.a=1;internal.b"));
+
+        props.retain(|_, v| !v.is_empty());
+        assert_eq!(
+            props,
+            Properties::from("internal.a=1;p1=v1;p2=internal.c;internalp3=v3")
+        );
+
+        let removed = props.remove_prefix("internal.");
+        assert_eq!(removed, Properties::from("internal.a=1"));
+        assert_eq!(props, Properties::from("p1=v1;p2=internal.c;internalp3=v3"));
+        assert!(props.remove_prefix("internal.").is_empty());
+        assert_eq!(props.remove_prefix("").len(), 3);
+        assert!(props.is_empty());
+    }
+
+    #[test]
+    fn test_properties_merge() {
+        let defaults = Properties::from("p1=d1;p2=d2;p3=d3");
+        let file = Properties::from("p2=f2;p3=d3;p4=f4");
+
+        let mut props = defaults.clone();
+        let overridden = props.merge(&file, MergePolicy::PreferOther);
+        assert_eq!(props, Properties::from("p1=d1;p2=f2;p3=d3;p4=f4"));
+        assert_eq!(overridden, vec![("p2".into(), "d2".into(), "f2".into())]);
+
+        let mut props = file.clone();
+        let overridden = props.merge(&defaults, MergePolicy::PreferSelf);
+        assert_eq!(props, Properties::from("p1=d1;p2=f2;p3=d3;p4=f4"));
+        assert_eq!(overridden, vec![("p2".into(), "d2".into(), "f2".into())]);
+
+        let mut props = Properties::from("p1=a|b|c;p2=a;p3=x|y;p4=");
+        let other = Properties::from("p1=c|d|a|e;p2=a;p3=y|x;p4=z;p5=n|m");
+        let overridden = props.merge(&other, MergePolicy::Combine);
+        assert_eq!(
+            props,
+            Properties::from("p1=a|b|c|d|e;p2=a;p3=x|y;p4=z;p5=n|m")
+        );
+        assert_eq!(
+            overridden,
+            vec![
+                ("p1".into(), "a|b|c".into(), "a|b|c|d|e".into()),
+                ("p4".into(), "".into(), "z".into()),
+            ]
+        );
+        // The order of the combined values is stable across merges
+        let overridden = props.merge(&other, MergePolicy::Combine);
+        assert!(overridden.is_empty());
+        assert_eq!(props.get("p1").unwrap(), "a|b|c|d|e");
+        assert_eq!(props.get("p5").unwrap(), "n|m");
+
+        // The escaped `|` are part of the values
+        let mut props = Properties::from("p1=a\\|b|c");
+        let overridden = props.merge(&Properties::from("p1=c|a"), MergePolicy::Combine);
+        assert_eq!(props.get("p1").unwrap(), "a\\|b|c|a");
+        assert_eq!(overridden.len(), 1);
+    }
+
+    #[test]
+    fn test_properties_values() {
+        let mut props = Properties::from("connect=tcp/a:7447|tcp/b:7447;listen=;mode=peer");
+        let values = props.values_vec("connect");
+        assert_eq!(values.len(), 2);
+        assert_eq!(values.collect::<Vec<_>>(), ["tcp/a:7447", "tcp/b:7447"]);
+        assert_eq!(props.values_vec("listen").len(), 0);
+        let props_with_empty = Properties::from("p=|a||b|");
+        let mut values = props_with_empty.values_vec("p");
+        assert_eq!(values.next().unwrap(), "a");
+        assert_eq!(values.len(), 1);
+        assert_eq!(props.values("listen").count(), 0);
+        assert_eq!(props.values("unset").count(), 0);
+        assert_eq!(props.values_vec("mode").collect::<Vec<_>>(), ["peer"]);
+
+        assert!(props.append_value("connect", "tcp/c:7447", false));
+        assert!(!props.append_value("connect", "tcp/a:7447", true));
+        assert!(props.append_value("connect", "tcp/a:7447", false));
+        assert_eq!(
+            props.get("connect").unwrap(),
+            "tcp/a:7447|tcp/b:7447|tcp/c:7447|tcp/a:7447"
+        );
+        assert!(props.append_value("listen", "tcp/[::]:7447", true));
+        assert!(props.append_value("scouting", "udp", true));
+        assert_eq!(props.get("listen").unwrap(), "tcp/[::]:7447");
+        assert_eq!(props.get("scouting").unwrap(), "udp");
+
+        assert!(props.remove_value("connect", "tcp/a:7447"));
+        assert_eq!(
+            props.values_vec("connect").collect::<Vec<_>>(),
+            ["tcp/b:7447", "tcp/c:7447", "tcp/a:7447"]
+        );
+        assert!(!props.remove_value("connect", "tcp/d:7447"));
+        assert!(!props.remove_value("unset", "tcp/d:7447"));
+        assert!(props.remove_value("scouting", "udp"));
+        assert!(!props.contains_key("scouting"));
+
+        props.set_values("connect", ["tcp/x:7447", "tcp/y:7447"]);
+        assert_eq!(props.get("connect").unwrap(), "tcp/x:7447|tcp/y:7447");
+        props.set_values("connect", []);
+        assert_eq!(props.values("connect").count(), 0);
+        assert_eq!(Properties::from(props.to_string()), props);
+
+        // The `|` and `\` of the values are escaped
+        props.set_values("cmd", ["a|b", "c\\"]);
+        assert_eq!(props.get("cmd").unwrap(), "a\\|b|c\\\\");
+        assert!(props.append_value("cmd", "|", true));
+        assert!(!props.append_value("cmd", "a|b", true));
+        let values = props.values_vec("cmd");
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.collect::<Vec<_>>(), ["a|b", "c\\", "|"]);
+        assert!(props.remove_value("cmd", "a|b"));
+        assert_eq!(props.get("cmd").unwrap(), "c\\\\|\\|");
+        assert_eq!(Properties::from(props.to_string()), props);
+    }
+
+    #[test]
+    fn test_properties_case() {
+        let mut props = Properties::from("MODE=client;Mode=router;mode=peer;Listen=tcp/[::]:0");
+        assert_eq!(props.get_ci("mode").unwrap(), "peer");
+        assert_eq!(props.get_ci("MODE").unwrap(), "client");
+        assert_eq!(props.get_ci("Mode").unwrap(), "router");
+        assert_eq!(props.get_ci("mOdE").unwrap(), "client");
+        assert_eq!(props.get_ci("listen").unwrap(), "tcp/[::]:0");
+        assert!(props.contains_key_ci("LISTEN"));
+        assert!(!props.contains_key("LISTEN"));
+        assert!(!props.contains_key_ci("connect"));
+
+        // The original casing is preserved until normalized
+        assert_eq!(Properties::from(props.to_string()), props);
+        let mut upper = props.clone();
+        upper.normalize_keys(Case::Upper);
+        assert_eq!(upper, Properties::from("MODE=client;LISTEN=tcp/[::]:0"));
+        props.normalize_keys(Case::Lower);
+        assert_eq!(props, Properties::from("mode=peer;listen=tcp/[::]:0"));
+
+        let mut props = Properties::from("Mode=router;MODE=client");
+        props.normalize_keys(Case::Lower);
+        assert_eq!(props, Properties::from("mode=client"));
+    }
+
+    #[test]
+    fn test_properties_scope() {
+        let mut props = Properties::from(
+            "transport.link.tx=on;transport.link.tx.batch_size=65535;transport.link.tx.queue.size=16;\
+             transport.link.txx=off;transport.link=unscoped;mode=peer",
+        );
+        let mut tx = props.scope("transport.link.tx");
+        let mut expected = Properties::from("batch_size=65535;queue.size=16");
+        expected.insert(String::new(), "on".into());
+        assert_eq!(tx, expected);
+        assert_eq!(tx.scope("queue"), Properties::from("size=16"));
+        assert!(props.scope("transport.link.rx").is_empty());
+
+        tx.insert("batch_size".into(), "8192".into());
+        tx.insert("queue.lease".into(), "10s".into());
+        tx.insert(String::new(), "off".into());
+        props.merge_scoped("transport.link.tx", &tx);
+        assert_eq!(
+            props,
+            Properties::from(
+                "transport.link.tx=off;transport.link.tx.batch_size=8192;transport.link.tx.queue.size=16;\
+                 transport.link.tx.queue.lease=10s;transport.link.txx=off;transport.link=unscoped;mode=peer"
+            )
+        );
+        assert_eq!(props.scope("transport.link.tx"), tx);
+
+        // The root namespace is all the properties
+        assert_eq!(props.scope(""), props);
+        let mut root = Properties::default();
+        root.merge_scoped("", &Properties::from("mode=client;scouting.delay=1s"));
+        assert_eq!(root, Properties::from("mode=client;scouting.delay=1s"));
+    }
+
+    #[test]
+    fn test_properties_diff() {
+        let old = Properties::from("mode=peer;connect=tcp/a:7447|tcp/b:7447;listen=tcp/[::]:0;p1");
+        let new =
+            Properties::from("mode=client;connect=tcp/b:7447|tcp/a:7447|tcp/a:7447;p1;p3=v3;p2=v2");
+        assert!(old.diff(&old, false).is_empty());
+
+        let diff = old.diff(&new, false);
+        assert_eq!(
+            diff,
+            PropertiesDiff {
+                added: vec![("p2".into(), "v2".into()), ("p3".into(), "v3".into())],
+                removed: vec![("listen".into(), "tcp/[::]:0".into())],
+                changed: vec![
+                    (
+                        "connect".into(),
+                        "tcp/a:7447|tcp/b:7447".into(),
+                        "tcp/b:7447|tcp/a:7447|tcp/a:7447".into()
+                    ),
+                    ("mode".into(), "peer".into(), "client".into()),
+                ],
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            "+ p2=v2\n+ p3=v3\n- listen=tcp/[::]:0\n\
+             ~ connect: tcp/a:7447|tcp/b:7447 -> tcp/b:7447|tcp/a:7447|tcp/a:7447\n\
+             ~ mode: peer -> client"
+        );
+
+        let diff = old.diff(&new, true);
+        assert_eq!(
+            diff.changed,
+            vec![("mode".into(), "peer".into(), "client".into())]
+        );
+        let diff = new.diff(&old, true);
+        assert_eq!(diff.added, vec![("listen".into(), "tcp/[::]:0".into())]);
+        assert_eq!(diff.removed.len(), 2);
+        assert_eq!(PropertiesDiff::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_properties_canonical() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |props: &Properties| {
+            let mut hasher = DefaultHasher::new();
+            props.hash(&mut hasher);
+            hasher.finish()
+        };
+        let p1 = Properties::from("p3=v3;p1=v1;p=v;p2;k\\;=v\\;");
+        let p2 = Properties::from("p2;k\\;=v\\;;p=v;p1=v1;p1=v1;p3=v3");
+        assert_eq!(p1, p2);
+        assert_eq!(hash(&p1), hash(&p2));
+        assert_eq!(p1.to_canonical_string(), "k\\;=v\\;;p=v;p1=v1;p2;p3=v3");
+        assert_eq!(p1.to_canonical_string(), p2.to_canonical_string());
+        assert_eq!(Properties::from(p1.to_canonical_string()), p1);
+        assert_ne!(hash(&p1), hash(&Properties::from("p3=v3;p1=v1;p=v;p2")));
+
+        // The canonical form is what parsing the formatted Properties gives
+        assert_eq!(p1.canonicalize(), p1);
+        let p3 = Properties::from(&[(" p1 ", " a||b| "), ("", "x"), ("p2", ""), ("p1", "c")][..]);
+        assert_eq!(p3.canonicalize(), Properties::from("p1=a|b;p2"));
+        assert_eq!(p3.to_canonical_string(), "p1=a|b;p2");
+        assert_eq!(
+            Properties::from(p3.to_canonical_string()),
+            p3.canonicalize()
+        );
+
+        let sorted = BTreeMap::from(&p1);
+        assert_eq!(
+            sorted.keys().collect::<Vec<_>>(),
+            ["k;", "p", "p1", "p2", "p3"]
+        );
+        assert_eq!(Properties::from(sorted), p1);
+        assert_eq!(
+            Properties::from(BTreeMap::from([("p1", "v1"), ("p2", "")])),
+            Properties::from("p2;p1=v1")
+        );
+    }
+
+    #[test]
+    fn test_properties_query_string() {
+        let props = Properties::from_query_string("a=hello%20world&x=1&x=2");
+        assert_eq!(props.get("a").unwrap(), "hello world");
+        assert_eq!(props.values("x").collect::<Vec<_>>(), ["1", "2"]);
+        assert_eq!(props.to_query_string(), "a=hello%20world&x=1&x=2");
+
+        let props = Properties::from_query_string("?k%3Bey=v%26a%3Dl+ue&flag&&x=&x=1&bad=%zz%4");
+        assert_eq!(
+            props,
+            Properties::from(
+                &[
+                    ("k;ey", "v&a=l ue"),
+                    ("flag", ""),
+                    ("x", "1"),
+                    ("bad", "%zz%4")
+                ][..]
+            )
+        );
+        assert_eq!(
+            props.to_query_string(),
+            "bad=%25zz%254&flag&k%3Bey=v%26a%3Dl%20ue&x=1"
+        );
+        assert_eq!(
+            Properties::from_query_string(&props.to_query_string()),
+            props
+        );
+        assert_eq!(
+            Properties::from_query_string("e=%C3%A9%FF")
+                .get("e")
+                .unwrap(),
+            "\u{e9}\u{fffd}"
+        );
+        assert!(Properties::from_query_string("").is_empty());
+        assert_eq!(Properties::default().to_query_string(), "");
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_properties_json() {
+        let json = serde_json::json!({
+            "mode": "peer",
+            "connect": ["tcp/a:7447", "tcp/b:7447"],
+            "transport": {
+                "link": {"tx": {"batch_size": 8192, "lease": 1.5, "enabled": true}},
+                "shm": null,
+            },
+        });
+        let props = Properties::from_json(&json).unwrap();
+        assert_eq!(
+            props.to_canonical_string(),
+            "connect=tcp/a:7447|tcp/b:7447;mode=peer;transport.link.tx.batch_size=8192;\
+             transport.link.tx.enabled=true;transport.link.tx.lease=1.5;transport.shm"
+        );
+        assert_eq!(
+            props.to_json().unwrap(),
+            serde_json::json!({
+                "mode": "peer",
+                "connect": ["tcp/a:7447", "tcp/b:7447"],
+                "transport": {
+                    "link": {"tx": {"batch_size": "8192", "lease": "1.5", "enabled": "true"}},
+                    "shm": "",
+                },
+            })
+        );
+        assert_eq!(
+            Properties::from_json(&props.to_json().unwrap()).unwrap(),
+            props
+        );
+
+        // The `|` of the array values are escaped
+        let json = serde_json::json!({"a": ["x|y", "z"], "b": ["x|y"]});
+        let props = Properties::from_json(&json).unwrap();
+        assert_eq!(props.values_vec("a").collect::<Vec<_>>(), ["x|y", "z"]);
+        assert_eq!(props.get("b").unwrap(), "x\\|y");
+        assert_eq!(props.to_json().unwrap(), json);
+
+        let err = |json: serde_json::Value| Properties::from_json(&json).unwrap_err().key;
+        assert_eq!(err(serde_json::json!([1, 2])), "");
+        assert_eq!(err(serde_json::json!({"a": [[1]]})), "a");
+        assert_eq!(err(serde_json::json!({"a": {"b": ["x", ""]}})), "a.b");
+        assert_eq!(err(serde_json::json!({"a": {"b": 1}, "a.b": 2})), "a.b");
+
+        let err = Properties::from("a=1;a.b=2").to_json().unwrap_err();
+        assert_eq!(err.key, "a.b");
+        assert_eq!(
+            err.to_string(),
+            "invalid property 'a.b': the prefix of other properties is also a value"
+        );
+        let err = Properties::from("a.b=2;a=1").to_json().unwrap_err();
+        assert_eq!(err.key, "a.b");
+    }
+
+    #[test]
+    fn test_properties_strict() {
+        use PropertiesParseIssueKind::*;
+
+        fn issues(s: &str) -> Vec<(usize, PropertiesParseIssueKind)> {
+            Properties::parse_strict(s)
+                .unwrap_err()
+                .into_iter()
+                .map(|issue| (issue.offset, issue.kind))
+                .collect()
+        }
+
+        for s in [
+            "",
+            "p1",
+            "p1=v1;p2=v2;",
+            "p1=v1\np2\r\np3=v3",
+            "# comment\np1=x=y",
+            " p 1 = v 1 ",
+        ] {
+            assert_eq!(Properties::parse_strict(s).unwrap(), Properties::from(s));
+        }
+
+        assert_eq!(issues("=v1"), [(0, EmptyKey)]);
+        assert_eq!(issues("p1=v1; =v2"), [(7, EmptyKey)]);
+        assert_eq!(issues(";p1=v1"), [(0, BareSeparator)]);
+        assert_eq!(issues("p1=v1;;p2=v2"), [(6, BareSeparator)]);
+        assert_eq!(issues("p1=v1\r\n\r\np2"), [(7, BareSeparator)]);
+        assert_eq!(issues("p#1=v1"), [(1, ReservedCharacter('#'))]);
+        assert_eq!(
+            issues("=v1;;p#"),
+            [
+                (0, EmptyKey),
+                (4, BareSeparator),
+                (6, ReservedCharacter('#'))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_properties_strict_with() {
+        let props = Properties::parse_strict_with("p1=v1&p2=a;b&", '&').unwrap();
+        assert_eq!(props, Properties::from(&[("p1", "v1"), ("p2", "a;b")][..]));
+        let issues = Properties::parse_strict_with("&p1&&=v2", '&').unwrap_err();
+        assert_eq!(
+            issues,
+            [
+                PropertiesParseIssue {
+                    offset: 0,
+                    kind: PropertiesParseIssueKind::BareSeparator
+                },
+                PropertiesParseIssue {
+                    offset: 4,
+                    kind: PropertiesParseIssueKind::BareSeparator
+                },
+                PropertiesParseIssue {
+                    offset: 5,
+                    kind: PropertiesParseIssueKind::EmptyKey
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_properties_escaping() {
+        let props = Properties::from(&[("cmd", "a;b"), ("k=1", "x=y"), ("#k", "C:\\dir\\")][..]);
+        let formatted = props.to_string();
+        for escaped in ["cmd=a\\;b", "k\\=1=x=y", "\\#k=C:\\dir\\\\"] {
+            assert!(formatted.contains(escaped), "{formatted}");
+        }
+        assert_eq!(Properties::from(formatted.as_str()), props);
+        assert_eq!(Properties::parse_strict(&formatted).unwrap(), props);
+
+        // The strings without escapable characters are formatted as they are
+        let props = Properties::from(&[("path", "C:\\dir"), ("p1", "x=y")][..]);
+        let formatted = props.to_string();
+        assert!(formatted.contains("path=C:\\dir") && formatted.contains("p1=x=y"));
+        assert_eq!(Properties::from(formatted.as_str()), props);
+        assert_eq!(
+            Properties::from("path=C:\\dir"),
+            Properties::from(&[("path", "C:\\dir")][..])
+        );
+        assert_eq!(
+            Properties::from("p1=a\\;b;p2=c\\\nd"),
+            Properties::from(&[("p1", "a;b"), ("p2", "c\nd")][..])
+        );
+
+        // The values separated by `|` are left as they are
+        let props = Properties::from("p1=a|b|c;p2=a\\;b|c");
+        assert_eq!(props.get("p1").map(String::as_str), Some("a|b|c"));
+        assert_eq!(props.get("p2").map(String::as_str), Some("a;b|c"));
+        let formatted = props.to_string();
+        assert!(formatted.contains("p1=a|b|c") && formatted.contains("p2=a\\;b|c"));
+        assert_eq!(Properties::from(formatted.as_str()), props);
+    }
+
+    #[test]
+    fn test_properties_escaping_fuzz() {
+        use rand::{Rng, SeedableRng};
+
+        const FUZZ_ROUNDS: usize = 10_000;
+        const ALPHABET: &[char] = &[
+            'a', 'b', ' ', '=', ';', '|', '#', '\\', '\r', '\n', '\u{e9}',
+        ];
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut random_string = |min_len: usize| loop {
+            let len = rng.gen_range(min_len..8);
+            let s: String = (0..len)
+                .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+                .collect();
+            // The leading and trailing whitespaces are trimmed
+            if s.trim().len() >= min_len {
+                break s.trim().to_string();
+            }
+        };
+        for _ in 0..FUZZ_ROUNDS {
+            let props = Properties::from(
+                (0..4)
+                    .map(|_| (random_string(1), random_string(0)))
+                    .collect::<HashMap<_, _>>(),
+            );
+            let formatted = props.to_string();
+            assert_eq!(Properties::from(formatted.as_str()), props, "{formatted:?}");
+            assert_eq!(
+                Properties::parse_strict(&formatted).unwrap(),
+                props,
+                "{formatted:?}"
+            );
+
+            let values: Vec<String> = (0..3).map(|_| random_string(1)).collect();
+            let mut props = Properties::default();
+            props.set_values("p", values.iter().map(String::as_str));
+            let formatted = props.to_string();
+            assert_eq!(
+                Properties::from(formatted.as_str())
+                    .values_vec("p")
+                    .collect::<Vec<_>>(),
+                values,
+                "{formatted:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_properties_serde() {
+        let props = Properties::from(&[("p1", "v1"), ("p2", ""), ("p;3", "a=b")][..]);
+
+        let json = serde_json::to_string(&props).unwrap();
+        assert_eq!(json, r#"{"p1":"v1","p2":"","p;3":"a=b"}"#);
+        assert_eq!(serde_json::from_str::<Properties>(&json).unwrap(), props);
+
+        let yaml = serde_yaml::to_string(&props).unwrap();
+        assert_eq!(yaml, "p1: v1\np2: ''\np;3: a=b\n");
+        assert_eq!(serde_yaml::from_str::<Properties>(&yaml).unwrap(), props);
+
+        // The string form of the existing configuration files is still accepted
+        let formatted = serde_json::to_string(&props.to_string()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Properties>(&formatted).unwrap(),
+            props
+        );
+        assert_eq!(
+            serde_yaml::from_str::<Properties>("p1=v1;p2;p\\;3=a=b").unwrap(),
+            props
+        );
+
+        #[derive(serde::Deserialize)]
+        struct Config {
+            properties: Properties,
+        }
+        let config: Config = serde_json::from_str(r#"{"properties": "p1=v1"}"#).unwrap();
+        assert_eq!(config.properties, Properties::from(&[("p1", "v1")][..]));
+        assert!(serde_json::from_str::<Properties>("42").is_err());
+    }
+
+    #[test]
+    fn test_properties_strict_fuzz() {
+        use rand::{Rng, SeedableRng};
+
+        const FUZZ_ROUNDS: usize = 100_000;
+        const ALPHABET: &[u8] = b"ab =;#\r\n\xc3\xa9";
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..FUZZ_ROUNDS {
+            let len = rng.gen_range(0..16);
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    if rng.gen_bool(0.1) {
+                        rng.gen()
+                    } else {
+                        ALPHABET[rng.gen_range(0..ALPHABET.len())]
+                    }
+                })
+                .collect();
+            let s = String::from_utf8_lossy(&bytes);
+            match Properties::parse_strict(&s) {
+                Ok(props) => assert_eq!(props, Properties::from(s.as_ref())),
+                Err(issues) => {
+                    assert!(!issues.is_empty());
+                    assert!(issues.iter().all(|issue| issue.offset < s.len()));
+                }
+            }
+        }
+    }
 }
@@ -107,6 +107,7 @@ impl Default for TransportUnicastConf {
             accept_pending: 100,
             max_sessions: 1_000,
             max_links: 1,
+            acceptance: AcceptanceUnicastConf::default(),
             lowlatency: false,
             qos: QoSUnicastConf::default(),
             compression: CompressionUnicastConf::default(),
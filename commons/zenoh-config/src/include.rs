@@ -175,3 +175,92 @@ where
 
     Ok(())
 }
+
+/// The property of the objects replaced by the content of a secret file, e.g. `{ __file__: "/run/secrets/password" }`.
+pub(crate) const SECRET_FILE_PROPERTY: &str = "__file__";
+
+/// Replace the `{ __file__: "<path>" }` objects found in `value` by the content of the files they
+/// point to, relative paths being resolved from `local_path`.
+///
+/// The JSON pointers of the replaced values are appended to `secrets`, so that they can be redacted.
+pub(crate) fn resolve_secret_files<P>(
+    pointer: &str, // JSON pointer to `value` for error reporting
+    value: &mut Value,
+    local_path: P,
+    strict: bool,
+    secrets: &mut Vec<String>,
+) -> ZResult<()>
+where
+    P: AsRef<Path>,
+{
+    match value {
+        Value::Object(values) if values.len() == 1 && values.contains_key(SECRET_FILE_PROPERTY) => {
+            let Some(file) = values[SECRET_FILE_PROPERTY].as_str() else {
+                bail!(
+                    "{}/{} : property must have string type",
+                    pointer,
+                    SECRET_FILE_PROPERTY
+                );
+            };
+            let file_path = local_path.as_ref().join(file);
+            let secret = match read_secret_file(&file_path, strict) {
+                Ok(secret) => secret,
+                Err(e) => bail!(
+                    "{}/{} : failed to read secret file '{}' - {}",
+                    pointer,
+                    SECRET_FILE_PROPERTY,
+                    file,
+                    e
+                ),
+            };
+            *value = Value::String(secret);
+            secrets.push(pointer.to_string());
+        }
+        Value::Object(values) => {
+            for (k, v) in values.iter_mut() {
+                let pointer = format!("{}/{}", pointer, k.replace('~', "~0").replace('/', "~1"));
+                resolve_secret_files(&pointer, v, local_path.as_ref(), strict, secrets)?;
+            }
+        }
+        Value::Array(values) => {
+            for (i, v) in values.iter_mut().enumerate() {
+                let pointer = format!("{}/{}", pointer, i);
+                resolve_secret_files(&pointer, v, local_path.as_ref(), strict, secrets)?;
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {}
+    }
+    Ok(())
+}
+
+/// Read a secret file, stripped of its trailing newline.
+///
+/// In `strict` mode, files readable by group or others are refused (on unix only).
+fn read_secret_file(path: &Path, strict: bool) -> ZResult<String> {
+    #[cfg(unix)]
+    if strict {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)?.permissions().mode();
+        if mode & 0o044 != 0 {
+            bail!(
+                "file is readable by group or others (mode {:o}), which is refused by `secrets.strict_permissions`",
+                mode & 0o777
+            );
+        }
+    }
+    #[cfg(not(unix))]
+    if strict {
+        tracing::warn!(
+            "`secrets.strict_permissions` is only supported on unix: permissions of '{}' are not checked",
+            path.display()
+        );
+    }
+    let mut secret = std::fs::read_to_string(path)?;
+    if secret.ends_with('\n') {
+        secret.pop();
+        if secret.ends_with('\r') {
+            secret.pop();
+        }
+    }
+    Ok(secret)
+}
@@ -16,7 +16,7 @@
 pub mod defaults;
 mod include;
 
-use include::recursive_include;
+use include::{recursive_include, resolve_secret_files};
 use secrecy::{CloneableSecret, DebugSecret, Secret, SerializableSecret, Zeroize};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -24,7 +24,7 @@ use serde_json::{Map, Value};
 use std::convert::TryFrom; // This is a false positive from the rust analyser
 use std::{
     any::Any,
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     fmt,
     io::Read,
     net::SocketAddr,
@@ -141,6 +141,15 @@ pub enum Permission {
     Deny,
 }
 
+/// The policies of the [`NoRouteConf`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoRoutePolicyConf {
+    Drop,
+    Fail,
+    Buffer,
+}
+
 pub trait ConfigValidator: Send + Sync {
     fn check_config(
         &self,
@@ -215,6 +224,11 @@ validated_struct::validator! {
     Config {
         /// The Zenoh ID of the instance. This ID MUST be unique throughout your Zenoh infrastructure and cannot exceed 16 bytes of length. If left unset, a random u128 will be generated.
         id: ZenohId,
+        /// The path of a keystore file persisting the Zenoh ID of the instance across restarts.
+        /// If the file doesn't exist, it is created and stores the `id` of this configuration. Otherwise, the Zenoh ID it stores is used instead of `id`.
+        /// The file is locked while the instance is running, so that two processes can't share the same identity.
+        /// Requires zenoh to be built with the `keystore` feature.
+        keystore: Option<String>,
         /// The metadata of the instance. Arbitrary json data available from the admin space
         metadata: Value,
         /// The node's mode ("router" (default value in `zenohd`), "peer" or "client").
@@ -283,6 +297,9 @@ validated_struct::validator! {
         pub timestamping: #[derive(Default)]
         TimestampingConf {
             /// Whether data messages should be timestamped if not already.
+            /// If disabled, no HLC is instantiated and the node never timestamps data messages,
+            /// which is meant for nodes without a reliable clock. Timestamps set by other nodes
+            /// are forwarded untouched.
             enabled: Option<ModeDependentValue<bool>>,
             /// Whether data messages with timestamps in the future should be dropped or not.
             /// If set to false (default), messages with timestamps in the future are retimestamped.
@@ -292,6 +309,46 @@ validated_struct::validator! {
 
         /// The default timeout to apply to queries in milliseconds.
         queries_default_timeout: Option<u64>,
+        /// Whether closing a session explicitly undeclares its remaining subscribers, queryables and liveliness tokens,
+        /// for compatibility with routers that don't purge the declarations of closed sessions (default false).
+        /// Otherwise, the remote nodes are left to purge them when the session's transports are closed.
+        /// Sessions sharing their runtime always undeclare them. The undeclarations are de-duplicated per key expression,
+        /// but still sent one message per key expression.
+        undeclare_on_close: Option<bool>,
+        /// The time in milliseconds that dropping a subscriber may wait for its undeclaration, unless
+        /// set on the subscriber (default unbounded). Past it, the undeclaration completes in the background.
+        /// Only effective with zenoh's `unstable` feature.
+        subscribers_drop_timeout: Option<u64>,
+        /// Whether puts, deletes, publishers and query replies may use key expressions containing wildcards.
+        /// They are rejected by default, as writing on a wildcard key expression is most often a mistake;
+        /// wildcard deletes to storages are a legitimate use.
+        allow_wildcard_writes: Option<bool>,
+        /// The maximum size in bytes of the payloads put by a session, and of the payloads it delivers
+        /// to its subscribers (default unlimited). Larger puts fail, larger received samples are dropped.
+        max_payload_size: Option<usize>,
+        /// How the puts and gets of a session are handled while no other node would receive them,
+        /// e.g. while a client isn't connected to a router yet.
+        /// Only effective with zenoh's `unstable` feature.
+        pub no_route: #[derive(Default)]
+        NoRouteConf {
+            /// "drop" (default): the messages are dropped by the routing.
+            /// "fail": the puts and gets fail with a `NoRoute` error.
+            /// "buffer": the messages are queued until they have a route, then sent in order.
+            policy: Option<NoRoutePolicyConf>,
+            /// The maximum number of queued messages with the "buffer" policy (default 1000).
+            buffer_size: Option<usize>,
+            /// The time after which the queued messages are dropped, in milliseconds (default 10000).
+            buffer_timeout_ms: Option<u64>,
+        },
+        /// The journal of the recent writes of a session, overlaid on the replies of its gets
+        /// issued with `read_your_writes`. Only effective with zenoh's `unstable` feature.
+        pub read_your_writes: #[derive(Default)]
+        ReadYourWritesConf {
+            /// The maximum number of key expressions in the journal (default 1000), 0 disabling it.
+            journal_size: Option<usize>,
+            /// The time after which the writes are removed from the journal, in milliseconds (default 10000).
+            journal_ttl_ms: Option<u64>,
+        },
 
         /// The routing strategy to use and it's configuration.
         pub routing: #[derive(Default)]
@@ -332,6 +389,14 @@ validated_struct::validator! {
                 max_sessions: usize,
                 /// Maximum number of unicast incoming links per transport session (default: 1)
                 max_links: usize,
+                /// Rules deciding which incoming unicast sessions are accepted, on top of `max_sessions`.
+                pub acceptance: #[derive(Default)]
+                AcceptanceUnicastConf {
+                    /// Only accept incoming sessions from nodes of these kinds, e.g. "router|peer" (default: all).
+                    whatami: Option<WhatAmIMatcher>,
+                    /// Refuse incoming sessions from nodes with these Zenoh IDs (default: none).
+                    denied_zids: Vec<ZenohId>,
+                },
                 /// Enables the LowLatency transport (default `false`).
                 /// This option does not make LowLatency transport mandatory, the actual implementation of transport
                 /// used will depend on Establish procedure and other party's settings
@@ -500,6 +565,17 @@ validated_struct::validator! {
 
         },
 
+        /// Configuration of the log verbosity. When set, it replaces the filter given with the `RUST_LOG`
+        /// environment variable, and it is applied again whenever it is changed at runtime.
+        /// Only effective when the logs were initialized with a reloadable filter, as `zenohd` does.
+        pub logging: #[derive(Default)]
+        LoggingConf {
+            /// The level of the logs of the modules that are not listed in `modules`, e.g. "info".
+            level: Option<String>,
+            /// The levels of specific modules, by module path prefix, e.g. `{ "zenoh::net::routing": "debug" }`.
+            modules: Option<BTreeMap<String, String>>,
+        },
+
         /// Configuration of the downsampling.
         downsampling: Vec<DownsamplingItemConf>,
 
@@ -510,6 +586,34 @@ validated_struct::validator! {
             pub rules: Option<Vec<AclConfigRules>>
         },
 
+        /// Faults injected in the data messages sent to the network by the sessions, for chaos testing.
+        /// Only effective when zenoh is built with the `testing` feature.
+        pub fault_injection: #[derive(Default)]
+        FaultInjectionConf {
+            /// The probability, between 0 and 1, to drop an outgoing data message (default 0).
+            drop_rate: Option<f64>,
+            /// The probability, between 0 and 1, to send an outgoing data message twice (default 0).
+            duplicate_rate: Option<f64>,
+            /// The outgoing data messages are delayed by a duration uniformly distributed
+            /// between `delay_min_ms` and `delay_max_ms` milliseconds (not delayed by default).
+            delay_min_ms: Option<u64>,
+            delay_max_ms: Option<u64>,
+            /// Close the transports of the session once it sent this number of data messages (never by default).
+            close_after: Option<usize>,
+        },
+
+        /// Any value of a configuration file may be replaced by `{ __file__: "<path>" }` to be read from the file at `<path>`
+        /// (relative to the configuration file), stripped of its trailing newline. This keeps passwords and private keys
+        /// out of the configuration file. These values are redacted from the configuration dumps.
+        pub secrets: #[derive(Default)]
+        SecretsConf {
+            /// Refuse the secret files that are readable by group or others (unix only, default false).
+            strict_permissions: Option<bool>,
+        },
+        /// The JSON pointers of the values read from secret files.
+        #[serde(skip)]
+        secret_pointers: Vec<String>,
+
         /// A list of directories where plugins may be searched for if no `__path__` was specified for them.
         /// The executable's current directory will be added to the search paths.
         pub plugins_loading: #[derive(Default)]
@@ -534,6 +638,20 @@ impl Default for PermissionsConf {
     }
 }
 
+impl LoggingConf {
+    /// The log filter directives described by this configuration, if any.
+    pub fn directives(&self) -> Option<String> {
+        let modules = self.modules.iter().flatten();
+        let directives: Vec<String> = self
+            .level
+            .iter()
+            .cloned()
+            .chain(modules.map(|(module, level)| format!("{module}={level}")))
+            .collect();
+        (!directives.is_empty()).then(|| directives.join(","))
+    }
+}
+
 fn set_true() -> bool {
     true
 }
@@ -696,6 +814,15 @@ impl Config {
         let path = path.as_ref();
         let mut config = Self::_from_file(path)?;
         config.plugins.load_external_configs()?;
+        // The plugins configurations may include secret files from their external configuration files
+        let strict = config.secrets.strict_permissions.unwrap_or(false);
+        resolve_secret_files(
+            "/plugins",
+            &mut config.plugins.values,
+            config_dir(path),
+            strict,
+            &mut config.secret_pointers,
+        )?;
         Ok(config)
     }
 
@@ -706,29 +833,70 @@ impl Config {
                 if let Err(e) = f.read_to_string(&mut content) {
                     bail!(e)
                 }
-                match path
+                let mut json = match path
                     .extension()
                     .map(|s| s.to_str().unwrap())
                 {
-                    Some("json") | Some("json5") => match json5::Deserializer::from_str(&content) {
-                        Ok(mut d) => Config::from_deserializer(&mut d).map_err(|e| match e {
-                            Ok(c) => zerror!("Invalid configuration: {}", c).into(),
-                            Err(e) => zerror!("JSON error: {}", e).into(),
-                        }),
-                        Err(e) => bail!(e),
-                    },
-                    Some("yaml") | Some("yml") => Config::from_deserializer(serde_yaml::Deserializer::from_str(&content)).map_err(|e| match e {
-                        Ok(c) => zerror!("Invalid configuration: {}", c).into(),
-                        Err(e) => zerror!("YAML error: {}", e).into(),
-                    }),
+                    Some("json") | Some("json5") => true,
+                    Some("yaml") | Some("yml") => false,
                     Some(other) => bail!("Unsupported file type '.{}' (.json, .json5 and .yaml are supported)", other),
                     None => bail!("Unsupported file type. Configuration files must have an extension (.json, .json5 and .yaml supported)")
-                }
+                };
+                let secret_pointers = Self::resolve_secret_files(path, &mut content, &mut json)?;
+                let mut config = if json {
+                    match json5::Deserializer::from_str(&content) {
+                        Ok(mut d) => Config::from_deserializer(&mut d).map_err(|e| match e {
+                            Ok(c) => zerror!("Invalid configuration: {}", c),
+                            Err(e) => zerror!("JSON error: {}", e),
+                        })?,
+                        Err(e) => bail!(e),
+                    }
+                } else {
+                    Config::from_deserializer(serde_yaml::Deserializer::from_str(&content))
+                        .map_err(|e| match e {
+                            Ok(c) => zerror!("Invalid configuration: {}", c),
+                            Err(e) => zerror!("YAML error: {}", e),
+                        })?
+                };
+                config.secret_pointers = secret_pointers;
+                Ok(config)
             }
             Err(e) => bail!(e),
         }
     }
 
+    /// Replace the secret files references of the configuration file `content` by their content.
+    ///
+    /// If any, `content` is rewritten as JSON.
+    fn resolve_secret_files(
+        path: &Path,
+        content: &mut String,
+        json: &mut bool,
+    ) -> ZResult<Vec<String>> {
+        let mut value: Value = if *json {
+            json5::from_str(content).map_err(|e| zerror!("JSON error: {}", e))?
+        } else {
+            serde_yaml::from_str(content).map_err(|e| zerror!("YAML error: {}", e))?
+        };
+        let strict = value
+            .pointer("/secrets/strict_permissions")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let mut secret_pointers = vec![];
+        resolve_secret_files(
+            "",
+            &mut value,
+            config_dir(path),
+            strict,
+            &mut secret_pointers,
+        )?;
+        if !secret_pointers.is_empty() {
+            *content = serde_json::to_string(&value)?;
+            *json = true;
+        }
+        Ok(secret_pointers)
+    }
+
     pub fn libloader(&self) -> LibLoader {
         if self.plugins_loading.enabled {
             match self.plugins_loading.search_dirs() {
@@ -741,11 +909,23 @@ impl Config {
     }
 }
 
+/// The replacement of the values read from secret files in the configuration dumps.
+pub const REDACTED: &str = "[REDACTED]";
+
+fn config_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new("."))
+}
+
 impl std::fmt::Display for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         serde_json::to_value(self)
             .map(|mut json| {
                 sift_privates(&mut json);
+                for pointer in &self.secret_pointers {
+                    if let Some(secret) = json.pointer_mut(pointer) {
+                        *secret = Value::String(REDACTED.to_string());
+                    }
+                }
                 write!(f, "{json}")
             })
             .map_err(|e| {
@@ -767,6 +947,77 @@ fn config_from_json() {
     println!("{}", serde_json::to_string_pretty(&config).unwrap());
 }
 
+#[cfg(test)]
+fn write_test_file(name: &str, content: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("zenoh-config-secrets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn config_secret_files() {
+    let secret = write_test_file("password", "s3cr3t\n");
+    let path = write_test_file(
+        "secret_file.json5",
+        r#"{
+            transport: { auth: { usrpwd: { user: "user", password: { __file__: "password" } } } },
+            plugins: { test: { token: { __file__: "password" } } },
+        }"#,
+    );
+    let config = Config::from_file(&path).unwrap();
+    assert_eq!(
+        config.transport.auth.usrpwd.password().as_deref(),
+        Some("s3cr3t")
+    );
+    assert_eq!(config.plugin("test").unwrap()["token"], "s3cr3t");
+    let dump = config.to_string();
+    assert!(!dump.contains("s3cr3t"));
+    assert!(dump.contains(REDACTED));
+
+    // Only one trailing newline is stripped
+    std::fs::write(secret, "s3cr3t\r\n\n").unwrap();
+    let config = Config::from_file(&path).unwrap();
+    assert_eq!(
+        config.transport.auth.usrpwd.password().as_deref(),
+        Some("s3cr3t\r\n")
+    );
+
+    let path = write_test_file(
+        "missing_secret_file.json5",
+        r#"{ transport: { auth: { usrpwd: { user: "user", password: { __file__: "missing" } } } } }"#,
+    );
+    let e = Config::from_file(path).unwrap_err().to_string();
+    assert!(e.contains("/transport/auth/usrpwd/password"));
+    assert!(e.contains("failed to read secret file 'missing'"));
+}
+
+#[cfg(unix)]
+#[test]
+fn config_secret_files_strict_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let secret = write_test_file("strict_password", "s3cr3t");
+    let path = write_test_file(
+        "strict_secret_file.json5",
+        r#"{
+            secrets: { strict_permissions: true },
+            transport: { auth: { usrpwd: { user: "user", password: { __file__: "strict_password" } } } },
+        }"#,
+    );
+    std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o644)).unwrap();
+    let e = Config::from_file(&path).unwrap_err().to_string();
+    assert!(e.contains("readable by group or others"));
+
+    std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o600)).unwrap();
+    let config = Config::from_file(&path).unwrap();
+    assert_eq!(
+        config.transport.auth.usrpwd.password().as_deref(),
+        Some("s3cr3t")
+    );
+}
+
 pub type Notification = Arc<str>;
 
 struct NotifierInner<T> {
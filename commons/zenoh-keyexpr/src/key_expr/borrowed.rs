@@ -588,31 +588,110 @@ enum KeyExprConstructionError {
     ContainsUnboundDollar = -8,
 }
 
+/// Why a string is not a valid key expression, see [`KeyExprError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyExprErrorKind {
+    /// An empty chunk, including the ones implied by leading and trailing slashes.
+    EmptyChunk,
+    /// A `$*` chunk, which must be replaced by `*` to reach canon-form.
+    LoneDollarStar,
+    /// A `**` chunk following `**`, which must be replaced by `**` to reach canon-form.
+    DoubleStarAfterDoubleStar,
+    /// A `*` chunk following `**`, which must be replaced by `*/**` to reach canon-form.
+    SingleStarAfterDoubleStar,
+    /// A `*` adjacent to other characters of its chunk, without being part of a `$*`.
+    WildcardAdjacent,
+    /// A `$` following a `$*`.
+    DollarAfterDollarStar,
+    /// A `$` that is not part of a `$*`.
+    UnboundDollar,
+    /// A forbidden character, such as `#` or `?`.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for KeyExprErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyExprErrorKind::EmptyChunk => f.write_str("empty chunk"),
+            KeyExprErrorKind::LoneDollarStar => f.write_str("lone `$*`"),
+            KeyExprErrorKind::DoubleStarAfterDoubleStar => f.write_str("`**` after `**`"),
+            KeyExprErrorKind::SingleStarAfterDoubleStar => f.write_str("`*` after `**`"),
+            KeyExprErrorKind::WildcardAdjacent => f.write_str("`*` adjacent to other characters"),
+            KeyExprErrorKind::DollarAfterDollarStar => f.write_str("`$` after `$*`"),
+            KeyExprErrorKind::UnboundDollar => f.write_str("`$` outside of `$*`"),
+            KeyExprErrorKind::InvalidCharacter(c) => write!(f, "forbidden character `{c}`"),
+        }
+    }
+}
+
+/// The location and the kind of the problem making a string an invalid key expression.
+///
+/// It is the source of the errors returned by the key expression constructors, and of the
+/// errors they cause when subscribing, publishing or querying with invalid key expressions:
+/// use [`KeyExprError::find`] to retrieve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyExprError {
+    /// The offset, in bytes, of the problem in the string.
+    pub offset: usize,
+    /// The kind of the problem.
+    pub kind: KeyExprErrorKind,
+}
+
+impl KeyExprError {
+    /// Finds the [`KeyExprError`] among `error` and its sources, if any.
+    #[cfg(feature = "std")]
+    pub fn find<'a>(mut error: &'a (dyn std::error::Error + 'static)) -> Option<&'a KeyExprError> {
+        loop {
+            if let Some(e) = error.downcast_ref::<KeyExprError>() {
+                return Some(e);
+            }
+            error = error.source()?;
+        }
+    }
+}
+
+impl fmt::Display for KeyExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl zenoh_result::IError for KeyExprError {}
+
 impl<'a> TryFrom<&'a str> for &'a keyexpr {
     type Error = ZError;
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let error = |offset, kind| KeyExprError { offset, kind };
         let mut in_big_wild = false;
+        let mut offset = 0;
         for chunk in value.split('/') {
+            let chunk_offset = offset;
+            offset += chunk.len() + 1;
             if chunk.is_empty() {
-                bail!((KeyExprConstructionError::EmptyChunk) "Invalid Key Expr `{}`: empty chunks are forbidden, as well as leading and trailing slashes", value)
+                bail!((KeyExprConstructionError::EmptyChunk) error(chunk_offset, KeyExprErrorKind::EmptyChunk) => "Invalid Key Expr `{}`: empty chunks are forbidden, as well as leading and trailing slashes", value)
             }
             if chunk == "$*" {
-                bail!((KeyExprConstructionError::LoneDollarStar)
+                bail!((KeyExprConstructionError::LoneDollarStar) error(chunk_offset, KeyExprErrorKind::LoneDollarStar) =>
                     "Invalid Key Expr `{}`: lone `$*`s must be replaced by `*` to reach canon-form",
                     value
                 )
             }
             if in_big_wild {
                 match chunk {
-                    "**" => bail!((KeyExprConstructionError::DoubleStarAfterDoubleStar)
-                        "Invalid Key Expr `{}`: `**/**` must be replaced by `**` to reach canon-form",
-                        value
-                    ),
-                    "*" => bail!((KeyExprConstructionError::SingleStarAfterDoubleStar)
-                        "Invalid Key Expr `{}`: `**/*` must be replaced by `*/**` to reach canon-form",
-                        value
-                    ),
+                    "**" => {
+                        bail!((KeyExprConstructionError::DoubleStarAfterDoubleStar) error(chunk_offset, KeyExprErrorKind::DoubleStarAfterDoubleStar) =>
+                            "Invalid Key Expr `{}`: `**/**` must be replaced by `**` to reach canon-form",
+                            value
+                        )
+                    }
+                    "*" => {
+                        bail!((KeyExprConstructionError::SingleStarAfterDoubleStar) error(chunk_offset, KeyExprErrorKind::SingleStarAfterDoubleStar) =>
+                            "Invalid Key Expr `{}`: `**/*` must be replaced by `*/**` to reach canon-form",
+                            value
+                        )
+                    }
                     _ => {}
                 }
             }
@@ -621,10 +700,12 @@ impl<'a> TryFrom<&'a str> for &'a keyexpr {
             } else {
                 in_big_wild = false;
                 if chunk != "*" {
-                    let mut split = chunk.split('*');
-                    split.next_back();
-                    if split.any(|s| !s.ends_with('$')) {
-                        bail!((KeyExprConstructionError::StarsInChunk)
+                    // Each `*` must be the end of a `$*`
+                    let bytes = chunk.as_bytes();
+                    if let Some(star) = (0..bytes.len())
+                        .find(|&i| bytes[i] == b'*' && (i == 0 || bytes[i - 1] != b'$'))
+                    {
+                        bail!((KeyExprConstructionError::StarsInChunk) error(chunk_offset + star, KeyExprErrorKind::WildcardAdjacent) =>
                             "Invalid Key Expr `{}`: `*` and `**` may only be preceded an followed by `/`",
                             value
                         )
@@ -644,16 +725,16 @@ impl<'a> TryFrom<&'a str> for &'a keyexpr {
             if forbidden == b'$' {
                 if let Some(b'*') = bytes.get(index + 1) {
                     if let Some(b'$') = bytes.get(index + 2) {
-                        bail!((KeyExprConstructionError::DollarAfterDollarOrStar)
+                        bail!((KeyExprConstructionError::DollarAfterDollarOrStar) error(index + 2, KeyExprErrorKind::DollarAfterDollarStar) =>
                             "Invalid Key Expr `{}`: `$` is not allowed after `$*`",
                             value
                         )
                     }
                 } else {
-                    bail!((KeyExprConstructionError::ContainsUnboundDollar)"Invalid Key Expr `{}`: `$` is only allowed in `$*`", value)
+                    bail!((KeyExprConstructionError::ContainsUnboundDollar) error(index, KeyExprErrorKind::UnboundDollar) => "Invalid Key Expr `{}`: `$` is only allowed in `$*`", value)
                 }
             } else {
-                bail!((KeyExprConstructionError::ContainsSharpOrQMark)
+                bail!((KeyExprConstructionError::ContainsSharpOrQMark) error(index, KeyExprErrorKind::InvalidCharacter(forbidden as char)) =>
                     "Invalid Key Expr `{}`: `#` and `?` are forbidden characters",
                     value
                 )
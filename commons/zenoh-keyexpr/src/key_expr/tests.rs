@@ -12,7 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use crate::key_expr::{fuzzer, intersect::*, keyexpr};
+use crate::key_expr::{fuzzer, intersect::*, keyexpr, KeyExprError, KeyExprErrorKind};
 use std::{convert::TryInto, fmt::Debug};
 
 type BoxedIntersectors = Vec<Box<dyn for<'a> Intersector<&'a keyexpr, &'a keyexpr> + Send + Sync>>;
@@ -200,3 +200,31 @@ fn fuzz() {
         ke1 = ke2;
     }
 }
+
+#[test]
+fn construction_errors() {
+    use KeyExprErrorKind::*;
+    for (value, offset, kind) in [
+        ("", 0, EmptyChunk),
+        ("/a", 0, EmptyChunk),
+        ("a/", 2, EmptyChunk),
+        ("a//b", 2, EmptyChunk),
+        ("a/$*", 2, LoneDollarStar),
+        ("a/**/**", 5, DoubleStarAfterDoubleStar),
+        ("a/**/*/b", 5, SingleStarAfterDoubleStar),
+        ("foo/**/**bar", 7, WildcardAdjacent),
+        ("foo/ba*r", 6, WildcardAdjacent),
+        ("foo/bar*", 7, WildcardAdjacent),
+        ("a/b$*$c", 5, DollarAfterDollarStar),
+        ("a/b$c", 3, UnboundDollar),
+        ("a/b#", 3, InvalidCharacter('#')),
+        ("abc/d?e=f", 5, InvalidCharacter('?')),
+    ] {
+        let error = <&keyexpr>::try_from(value).unwrap_err();
+        assert_eq!(
+            KeyExprError::find(&*error),
+            Some(&KeyExprError { offset, kind }),
+            "{value}"
+        );
+    }
+}
@@ -42,6 +42,35 @@
 //!
 //! [`kedefine`] also allows you to define formats at compile time, allowing a more performant, but more importantly safer and more convenient use of said formats,
 //! as the [`keformat`] and [`kewrite`] macros will be able to tell you if you're attempting to set fields of the format that do not exist.
+//!
+//! # Standalone usage
+//! This crate implements the exact matching semantics used by Zenoh, which re-exports it as `zenoh::key_expr`.
+//! Tools that only need these semantics (linters, ACL editors...) can depend on it alone: it has no async runtime
+//! nor transport dependencies, and builds in `no_std` environments with `default-features = false`.
+//! ```
+//! use zenoh_keyexpr::keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut, KeBoxTree};
+//! use zenoh_keyexpr::{keyexpr, OwnedKeyExpr, SetIntersectionLevel};
+//!
+//! // Validation and canonicalization
+//! assert!(keyexpr::new("robot/*/pose").is_ok());
+//! assert!(keyexpr::new("robot/**/**").is_err());
+//! let canon = OwnedKeyExpr::autocanonize(String::from("robot/**/**")).unwrap();
+//! assert_eq!(canon.as_str(), "robot/**");
+//!
+//! // Intersection and inclusion
+//! let poses = keyexpr::new("robot/*/pose").unwrap();
+//! let robot = keyexpr::new("robot/1/pose").unwrap();
+//! assert!(poses.intersects(robot));
+//! assert!(poses.includes(robot));
+//! assert_eq!(poses.relation_to(robot), SetIntersectionLevel::Includes);
+//!
+//! // Storing values tied to key expressions
+//! let mut tree = KeBoxTree::new();
+//! tree.insert(robot, "pose of robot 1");
+//! tree.insert(keyexpr::new("robot/1/status").unwrap(), "status of robot 1");
+//! let matching: Vec<_> = tree.intersecting_keys(poses).collect();
+//! assert_eq!(matching, [robot.to_owned()]);
+//! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
 extern crate alloc;
@@ -0,0 +1,88 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh_keyexpr::keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut, KeBoxTree};
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr, SetIntersectionLevel};
+
+fn ke(s: &str) -> &keyexpr {
+    keyexpr::new(s).unwrap()
+}
+
+#[test]
+fn validation() {
+    for valid in ["a", "a/b", "a/*/c", "a/**", "a/b$*", "@a/b"] {
+        assert!(keyexpr::new(valid).is_ok(), "`{valid}` should be valid");
+    }
+    for invalid in [
+        "", "/a", "a/", "a//b", "a/**/**", "a/**/*", "a?b", "a#b", "a/$",
+    ] {
+        assert!(
+            keyexpr::new(invalid).is_err(),
+            "`{invalid}` should be invalid"
+        );
+    }
+}
+
+#[test]
+fn canonicalization() {
+    for (raw, canon) in [
+        ("a/**/**", "a/**"),
+        ("a/**/*", "a/*/**"),
+        ("a/$*", "a/*"),
+        ("a/b$*$*", "a/b$*"),
+    ] {
+        let owned = OwnedKeyExpr::autocanonize(String::from(raw)).unwrap();
+        assert_eq!(owned.as_str(), canon);
+    }
+}
+
+#[test]
+fn relations() {
+    for (l, r, level) in [
+        ("a/b", "a/b", SetIntersectionLevel::Equals),
+        ("a/*", "a/b", SetIntersectionLevel::Includes),
+        ("a/**", "a/b/c", SetIntersectionLevel::Includes),
+        ("a/*/c", "a/b/*", SetIntersectionLevel::Intersects),
+        ("a/b", "a/c", SetIntersectionLevel::Disjoint),
+        ("a/*", "a/b/c", SetIntersectionLevel::Disjoint),
+    ] {
+        let (l, r) = (ke(l), ke(r));
+        assert_eq!(l.relation_to(r), level, "{l} vs {r}");
+        assert_eq!(l.intersects(r), level >= SetIntersectionLevel::Intersects);
+        assert_eq!(r.intersects(l), l.intersects(r));
+        assert_eq!(l.includes(r), level >= SetIntersectionLevel::Includes);
+    }
+}
+
+/// The keys yielded by `keys`, sorted.
+fn sorted(keys: impl Iterator<Item = OwnedKeyExpr>) -> Vec<String> {
+    let mut keys: Vec<_> = keys.map(|key| key.to_string()).collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn ketree_matching() {
+    let mut tree = KeBoxTree::new();
+    for key in ["a/b", "a/b/c", "a/*/d", "e/**"] {
+        tree.insert(ke(key), key);
+    }
+    assert_eq!(sorted(tree.intersecting_keys(ke("a/*"))), ["a/b"]);
+    assert_eq!(sorted(tree.intersecting_keys(ke("a/x/d"))), ["a/*/d"]);
+    assert_eq!(
+        sorted(tree.included_keys(ke("a/**"))),
+        ["a/*/d", "a/b", "a/b/c"]
+    );
+    assert_eq!(sorted(tree.keys_including(ke("e/f/g"))), ["e/**"]);
+    assert_eq!(tree.weight_at(ke("a/b/c")), Some(&"a/b/c"));
+}
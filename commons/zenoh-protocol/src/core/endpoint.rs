@@ -616,6 +616,33 @@ impl TryFrom<String> for EndPoint {
         const ERR: &str =
             "Endpoints must be of the form <protocol>/<address>[?<metadata>][#<config>]";
 
+        // Rejects the `<key>[=<value>]` pairs of the list at `s[start..end]` that
+        // `Properties::parse_strict` reports, instead of mis-splitting them.
+        #[cfg(feature = "std")]
+        fn check_list(s: &str, start: usize, end: usize) -> ZResult<()> {
+            use zenoh_collections::{Properties, PropertiesParseIssue};
+
+            if let Err(issues) = Properties::parse_strict(&s[start..end]) {
+                let issues = issues
+                    .into_iter()
+                    .map(|issue| {
+                        PropertiesParseIssue {
+                            offset: start + issue.offset,
+                            ..issue
+                        }
+                        .to_string()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                bail!("Invalid endpoint `{}`: {}", s, issues);
+            }
+            Ok(())
+        }
+        #[cfg(not(feature = "std"))]
+        fn check_list(_s: &str, _start: usize, _end: usize) -> ZResult<()> {
+            Ok(())
+        }
+
         fn sort_hashmap(from: &str, into: &mut String) {
             let mut from = from
                 .split(LIST_SEPARATOR)
@@ -647,6 +674,7 @@ impl TryFrom<String> for EndPoint {
             (None, None) => Ok(EndPoint { inner: s }),
             // There is some metadata
             (Some(midx), None) if midx > pidx && !s[midx + 1..].is_empty() => {
+                check_list(&s, midx + 1, s.len())?;
                 let mut inner = String::with_capacity(s.len());
                 inner.push_str(&s[..midx + 1]); // Includes metadata separator
                 sort_hashmap(&s[midx + 1..], &mut inner);
@@ -654,6 +682,7 @@ impl TryFrom<String> for EndPoint {
             }
             // There is some config
             (None, Some(cidx)) if cidx > pidx && !s[cidx + 1..].is_empty() => {
+                check_list(&s, cidx + 1, s.len())?;
                 let mut inner = String::with_capacity(s.len());
                 inner.push_str(&s[..cidx + 1]); // Includes config separator
                 sort_hashmap(&s[cidx + 1..], &mut inner);
@@ -666,6 +695,8 @@ impl TryFrom<String> for EndPoint {
                     && !s[midx + 1..cidx].is_empty()
                     && !s[cidx + 1..].is_empty() =>
             {
+                check_list(&s, midx + 1, cidx)?;
+                check_list(&s, cidx + 1, s.len())?;
                 let mut inner = String::with_capacity(s.len());
                 inner.push_str(&s[..midx + 1]); // Includes metadata separator
 
@@ -766,6 +797,17 @@ fn endpoints() {
     assert!(EndPoint::from_str("udp#127.0.0.1:7447/?").is_err());
     assert!(EndPoint::from_str("udp/127.0.0.1:7447?a=1#").is_err());
 
+    // The malformed metadata and config are reported with their offset
+    let err = |s: &str| EndPoint::from_str(s).unwrap_err().to_string();
+    assert!(err("udp/127.0.0.1:7447?a=1;;b=2").contains("bare separator at byte 23"));
+    assert!(err("udp/127.0.0.1:7447#;A=1").contains("bare separator at byte 19"));
+    assert!(err("udp/127.0.0.1:7447?a=1#=1").contains("empty key at byte 23"));
+    assert!(err("udp/127.0.0.1:7447?a=1#A=1;B#=2").contains("reserved character '#' at byte 28"));
+    EndPoint::from_str("tls/127.0.0.1:7447#root_ca_certificate_base64=YQ==").unwrap();
+    // A trailing separator is accepted
+    EndPoint::from_str("udp/127.0.0.1:7447?a=1;").unwrap();
+    EndPoint::from_str("udp/127.0.0.1:7447?a=1;#A=1;").unwrap();
+
     let endpoint = EndPoint::from_str("udp/127.0.0.1:7447").unwrap();
     assert_eq!(endpoint.as_str(), "udp/127.0.0.1:7447");
     assert_eq!(endpoint.protocol().as_str(), "udp");
@@ -345,7 +345,12 @@ impl TryFrom<u8> for Priority {
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+/// The reliability of a subscription.
+///
+/// Its string form, used by [`Display`](fmt::Display), [`FromStr`] and serde,
+/// is `"best_effort"` or `"reliable"`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum Reliability {
     #[default]
@@ -354,6 +359,13 @@ pub enum Reliability {
 }
 
 impl Reliability {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Reliability::BestEffort => "best_effort",
+            Reliability::Reliable => "reliable",
+        }
+    }
+
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
         use rand::Rng;
@@ -368,6 +380,27 @@ impl Reliability {
     }
 }
 
+impl fmt::Display for Reliability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Reliability {
+    type Err = zenoh_result::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "best_effort" => Ok(Reliability::BestEffort),
+            "reliable" => Ok(Reliability::Reliable),
+            _ => bail!(
+                "Invalid reliability: {} - admitted values are: best_effort, reliable",
+                s
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub struct Channel {
     pub priority: Priority,
@@ -384,7 +417,11 @@ pub enum CongestionControl {
 }
 
 /// The subscription mode.
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+///
+/// Its string form, used by [`Display`](fmt::Display), [`FromStr`] and serde,
+/// is `"push"` or `"pull"`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum SubMode {
     #[default]
@@ -392,7 +429,42 @@ pub enum SubMode {
     Pull = 1,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+impl SubMode {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            SubMode::Push => "push",
+            SubMode::Pull => "pull",
+        }
+    }
+}
+
+impl fmt::Display for SubMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SubMode {
+    type Err = zenoh_result::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "push" => Ok(SubMode::Push),
+            "pull" => Ok(SubMode::Pull),
+            _ => bail!(
+                "Invalid subscription mode: {} - admitted values are: push, pull",
+                s
+            ),
+        }
+    }
+}
+
+/// The information of a subscription.
+///
+/// It is serialized as `{"reliability": "best_effort" | "reliable", "mode": "push" | "pull"}`,
+/// both fields defaulting to their default value when missing.
+#[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SubInfo {
     pub reliability: Reliability,
     pub mode: SubMode,
@@ -405,6 +477,9 @@ pub struct QueryableInfo {
 }
 
 /// The kind of consolidation.
+///
+/// Samples without timestamp are never considered more recent than another sample: with `Monotonic`
+/// and `Latest`, the first one received for a key is kept unless a timestamped one follows.
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum ConsolidationMode {
     /// No consolidation applied: multiple samples may be received for the same key-timestamp.
@@ -431,3 +506,56 @@ pub enum QueryTarget {
     #[cfg(feature = "complete_n")]
     Complete(u64),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reliability_string_form() {
+        for reliability in [Reliability::BestEffort, Reliability::Reliable] {
+            let s = reliability.to_string();
+            assert_eq!(s.parse::<Reliability>().unwrap(), reliability);
+            let json = serde_json::to_string(&reliability).unwrap();
+            assert_eq!(json, format!("\"{s}\""));
+            assert_eq!(
+                serde_json::from_str::<Reliability>(&json).unwrap(),
+                reliability
+            );
+        }
+        assert!("BestEffort".parse::<Reliability>().is_err());
+        assert!(serde_json::from_str::<Reliability>("\"unknown\"").is_err());
+    }
+
+    #[test]
+    fn sub_mode_string_form() {
+        for mode in [SubMode::Push, SubMode::Pull] {
+            let s = mode.to_string();
+            assert_eq!(s.parse::<SubMode>().unwrap(), mode);
+            let json = serde_json::to_string(&mode).unwrap();
+            assert_eq!(json, format!("\"{s}\""));
+            assert_eq!(serde_json::from_str::<SubMode>(&json).unwrap(), mode);
+        }
+        assert!("Push".parse::<SubMode>().is_err());
+    }
+
+    #[test]
+    fn sub_info_json_form() {
+        for reliability in [Reliability::BestEffort, Reliability::Reliable] {
+            for mode in [SubMode::Push, SubMode::Pull] {
+                let info = SubInfo { reliability, mode };
+                let json = serde_json::to_string(&info).unwrap();
+                assert_eq!(
+                    json,
+                    format!(r#"{{"reliability":"{reliability}","mode":"{mode}"}}"#)
+                );
+                assert_eq!(serde_json::from_str::<SubInfo>(&json).unwrap(), info);
+            }
+        }
+        assert_eq!(
+            serde_json::from_str::<SubInfo>("{}").unwrap(),
+            SubInfo::default()
+        );
+        assert!(serde_json::from_str::<SubInfo>(r#"{"period":1}"#).is_err());
+    }
+}
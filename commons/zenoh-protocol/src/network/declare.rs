@@ -17,13 +17,14 @@ use crate::{
     network::Mapping,
     zextz64, zextzbuf,
 };
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, vec::Vec};
 use core::ops::BitOr;
 pub use interest::*;
 pub use keyexpr::*;
 pub use queryable::*;
 pub use subscriber::*;
 pub use token::*;
+use zenoh_buffers::{buffer::Buffer, ZBuf};
 
 pub mod flag {
     // pub const X: u8 = 1 << 5; // 0x20 Reserved
@@ -323,6 +324,7 @@ pub mod subscriber {
         pub id: SubscriberId,
         pub wire_expr: WireExpr<'static>,
         pub ext_info: ext::SubscriberInfo,
+        pub ext_payload_filter: Option<ext::PayloadFilterType>,
     }
 
     pub mod ext {
@@ -391,6 +393,102 @@ pub mod subscriber {
                 Info::new(v)
             }
         }
+
+        /// Experimental: the subscription only accepts the payloads matching one of its prefixes.
+        /// The publishers may use it to avoid sending the other payloads, the routers and peers
+        /// that don't support it ignore it.
+        pub type PayloadFilter = zextzbuf!(0x02, false);
+
+        /// # The payload filter of the subscription.
+        ///
+        /// ```text
+        ///  7 6 5 4 3 2 1 0
+        /// +-+-+-+-+-+-+-+-+
+        /// |Z|1_0|    ID   |
+        /// +-+-+-+---------+
+        /// %   length:z32  %
+        /// +---------------+
+        /// %   count:z32   %
+        /// +---------------+
+        /// ~  [prefixes]   ~  count times
+        /// +---------------+
+        ///
+        /// prefix:
+        /// +-+-+-+-+-+-+-+-+
+        /// %  offset:z64   %
+        /// +---------------+
+        /// ~ bytes:<u8;z32>~
+        /// +---------------+
+        /// ```
+        #[derive(Debug, Default, Clone, PartialEq, Eq)]
+        pub struct PayloadFilterType {
+            pub prefixes: Vec<PayloadPrefix>,
+        }
+
+        impl PayloadFilterType {
+            /// Returns true if `payload` matches any of the prefixes of this filter.
+            pub fn matches(&self, payload: &ZBuf) -> bool {
+                self.prefixes.iter().any(|prefix| prefix.matches(payload))
+            }
+
+            #[cfg(feature = "test")]
+            pub fn rand() -> Self {
+                use rand::Rng;
+                let mut rng = rand::thread_rng();
+
+                let prefixes = (0..rng.gen_range(1..4))
+                    .map(|_| PayloadPrefix::rand())
+                    .collect();
+                Self { prefixes }
+            }
+        }
+
+        /// The bytes expected at a given offset of a payload.
+        #[derive(Debug, Default, Clone, PartialEq, Eq)]
+        pub struct PayloadPrefix {
+            pub offset: u64,
+            pub bytes: Vec<u8>,
+        }
+
+        impl PayloadPrefix {
+            /// Returns true if `payload` contains the bytes of this prefix at its offset.
+            pub fn matches(&self, payload: &ZBuf) -> bool {
+                let end = match usize::try_from(self.offset)
+                    .ok()
+                    .and_then(|offset| offset.checked_add(self.bytes.len()))
+                {
+                    Some(end) if end <= payload.len() => end,
+                    _ => return false,
+                };
+                let start = end - self.bytes.len();
+                // Compare the bytes covered by the prefix in each slice, without copying them
+                let mut pos = 0;
+                for slice in payload.zslices() {
+                    let from = start.max(pos);
+                    let to = end.min(pos + slice.len());
+                    if from < to
+                        && slice[from - pos..to - pos] != self.bytes[from - start..to - start]
+                    {
+                        return false;
+                    }
+                    pos += slice.len();
+                    if pos >= end {
+                        break;
+                    }
+                }
+                true
+            }
+
+            #[cfg(feature = "test")]
+            pub fn rand() -> Self {
+                use rand::Rng;
+                let mut rng = rand::thread_rng();
+
+                let offset = rng.gen_range(0..16);
+                let bytes = (0..rng.gen_range(0..8)).map(|_| rng.gen()).collect();
+                Self { offset, bytes }
+            }
+        }
     }
 
     impl DeclareSubscriber {
@@ -402,11 +500,13 @@ pub mod subscriber {
             let id: SubscriberId = rng.gen();
             let wire_expr = WireExpr::rand();
             let ext_info = ext::SubscriberInfo::rand();
+            let ext_payload_filter = rng.gen_bool(0.5).then(ext::PayloadFilterType::rand);
 
             Self {
                 id,
                 wire_expr,
                 ext_info,
+                ext_payload_filter,
             }
         }
     }
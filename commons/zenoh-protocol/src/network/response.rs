@@ -68,6 +68,10 @@ pub mod ext {
 
     pub type ResponderId = zextzbuf!(0x3, false);
     pub type ResponderIdType = crate::network::ext::EntityIdType<{ ResponderId::ID }>;
+
+    // The number of samples served by a pull bounded with a budget
+    pub type Served = zextz64!(0x4, false);
+    pub type ServedType = u32;
 }
 
 impl Response {
@@ -118,6 +122,7 @@ pub struct ResponseFinal {
     pub rid: RequestId,
     pub ext_qos: ext::QoSType,
     pub ext_tstamp: Option<ext::TimestampType>,
+    pub ext_served: Option<ext::ServedType>,
 }
 
 impl ResponseFinal {
@@ -129,11 +134,13 @@ impl ResponseFinal {
         let rid: RequestId = rng.gen();
         let ext_qos = ext::QoSType::rand();
         let ext_tstamp = rng.gen_bool(0.5).then(ext::TimestampType::rand);
+        let ext_served = rng.gen_bool(0.5).then(|| rng.gen());
 
         Self {
             rid,
             ext_qos,
             ext_tstamp,
+            ext_served,
         }
     }
 }
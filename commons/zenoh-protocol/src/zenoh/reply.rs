@@ -11,7 +11,10 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{common::ZExtUnknown, core::Encoding};
+use crate::{
+    common::ZExtUnknown,
+    core::{Encoding, SampleKind},
+};
 use alloc::vec::Vec;
 use uhlc::Timestamp;
 use zenoh_buffers::ZBuf;
@@ -52,6 +55,7 @@ pub struct Reply {
     #[cfg(feature = "shared-memory")]
     pub ext_shm: Option<ext::ShmType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_kind: SampleKind,
     pub ext_unknown: Vec<ZExtUnknown>,
     pub payload: ZBuf,
 }
@@ -83,6 +87,10 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x4, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # Sample kind extension
+    /// Used to carry the kind of the replied sample, `Put` if absent
+    pub type Kind = zextz64!(0x5, false);
 }
 
 impl Reply {
@@ -103,12 +111,14 @@ impl Reply {
         #[cfg(feature = "shared-memory")]
         let ext_shm = rng.gen_bool(0.5).then_some(ext::ShmType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_kind = if rng.gen_bool(0.5) {
+            SampleKind::Put
+        } else {
+            SampleKind::Delete
+        };
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
-            ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
-                false,
-            ));
+            ext_unknown.push(ZExtUnknown::rand2(iext::mid(ext::Kind::ID) + 1, false));
         }
         let payload = ZBuf::rand(rng.gen_range(1..=64));
 
@@ -120,6 +130,7 @@ impl Reply {
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_kind,
             ext_unknown,
             payload,
         }
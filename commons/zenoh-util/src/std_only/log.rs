@@ -11,7 +11,11 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use tracing_subscriber::EnvFilter;
+use std::sync::OnceLock;
+use tracing_subscriber::{layer::SubscriberExt, reload, EnvFilter, Registry};
+use zenoh_result::{zerror, ZResult};
+
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
 
 /// This is an utility function to enable the tracing formatting subscriber from
 /// the `RUST_LOG` environment variable. If `RUST_LOG` is not set, then logging is not enabled.
@@ -44,17 +48,51 @@ where
 }
 
 fn init_env_filter(env_filter: EnvFilter) {
-    let subscriber = tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
+    let fmt_layer = tracing_subscriber::fmt::Layer::new()
         .with_thread_ids(true)
         .with_thread_names(true)
         .with_level(true)
         .with_target(true);
 
-    let subscriber = subscriber.finish();
+    let subscriber = tracing_subscriber::registry()
+        .with(reloadable_log_filter(env_filter))
+        .with(fmt_layer);
     let _ = tracing::subscriber::set_global_default(subscriber);
 }
 
+/// Wraps the given filter in a layer that can be replaced at runtime with [`set_log_filter`].
+/// It must be the first layer of the [`Registry`].
+///
+/// Only the first reloadable filter created in the process is controlled by [`set_log_filter`].
+pub fn reloadable_log_filter(env_filter: EnvFilter) -> reload::Layer<EnvFilter, Registry> {
+    let (layer, handle) = reload::Layer::new(env_filter);
+    let _ = LOG_FILTER.set(handle);
+    layer
+}
+
+/// Replaces the filter of the logs with the given directives, e.g. `"info,zenoh::net::routing=debug"`.
+/// Invalid directives are rejected and leave the current filter untouched.
+///
+/// This fails if the logs were not initialized with a [`reloadable_log_filter`],
+/// which the initialization functions of this module do.
+pub fn set_log_filter(directives: &str) -> ZResult<()> {
+    let handle = LOG_FILTER
+        .get()
+        .ok_or_else(|| zerror!("The logs were not initialized with a reloadable filter"))?;
+    let env_filter = EnvFilter::try_new(directives)
+        .map_err(|e| zerror!("Invalid log filter '{}': {}", directives, e))?;
+    handle
+        .reload(env_filter)
+        .map_err(|e| zerror!("Unable to set log filter '{}': {}", directives, e))?;
+    tracing::info!("Log filter set to '{}'", directives);
+    Ok(())
+}
+
+/// Returns the directives of the current log filter, if it can be replaced with [`set_log_filter`].
+pub fn log_filter() -> Option<String> {
+    LOG_FILTER.get()?.with_current(|f| f.to_string()).ok()
+}
+
 #[cfg(feature = "test")]
 // Used to verify memory leaks for valgrind CI.
 // `EnvFilter` internally uses a static reference that is not cleaned up yielding to false positive in valgrind.
@@ -0,0 +1,79 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use zenoh::config::Config;
+use zenoh::prelude::r#async::*;
+use zenoh_examples::CommonArgs;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Measurement {
+    sensor: String,
+    value: f64,
+}
+
+#[tokio::main]
+async fn main() {
+    // initiate logging
+    zenoh_util::try_init_log_from_env();
+
+    let (config, key_expr) = parse_args();
+
+    println!("Opening session...");
+    let session = zenoh::open(config).res().await.unwrap();
+
+    // Register a bincode codec for Measurement
+    let encoding = Encoding::APP_CUSTOM.with_suffix("bincode").unwrap();
+    session.serialization().register::<Measurement>(
+        encoding.clone(),
+        |m| Ok(bincode::serialize(m)?.into()),
+        |v| Ok(bincode::deserialize(&v.payload.contiguous())?),
+    );
+
+    println!("Declaring Subscriber on '{key_expr}'...");
+    let subscriber = session.declare_subscriber(&key_expr).res().await.unwrap();
+
+    let measurement = Measurement {
+        sensor: "thermometer".to_string(),
+        value: 21.5,
+    };
+    let value = session
+        .serialization()
+        .serialize(&measurement, &encoding)
+        .unwrap();
+    println!(
+        "Putting {measurement:?} as {} bytes of '{encoding}'...",
+        value.payload.len()
+    );
+    session.put(&key_expr, value).res().await.unwrap();
+
+    let sample = subscriber.recv_async().await.unwrap();
+    let received: Measurement = session.serialization().deserialize(&sample.value).unwrap();
+    println!(">> Received {received:?} ('{}')", sample.key_expr);
+    assert_eq!(received, measurement);
+}
+
+#[derive(clap::Parser, Clone, PartialEq, Eq, Hash, Debug)]
+struct Args {
+    #[arg(short, long, default_value = "demo/example/zenoh-rs-codec")]
+    /// The key expression to publish onto and subscribe to.
+    key: KeyExpr<'static>,
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+fn parse_args() -> (Config, KeyExpr<'static>) {
+    let args = Args::parse();
+    (args.common.into(), args.key)
+}
@@ -15,6 +15,7 @@ use clap::Parser;
 use std::convert::TryFrom;
 use std::time::Duration;
 use zenoh::config::Config;
+use zenoh::fmt::{render, RenderOptions};
 use zenoh::prelude::r#async::*;
 use zenoh_examples::CommonArgs;
 
@@ -43,7 +44,7 @@ async fn main() {
             Ok(sample) => println!(
                 ">> Received ('{}': '{}')",
                 sample.key_expr.as_str(),
-                sample.value,
+                render(&sample, RenderOptions::default()),
             ),
             Err(err) => println!(">> Received (ERROR: '{}')", String::try_from(&err).unwrap()),
         }
@@ -14,6 +14,7 @@
 use clap::Parser;
 use std::time::Duration;
 use zenoh::config::Config;
+use zenoh::fmt::{render, RenderOptions};
 use zenoh::prelude::r#async::*;
 use zenoh_examples::CommonArgs;
 
@@ -37,7 +38,7 @@ async fn main() {
                 ">> [Subscriber] Received {} ('{}': '{}')",
                 sample.kind,
                 sample.key_expr.as_str(),
-                sample.value,
+                render(&sample, RenderOptions::default()),
             );
         })
         .res()
@@ -13,6 +13,7 @@
 //
 use clap::Parser;
 use zenoh::config::Config;
+use zenoh::fmt::{render, RenderOptions};
 use zenoh::prelude::r#async::*;
 use zenoh_examples::CommonArgs;
 
@@ -41,7 +42,7 @@ async fn main() {
             ">> [Subscriber] Received {} ('{}': '{}')",
             sample.kind,
             sample.key_expr.as_str(),
-            sample.value
+            render(&sample, RenderOptions::default())
         );
     }
 }
@@ -0,0 +1,96 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use clap::Parser;
+use std::time::Duration;
+use zenoh::config::Config;
+use zenoh::fmt::{render, RenderOptions};
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::DynSubscriberHandle;
+use zenoh_examples::CommonArgs;
+
+/// A processing plugin, loaded at runtime and only known through a trait object.
+trait Plugin {
+    fn name(&self) -> &str;
+    fn register(&self, session: &Session, prefix: &KeyExpr) -> ZResult<Vec<DynSubscriberHandle>>;
+}
+
+/// A plugin logging the temperature and humidity samples published under a prefix.
+struct Monitor;
+
+impl Plugin for Monitor {
+    fn name(&self) -> &str {
+        "monitor"
+    }
+
+    fn register(&self, session: &Session, prefix: &KeyExpr) -> ZResult<Vec<DynSubscriberHandle>> {
+        let mut handles = vec![];
+        for measure in ["temperature", "humidity"] {
+            let key_expr = prefix.join(measure)?;
+            let mut count = 0;
+            handles.push(session.subscribe_dyn(
+                &key_expr,
+                Box::new(move |sample| {
+                    count += 1;
+                    println!(
+                        ">> [{measure} #{count}] Received {} ('{}': '{}')",
+                        sample.kind,
+                        sample.key_expr.as_str(),
+                        render(&sample, RenderOptions::default())
+                    );
+                }),
+            )?);
+        }
+        Ok(handles)
+    }
+}
+
+type ZResult<T> = zenoh::Result<T>;
+
+#[tokio::main]
+async fn main() {
+    // Initiate logging
+    zenoh_util::try_init_log_from_env();
+
+    let (config, prefix) = parse_args();
+
+    println!("Opening session...");
+    let session = zenoh::open(config).res().await.unwrap();
+
+    let plugins: Vec<Box<dyn Plugin>> = vec![Box::new(Monitor)];
+    let mut _handles = vec![];
+    for plugin in &plugins {
+        println!("Registering plugin '{}' on '{}'...", plugin.name(), prefix);
+        _handles.extend(plugin.register(&session, &prefix).unwrap());
+    }
+
+    // The subscriptions live as long as their handles
+    println!("Press CTRL-C to quit...");
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[derive(clap::Parser, Clone, PartialEq, Eq, Hash, Debug)]
+struct Args {
+    #[arg(short, long, default_value = "demo/example")]
+    /// The key expression prefix under which the plugins subscribe.
+    prefix: KeyExpr<'static>,
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+fn parse_args() -> (Config, KeyExpr<'static>) {
+    let args = Args::parse();
+    (args.common.into(), args.prefix)
+}
@@ -0,0 +1,59 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use clap::Parser;
+use zenoh::config::Config;
+use zenoh::prelude::sync::*;
+use zenoh_examples::CommonArgs;
+
+fn main() {
+    // Initiate logging
+    zenoh_util::try_init_log_from_env();
+
+    let (config, key_expr) = parse_args();
+
+    println!("Opening session...");
+    let session = zenoh::open(config).res().unwrap();
+
+    println!("Declaring Subscriber on '{}'...", &key_expr);
+    // The samples are received through a channel of the standard library
+    let subscriber = session
+        .declare_subscriber(&key_expr)
+        .with(std::sync::mpsc::sync_channel(256))
+        .res()
+        .unwrap();
+
+    println!("Press CTRL-C to quit...");
+    for sample in subscriber.iter() {
+        println!(
+            ">> [Subscriber] Received {} ('{}': '{}')",
+            sample.kind,
+            sample.key_expr.as_str(),
+            sample.value,
+        );
+    }
+}
+
+#[derive(clap::Parser, Clone, PartialEq, Eq, Hash, Debug)]
+struct SubArgs {
+    #[arg(short, long, default_value = "demo/example/**")]
+    /// The Key Expression to subscribe to.
+    key: KeyExpr<'static>,
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+fn parse_args() -> (Config, KeyExpr<'static>) {
+    let args = SubArgs::parse();
+    (args.common.into(), args.key)
+}
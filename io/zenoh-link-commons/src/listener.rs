@@ -28,6 +28,8 @@ pub struct ListenerUnicastIP {
     endpoint: EndPoint,
     token: CancellationToken,
     handle: JoinHandle<ZResult<()>>,
+    // Cancelled once the accept loop terminated, whatever the reason
+    terminated: CancellationToken,
 }
 
 impl ListenerUnicastIP {
@@ -35,11 +37,13 @@ impl ListenerUnicastIP {
         endpoint: EndPoint,
         token: CancellationToken,
         handle: JoinHandle<ZResult<()>>,
+        terminated: CancellationToken,
     ) -> ListenerUnicastIP {
         ListenerUnicastIP {
             endpoint,
             token,
             handle,
+            terminated,
         }
     }
 
@@ -75,15 +79,18 @@ impl ListenersUnicastIP {
         let mut listeners = zwrite!(self.listeners);
         let c_listeners = self.listeners.clone();
         let c_addr = addr;
+        let terminated = CancellationToken::new();
+        let c_terminated = terminated.clone();
         let task = async move {
             // Wait for the accept loop to terminate
             let res = future.await;
             zwrite!(c_listeners).remove(&c_addr);
+            c_terminated.cancel();
             res
         };
         let handle = zenoh_runtime::ZRuntime::Acceptor.spawn(task);
 
-        let listener = ListenerUnicastIP::new(endpoint, token, handle);
+        let listener = ListenerUnicastIP::new(endpoint, token, handle, terminated);
         // Update the list of active listeners on the manager
         listeners.insert(addr, listener);
         Ok(())
@@ -103,6 +110,18 @@ impl ListenersUnicastIP {
         listener.handle.await?
     }
 
+    /// Waits for the accept loop of the listener on `endpoint` to terminate, either because
+    /// it was deleted or because it failed. Returns immediately if there is no such listener.
+    pub async fn terminated(&self, endpoint: &EndPoint) {
+        let terminated = zread!(self.listeners)
+            .values()
+            .find(|listener| listener.endpoint == *endpoint)
+            .map(|listener| listener.terminated.clone());
+        if let Some(terminated) = terminated {
+            terminated.cancelled().await;
+        }
+    }
+
     pub fn get_endpoints(&self) -> Vec<EndPoint> {
         zread!(self.listeners)
             .values()
@@ -30,6 +30,12 @@ pub trait LinkManagerUnicastTrait: Send + Sync {
     async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()>;
     async fn get_listeners(&self) -> Vec<EndPoint>;
     async fn get_locators(&self) -> Vec<Locator>;
+    /// Waits for the accept loop of the listener on `endpoint` to terminate, returning
+    /// immediately if there is no such listener.
+    /// Link managers which can't tell when their accept loops terminate never return.
+    async fn listener_terminated(&self, _endpoint: &EndPoint) {
+        std::future::pending().await
+    }
 }
 pub type NewLinkChannelSender = flume::Sender<LinkUnicast>;
 pub trait ConstructibleLinkManagerUnicast<T>: Sized {
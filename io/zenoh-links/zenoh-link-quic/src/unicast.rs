@@ -340,6 +340,10 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
         self.listeners.get_endpoints()
     }
 
+    async fn listener_terminated(&self, endpoint: &EndPoint) {
+        self.listeners.terminated(endpoint).await
+    }
+
     async fn get_locators(&self) -> Vec<Locator> {
         self.listeners.get_locators()
     }
@@ -429,6 +429,19 @@ impl TransportManager {
         }
     }
 
+    /// Waits for the accept loop of the listener on `endpoint` to terminate.
+    /// Never returns for multicast endpoints, whose listeners have no accept loop.
+    pub async fn listener_terminated(&self, endpoint: &EndPoint) {
+        match self
+            .locator_inspector
+            .is_multicast(&endpoint.to_locator())
+            .await
+        {
+            Ok(false) => self.listener_terminated_unicast(endpoint).await,
+            _ => std::future::pending().await,
+        }
+    }
+
     pub async fn get_listeners(&self) -> Vec<EndPoint> {
         let mut lsu = self.get_listeners_unicast().await;
         let mut lsm = self.get_listeners_multicast().await;
@@ -21,7 +21,7 @@ use crate::{
             LinkUnicastWithOpenAck, TransportLinkUnicast, TransportLinkUnicastConfig,
             TransportLinkUnicastDirection,
         },
-        TransportConfigUnicast,
+        TransportAcceptCandidate, TransportConfigUnicast,
     },
     TransportManager,
 };
@@ -670,6 +670,37 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         };
         let isyn_out = step!(fsm.recv_init_syn((&mut state, isyn_in)).await);
 
+        // Two nodes sharing the same ZenohId can't be told apart by the routing
+        if isyn_out.other_zid == manager.config.zid {
+            let e = zerror!(
+                "ZenohId collision: {} is used both by the local node on {} and the remote node on {}",
+                manager.config.zid,
+                fsm.link.link.get_src(),
+                fsm.link.link.get_dst()
+            );
+            tracing::error!("{}", e);
+            let _ = link.close(Some(close::reason::INVALID)).await;
+            return Err(e.into());
+        }
+
+        // Check the acceptance policy now that the identity of the remote node is known
+        let candidate = TransportAcceptCandidate {
+            zid: isyn_out.other_zid,
+            whatami: isyn_out.other_whatami,
+            locator: fsm.link.link.get_dst().clone(),
+        };
+        if !manager.accepts_transport_unicast(&candidate) {
+            let e = zerror!(
+                "Transport with {} ({}) on {} refused by the acceptance policy",
+                candidate.zid,
+                candidate.whatami,
+                candidate.locator
+            );
+            tracing::debug!("{}", e);
+            let _ = link.close(Some(close::reason::GENERIC)).await;
+            return Err(e.into());
+        }
+
         let iack_in = SendInitAckIn {
             mine_version: manager.config.version,
             mine_zid: manager.config.zid,
@@ -596,6 +596,19 @@ pub(crate) async fn open_link(
 
     let iack_out = step!(fsm.recv_init_ack((&mut link, &mut state)).await);
 
+    // Two nodes sharing the same ZenohId can't be told apart by the routing
+    if iack_out.other_zid == manager.config.zid {
+        let e = zerror!(
+            "ZenohId collision: {} is used both by the local node on {} and the remote node on {}",
+            manager.config.zid,
+            link.link.get_src(),
+            link.link.get_dst()
+        );
+        tracing::error!("{}", e);
+        let _ = link.close(Some(close::reason::INVALID)).await;
+        return Err(e.into());
+    }
+
     // Open handshake
     let osyn_in = SendOpenSynIn {
         mine_zid: manager.config.zid,
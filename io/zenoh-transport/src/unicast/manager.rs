@@ -45,11 +45,55 @@ use zenoh_core::{zasynclock, zcondfeat};
 use zenoh_crypto::PseudoRng;
 use zenoh_link::*;
 use zenoh_protocol::{
-    core::{endpoint, ZenohId},
+    core::{endpoint, Locator, WhatAmI, WhatAmIMatcher, ZenohId},
     transport::{close, TransportSn},
 };
 use zenoh_result::{bail, zerror, ZResult};
 
+/*************************************/
+/*         ACCEPTANCE POLICY         */
+/*************************************/
+/// A remote node attempting to establish an incoming unicast transport.
+#[derive(Debug, Clone)]
+pub struct TransportAcceptCandidate {
+    pub zid: ZenohId,
+    pub whatami: WhatAmI,
+    /// The locator of the remote end of the link.
+    pub locator: Locator,
+}
+
+/// A policy deciding which incoming unicast transports are accepted.
+///
+/// The policy is evaluated during the establishment of incoming transports, once the identity of
+/// the remote node is known, and in addition to the `max_sessions` limit.
+pub trait TransportAcceptancePolicy: Send + Sync {
+    fn accept(&self, candidate: &TransportAcceptCandidate) -> bool;
+}
+
+impl<F> TransportAcceptancePolicy for F
+where
+    F: Fn(&TransportAcceptCandidate) -> bool + Send + Sync,
+{
+    fn accept(&self, candidate: &TransportAcceptCandidate) -> bool {
+        self(candidate)
+    }
+}
+
+/// The [`TransportAcceptancePolicy`] built from the `transport/unicast/acceptance` configuration.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigAcceptancePolicy {
+    pub whatami: Option<WhatAmIMatcher>,
+    pub denied_zids: Vec<ZenohId>,
+}
+
+impl TransportAcceptancePolicy for ConfigAcceptancePolicy {
+    fn accept(&self, candidate: &TransportAcceptCandidate) -> bool {
+        self.whatami
+            .map_or(true, |whatami| whatami.matches(candidate.whatami))
+            && !self.denied_zids.contains(&candidate.zid)
+    }
+}
+
 /*************************************/
 /*         TRANSPORT CONFIG          */
 /*************************************/
@@ -59,6 +103,7 @@ pub struct TransportManagerConfigUnicast {
     pub accept_timeout: Duration,
     pub accept_pending: usize,
     pub max_sessions: usize,
+    pub acceptance: Arc<dyn TransportAcceptancePolicy>,
     pub is_qos: bool,
     pub is_lowlatency: bool,
     #[cfg(feature = "transport_multilink")]
@@ -72,6 +117,8 @@ pub struct TransportManagerConfigUnicast {
 pub struct TransportManagerStateUnicast {
     // Incoming uninitialized transports
     pub(super) incoming: Arc<AtomicUsize>,
+    // Transports refused by the acceptance policy or the max_sessions limit
+    pub(super) rejected: Arc<AtomicUsize>,
     // Established listeners
     pub(super) protocols: Arc<AsyncMutex<HashMap<String, LinkManagerUnicast>>>,
     // Established transports
@@ -103,6 +150,7 @@ pub struct TransportManagerBuilderUnicast {
     pub(super) accept_timeout: Duration,
     pub(super) accept_pending: usize,
     pub(super) max_sessions: usize,
+    pub(super) acceptance: Arc<dyn TransportAcceptancePolicy>,
     pub(super) is_qos: bool,
     #[cfg(feature = "transport_multilink")]
     pub(super) max_links: usize,
@@ -141,6 +189,14 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    pub fn acceptance_policy<P>(mut self, acceptance: P) -> Self
+    where
+        P: TransportAcceptancePolicy + 'static,
+    {
+        self.acceptance = Arc::new(acceptance);
+        self
+    }
+
     pub fn qos(mut self, is_qos: bool) -> Self {
         self.is_qos = is_qos;
         self
@@ -185,6 +241,11 @@ impl TransportManagerBuilderUnicast {
         ));
         self = self.accept_pending(*config.transport().unicast().accept_pending());
         self = self.max_sessions(*config.transport().unicast().max_sessions());
+        let acceptance = config.transport().unicast().acceptance();
+        self = self.acceptance_policy(ConfigAcceptancePolicy {
+            whatami: *acceptance.whatami(),
+            denied_zids: acceptance.denied_zids().clone(),
+        });
         self = self.qos(*config.transport().unicast().qos().enabled());
         self = self.lowlatency(*config.transport().unicast().lowlatency());
 
@@ -222,6 +283,7 @@ impl TransportManagerBuilderUnicast {
             accept_timeout: self.accept_timeout,
             accept_pending: self.accept_pending,
             max_sessions: self.max_sessions,
+            acceptance: self.acceptance,
             is_qos: self.is_qos,
             #[cfg(feature = "transport_multilink")]
             max_links: self.max_links,
@@ -234,6 +296,7 @@ impl TransportManagerBuilderUnicast {
 
         let state = TransportManagerStateUnicast {
             incoming: Arc::new(AtomicUsize::new(0)),
+            rejected: Arc::new(AtomicUsize::new(0)),
             protocols: Arc::new(AsyncMutex::new(HashMap::new())),
             transports: Arc::new(AsyncMutex::new(HashMap::new())),
             #[cfg(feature = "transport_multilink")]
@@ -266,6 +329,7 @@ impl Default for TransportManagerBuilderUnicast {
             accept_timeout: Duration::from_millis(*transport.accept_timeout()),
             accept_pending: *transport.accept_pending(),
             max_sessions: *transport.max_sessions(),
+            acceptance: Arc::new(ConfigAcceptancePolicy::default()),
             is_qos: *qos.enabled(),
             #[cfg(feature = "transport_multilink")]
             max_links: *transport.max_links(),
@@ -398,6 +462,15 @@ impl TransportManager {
         Ok(())
     }
 
+    pub async fn listener_terminated_unicast(&self, endpoint: &EndPoint) {
+        if let Ok(lm) = self
+            .get_link_manager_unicast(endpoint.protocol().as_str())
+            .await
+        {
+            lm.listener_terminated(endpoint).await
+        }
+    }
+
     pub async fn get_listeners_unicast(&self) -> Vec<EndPoint> {
         let mut vec: Vec<EndPoint> = vec![];
         for p in zasynclock!(self.state.unicast.protocols).values() {
@@ -526,11 +599,12 @@ impl TransportManager {
                 self.config.unicast.max_sessions,
                 config.zid
             );
-            tracing::trace!("{e}");
+            tracing::debug!("{e}");
+            self.state.unicast.rejected.fetch_add(1, SeqCst);
             return Err(InitTransportError::Link((
                 e.into(),
                 link.fail(),
-                close::reason::INVALID,
+                close::reason::MAX_SESSIONS,
             )));
         }
 
@@ -706,6 +780,22 @@ impl TransportManager {
         super::establishment::open::open_link(link, self).await
     }
 
+    /// Returns whether an incoming transport from `candidate` is allowed by the acceptance policy.
+    /// Refused candidates are accounted in [`TransportManager::get_rejected_transports_unicast`].
+    pub(super) fn accepts_transport_unicast(&self, candidate: &TransportAcceptCandidate) -> bool {
+        let accepted = self.config.unicast.acceptance.accept(candidate);
+        if !accepted {
+            self.state.unicast.rejected.fetch_add(1, SeqCst);
+        }
+        accepted
+    }
+
+    /// Returns the number of unicast transports refused so far, either by the acceptance policy
+    /// or because `max_sessions` was reached.
+    pub fn get_rejected_transports_unicast(&self) -> usize {
+        self.state.unicast.rejected.load(SeqCst)
+    }
+
     pub async fn get_transport_unicast(&self, peer: &ZenohId) -> Option<TransportUnicast> {
         zasynclock!(self.state.unicast.transports)
             .get(peer)
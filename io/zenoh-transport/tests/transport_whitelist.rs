@@ -67,6 +67,7 @@ impl TransportPeerEventHandler for SCRouter {
 
 async fn run(endpoints: &[EndPoint]) {
     // Define client and router IDs
+    let client_id = ZenohId::try_from([2]).unwrap();
     let router_id = ZenohId::try_from([1]).unwrap();
 
     // Create the router transport manager
@@ -100,6 +101,17 @@ async fn run(endpoints: &[EndPoint]) {
         .build(Arc::new(SHRouter))
         .unwrap();
 
+    // Create the client transport manager
+    let unicast = TransportManager::config_unicast().max_links(usize::MAX);
+    let client_manager = TransportManager::builder()
+        .zid(client_id)
+        .unicast(unicast)
+        .protocols(Some(Vec::from_iter(
+            endpoints.iter().map(|e| e.protocol().to_string()),
+        )))
+        .build(Arc::new(SHRouter))
+        .unwrap();
+
     // Create the listener on the router
     for e in endpoints.iter() {
         println!("Listener endpoint: {e}");
@@ -108,7 +120,7 @@ async fn run(endpoints: &[EndPoint]) {
         tokio::time::sleep(SLEEP).await;
 
         println!("Open endpoint: {e}");
-        let _ = ztimeout!(router_manager.open_transport_unicast(e.clone())).unwrap();
+        let _ = ztimeout!(client_manager.open_transport_unicast(e.clone())).unwrap();
 
         tokio::time::sleep(SLEEP).await;
     }
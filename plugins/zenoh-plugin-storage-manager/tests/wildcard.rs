@@ -63,6 +63,7 @@ async fn test_wild_card_in_order() {
         zasync_executor_init!();
     });
     let mut config = Config::default();
+    config.set_allow_wildcard_writes(Some(true)).unwrap();
     config
         .insert_json5(
             "plugins/storage-manager",
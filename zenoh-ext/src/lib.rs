@@ -22,7 +22,7 @@ pub use querying_subscriber::{
 };
 pub use session_ext::SessionExt;
 pub use subscriber_ext::SubscriberBuilderExt;
-pub use subscriber_ext::SubscriberForward;
+pub use subscriber_ext::{SampleStream, SubscriberForward, TokioStream};
 
 /// The space of keys to use in a [`FetchingSubscriber`].
 pub enum KeySpace {
@@ -19,12 +19,12 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use zenoh::handlers::{locked, DefaultHandler};
 use zenoh::prelude::r#async::*;
-use zenoh::query::{QueryConsolidation, QueryTarget, ReplyKeyExpr};
+use zenoh::query::{QueryConsolidation, QueryTarget, Reply, ReplyKeyExpr};
 use zenoh::subscriber::{Reliability, Subscriber};
 use zenoh::time::Timestamp;
 use zenoh::Result as ZResult;
 use zenoh::SessionRef;
-use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
+use zenoh_core::{bail, zlock, AsyncResolve, Resolvable, ResolveClosure, SyncResolve};
 
 /// The builder of [`FetchingSubscriber`], allowing to configure it.
 #[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
@@ -218,7 +218,7 @@ where
     type To = ZResult<FetchingSubscriber<'a, Handler::Receiver>>;
 }
 
-impl<KeySpace, Handler> SyncResolve for QueryingSubscriberBuilder<'_, '_, KeySpace, Handler>
+impl<'a, KeySpace, Handler> SyncResolve for QueryingSubscriberBuilder<'a, '_, KeySpace, Handler>
 where
     KeySpace: Into<crate::KeySpace> + Clone,
     Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
@@ -236,34 +236,46 @@ where
         let query_consolidation = self.query_consolidation;
         let query_accept_replies = self.query_accept_replies;
         let query_timeout = self.query_timeout;
-        FetchingSubscriberBuilder {
+        let query: Arc<QueryFn<'a>> = {
+            let key_expr = key_expr.clone().into_owned();
+            let query_selector = query_selector.map(Selector::into_owned);
+            Arc::new(
+                move |cb: Box<dyn Fn(Reply) + Send + Sync>| match key_space {
+                    crate::KeySpace::User => match &query_selector {
+                        Some(s) => session.get(s),
+                        None => session.get(&key_expr),
+                    }
+                    .callback(cb)
+                    .target(query_target)
+                    .consolidation(query_consolidation)
+                    .accept_replies(query_accept_replies)
+                    .timeout(query_timeout)
+                    .res_sync(),
+                    crate::KeySpace::Liveliness => session
+                        .liveliness()
+                        .get(key_expr.clone())
+                        .callback(cb)
+                        .timeout(query_timeout)
+                        .res_sync(),
+                },
+            )
+        };
+        let mut subscriber = FetchingSubscriberBuilder {
             session: self.session,
-            key_expr: Ok(key_expr.clone()),
+            key_expr: Ok(key_expr),
             key_space: self.key_space,
             reliability: self.reliability,
             origin: self.origin,
-            fetch: |cb| match key_space {
-                crate::KeySpace::User => match query_selector {
-                    Some(s) => session.get(s),
-                    None => session.get(key_expr),
-                }
-                .callback(cb)
-                .target(query_target)
-                .consolidation(query_consolidation)
-                .accept_replies(query_accept_replies)
-                .timeout(query_timeout)
-                .res_sync(),
-                crate::KeySpace::Liveliness => session
-                    .liveliness()
-                    .get(key_expr)
-                    .callback(cb)
-                    .timeout(query_timeout)
-                    .res_sync(),
+            fetch: {
+                let query = query.clone();
+                move |cb: Box<dyn Fn(Reply) + Send + Sync>| query(cb)
             },
             handler: self.handler,
             phantom: std::marker::PhantomData,
         }
-        .res_sync()
+        .res_sync()?;
+        subscriber.query = Some(query);
+        Ok(subscriber)
     }
 }
 
@@ -593,6 +605,11 @@ where
 ///
 /// A typical usage of the `FetchingSubscriber` is to retrieve publications that were made in the past, but stored in some zenoh Storage.
 ///
+/// The samples are merged according to their timestamps. The publications received without timestamp
+/// while a fetch is in progress are given a reception timestamp, so that they are delivered after the
+/// fetched samples. Fetched samples without timestamp, e.g. from nodes with timestamping disabled,
+/// are delivered first, in their order of reception.
+///
 /// # Examples
 /// ```no_run
 /// # #[tokio::main]
@@ -622,9 +639,14 @@ pub struct FetchingSubscriber<'a, Receiver> {
     subscriber: Subscriber<'a, ()>,
     callback: Arc<dyn Fn(Sample) + Send + Sync + 'static>,
     state: Arc<Mutex<InnerState>>,
+    query: Option<Arc<QueryFn<'a>>>,
     receiver: Receiver,
 }
 
+/// The query issued by a [`FetchingSubscriber`] declared with
+/// [`querying`](crate::SubscriberBuilderExt::querying), as a fetch function.
+type QueryFn<'a> = dyn Fn(Box<dyn Fn(Reply) + Send + Sync>) -> ZResult<()> + Send + Sync + 'a;
+
 impl<Receiver> std::ops::Deref for FetchingSubscriber<'_, Receiver> {
     type Target = Receiver;
     fn deref(&self) -> &Self::Target {
@@ -703,6 +725,7 @@ impl<'a, Receiver> FetchingSubscriber<'a, Receiver> {
             subscriber,
             callback,
             state,
+            query: None,
             receiver,
         };
 
@@ -783,6 +806,45 @@ impl<'a, Receiver> FetchingSubscriber<'a, Receiver> {
             callback: self.callback.clone(),
         }
     }
+
+    /// Issue again the query of a FetchingSubscriber declared with
+    /// [`querying`](crate::SubscriberBuilderExt::querying), e.g. after samples were missed.
+    ///
+    /// Like for [`fetch`](FetchingSubscriber::fetch), the replies are merged with the received
+    /// publications. Returns an error if this FetchingSubscriber was declared with
+    /// [`fetching`](crate::SubscriberBuilderExt::fetching).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expr")
+    ///     .querying()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// // query the current values again
+    /// subscriber.query().res().await.unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn query(&self) -> impl Resolve<ZResult<()>> + '_ {
+        // Prepare the fetch beforehand, so as not to capture the receiver, which may not be Sync
+        let fetch = self
+            .query
+            .clone()
+            .map(|query| self.fetch(move |cb: Box<dyn Fn(Reply) + Send + Sync>| query(cb)));
+        ResolveClosure::new(move || match fetch {
+            Some(fetch) => fetch.res_sync(),
+            None => bail!("This FetchingSubscriber was not declared with a query"),
+        })
+    }
 }
 
 struct RepliesHandler {
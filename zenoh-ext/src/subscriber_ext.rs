@@ -13,6 +13,9 @@
 //
 use flume::r#async::RecvStream;
 use futures::stream::{Forward, Map};
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::{convert::TryInto, time::Duration};
 use zenoh::query::ReplyKeyExpr;
 use zenoh::sample::Locality;
@@ -27,17 +30,89 @@ use zenoh::{
 use crate::{querying_subscriber::QueryingSubscriberBuilder, FetchingSubscriberBuilder};
 
 /// Allows writing `subscriber.forward(receiver)` instead of `subscriber.stream().map(Ok).forward(publisher)`
+///
+/// It is implemented for the [`Subscriber`]s whose receiver is a [`SampleStream`].
 pub trait SubscriberForward<'a, S> {
     type Output;
     fn forward(&'a mut self, sink: S) -> Self::Output;
 }
-impl<'a, S> SubscriberForward<'a, S> for Subscriber<'_, flume::Receiver<Sample>>
+impl<'a, 'b: 'a, S, Receiver> SubscriberForward<'a, S> for Subscriber<'b, Receiver>
 where
     S: futures::sink::Sink<Sample>,
+    Receiver: SampleStream + 'a,
 {
-    type Output = Forward<Map<RecvStream<'a, Sample>, fn(Sample) -> Result<Sample, S::Error>>, S>;
+    type Output = Forward<Map<Receiver::Stream<'a>, fn(Sample) -> Result<Sample, S::Error>>, S>;
     fn forward(&'a mut self, sink: S) -> Self::Output {
-        futures::StreamExt::forward(futures::StreamExt::map(self.receiver.stream(), Ok), sink)
+        futures::StreamExt::forward(
+            futures::StreamExt::map(self.receiver.sample_stream(), Ok),
+            sink,
+        )
+    }
+}
+
+/// A receiver of [`Sample`]s that can be polled as a [`Stream`].
+///
+/// Implement it for the receiver of a custom [`Handler`](zenoh::prelude::IntoCallbackReceiverPair)
+/// to [`forward`](SubscriberForward::forward) its subscribers.
+pub trait SampleStream {
+    type Stream<'a>: Stream<Item = Sample>
+    where
+        Self: 'a;
+
+    /// Returns a stream of the samples received by this receiver.
+    fn sample_stream(&mut self) -> Self::Stream<'_>;
+}
+
+impl SampleStream for flume::Receiver<Sample> {
+    type Stream<'a> = RecvStream<'a, Sample>;
+    fn sample_stream(&mut self) -> Self::Stream<'_> {
+        self.stream()
+    }
+}
+
+impl SampleStream for futures::channel::mpsc::Receiver<Sample> {
+    type Stream<'a> = &'a mut Self;
+    fn sample_stream(&mut self) -> Self::Stream<'_> {
+        self
+    }
+}
+
+impl SampleStream for futures::channel::mpsc::UnboundedReceiver<Sample> {
+    type Stream<'a> = &'a mut Self;
+    fn sample_stream(&mut self) -> Self::Stream<'_> {
+        self
+    }
+}
+
+impl SampleStream for tokio::sync::mpsc::Receiver<Sample> {
+    type Stream<'a> = TokioStream<'a, Self>;
+    fn sample_stream(&mut self) -> Self::Stream<'_> {
+        TokioStream(self)
+    }
+}
+
+impl SampleStream for tokio::sync::mpsc::UnboundedReceiver<Sample> {
+    type Stream<'a> = TokioStream<'a, Self>;
+    fn sample_stream(&mut self) -> Self::Stream<'_> {
+        TokioStream(self)
+    }
+}
+
+/// The [`Stream`] of the samples of a `tokio` receiver, see [`SampleStream`].
+#[derive(Debug)]
+pub struct TokioStream<'a, Receiver>(&'a mut Receiver);
+
+impl Stream for TokioStream<'_, tokio::sync::mpsc::Receiver<Sample>> {
+    type Item = Sample;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Sample>> {
+        self.get_mut().0.poll_recv(cx)
+    }
+}
+
+impl Stream for TokioStream<'_, tokio::sync::mpsc::UnboundedReceiver<Sample>> {
+    type Item = Sample;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Sample>> {
+        self.get_mut().0.poll_recv(cx)
     }
 }
 
@@ -122,6 +197,38 @@ pub trait SubscriberBuilderExt<'a, 'b, Handler> {
     /// # }
     /// ```
     fn querying(self) -> QueryingSubscriberBuilder<'a, 'b, Self::KeySpace, Handler>;
+
+    /// Create a FetchingSubscriber that queries the current values of its key expression on startup,
+    /// before delivering the live publications. This is a synonym of [`querying`](SubscriberBuilderExt::querying).
+    ///
+    /// The query can be issued again later on calling [`FetchingSubscriber::query()`](super::FetchingSubscriber::query()).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expr")
+    ///     .query_on_startup()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {:?}", sample);
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    fn query_on_startup(self) -> QueryingSubscriberBuilder<'a, 'b, Self::KeySpace, Handler>
+    where
+        Self: Sized,
+    {
+        self.querying()
+    }
 }
 
 impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
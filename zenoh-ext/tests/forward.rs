@@ -0,0 +1,54 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use futures::StreamExt;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::SubscriberForward;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn forward_from_tokio_receiver() {
+    let session = zenoh::open(config::peer())
+        .res_async()
+        .await
+        .unwrap()
+        .into_arc();
+    let mut subscriber = session
+        .declare_subscriber("test/forward/tokio")
+        .with(tokio::sync::mpsc::channel(16))
+        .res_async()
+        .await
+        .unwrap();
+    let (sink, mut forwarded) = futures::channel::mpsc::unbounded();
+    let forwarding = tokio::spawn(async move {
+        subscriber.forward(sink).await.unwrap();
+    });
+
+    for i in 0..3 {
+        session
+            .put("test/forward/tokio", i)
+            .res_async()
+            .await
+            .unwrap();
+    }
+    for i in 0..3 {
+        let sample = tokio::time::timeout(TIMEOUT, forwarded.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample.value.to_string(), i.to_string());
+    }
+    forwarding.abort();
+}
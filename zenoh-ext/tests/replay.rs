@@ -0,0 +1,114 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::subscriber::{FlumeSubscriber, PausePolicy};
+use zenoh_ext::SessionExt;
+
+const SLEEP: Duration = Duration::from_millis(100);
+
+fn open() -> Session {
+    let mut config = config::peer();
+    config.insert_json5("timestamping/enabled", "true").unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+fn put(session: &Session, key_expr: &str, values: std::ops::Range<usize>) {
+    for i in values {
+        session.put(key_expr, i as u64).res().unwrap();
+    }
+}
+
+fn values(subscriber: &FlumeSubscriber) -> Vec<(String, bool)> {
+    subscriber
+        .drain(usize::MAX)
+        .into_iter()
+        .map(|sample| (sample.value.to_string(), sample.historical))
+        .collect()
+}
+
+#[test]
+fn resume_with_replay_fills_the_gap() {
+    let session = open();
+    let _cache = session
+        .declare_publication_cache("test/replay/drop")
+        .history(10)
+        .res()
+        .unwrap();
+    let subscriber = session
+        .declare_subscriber("test/replay/drop")
+        .res()
+        .unwrap();
+
+    put(&session, "test/replay/drop", 0..2);
+    std::thread::sleep(SLEEP);
+    subscriber.pause().res().unwrap();
+    put(&session, "test/replay/drop", 2..4);
+    // Let the cache store the samples published while paused
+    std::thread::sleep(SLEEP);
+    subscriber.resume_with_replay().res().unwrap();
+    assert!(!subscriber.is_paused());
+    put(&session, "test/replay/drop", 4..5);
+
+    let expected = [
+        ("0".to_string(), false),
+        ("1".to_string(), false),
+        ("2".to_string(), true),
+        ("3".to_string(), true),
+        ("4".to_string(), false),
+    ];
+    assert_eq!(values(&subscriber), expected);
+}
+
+#[test]
+fn resume_with_replay_skips_buffered_samples() {
+    let session = open();
+    let _cache = session
+        .declare_publication_cache("test/replay/buffer")
+        .history(10)
+        .res()
+        .unwrap();
+    let subscriber = session
+        .declare_subscriber("test/replay/buffer")
+        .res()
+        .unwrap();
+
+    subscriber.pause_with(PausePolicy::Buffer(1)).res().unwrap();
+    put(&session, "test/replay/buffer", 0..3);
+    std::thread::sleep(SLEEP);
+    // Only the first sample was buffered, the replayed ones follow it in timestamp order
+    subscriber.resume_with_replay().res().unwrap();
+    put(&session, "test/replay/buffer", 3..4);
+
+    let expected = [
+        ("0".to_string(), false),
+        ("1".to_string(), true),
+        ("2".to_string(), true),
+        ("3".to_string(), false),
+    ];
+    assert_eq!(values(&subscriber), expected);
+}
+
+#[test]
+fn resume_with_replay_without_pause() {
+    let session = open();
+    let subscriber = session
+        .declare_subscriber("test/replay/unpaused")
+        .res()
+        .unwrap();
+
+    subscriber.resume_with_replay().res().unwrap();
+    put(&session, "test/replay/unpaused", 0..1);
+    assert_eq!(values(&subscriber), [("0".to_string(), false)]);
+}
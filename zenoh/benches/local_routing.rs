@@ -0,0 +1,57 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Cost of the local delivery of a publication, depending on the number of subscribers
+//! declared on the session.
+//!
+//! Both the subscribers and the publications are restricted to the session, so that only
+//! the session's own routing of the samples to its subscribers is measured.
+use std::time::Instant;
+use zenoh::prelude::sync::*;
+
+const MESSAGES: u32 = 10_000;
+
+fn main() {
+    for subscribers in [10, 100, 1_000, 10_000] {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let session = zenoh::open(config).res().unwrap();
+        let _subscribers: Vec<_> = (0..subscribers)
+            .map(|i| {
+                session
+                    .declare_subscriber(format!("bench/routing/{i}/**"))
+                    .allowed_origin(Locality::SessionLocal)
+                    .callback(|_| {})
+                    .res()
+                    .unwrap()
+            })
+            .collect();
+
+        // The key expressions aren't declared, so that each publication is matched
+        // against the subscribers of the session.
+        let start = Instant::now();
+        for i in 0..MESSAGES {
+            session
+                .put(format!("bench/routing/0/{i}"), "payload")
+                .allowed_destination(Locality::SessionLocal)
+                .res()
+                .unwrap();
+        }
+        let elapsed = start.elapsed();
+        println!(
+            "{subscribers:>6} subscribers: {:>8.2} µs/msg",
+            elapsed.as_secs_f64() * 1e6 / MESSAGES as f64
+        );
+    }
+}
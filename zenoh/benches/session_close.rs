@@ -0,0 +1,81 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+use std::time::{Duration, Instant};
+use zenoh::prelude::sync::*;
+
+const SUBSCRIBERS: usize = 10_000;
+const ENDPOINT: &str = "tcp/127.0.0.1:17494";
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut config = config::peer();
+    config.listen.endpoints = vec![ENDPOINT.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let listener = zenoh::open(config).res().unwrap();
+    // The subscribers are declared in order, so the last one matching means all of them are
+    let last = listener
+        .declare_publisher(format!("bench/close/{}", SUBSCRIBERS - 1))
+        .res()
+        .unwrap();
+    let wait_matching = |expected: bool| {
+        while last.matching_status().res().unwrap().matching_subscribers() != expected {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+
+    let mut group = c.benchmark_group("Session close");
+    group.sample_size(10);
+    for undeclare_on_close in [false, true] {
+        group.bench_function(
+            format!("10k subscribers, undeclare_on_close={undeclare_on_close}"),
+            |b| {
+                b.iter_custom(|iters| {
+                    let mut elapsed = Duration::ZERO;
+                    for _ in 0..iters {
+                        let mut config = config::peer();
+                        config.connect.endpoints = vec![ENDPOINT.parse().unwrap()];
+                        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+                        config
+                            .set_undeclare_on_close(Some(undeclare_on_close))
+                            .unwrap();
+                        let session = zenoh::open(config).res().unwrap();
+                        for i in 0..SUBSCRIBERS {
+                            let subscriber = session
+                                .declare_subscriber(format!("bench/close/{i}"))
+                                .callback(|_| {})
+                                .res()
+                                .unwrap();
+                            // Leave the subscriber declared until the session is closed
+                            std::mem::forget(subscriber);
+                        }
+                        wait_matching(true);
+
+                        let start = Instant::now();
+                        session.close().res().unwrap();
+                        elapsed += start.elapsed();
+                        wait_matching(false);
+                    }
+                    elapsed
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
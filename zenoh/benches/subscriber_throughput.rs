@@ -0,0 +1,77 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Throughput of the delivery of samples to callback subscribers, depending on the number
+//! of threads publishing concurrently.
+//!
+//! The samples are delivered to the subscribers on the publishing threads, so that the cost
+//! of the callbacks wrapping, and any contention between the threads, is measured.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use zenoh::prelude::sync::*;
+
+const MESSAGES: usize = 100_000;
+const SUBSCRIBERS: usize = 4;
+
+fn main() {
+    for threads in [1, 2, 4, 8] {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let session = zenoh::open(config).res().unwrap().into_arc();
+        let received = Arc::new(AtomicUsize::new(0));
+        let _subscribers: Vec<_> = (0..SUBSCRIBERS)
+            .map(|_| {
+                let received = received.clone();
+                session
+                    .declare_subscriber("bench/throughput/**")
+                    .allowed_origin(Locality::SessionLocal)
+                    .callback(move |_| {
+                        received.fetch_add(1, Ordering::Relaxed);
+                    })
+                    .res()
+                    .unwrap()
+            })
+            .collect();
+
+        let start = Instant::now();
+        let publishers: Vec<_> = (0..threads)
+            .map(|t| {
+                let session = session.clone();
+                std::thread::spawn(move || {
+                    let publisher = session
+                        .declare_publisher(format!("bench/throughput/{t}"))
+                        .allowed_destination(Locality::SessionLocal)
+                        .res()
+                        .unwrap();
+                    for _ in 0..MESSAGES / threads {
+                        publisher.put("payload").res().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for publisher in publishers {
+            publisher.join().unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert_eq!(
+            received.load(Ordering::Relaxed),
+            SUBSCRIBERS * threads * (MESSAGES / threads)
+        );
+        println!(
+            "{threads:>2} threads: {:>10.0} msg/s",
+            (threads * (MESSAGES / threads)) as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
     prelude::sync::{KeyExpr, Locality, SampleKind},
     queryable::Query,
     sample::DataInfo,
-    Sample, Session, ZResult,
+    Sample, Session, Value, ZResult,
 };
 use std::{
     collections::hash_map::DefaultHasher,
@@ -26,7 +26,7 @@ use std::{
 use zenoh_core::SyncResolve;
 use zenoh_protocol::{
     core::{Encoding, KnownEncoding, WireExpr},
-    network::NetworkMessage,
+    network::{declare::subscriber::ext::SubscriberInfo, NetworkMessage},
 };
 use zenoh_transport::{
     TransportEventHandler, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
@@ -39,12 +39,23 @@ macro_rules! ke_for_sure {
 }
 
 lazy_static::lazy_static!(
+    static ref KE_STAR: &'static keyexpr = ke_for_sure!("*");
     static ref KE_STARSTAR: &'static keyexpr = ke_for_sure!("**");
     static ref KE_PREFIX: &'static keyexpr = ke_for_sure!("@/session");
     static ref KE_TRANSPORT_UNICAST: &'static keyexpr = ke_for_sure!("transport/unicast");
     static ref KE_LINK: &'static keyexpr = ke_for_sure!("link");
+    static ref KE_QUERY: &'static keyexpr = ke_for_sure!("query");
+    static ref KE_ENTITY: &'static keyexpr = ke_for_sure!("entity");
+    static ref KE_SUBSCRIBER: &'static keyexpr = ke_for_sure!("subscriber");
+    static ref KE_PUBLICATION: &'static keyexpr = ke_for_sure!("publication");
+    static ref KE_CONFIG_LOGGING: &'static keyexpr = ke_for_sure!("config/logging");
+    static ref KE_ROUTED: &'static keyexpr = ke_for_sure!("routed");
 );
 
+/// The selector of the queries sent by [`Session::routed`].
+#[cfg(feature = "unstable")]
+pub(crate) const ROUTED_SELECTOR: &str = "@/session/*/routed";
+
 pub(crate) fn init(session: &Session) {
     if let Ok(own_zid) = keyexpr::new(&session.zid().to_string()) {
         let admin_key = KeyExpr::from(*KE_PREFIX / own_zid / *KE_STARSTAR)
@@ -60,6 +71,146 @@ pub(crate) fn init(session: &Session) {
                 move |q| super::admin::on_admin_query(&session, q)
             }),
         );
+
+        // The log filter may be changed by remote operators, so these are not session local
+        let logging_key = KeyExpr::from(*KE_PREFIX / own_zid / *KE_CONFIG_LOGGING);
+        let _logging_sub = session.declare_subscriber_inner(
+            &logging_key,
+            &None,
+            Locality::Any,
+            Arc::new({
+                let session = session.clone();
+                move |sample| super::admin::on_logging_put(&session, sample)
+            }),
+            &SubscriberInfo::default(),
+            #[cfg(feature = "unstable")]
+            None,
+        );
+        let _logging_qabl = session.declare_queryable_inner(
+            &logging_key.to_wire(session).to_owned(),
+            true,
+            Locality::Any,
+            Arc::new({
+                let session = session.clone();
+                move |q| super::admin::on_logging_query(&session, q)
+            }),
+        );
+
+        // The subscribers are only exposed to remote operators when the admin space is enabled,
+        // the local queries being answered by the admin queryable above
+        #[cfg(feature = "unstable")]
+        if *session.runtime.config().lock().adminspace.enabled() {
+            let subscribers_key = KeyExpr::from(*KE_PREFIX / own_zid / *KE_SUBSCRIBER / *KE_STAR);
+            let _subscribers_qabl = session.declare_queryable_inner(
+                &subscribers_key.to_wire(session).to_owned(),
+                true,
+                Locality::Remote,
+                Arc::new({
+                    let session = session.clone();
+                    move |q| super::admin::on_subscribers_query(&session, &q)
+                }),
+            );
+        }
+
+        // Answered by the remote sessions once they have processed the declarations received
+        // before the query, see `Session::routed`
+        #[cfg(feature = "unstable")]
+        {
+            let routed_key = KeyExpr::from(*KE_PREFIX / own_zid / *KE_ROUTED);
+            let _routed_qabl = session.declare_queryable_inner(
+                &routed_key.to_wire(session).to_owned(),
+                false,
+                Locality::Remote,
+                Arc::new(move |q: Query| {
+                    let sample = Sample::new(routed_key.clone(), Value::empty());
+                    let _ = q.reply(Ok(sample)).res_sync();
+                }),
+            );
+        }
+    }
+}
+
+fn set_log_filter(session: &Session, value: &Value) -> ZResult<String> {
+    if !session
+        .runtime
+        .config()
+        .lock()
+        .adminspace
+        .permissions()
+        .write
+    {
+        bail!("The log filter can not be set: adminspace.permissions.write=false in configuration")
+    }
+    let directives = String::try_from(value)?;
+    zenoh_util::set_log_filter(&directives)?;
+    Ok(directives)
+}
+
+/// Sets the log filter to the directives put on `@/session/<zid>/config/logging`.
+pub(crate) fn on_logging_put(session: &Session, sample: Sample) {
+    if sample.kind == SampleKind::Put {
+        if let Err(e) = set_log_filter(session, &sample.value) {
+            tracing::error!("{}", e);
+        }
+    }
+}
+
+/// Replies the current log filter to the queries on `@/session/<zid>/config/logging`,
+/// after setting it to the directives given as the query value, if any.
+pub(crate) fn on_logging_query(session: &Session, query: Query) {
+    let reply = match query.value() {
+        Some(value) => set_log_filter(session, value),
+        None if session
+            .runtime
+            .config()
+            .lock()
+            .adminspace
+            .permissions()
+            .read =>
+        {
+            zenoh_util::log_filter().ok_or_else(|| {
+                zerror!("The logs were not initialized with a reloadable filter").into()
+            })
+        }
+        None => Err(zerror!(
+            "The log filter can not be read: adminspace.permissions.read=false in configuration"
+        )
+        .into()),
+    };
+    let reply = reply
+        .map(|directives| Sample::new(query.key_expr().clone(), directives))
+        .map_err(|e| Value::from(e.to_string()));
+    let _ = query.reply(reply).res_sync();
+}
+
+/// Replies the state and the metrics of the subscribers of the session to the queries on
+/// `@/session/<zid>/subscriber/<id>`.
+#[cfg(feature = "unstable")]
+pub(crate) fn on_subscribers_query(session: &Session, query: &Query) {
+    let zid = session.zid().to_string();
+    let Ok(own_zid) = keyexpr::new(&zid) else {
+        return;
+    };
+    for sub in session.subscribers() {
+        let id = sub.id.to_string();
+        if let Ok(id) = keyexpr::new(&id) {
+            let key_expr = *KE_PREFIX / own_zid / *KE_SUBSCRIBER / id;
+            if query.key_expr().intersects(&key_expr) {
+                let mut value = serde_json::json!({
+                    "key_expr": sub.key_expr.to_string(),
+                    "mode": format!("{:?}", sub.mode),
+                    "reliability": format!("{:?}", sub.reliability),
+                    "origin": sub.origin,
+                });
+                if let Some(metrics) = sub.metrics {
+                    value["samples_delivered"] = metrics.samples_delivered.into();
+                    value["bytes_delivered"] = metrics.bytes_delivered.into();
+                    value["samples_dropped"] = metrics.samples_dropped.into();
+                    value["queued"] = metrics.queued.into();
+                }
+                let _ = query.reply(Ok(Sample::new(key_expr, value))).res_sync();
+            }
+        }
     }
 }
 
@@ -105,6 +256,50 @@ pub(crate) fn on_admin_query(session: &Session, query: Query) {
                 reply_peer(own_zid, &query, peer);
             }
         }
+        #[cfg(feature = "unstable")]
+        for pending in session.pending_queries() {
+            if let Ok(qid) = keyexpr::new(&pending.qid.to_string()) {
+                let key_expr = *KE_PREFIX / own_zid / *KE_QUERY / qid;
+                if query.key_expr().intersects(&key_expr) {
+                    let value = serde_json::json!({
+                        "selector": pending.selector.to_string(),
+                        "age_ms": pending.age.as_millis() as u64,
+                        "replies": pending.replies,
+                    });
+                    let _ = query.reply(Ok(Sample::new(key_expr, value))).res_sync();
+                }
+            }
+        }
+        #[cfg(feature = "unstable")]
+        for entity in session.entities() {
+            if let Ok(id) = keyexpr::new(&entity.id.to_string()) {
+                let key_expr = *KE_PREFIX / own_zid / *KE_ENTITY / id;
+                if query.key_expr().intersects(&key_expr) {
+                    let value = serde_json::json!({
+                        "kind": entity.kind.to_string(),
+                        "key_expr": entity.key_expr.to_string(),
+                    });
+                    let _ = query.reply(Ok(Sample::new(key_expr, value))).res_sync();
+                }
+            }
+        }
+        #[cfg(feature = "unstable")]
+        on_subscribers_query(session, &query);
+        #[cfg(feature = "unstable")]
+        for publication in session.publications() {
+            if let Ok(id) = keyexpr::new(&publication.id.to_string()) {
+                let key_expr = *KE_PREFIX / own_zid / *KE_PUBLICATION / id;
+                if query.key_expr().intersects(&key_expr) {
+                    let value = serde_json::json!({
+                        "key_expr": publication.key_expr.to_string(),
+                        "congestion_control": format!("{:?}", publication.congestion_control),
+                        "priority": format!("{:?}", publication.priority),
+                        "destination": publication.destination,
+                    });
+                    let _ = query.reply(Ok(Sample::new(key_expr, value))).res_sync();
+                }
+            }
+        }
     }
 }
 
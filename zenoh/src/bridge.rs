@@ -0,0 +1,350 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Forwarding of selected key expressions between two sessions.
+//!
+//! A [`Bridge`], declared with [`zenoh::bridge`](crate::bridge()), connects two sessions
+//! typically opened on isolated zenoh domains (e.g. distinct scouting groups). Each of its
+//! [`BridgeRule`]s subscribes to a key expression on one or both sessions and re-publishes
+//! the received samples on the other session, like [`Publisher::forward`] does.
+//!
+//! The samples already bridged into a session are never forwarded back to it,
+//! so that bridges in both directions, or chained bridges, don't form loops.
+//! The bridge relies on the subscriptions of the sessions, which are restored
+//! when they reconnect to their networks.
+use crate::prelude::sync::*;
+use crate::publication::{Publisher, DEFAULT_MAX_PROVENANCE_HOPS};
+use crate::subscriber::Subscriber;
+use crate::SessionRef;
+use std::future::Ready;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use zenoh_core::{bail, AsyncResolve, Resolvable, Resolve, ResolveClosure, SyncResolve};
+use zenoh_result::ZResult;
+
+/// The sessions a [`BridgeRule`] forwards samples from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeDirection {
+    /// Forward the samples published on the first session to the second one.
+    AToB,
+    /// Forward the samples published on the second session to the first one.
+    BToA,
+    /// Forward the samples in both directions.
+    Both,
+}
+
+impl BridgeDirection {
+    fn a_to_b(self) -> bool {
+        matches!(self, BridgeDirection::AToB | BridgeDirection::Both)
+    }
+
+    fn b_to_a(self) -> bool {
+        matches!(self, BridgeDirection::BToA | BridgeDirection::Both)
+    }
+}
+
+/// A key expression forwarded by a [`Bridge`].
+///
+/// # Examples
+/// ```
+/// use zenoh::bridge::{BridgeDirection, BridgeRule};
+/// use zenoh::prelude::sync::*;
+///
+/// let rule = BridgeRule::new("site/a/**", BridgeDirection::AToB)
+///     .remap("site/a", "remote/a")
+///     .priority(Priority::DataLow);
+/// ```
+#[derive(Debug)]
+pub struct BridgeRule {
+    key_expr: ZResult<KeyExpr<'static>>,
+    direction: BridgeDirection,
+    remap: Option<ZResult<(KeyExpr<'static>, KeyExpr<'static>)>>,
+    congestion_control: Option<CongestionControl>,
+    priority: Option<Priority>,
+}
+
+impl BridgeRule {
+    /// Forward the samples published on `key_expr` in the given direction.
+    pub fn new<TryIntoKeyExpr>(key_expr: TryIntoKeyExpr, direction: BridgeDirection) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'static>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'static>>>::Error: Into<zenoh_result::Error>,
+    {
+        BridgeRule {
+            key_expr: key_expr.try_into().map_err(Into::into),
+            direction,
+            remap: None,
+            congestion_control: None,
+            priority: None,
+        }
+    }
+
+    /// Re-publish the samples whose key expression starts with the `from` chunks
+    /// with these chunks replaced by `to`.
+    ///
+    /// The other samples are re-published on their own key expression.
+    /// Neither `from` nor `to` may contain wildcards.
+    pub fn remap<TryIntoFrom, TryIntoTo>(mut self, from: TryIntoFrom, to: TryIntoTo) -> Self
+    where
+        TryIntoFrom: TryInto<KeyExpr<'static>>,
+        <TryIntoFrom as TryInto<KeyExpr<'static>>>::Error: Into<zenoh_result::Error>,
+        TryIntoTo: TryInto<KeyExpr<'static>>,
+        <TryIntoTo as TryInto<KeyExpr<'static>>>::Error: Into<zenoh_result::Error>,
+    {
+        let from = from.try_into().map_err(Into::into);
+        let to = to.try_into().map_err(Into::into);
+        self.remap = Some(from.and_then(|from| Ok((from, to?))));
+        self
+    }
+
+    /// Change the `congestion_control` of the re-published samples
+    /// (the one of the received sample by default).
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = Some(congestion_control);
+        self
+    }
+
+    /// Change the priority of the re-published samples (the one of the received sample by default).
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+}
+
+/// The forwarding statistics of a [`BridgeRule`], in both directions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BridgeRuleStats {
+    /// The number of samples re-published.
+    pub forwarded: u64,
+    /// The number of samples not re-published as they were already bridged into their session.
+    pub looped: u64,
+    /// The number of samples that failed to be re-published.
+    pub failed: u64,
+}
+
+#[derive(Default)]
+struct RuleCounters {
+    forwarded: AtomicU64,
+    looped: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl RuleCounters {
+    fn stats(&self) -> BridgeRuleStats {
+        BridgeRuleStats {
+            forwarded: self.forwarded.load(Ordering::Relaxed),
+            looped: self.looped.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A builder returned by [`zenoh::bridge`](crate::bridge()) used to declare a [`Bridge`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct BridgeBuilder {
+    pub(crate) session_a: Arc<Session>,
+    pub(crate) session_b: Arc<Session>,
+    pub(crate) rules: Vec<BridgeRule>,
+}
+
+impl Resolvable for BridgeBuilder {
+    type To = ZResult<Bridge>;
+}
+
+impl SyncResolve for BridgeBuilder {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let mut rules = vec![];
+        for rule in self.rules {
+            let forwarding = Forwarding {
+                remap: match rule.remap {
+                    Some(Ok((from, to))) if from.is_wild() || to.is_wild() => {
+                        bail!("Invalid bridge remapping {} -> {}: wildcards", from, to)
+                    }
+                    remap => remap.transpose()?,
+                },
+                congestion_control: rule.congestion_control,
+                priority: rule.priority,
+                counters: Arc::default(),
+            };
+            let key_expr = rule.key_expr?;
+            let mut subscribers = vec![];
+            if rule.direction.a_to_b() {
+                subscribers.push(forwarding.clone().declare(
+                    &self.session_a,
+                    &self.session_b,
+                    &key_expr,
+                )?);
+            }
+            if rule.direction.b_to_a() {
+                subscribers.push(forwarding.clone().declare(
+                    &self.session_b,
+                    &self.session_a,
+                    &key_expr,
+                )?);
+            }
+            rules.push(BridgedRule {
+                counters: forwarding.counters,
+                subscribers,
+            });
+        }
+        Ok(Bridge { rules })
+    }
+}
+
+impl AsyncResolve for BridgeBuilder {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+#[derive(Clone)]
+struct Forwarding {
+    remap: Option<(KeyExpr<'static>, KeyExpr<'static>)>,
+    congestion_control: Option<CongestionControl>,
+    priority: Option<Priority>,
+    counters: Arc<RuleCounters>,
+}
+
+impl Forwarding {
+    fn key_expr(&self, key_expr: &KeyExpr<'static>) -> ZResult<KeyExpr<'static>> {
+        let Some((from, to)) = &self.remap else {
+            return Ok(key_expr.clone());
+        };
+        match key_expr.as_str().strip_prefix(from.as_str()) {
+            Some("") => Ok(to.clone()),
+            Some(rest) if rest.starts_with('/') => to.join(&rest[1..]),
+            _ => Ok(key_expr.clone()),
+        }
+    }
+
+    fn forward(&self, from: &Session, to: &Arc<Session>, sample: Sample) {
+        let counters = &self.counters;
+        let (from_zid, to_zid) = (from.zid(), to.zid());
+        if sample
+            .provenance()
+            .iter()
+            .any(|hop| hop.zid == from_zid || hop.zid == to_zid)
+        {
+            counters.looped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let result = self.key_expr(&sample.key_expr).and_then(|key_expr| {
+            let publisher = Publisher {
+                id: to.next_publisher_id(),
+                session: SessionRef::Shared(to.clone()),
+                key_expr,
+                congestion_control: self
+                    .congestion_control
+                    .unwrap_or(sample.qos.congestion_control()),
+                priority: self.priority.unwrap_or(sample.qos.priority()),
+                destination: Locality::Any,
+                no_route: None,
+                loop_guard: false,
+                max_provenance_hops: DEFAULT_MAX_PROVENANCE_HOPS,
+                transform_out: None,
+                flow: None,
+                announcement: None,
+            };
+            publisher.forward(&sample).res_sync()
+        });
+        match result {
+            Ok(()) => counters.forwarded.fetch_add(1, Ordering::Relaxed),
+            Err(e) => {
+                tracing::warn!("Failed to bridge sample on {}: {}", sample.key_expr, e);
+                counters.failed.fetch_add(1, Ordering::Relaxed)
+            }
+        };
+    }
+
+    fn declare(
+        self,
+        from: &Arc<Session>,
+        to: &Arc<Session>,
+        key_expr: &KeyExpr<'static>,
+    ) -> ZResult<Subscriber<'static, ()>> {
+        let to = to.clone();
+        let source = from.clone();
+        from.declare_subscriber(key_expr.clone())
+            .callback(move |sample| self.forward(&source, &to, sample))
+            .res_sync()
+    }
+}
+
+struct BridgedRule {
+    counters: Arc<RuleCounters>,
+    subscribers: Vec<Subscriber<'static, ()>>,
+}
+
+/// Forwards selected key expressions between two sessions.
+///
+/// Bridges are closed when dropped.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::bridge::{BridgeDirection, BridgeRule};
+/// use zenoh::prelude::r#async::*;
+///
+/// let site = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let cloud = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let bridge = zenoh::bridge(
+///     site,
+///     cloud,
+///     [BridgeRule::new("telemetry/**", BridgeDirection::AToB).remap("telemetry", "site/telemetry")],
+/// )
+/// .res()
+/// .await
+/// .unwrap();
+/// println!("{:?}", bridge.stats());
+/// # }
+/// ```
+pub struct Bridge {
+    rules: Vec<BridgedRule>,
+}
+
+impl Bridge {
+    /// The forwarding statistics of the rules of this Bridge, in their declaration order.
+    pub fn stats(&self) -> Vec<BridgeRuleStats> {
+        self.rules
+            .iter()
+            .map(|rule| rule.counters.stats())
+            .collect()
+    }
+
+    /// Close this Bridge, undeclaring its subscribers.
+    pub fn close(self) -> impl Resolve<ZResult<()>> {
+        ResolveClosure::new(move || {
+            for rule in self.rules {
+                for subscriber in rule.subscribers {
+                    subscriber.undeclare().res_sync()?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+impl std::fmt::Debug for Bridge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bridge")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
@@ -0,0 +1,316 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Session events.
+//!
+//! see [`Session::event_listener`](crate::Session::event_listener)
+use crate::handlers::{locked, Callback, DefaultHandler, IntoCallbackReceiverPair};
+use crate::{SessionRef, Undeclarable};
+use std::future::Ready;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use zenoh_core::{zread, zwrite, AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::ZResult;
+
+/// An event occurring on a [`Session`](crate::Session).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// An internal task of the session terminated abnormally.
+    ///
+    /// Recoverable tasks are restarted a bounded number of times. If a task can't be recovered,
+    /// the session is marked as unhealthy (see [`Session::is_healthy`](crate::Session::is_healthy)).
+    InternalTaskFailed {
+        /// The name of the failed task.
+        task: String,
+        /// A description of the failure.
+        error: String,
+    },
+    /// A received sample was dropped because its payload exceeded the `max_payload_size`
+    /// of the session.
+    PayloadTooLarge {
+        /// The key expression of the dropped sample.
+        key_expr: String,
+        /// The size of the payload, in bytes.
+        size: usize,
+        /// The `max_payload_size` of the session, in bytes.
+        limit: usize,
+    },
+}
+
+/// The listeners of the events emitted by a [`Session`](crate::Session) itself,
+/// rather than by the internal tasks of its runtime.
+#[derive(Default)]
+pub(crate) struct SessionEvents {
+    next_id: AtomicUsize,
+    listeners: RwLock<Vec<(usize, Callback<'static, SessionEvent>)>>,
+}
+
+impl SessionEvents {
+    fn add(&self, callback: Callback<'static, SessionEvent>) -> usize {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        zwrite!(self.listeners).push((id, callback));
+        id
+    }
+
+    fn remove(&self, id: usize) {
+        zwrite!(self.listeners).retain(|(listener_id, _)| *listener_id != id);
+    }
+
+    pub(crate) fn emit(&self, event: SessionEvent) {
+        let callbacks: Vec<_> = zread!(self.listeners)
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(event.clone());
+        }
+    }
+}
+
+impl std::fmt::Debug for SessionEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionEvents").finish_non_exhaustive()
+    }
+}
+
+/// A builder for initializing a [`SessionEventListener`].
+#[derive(Debug)]
+pub struct SessionEventListenerBuilder<'a, Handler> {
+    pub(crate) session: SessionRef<'a>,
+    pub handler: Handler,
+}
+
+impl<'a> SessionEventListenerBuilder<'a, DefaultHandler> {
+    /// Receive the [`SessionEvent`]s with a callback.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let listener = session
+    ///     .event_listener()
+    ///     .callback(|event| println!("Session event: {:?}", event))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn callback<Callback>(self, callback: Callback) -> SessionEventListenerBuilder<'a, Callback>
+    where
+        Callback: Fn(SessionEvent) + Send + Sync + 'static,
+    {
+        SessionEventListenerBuilder {
+            session: self.session,
+            handler: callback,
+        }
+    }
+
+    /// Receive the [`SessionEvent`]s with a mutable callback.
+    #[inline]
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> SessionEventListenerBuilder<'a, impl Fn(SessionEvent) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(SessionEvent) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Receive the [`SessionEvent`]s with a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let listener = session
+    ///     .event_listener()
+    ///     .with(flume::bounded(32))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(event) = listener.recv_async().await {
+    ///     println!("Session event: {:?}", event);
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn with<Handler>(self, handler: Handler) -> SessionEventListenerBuilder<'a, Handler>
+    where
+        Handler: IntoCallbackReceiverPair<'static, SessionEvent>,
+    {
+        SessionEventListenerBuilder {
+            session: self.session,
+            handler,
+        }
+    }
+}
+
+impl<'a, Handler> Resolvable for SessionEventListenerBuilder<'a, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, SessionEvent> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<SessionEventListener<'a, Handler::Receiver>>;
+}
+
+impl<'a, Handler> SyncResolve for SessionEventListenerBuilder<'a, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, SessionEvent> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let (callback, receiver): (Callback<'static, SessionEvent>, _) =
+            self.handler.into_cb_receiver_pair();
+        let event_id = self.session.events.add(callback.clone());
+        let id = self.session.runtime.add_task_failure_handler(Arc::new(
+            move |task: &str, error: &str| {
+                callback(SessionEvent::InternalTaskFailed {
+                    task: task.to_string(),
+                    error: error.to_string(),
+                })
+            },
+        ));
+        Ok(SessionEventListener {
+            listener: SessionEventListenerInner {
+                session: self.session,
+                id,
+                event_id,
+                alive: true,
+            },
+            receiver,
+        })
+    }
+}
+
+impl<'a, Handler> AsyncResolve for SessionEventListenerBuilder<'a, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, SessionEvent> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+pub(crate) struct SessionEventListenerInner<'a> {
+    session: SessionRef<'a>,
+    id: usize,
+    event_id: usize,
+    alive: bool,
+}
+
+impl SessionEventListenerInner<'_> {
+    fn remove(&self) {
+        self.session.runtime.remove_task_failure_handler(self.id);
+        self.session.events.remove(self.event_id);
+    }
+}
+
+impl Drop for SessionEventListenerInner<'_> {
+    fn drop(&mut self) {
+        if self.alive {
+            self.remove();
+        }
+    }
+}
+
+/// A listener that receives the [`SessionEvent`]s of a [`Session`](crate::Session).
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let listener = session.event_listener().res().await.unwrap();
+/// while let Ok(event) = listener.recv_async().await {
+///     println!("Session event: {:?}", event);
+/// }
+/// # }
+/// ```
+pub struct SessionEventListener<'a, Receiver> {
+    pub(crate) listener: SessionEventListenerInner<'a>,
+    pub receiver: Receiver,
+}
+
+impl<'a, Receiver> SessionEventListener<'a, Receiver> {
+    /// Close a [`SessionEventListener`].
+    ///
+    /// SessionEventListeners are automatically closed when dropped, but you may want to use this function to handle errors or
+    /// close the SessionEventListener asynchronously.
+    #[inline]
+    pub fn undeclare(self) -> SessionEventListenerUndeclaration<'a> {
+        Undeclarable::undeclare_inner(self, ())
+    }
+}
+
+impl<'a, T> Undeclarable<(), SessionEventListenerUndeclaration<'a>>
+    for SessionEventListener<'a, T>
+{
+    fn undeclare_inner(self, _: ()) -> SessionEventListenerUndeclaration<'a> {
+        SessionEventListenerUndeclaration {
+            listener: self.listener,
+        }
+    }
+}
+
+impl<Receiver> std::ops::Deref for SessionEventListener<'_, Receiver> {
+    type Target = Receiver;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> std::ops::DerefMut for SessionEventListener<'_, Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+pub struct SessionEventListenerUndeclaration<'a> {
+    listener: SessionEventListenerInner<'a>,
+}
+
+impl Resolvable for SessionEventListenerUndeclaration<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for SessionEventListenerUndeclaration<'_> {
+    fn res_sync(mut self) -> <Self as Resolvable>::To {
+        self.listener.alive = false;
+        self.listener.remove();
+        Ok(())
+    }
+}
+
+impl AsyncResolve for SessionEventListenerUndeclaration<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
@@ -0,0 +1,330 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Human readable rendering of samples.
+//!
+//! see [`render`]
+use crate::prelude::{KnownEncoding, Sample, SplitBuffer};
+use std::fmt::Write;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_DIM: &str = "\x1b[2m";
+const ANSI_KEY: &str = "\x1b[34m";
+const ANSI_STRING: &str = "\x1b[32m";
+const ANSI_NUMBER: &str = "\x1b[33m";
+const ANSI_LITERAL: &str = "\x1b[35m";
+
+/// The options of [`render`].
+///
+/// # Examples
+/// ```
+/// use zenoh::fmt::RenderOptions;
+///
+/// let options = RenderOptions::default().indent(4).max_bytes(1024).color(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    indent: usize,
+    max_bytes: Option<usize>,
+    hex_width: usize,
+    color: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            indent: 2,
+            max_bytes: None,
+            hex_width: 16,
+            color: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Change the number of spaces per indentation level of JSON payloads (2 by default).
+    #[inline]
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Only render the first `max_bytes` bytes of the payload (unlimited by default).
+    ///
+    /// Truncated JSON payloads are rendered as plain text.
+    #[inline]
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Change the number of bytes per line of hex dumps (16 by default).
+    #[inline]
+    pub fn hex_width(mut self, hex_width: usize) -> Self {
+        self.hex_width = hex_width.max(1);
+        self
+    }
+
+    /// Enable ANSI colors in the rendering (disabled by default).
+    #[inline]
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn paint(&self, out: &mut String, color: &str, text: &str) {
+        if self.color {
+            out.push_str(color);
+            out.push_str(text);
+            out.push_str(ANSI_RESET);
+        } else {
+            out.push_str(text);
+        }
+    }
+}
+
+/// Render the value of a [`Sample`] according to its encoding.
+///
+/// - JSON payloads are pretty-printed;
+/// - integer and float payloads are rendered as numbers;
+/// - textual payloads are rendered as is;
+/// - binary payloads, and textual payloads that aren't valid UTF-8, are rendered as a hex dump.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::fmt::RenderOptions;
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+/// # session.put("key/expression", "value").res().await.unwrap();
+/// let sample = subscriber.recv_async().await.unwrap();
+/// println!("{}", zenoh::fmt::render(&sample, RenderOptions::default()));
+/// # }
+/// ```
+pub fn render(sample: &Sample, options: RenderOptions) -> String {
+    let payload = sample.value.payload.contiguous();
+    let (bytes, truncated) = match options.max_bytes {
+        Some(max) if payload.len() > max => (&payload[..max], payload.len() - max),
+        _ => (&payload[..], 0),
+    };
+
+    let mut out = String::new();
+    match sample.value.encoding.prefix() {
+        KnownEncoding::AppJson | KnownEncoding::TextJson if truncated == 0 => {
+            match serde_json::from_slice::<serde_json::Value>(bytes) {
+                Ok(json) => render_json(&mut out, &json, 0, &options),
+                Err(_) => render_text(&mut out, bytes, &options),
+            }
+        }
+        KnownEncoding::AppInteger => match parse::<i64>(bytes) {
+            Some(i) => options.paint(&mut out, ANSI_NUMBER, &i.to_string()),
+            None => render_text(&mut out, bytes, &options),
+        },
+        KnownEncoding::AppFloat => match parse::<f64>(bytes) {
+            Some(f) => options.paint(&mut out, ANSI_NUMBER, &f.to_string()),
+            None => render_text(&mut out, bytes, &options),
+        },
+        KnownEncoding::AppOctetStream
+        | KnownEncoding::AppCustom
+        | KnownEncoding::ImageJpeg
+        | KnownEncoding::ImagePng
+        | KnownEncoding::ImageGif => render_hex(&mut out, bytes, &options),
+        _ => render_text(&mut out, bytes, &options),
+    }
+    if truncated > 0 {
+        options.paint(&mut out, ANSI_DIM, &format!("... ({truncated} more bytes)"));
+    }
+    out
+}
+
+fn parse<T: std::str::FromStr>(bytes: &[u8]) -> Option<T> {
+    std::str::from_utf8(bytes).ok()?.trim().parse().ok()
+}
+
+fn render_text(out: &mut String, bytes: &[u8], options: &RenderOptions) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => out.push_str(text),
+        // The payload may have been truncated in the middle of a character
+        Err(e) if e.error_len().is_none() => {
+            out.push_str(std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap())
+        }
+        Err(_) => render_hex(out, bytes, options),
+    }
+}
+
+fn render_hex(out: &mut String, bytes: &[u8], options: &RenderOptions) {
+    let width = options.hex_width;
+    for (i, line) in bytes.chunks(width).enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        options.paint(out, ANSI_DIM, &format!("{:08x}", i * width));
+        out.push(' ');
+        for byte in line {
+            let _ = write!(out, " {byte:02x}");
+        }
+        for _ in line.len()..width {
+            out.push_str("   ");
+        }
+        let ascii: String = line
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        out.push_str("  |");
+        options.paint(out, ANSI_STRING, &ascii);
+        out.push('|');
+    }
+}
+
+fn render_json(out: &mut String, json: &serde_json::Value, level: usize, options: &RenderOptions) {
+    let newline = |out: &mut String, level: usize| {
+        out.push('\n');
+        out.push_str(&" ".repeat(level * options.indent));
+    };
+    match json {
+        serde_json::Value::Null | serde_json::Value::Bool(_) => {
+            options.paint(out, ANSI_LITERAL, &json.to_string())
+        }
+        serde_json::Value::Number(n) => options.paint(out, ANSI_NUMBER, &n.to_string()),
+        serde_json::Value::String(_) => options.paint(out, ANSI_STRING, &json.to_string()),
+        serde_json::Value::Array(array) if array.is_empty() => out.push_str("[]"),
+        serde_json::Value::Array(array) => {
+            out.push('[');
+            for (i, item) in array.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, level + 1);
+                render_json(out, item, level + 1, options);
+            }
+            newline(out, level);
+            out.push(']');
+        }
+        serde_json::Value::Object(object) if object.is_empty() => out.push_str("{}"),
+        serde_json::Value::Object(object) => {
+            out.push('{');
+            for (i, (key, value)) in object.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, level + 1);
+                options.paint(
+                    out,
+                    ANSI_KEY,
+                    &serde_json::Value::from(key.as_str()).to_string(),
+                );
+                out.push_str(": ");
+                render_json(out, value, level + 1, options);
+            }
+            newline(out, level);
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Encoding, KeyExpr, Value};
+
+    fn sample(payload: &[u8], encoding: Encoding) -> Sample {
+        Sample::new(
+            KeyExpr::try_from("test/render").unwrap(),
+            Value::from(payload.to_vec()).encoding(encoding),
+        )
+    }
+
+    #[test]
+    fn render_json() {
+        let sample = sample(br#"{"a":[1,true],"b":{}}"#, Encoding::APP_JSON);
+        assert_eq!(
+            render(&sample, RenderOptions::default()),
+            "{\n  \"a\": [\n    1,\n    true\n  ],\n  \"b\": {}\n}"
+        );
+        assert_eq!(
+            render(&sample, RenderOptions::default().indent(0)),
+            "{\n\"a\": [\n1,\ntrue\n],\n\"b\": {}\n}"
+        );
+        let colored = render(&sample, RenderOptions::default().color(true));
+        assert!(colored.contains("\x1b[34m\"a\"\x1b[0m"));
+
+        // Invalid JSON is rendered as text
+        let sample = self::sample(b"{invalid", Encoding::APP_JSON);
+        assert_eq!(render(&sample, RenderOptions::default()), "{invalid");
+    }
+
+    #[test]
+    fn render_numbers() {
+        let sample = sample(b"42", Encoding::APP_INTEGER);
+        assert_eq!(render(&sample, RenderOptions::default()), "42");
+        let sample = self::sample(b"1.5", Encoding::APP_FLOAT);
+        assert_eq!(render(&sample, RenderOptions::default()), "1.5");
+        let sample = self::sample(b"NaN?", Encoding::APP_INTEGER);
+        assert_eq!(render(&sample, RenderOptions::default()), "NaN?");
+    }
+
+    #[test]
+    fn render_hex() {
+        let sample = sample(b"\x00\x01abc", Encoding::APP_OCTET_STREAM);
+        assert_eq!(
+            render(&sample, RenderOptions::default().hex_width(4)),
+            "00000000  00 01 61 62  |..ab|\n00000004  63           |c|"
+        );
+    }
+
+    #[test]
+    fn render_truncated() {
+        let sample = sample(b"hello world", Encoding::TEXT_PLAIN);
+        assert_eq!(
+            render(&sample, RenderOptions::default().max_bytes(5)),
+            "hello... (6 more bytes)"
+        );
+        assert_eq!(
+            render(&sample, RenderOptions::default().max_bytes(64)),
+            "hello world"
+        );
+
+        // Truncating in the middle of a multi-bytes character
+        let sample = self::sample("é".as_bytes(), Encoding::TEXT_PLAIN);
+        assert_eq!(
+            render(&sample, RenderOptions::default().max_bytes(1)),
+            "... (1 more bytes)"
+        );
+
+        // Truncated JSON is rendered as text
+        let sample = self::sample(br#"{"a":1}"#, Encoding::APP_JSON);
+        assert_eq!(
+            render(&sample, RenderOptions::default().max_bytes(4)),
+            "{\"a\"... (3 more bytes)"
+        );
+    }
+
+    #[test]
+    fn render_invalid_utf8_text() {
+        let sample = sample(b"ab\xffcd", Encoding::TEXT_PLAIN);
+        assert_eq!(
+            render(&sample, RenderOptions::default()),
+            "00000000  61 62 ff 63 64                                   |ab.cd|"
+        );
+    }
+}
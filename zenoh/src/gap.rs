@@ -0,0 +1,328 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Detection of the samples missed by a subscriber, see
+//! [`SubscriberBuilder::on_gap`](crate::subscriber::SubscriberBuilder::on_gap).
+//!
+//! Every put or delete of a session carries the [`ZenohId`] of the session and a sequence number,
+//! incremented for each write on the same key: they are exposed as the
+//! [`SourceInfo`](crate::sample::SourceInfo) of the received [`Sample`]s. A subscriber receiving,
+//! from the same source and on the same key, a sequence number past the one following the
+//! previous sample detects that the samples in between were missed.
+//!
+//! The same sequence numbers let a subscriber deliver the samples in their publication order,
+//! see [`SubscriberBuilder::ordered`](crate::subscriber::SubscriberBuilder::ordered).
+use crate::handlers::Callback;
+use crate::prelude::{KeyExpr, Sample};
+use crate::sample::SourceSn;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+use zenoh_protocol::core::ZenohId;
+
+/// A discontinuity in the sequence numbers of the samples received from a source on a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapInfo {
+    /// The [`ZenohId`] of the session that published the samples.
+    pub source: ZenohId,
+    /// The key of the samples.
+    pub key_expr: KeyExpr<'static>,
+    /// The sequence number following the one of the previous sample received.
+    pub expected: SourceSn,
+    /// The sequence number of the sample received instead.
+    pub received: SourceSn,
+}
+
+impl GapInfo {
+    /// Returns the number of samples missed.
+    pub fn missed(&self) -> SourceSn {
+        (self.received as u32).wrapping_sub(self.expected as u32) as SourceSn
+    }
+}
+
+/// The number of keys a session numbers its writes on at once.
+///
+/// Past it, the key written on the least recently forgets its sequence number: its next write
+/// restarts from 0, which subscribers treat as a restarted source rather than as a gap.
+const MAX_SEQUENCED_KEYS: usize = 1024;
+
+/// The sequence numbers of the writes of a session, per key.
+#[derive(Debug, Default)]
+pub(crate) struct SourceSequencer {
+    sns: Mutex<HashMap<OwnedKeyExpr, (u32, Instant)>>,
+}
+
+impl SourceSequencer {
+    /// Returns the sequence number of the next write on `key_expr`.
+    pub(crate) fn next(&self, key_expr: &keyexpr) -> u32 {
+        let now = Instant::now();
+        let mut sns = zlock!(self.sns);
+        if let Some((sn, written)) = sns.get_mut(key_expr) {
+            *sn = sn.wrapping_add(1);
+            *written = now;
+            return *sn;
+        }
+        if sns.len() >= MAX_SEQUENCED_KEYS {
+            let oldest = sns
+                .iter()
+                .min_by_key(|(_, (_, written))| *written)
+                .map(|(key_expr, _)| key_expr.clone());
+            if let Some(oldest) = oldest {
+                sns.remove(&oldest);
+            }
+        }
+        sns.insert(key_expr.to_owned(), (0, now));
+        0
+    }
+}
+
+/// Checks the sequence numbers of the samples received by a subscriber, calling a callback
+/// for each discontinuity, see [`SubscriberBuilder::on_gap`](crate::subscriber::SubscriberBuilder::on_gap).
+///
+/// The last sequence number is kept for each source and key the subscriber received samples from.
+pub struct GapDetector {
+    on_gap: Callback<'static, GapInfo>,
+    last: Mutex<HashMap<ZenohId, HashMap<OwnedKeyExpr, u32>>>,
+}
+
+impl GapDetector {
+    /// Create a detector calling `on_gap` for each discontinuity.
+    pub fn new<OnGap>(on_gap: OnGap) -> Self
+    where
+        OnGap: Fn(GapInfo) + Send + Sync + 'static,
+    {
+        GapDetector {
+            on_gap: Arc::new(on_gap),
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn check(&self, sample: &Sample) -> Option<GapInfo> {
+        let source = sample.source_info.source_id?;
+        let sn = sample.source_info.source_sn? as u32;
+        let mut last = zlock!(self.last);
+        let sns = last.entry(source).or_default();
+        let previous = match sns.get_mut(sample.key_expr.as_keyexpr()) {
+            Some(previous) => std::mem::replace(previous, sn),
+            None => {
+                sns.insert(sample.key_expr.as_keyexpr().to_owned(), sn);
+                return None;
+            }
+        };
+        drop(last);
+        let expected = previous.wrapping_add(1);
+        // A sequence number behind the expected one is a duplicate or a restarted source,
+        // which are not gaps: the detection restarts from it
+        let ahead = sn.wrapping_sub(expected);
+        (ahead != 0 && ahead < u32::MAX / 2).then(|| GapInfo {
+            source,
+            key_expr: sample.key_expr.clone().into_owned(),
+            expected: expected as SourceSn,
+            received: sn as SourceSn,
+        })
+    }
+
+    pub(crate) fn wrap(self, callback: Callback<'static, Sample>) -> Callback<'static, Sample> {
+        Arc::new(move |sample: Sample| {
+            if let Some(gap) = self.check(&sample) {
+                (self.on_gap)(gap);
+            }
+            callback(sample)
+        })
+    }
+}
+
+impl std::fmt::Debug for GapDetector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GapDetector").finish_non_exhaustive()
+    }
+}
+
+/// The number of samples an [`OrderingBuffer`] holds back per source by default.
+pub const DEFAULT_ORDERING_CAPACITY: usize = 1024;
+
+/// Delivers the samples received by a subscriber in the order of their sequence numbers, per
+/// source and key, see [`SubscriberBuilder::ordered`](crate::subscriber::SubscriberBuilder::ordered).
+///
+/// The samples received ahead of a missing one are held back until it is received, or until
+/// they are held for longer than the window: the missing samples are then skipped. The samples
+/// received after the ones following them were delivered, and the samples without sequence
+/// number, are delivered on reception.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderingBuffer {
+    window: Duration,
+    capacity: usize,
+}
+
+impl OrderingBuffer {
+    /// Hold the samples received ahead of a missing one for at most `window`.
+    pub fn new(window: Duration) -> Self {
+        OrderingBuffer {
+            window,
+            capacity: DEFAULT_ORDERING_CAPACITY,
+        }
+    }
+
+    /// Hold at most `capacity` samples per source (at least 1), [`DEFAULT_ORDERING_CAPACITY`]
+    /// by default.
+    ///
+    /// Past it, the samples held for the longest are delivered, skipping the missing ones.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub(crate) fn wrap(self, callback: Callback<'static, Sample>) -> Callback<'static, Sample> {
+        let reorderer = Arc::new(Mutex::new(Reorderer {
+            buffer: self,
+            callback,
+            sources: HashMap::new(),
+        }));
+        // The held samples are also flushed without new receptions, until the subscriber is dropped
+        let period = (self.window / 2).max(Duration::from_millis(1));
+        let weak = Arc::downgrade(&reorderer);
+        zenoh_runtime::ZRuntime::Application.spawn(async move {
+            loop {
+                tokio::time::sleep(period).await;
+                match weak.upgrade() {
+                    Some(reorderer) => zlock!(reorderer).flush(Instant::now()),
+                    None => return,
+                }
+            }
+        });
+        // The lock is held while delivering, so that the samples of a key are never delivered concurrently
+        Arc::new(move |sample: Sample| zlock!(reorderer).push(sample, Instant::now()))
+    }
+}
+
+impl From<Duration> for OrderingBuffer {
+    fn from(window: Duration) -> Self {
+        OrderingBuffer::new(window)
+    }
+}
+
+struct Reorderer {
+    buffer: OrderingBuffer,
+    callback: Callback<'static, Sample>,
+    sources: HashMap<ZenohId, SourceStreams>,
+}
+
+/// The samples held back for a source.
+#[derive(Default)]
+struct SourceStreams {
+    held: usize,
+    streams: HashMap<OwnedKeyExpr, SnStream>,
+}
+
+/// The samples held back for a source and key, by sequence number.
+///
+/// The sequence numbers are extended past 32 bits, so that they keep increasing when they wrap.
+struct SnStream {
+    next: i64,
+    held: BTreeMap<i64, (Sample, Instant)>,
+}
+
+impl SnStream {
+    /// Deliver the held samples following the delivered ones.
+    fn release(&mut self, held: &mut usize, callback: &Callback<'static, Sample>) {
+        while let Some(entry) = self.held.first_entry() {
+            if *entry.key() != self.next {
+                return;
+            }
+            let (sample, _) = entry.remove();
+            *held -= 1;
+            self.next += 1;
+            callback(sample);
+        }
+    }
+
+    /// Skip the missing samples before the first one held, then deliver it and its followers.
+    fn skip(&mut self, held: &mut usize, callback: &Callback<'static, Sample>) {
+        if let Some((&sn, _)) = self.held.first_key_value() {
+            self.next = sn;
+            self.release(held, callback);
+        }
+    }
+
+    /// Returns when the first sample held was received.
+    fn oldest(&self) -> Option<Instant> {
+        self.held
+            .first_key_value()
+            .map(|(_, (_, received))| *received)
+    }
+}
+
+impl Reorderer {
+    fn push(&mut self, sample: Sample, now: Instant) {
+        let (Some(source), Some(sn)) = (sample.source_info.source_id, sample.source_info.source_sn)
+        else {
+            return (self.callback)(sample);
+        };
+        let sn = sn as u32;
+        let source = self.sources.entry(source).or_default();
+        let Some(stream) = source.streams.get_mut(sample.key_expr.as_keyexpr()) else {
+            source.streams.insert(
+                sample.key_expr.as_keyexpr().to_owned(),
+                SnStream {
+                    next: sn as i64 + 1,
+                    held: BTreeMap::new(),
+                },
+            );
+            return (self.callback)(sample);
+        };
+        let sn = stream.next + sn.wrapping_sub(stream.next as u32) as i32 as i64;
+        if sn < stream.next {
+            // Received too late, or duplicated
+            return (self.callback)(sample);
+        }
+        if sn == stream.next {
+            stream.next += 1;
+            (self.callback)(sample);
+            return stream.release(&mut source.held, &self.callback);
+        }
+        if stream.held.insert(sn, (sample, now)).is_none() {
+            source.held += 1;
+        }
+        if source.held > self.buffer.capacity {
+            let oldest = source
+                .streams
+                .values_mut()
+                .filter_map(|stream| Some((stream.oldest()?, stream)))
+                .min_by_key(|(received, _)| *received);
+            if let Some((_, stream)) = oldest {
+                stream.skip(&mut source.held, &self.callback);
+            }
+        }
+    }
+
+    /// Deliver the samples held for longer than the window.
+    fn flush(&mut self, now: Instant) {
+        let window = self.buffer.window;
+        for source in self.sources.values_mut() {
+            if source.held == 0 {
+                continue;
+            }
+            for stream in source.streams.values_mut() {
+                while stream
+                    .oldest()
+                    .is_some_and(|received| now.duration_since(received) >= window)
+                {
+                    stream.skip(&mut source.held, &self.callback);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The handler keeping the latest sample received on each key.
+use super::{Callback, IntoCallbackReceiverPair};
+use crate::prelude::{Sample, SampleKind};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use zenoh_core::{zread, zwrite};
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+
+/// The [`Handler`](crate::prelude::IntoCallbackReceiverPair) of the subscribers declared
+/// with [`with_cache`](crate::subscriber::SubscriberBuilder::with_cache).
+#[zenoh_macros::unstable]
+pub struct CacheHandler {
+    pub(crate) callback: Option<Callback<'static, Sample>>,
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for CacheHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheHandler")
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl IntoCallbackReceiverPair<'static, Sample> for CacheHandler {
+    type Receiver = SampleCache;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let cache = SampleCache::new();
+        let receiver = cache.clone();
+        let forward = self.callback;
+        (
+            Arc::new(move |sample: Sample| match forward.as_ref() {
+                Some(forward) => {
+                    cache.update(sample.clone());
+                    forward(sample)
+                }
+                None => cache.update(sample),
+            }),
+            receiver,
+        )
+    }
+}
+
+#[zenoh_macros::unstable]
+const CACHE_SHARDS: usize = 16;
+
+/// The latest sample received on each key by a [`CachedSubscriber`](crate::subscriber::CachedSubscriber).
+///
+/// The keys are spread over several shards, each behind its own lock, so that reading the cache
+/// only blocks the reception of the samples on the keys of the shard being read.
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct SampleCache {
+    shards: Arc<[RwLock<HashMap<OwnedKeyExpr, Sample>>]>,
+}
+
+#[zenoh_macros::unstable]
+impl SampleCache {
+    fn new() -> Self {
+        SampleCache {
+            shards: (0..CACHE_SHARDS)
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, key_expr: &keyexpr) -> &RwLock<HashMap<OwnedKeyExpr, Sample>> {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key_expr.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    fn update(&self, sample: Sample) {
+        let shard = self.shard(&sample.key_expr);
+        match sample.kind {
+            SampleKind::Put => {
+                zwrite!(shard).insert(sample.key_expr.as_keyexpr().to_owned(), sample);
+            }
+            SampleKind::Delete => {
+                zwrite!(shard).remove(sample.key_expr.as_keyexpr());
+            }
+        }
+    }
+
+    /// Returns the latest sample received on `key_expr`, if any and not deleted since.
+    pub fn get(&self, key_expr: &keyexpr) -> Option<Sample> {
+        zread!(self.shard(key_expr)).get(key_expr).cloned()
+    }
+
+    /// Returns the latest sample received on each key, in no particular order.
+    pub fn snapshot(&self) -> Vec<Sample> {
+        self.shards
+            .iter()
+            .flat_map(|shard| zread!(shard).values().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// Returns the number of keys in the cache.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| zread!(shard).len()).sum()
+    }
+
+    /// Returns `true` if the cache holds no sample.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for SampleCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleCache")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,85 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The handler running an async callback on the samples.
+use super::{Callback, IntoCallbackReceiverPair};
+use crate::prelude::Sample;
+use futures::StreamExt;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+#[zenoh_macros::unstable]
+pub(crate) const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+/// The [`Handler`](crate::prelude::IntoCallbackReceiverPair) of the subscribers declared
+/// with [`callback_async`](crate::subscriber::SubscriberBuilder::callback_async).
+#[zenoh_macros::unstable]
+pub struct CallbackAsync<F> {
+    pub(crate) callback: F,
+    pub(crate) max_in_flight: usize,
+}
+
+#[zenoh_macros::unstable]
+impl<F> fmt::Debug for CallbackAsync<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackAsync")
+            .field("max_in_flight", &self.max_in_flight)
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<F, Fut> IntoCallbackReceiverPair<'static, Sample> for CallbackAsync<F>
+where
+    F: Fn(Sample) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    type Receiver = ();
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        use futures::FutureExt;
+        use std::panic::AssertUnwindSafe;
+
+        // The channel blocks the delivery of samples while the task has too many pending futures
+        let (sender, receiver) = flume::bounded::<Sample>(self.max_in_flight);
+        let callback = self.callback;
+        // The task ends once the subscriber, and thus the sender, is dropped
+        zenoh_runtime::ZRuntime::Application.spawn(receiver.into_stream().for_each_concurrent(
+            self.max_in_flight,
+            move |sample| {
+                let key_expr = sample.key_expr.clone();
+                AssertUnwindSafe(callback(sample))
+                    .catch_unwind()
+                    .map(move |result| {
+                        if let Err(panic) = result {
+                            let error =
+                                crate::net::runtime::supervisor::panic_message(panic.as_ref());
+                            tracing::error!(
+                                "Async callback of subscriber panicked on sample {}: {}",
+                                key_expr,
+                                error
+                            );
+                        }
+                    })
+            },
+        ));
+        let callback = move |sample| {
+            if let Err(e) = sender.send(sample) {
+                tracing::error!("{}", e);
+            }
+        };
+        (Arc::new(callback), ())
+    }
+}
@@ -0,0 +1,193 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The bounded and unbounded sample channels of the subscribers, and their overflow policies.
+use super::{Callback, IntoCallbackReceiverPair};
+use crate::prelude::Sample;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh_core::zlock;
+
+/// How a [`SampleChannel`] handles the samples received while it is full.
+///
+/// Dropping samples breaks the end-to-end guarantees of [`Reliability::Reliable`](crate::subscriber::Reliability::Reliable) subscriptions:
+/// the samples are reliably received by the session, but not by the application.
+/// Conversely, [`OverflowPolicy::Block`] preserves them by blocking the reception of the session,
+/// which applies backpressure to the transports: with reliable subscriptions, publishers using
+/// [`CongestionControl::Block`](crate::publication::CongestionControl::Block) are eventually blocked,
+/// and the other subscribers of the session stop receiving samples until the channel has room again.
+#[zenoh_macros::unstable]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Block the delivery of samples until the channel has room.
+    #[default]
+    Block,
+    /// Drop the received sample.
+    DropNewest,
+    /// Drop the oldest sample of the channel to make room for the received sample.
+    DropOldest,
+}
+
+/// The [`Handler`](crate::prelude::IntoCallbackReceiverPair) of the subscribers declared
+/// with [`channel_size`](crate::subscriber::SubscriberBuilder::channel_size), [`unbounded`](crate::subscriber::SubscriberBuilder::unbounded)
+/// or [`on_full`](crate::subscriber::SubscriberBuilder::on_full).
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct SampleChannel {
+    pub(crate) capacity: Option<usize>,
+    pub(crate) on_full: OverflowPolicy,
+    pub(crate) dropped: Arc<AtomicUsize>,
+    pub(crate) flow_control: bool,
+    pub(crate) high_watermark: Option<usize>,
+    pub(crate) low_watermark: Option<usize>,
+    pub(crate) occupancy: Arc<ChannelOccupancy>,
+}
+
+#[zenoh_macros::unstable]
+impl Default for SampleChannel {
+    fn default() -> Self {
+        SampleChannel {
+            capacity: Some(*crate::API_DATA_RECEPTION_CHANNEL_SIZE),
+            on_full: OverflowPolicy::default(),
+            dropped: Arc::new(AtomicUsize::new(0)),
+            flow_control: false,
+            high_watermark: None,
+            low_watermark: None,
+            occupancy: Arc::new(ChannelOccupancy::default()),
+        }
+    }
+}
+
+/// How often a flow-controlled [`SampleChannel`] checks whether it was drained.
+#[cfg(feature = "unstable")]
+const FLOW_CONTROL_POLL_PERIOD: Duration = Duration::from_millis(1);
+
+#[zenoh_macros::unstable]
+impl SampleChannel {
+    /// The high and low watermarks of the flow control of this channel.
+    pub(crate) fn watermarks(&self) -> (usize, usize) {
+        let high = self
+            .high_watermark
+            .or(self.capacity)
+            .unwrap_or(*crate::API_DATA_RECEPTION_CHANNEL_SIZE);
+        let low = self.low_watermark.unwrap_or(high / 2);
+        (high, low)
+    }
+
+    fn flow_controlled(
+        self,
+        on_drop: Callback<'static, Sample>,
+    ) -> (Callback<'static, Sample>, flume::Receiver<Sample>) {
+        let (high, low) = self.watermarks();
+        let low = low.min(high.saturating_sub(1));
+        let (sender, receiver) = flume::bounded(high.max(1));
+        self.occupancy.track(&sender);
+        let callback = move |sample| {
+            if sender.len() >= high {
+                // Stop reading the transports until the channel is drained down to the low watermark
+                while sender.len() > low && !sender.is_disconnected() {
+                    std::thread::sleep(FLOW_CONTROL_POLL_PERIOD);
+                }
+            }
+            if let Err(e) = sender.send(sample) {
+                tracing::error!("{}", e);
+                on_drop(e.into_inner());
+            }
+        };
+        (Arc::new(callback), receiver)
+    }
+}
+
+/// The number of samples queued in the channel of a [`SampleChannel`] handler.
+#[zenoh_macros::unstable]
+#[derive(Default)]
+pub struct ChannelOccupancy(Mutex<Option<flume::WeakSender<Sample>>>);
+
+#[zenoh_macros::unstable]
+impl ChannelOccupancy {
+    fn track(&self, sender: &flume::Sender<Sample>) {
+        *zlock!(self.0) = Some(sender.downgrade());
+    }
+
+    /// The number of samples currently queued in the channel, 0 once it is closed.
+    pub fn get(&self) -> usize {
+        zlock!(self.0)
+            .as_ref()
+            .and_then(flume::WeakSender::upgrade)
+            .map_or(0, |sender| sender.len())
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for ChannelOccupancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ChannelOccupancy")
+            .field(&self.get())
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl IntoCallbackReceiverPair<'static, Sample> for SampleChannel {
+    type Receiver = flume::Receiver<Sample>;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        self.into_cb_receiver_pair_with_drop(Arc::new(|_| ()))
+    }
+
+    fn into_cb_receiver_pair_with_drop(
+        self,
+        on_drop: Callback<'static, Sample>,
+    ) -> (Callback<'static, Sample>, Self::Receiver) {
+        if self.flow_control {
+            return self.flow_controlled(on_drop);
+        }
+        let (sender, receiver) = match self.capacity {
+            Some(capacity) => flume::bounded(capacity),
+            None => flume::unbounded(),
+        };
+        self.occupancy.track(&sender);
+        if self.on_full == OverflowPolicy::Block || self.capacity.is_none() {
+            return (sender, receiver).into_cb_receiver_pair_with_drop(on_drop);
+        }
+        // With DropOldest, the sender takes the oldest samples out of the channel itself
+        let oldest = receiver.clone();
+        let on_full = self.on_full;
+        let dropped = self.dropped;
+        let callback = move |mut sample| loop {
+            match sender.try_send(sample) {
+                Ok(()) => return,
+                Err(flume::TrySendError::Full(s)) => {
+                    dropped.fetch_add(1, Ordering::Relaxed);
+                    if on_full == OverflowPolicy::DropNewest {
+                        on_drop(s);
+                        return;
+                    }
+                    if let Ok(old) = oldest.try_recv() {
+                        on_drop(old);
+                    }
+                    sample = s;
+                }
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    on_drop(e.into_inner());
+                    return;
+                }
+            }
+        };
+        (Arc::new(callback), receiver)
+    }
+}
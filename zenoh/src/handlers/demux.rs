@@ -0,0 +1,162 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The handler dispatching the samples to callbacks according to their key.
+use super::{Callback, IntoCallbackReceiverPair};
+use crate::prelude::{KeyExpr, Sample};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use zenoh_core::{zread, zwrite};
+use zenoh_keyexpr::keyexpr;
+use zenoh_result::ZResult;
+
+/// The [`Handler`](crate::prelude::IntoCallbackReceiverPair) of the subscribers declared
+/// with [`demux`](crate::subscriber::SubscriberBuilder::demux).
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct DemuxHandler {
+    pub(crate) demux: Demux,
+}
+
+#[zenoh_macros::unstable]
+impl IntoCallbackReceiverPair<'static, Sample> for DemuxHandler {
+    type Receiver = Demux;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let demux = self.demux.clone();
+        (Arc::new(move |sample| demux.dispatch(sample)), self.demux)
+    }
+}
+
+/// Which of the matching routes of a [`Demux`] receive a sample.
+#[zenoh_macros::unstable]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchPolicy {
+    /// Only the first matching route, in the order the routes were added.
+    #[default]
+    First,
+    /// All the matching routes.
+    All,
+}
+
+#[zenoh_macros::unstable]
+#[derive(Default)]
+struct DemuxRoutes {
+    routes: Vec<(KeyExpr<'static>, Callback<'static, Sample>)>,
+    default: Option<Callback<'static, Sample>>,
+    policy: DispatchPolicy,
+}
+
+/// The routes of a [`DemuxSubscriber`](crate::subscriber::DemuxSubscriber), dispatching its samples to callbacks according to their key.
+///
+/// The routes can be changed while samples are received, including from the callbacks.
+#[zenoh_macros::unstable]
+#[derive(Clone, Default)]
+pub struct Demux {
+    routes: Arc<RwLock<DemuxRoutes>>,
+}
+
+#[zenoh_macros::unstable]
+impl Demux {
+    /// Deliver the samples whose key intersects `key_expr` to `callback`.
+    ///
+    /// The routes are matched in the order they were added.
+    pub fn add_route<'k, TryIntoKeyExpr, Callback>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+        callback: Callback,
+    ) -> ZResult<()>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'k>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'k>>>::Error: Into<zenoh_result::Error>,
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        let key_expr = key_expr.try_into().map_err(Into::into)?.into_owned();
+        zwrite!(self.routes)
+            .routes
+            .push((key_expr, Arc::new(callback)));
+        Ok(())
+    }
+
+    /// Remove the routes added for `key_expr`, returning `true` if there were any.
+    pub fn remove_route(&self, key_expr: &keyexpr) -> bool {
+        let mut routes = zwrite!(self.routes);
+        let len = routes.routes.len();
+        routes.routes.retain(|(k, _)| k.as_keyexpr() != key_expr);
+        routes.routes.len() != len
+    }
+
+    /// Deliver the samples matching none of the routes to `callback`, instead of dropping them.
+    pub fn set_default<Callback>(&self, callback: Callback)
+    where
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        zwrite!(self.routes).default = Some(Arc::new(callback));
+    }
+
+    /// Change whether the samples are delivered to the first matching route or to all of them.
+    pub fn set_policy(&self, policy: DispatchPolicy) {
+        zwrite!(self.routes).policy = policy;
+    }
+
+    /// Returns the key expressions of the routes, in the order they are matched.
+    pub fn routes(&self) -> Vec<KeyExpr<'static>> {
+        zread!(self.routes)
+            .routes
+            .iter()
+            .map(|(key_expr, _)| key_expr.clone())
+            .collect()
+    }
+
+    fn dispatch(&self, sample: Sample) {
+        // The callbacks are called without the lock held, so that they can change the routes
+        let mut callbacks: Vec<_> = {
+            let routes = zread!(self.routes);
+            let matching = routes
+                .routes
+                .iter()
+                .filter(|(key_expr, _)| key_expr.intersects(&sample.key_expr))
+                .map(|(_, callback)| callback.clone());
+            let mut callbacks: Vec<_> = match routes.policy {
+                DispatchPolicy::First => matching.take(1).collect(),
+                DispatchPolicy::All => matching.collect(),
+            };
+            if callbacks.is_empty() {
+                callbacks.extend(routes.default.clone());
+            }
+            callbacks
+        };
+        if let Some(last) = callbacks.pop() {
+            for callback in callbacks {
+                callback(sample.clone());
+            }
+            last(sample);
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for Demux {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routes = zread!(self.routes);
+        f.debug_struct("Demux")
+            .field(
+                "routes",
+                &routes.routes.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            )
+            .field("default", &routes.default.is_some())
+            .field("policy", &routes.policy)
+            .finish()
+    }
+}
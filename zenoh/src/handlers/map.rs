@@ -0,0 +1,53 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The handler mapping the samples before delivering them to another handler.
+use super::{Callback, IntoCallbackReceiverPair};
+use crate::prelude::Sample;
+use std::fmt;
+use std::sync::Arc;
+
+/// The [`Handler`](crate::prelude::IntoCallbackReceiverPair) of the subscribers declared
+/// with [`map`](crate::subscriber::SubscriberBuilder::map), delivering the mapped samples to another handler.
+///
+/// The values dropped by that handler, e.g. when its channel is full, aren't passed to the
+/// [`on_drop`](crate::subscriber::SubscriberBuilder::on_drop) callback of the subscriber, which only receives samples.
+#[zenoh_macros::unstable]
+pub struct MapHandler<F, Handler> {
+    pub(crate) map: F,
+    pub(crate) handler: Handler,
+}
+
+#[zenoh_macros::unstable]
+impl<F, Handler> fmt::Debug for MapHandler<F, Handler> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapHandler").finish_non_exhaustive()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<F, T, Handler> IntoCallbackReceiverPair<'static, Sample> for MapHandler<F, Handler>
+where
+    F: Fn(Sample) -> T + Send + Sync + 'static,
+    T: 'static,
+    Handler: IntoCallbackReceiverPair<'static, T>,
+{
+    type Receiver = Handler::Receiver;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let map = self.map;
+        (Arc::new(move |sample| callback(map(sample))), receiver)
+    }
+}
@@ -0,0 +1,432 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Callback handler trait.
+use crate::API_DATA_RECEPTION_CHANNEL_SIZE;
+#[zenoh_macros::unstable]
+use std::time::{Duration, Instant};
+#[zenoh_macros::unstable]
+use zenoh_result::ZResult;
+
+#[cfg(feature = "unstable")]
+mod cache;
+#[cfg(feature = "unstable")]
+mod callback_async;
+#[cfg(feature = "unstable")]
+mod channel;
+#[cfg(feature = "unstable")]
+mod demux;
+#[cfg(feature = "unstable")]
+mod map;
+#[cfg(feature = "unstable")]
+mod typed;
+
+#[zenoh_macros::unstable]
+pub use cache::{CacheHandler, SampleCache};
+#[zenoh_macros::unstable]
+pub use callback_async::CallbackAsync;
+#[zenoh_macros::unstable]
+pub(crate) use callback_async::DEFAULT_MAX_IN_FLIGHT;
+#[zenoh_macros::unstable]
+pub use channel::{ChannelOccupancy, OverflowPolicy, SampleChannel};
+#[zenoh_macros::unstable]
+pub use demux::{Demux, DemuxHandler, DispatchPolicy};
+#[zenoh_macros::unstable]
+pub use map::MapHandler;
+#[zenoh_macros::unstable]
+pub use typed::TypedHandler;
+
+/// An alias for `Arc<T>`.
+pub type Dyn<T> = std::sync::Arc<T>;
+/// An immutable callback function.
+pub type Callback<'a, T> = Dyn<dyn Fn(T) + Send + Sync + 'a>;
+
+/// A type that can be converted into a [`Callback`]-receiver pair.
+///
+/// When Zenoh functions accept types that implement these, it intends to use the [`Callback`] as just that,
+/// while granting you access to the receiver through the returned value via [`std::ops::Deref`] and [`std::ops::DerefMut`].
+///
+/// Any closure that accepts `T` can be converted into a pair of itself and `()`.
+pub trait IntoCallbackReceiverPair<'a, T> {
+    type Receiver;
+    fn into_cb_receiver_pair(self) -> (Callback<'a, T>, Self::Receiver);
+
+    /// Like [`into_cb_receiver_pair`](IntoCallbackReceiverPair::into_cb_receiver_pair), with
+    /// `on_drop` called with each value the handler fails to deliver to its receiver.
+    ///
+    /// The handlers that never drop values, like the callbacks, ignore `on_drop`.
+    #[zenoh_macros::unstable]
+    fn into_cb_receiver_pair_with_drop(
+        self,
+        on_drop: Callback<'a, T>,
+    ) -> (Callback<'a, T>, Self::Receiver)
+    where
+        Self: Sized,
+    {
+        let _ = on_drop;
+        self.into_cb_receiver_pair()
+    }
+}
+impl<'a, T, F> IntoCallbackReceiverPair<'a, T> for F
+where
+    F: Fn(T) + Send + Sync + 'a,
+{
+    type Receiver = ();
+    fn into_cb_receiver_pair(self) -> (Callback<'a, T>, Self::Receiver) {
+        (Dyn::from(self), ())
+    }
+}
+
+/// The sending half of a channel.
+trait ChannelSender<T>: Send + Sync + 'static {
+    type Error: std::fmt::Display;
+
+    fn send_value(&self, t: T) -> Result<(), Self::Error>;
+
+    /// Returns the value that failed to be sent.
+    fn into_value(e: Self::Error) -> T;
+}
+
+/// A callback sending the values through `sender`, which logs the values it fails to send
+/// and hands them to `on_drop`, if any.
+fn sender_callback<T, S>(sender: S, on_drop: Option<Callback<'static, T>>) -> Callback<'static, T>
+where
+    T: 'static,
+    S: ChannelSender<T>,
+{
+    Dyn::new(move |t| {
+        if let Err(e) = sender.send_value(t) {
+            tracing::error!("{}", e);
+            if let Some(on_drop) = on_drop.as_ref() {
+                on_drop(S::into_value(e))
+            }
+        }
+    })
+}
+
+/// Implements [`IntoCallbackReceiverPair`] for the pairs of `$sender` and `$receiver`.
+macro_rules! impl_channel_handler {
+    ($(#[$meta:meta])* $t:ident $(: $bound:path)?, $sender:ty, $receiver:ty) => {
+        $(#[$meta])*
+        impl<$t: Send $(+ $bound)? + 'static> IntoCallbackReceiverPair<'static, $t>
+            for ($sender, $receiver)
+        {
+            type Receiver = $receiver;
+            fn into_cb_receiver_pair(self) -> (Callback<'static, $t>, Self::Receiver) {
+                let (sender, receiver) = self;
+                (sender_callback(sender, None), receiver)
+            }
+            #[zenoh_macros::unstable]
+            fn into_cb_receiver_pair_with_drop(
+                self,
+                on_drop: Callback<'static, $t>,
+            ) -> (Callback<'static, $t>, Self::Receiver) {
+                let (sender, receiver) = self;
+                (sender_callback(sender, Some(on_drop)), receiver)
+            }
+        }
+    };
+}
+
+impl<T: Send + 'static> ChannelSender<T> for flume::Sender<T> {
+    type Error = flume::SendError<T>;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        self.send(t)
+    }
+    fn into_value(e: Self::Error) -> T {
+        e.0
+    }
+}
+impl_channel_handler!(T, flume::Sender<T>, flume::Receiver<T>);
+
+#[cfg(feature = "crossbeam-channel")]
+impl<T: Send + 'static> ChannelSender<T> for crossbeam_channel::Sender<T> {
+    type Error = crossbeam_channel::SendError<T>;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        self.send(t)
+    }
+    fn into_value(e: Self::Error) -> T {
+        e.0
+    }
+}
+impl_channel_handler!(
+    #[cfg(feature = "crossbeam-channel")]
+    T,
+    crossbeam_channel::Sender<T>,
+    crossbeam_channel::Receiver<T>
+);
+
+pub struct DefaultHandler;
+impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for DefaultHandler {
+    type Receiver = flume::Receiver<T>;
+    fn into_cb_receiver_pair(self) -> (Callback<'static, T>, Self::Receiver) {
+        flume::bounded(*API_DATA_RECEPTION_CHANNEL_SIZE).into_cb_receiver_pair()
+    }
+    #[zenoh_macros::unstable]
+    fn into_cb_receiver_pair_with_drop(
+        self,
+        on_drop: Callback<'static, T>,
+    ) -> (Callback<'static, T>, Self::Receiver) {
+        flume::bounded(*API_DATA_RECEPTION_CHANNEL_SIZE).into_cb_receiver_pair_with_drop(on_drop)
+    }
+}
+
+// The std receivers are `!Sync`, which is fine: the subscribers, queryables and gets only require
+// their receivers to be `Send`.
+impl<T: Send + 'static> ChannelSender<T> for std::sync::mpsc::SyncSender<T> {
+    type Error = std::sync::mpsc::SendError<T>;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        self.send(t)
+    }
+    fn into_value(e: Self::Error) -> T {
+        e.0
+    }
+}
+impl_channel_handler!(
+    T,
+    std::sync::mpsc::SyncSender<T>,
+    std::sync::mpsc::Receiver<T>
+);
+
+impl<T: Send + 'static> ChannelSender<T> for std::sync::mpsc::Sender<T> {
+    type Error = std::sync::mpsc::SendError<T>;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        self.send(t)
+    }
+    fn into_value(e: Self::Error) -> T {
+        e.0
+    }
+}
+impl_channel_handler!(T, std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>);
+
+impl<T: Send + 'static> ChannelSender<T> for tokio::sync::mpsc::Sender<T> {
+    type Error = tokio::sync::mpsc::error::SendError<T>;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        use tokio::sync::mpsc::error::{SendError, TrySendError};
+        match self.try_send(t) {
+            Ok(()) => Ok(()),
+            // The callbacks may run on the tokio runtimes of zenoh, on which `blocking_send` panics
+            Err(TrySendError::Full(t)) => {
+                zenoh_runtime::ZRuntime::Application.block_in_place(self.send(t))
+            }
+            Err(TrySendError::Closed(t)) => Err(SendError(t)),
+        }
+    }
+    fn into_value(e: Self::Error) -> T {
+        e.0
+    }
+}
+impl_channel_handler!(
+    T,
+    tokio::sync::mpsc::Sender<T>,
+    tokio::sync::mpsc::Receiver<T>
+);
+
+impl<T: Clone + Send + 'static> ChannelSender<T> for tokio::sync::broadcast::Sender<T> {
+    type Error = tokio::sync::broadcast::error::SendError<T>;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        self.send(t).map(|_| ())
+    }
+    fn into_value(e: Self::Error) -> T {
+        e.0
+    }
+}
+impl_channel_handler!(
+    T: Clone,
+    tokio::sync::broadcast::Sender<T>,
+    tokio::sync::broadcast::Receiver<T>
+);
+
+// Only the latest value is kept, so the watch channels never fail to send
+impl<T: Send + Sync + 'static> ChannelSender<T> for tokio::sync::watch::Sender<Option<T>> {
+    type Error = std::convert::Infallible;
+    fn send_value(&self, t: T) -> Result<(), Self::Error> {
+        self.send_replace(Some(t));
+        Ok(())
+    }
+    fn into_value(e: Self::Error) -> T {
+        match e {}
+    }
+}
+impl_channel_handler!(
+    T: Sync,
+    tokio::sync::watch::Sender<Option<T>>,
+    tokio::sync::watch::Receiver<Option<T>>
+);
+
+/// A function that can transform a [`FnMut`]`(T)` to
+/// a [`Fn`]`(T)` with the help of a [`Mutex`](std::sync::Mutex).
+pub fn locked<T>(fnmut: impl FnMut(T)) -> impl Fn(T) {
+    let lock = std::sync::Mutex::new(fnmut);
+    move |x| zlock!(lock)(x)
+}
+
+/// A handler containing 2 callback functions:
+///  - `callback`: the typical callback function. `context` will be passed as its last argument.
+///  - `drop`: a callback called when this handler is dropped.
+///
+/// It is guaranteed that:
+///
+///   - `callback` will never be called once `drop` has started.
+///   - `drop` will only be called **once**, and **after every** `callback` has ended.
+///   - The two previous guarantees imply that `call` and `drop` are never called concurrently.
+pub struct CallbackPair<Callback, DropFn>
+where
+    DropFn: FnMut() + Send + Sync + 'static,
+{
+    pub callback: Callback,
+    pub drop: DropFn,
+}
+
+impl<Callback, DropFn> Drop for CallbackPair<Callback, DropFn>
+where
+    DropFn: FnMut() + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        (self.drop)()
+    }
+}
+
+impl<'a, OnEvent, Event, DropFn> IntoCallbackReceiverPair<'a, Event>
+    for CallbackPair<OnEvent, DropFn>
+where
+    OnEvent: Fn(Event) + Send + Sync + 'a,
+    DropFn: FnMut() + Send + Sync + 'static,
+{
+    type Receiver = ();
+    fn into_cb_receiver_pair(self) -> (Callback<'a, Event>, Self::Receiver) {
+        (Dyn::from(move |evt| (self.callback)(evt)), ())
+    }
+}
+
+/// A receiver of a handler that can be waited on with a deadline,
+/// whichever channel it belongs to.
+///
+/// The [`Subscriber::recv_timeout`](crate::subscriber::Subscriber::recv_timeout) and
+/// [`Subscriber::recv_deadline`](crate::subscriber::Subscriber::recv_deadline) functions
+/// rely on it, so that they don't depend on the handler of the subscriber.
+#[zenoh_macros::unstable]
+pub trait SampleReceiver<T> {
+    /// Wait for a value until `deadline`.
+    ///
+    /// Returns `Ok(None)` if `deadline` is reached, and a [`ChannelDisconnected`] error if the
+    /// channel is disconnected.
+    fn recv_deadline(&mut self, deadline: Instant) -> ZResult<Option<T>>;
+
+    /// Wait for a value for at most `timeout`.
+    ///
+    /// Returns `Ok(None)` if `timeout` elapses, and a [`ChannelDisconnected`] error if the
+    /// channel is disconnected.
+    fn recv_timeout(&mut self, timeout: Duration) -> ZResult<Option<T>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            // Too far in the future to ever be reached
+            None => self.recv_deadline(Instant::now() + Duration::from_secs(u32::MAX as u64)),
+        }
+    }
+}
+
+/// The error of a [`SampleReceiver`] whose channel is disconnected, i.e. whose senders were
+/// all dropped, e.g. when its subscriber was undeclared.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelDisconnected;
+
+#[zenoh_macros::unstable]
+impl std::fmt::Display for ChannelDisconnected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The channel is disconnected")
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::error::Error for ChannelDisconnected {}
+
+#[zenoh_macros::unstable]
+impl<T> SampleReceiver<T> for flume::Receiver<T> {
+    fn recv_deadline(&mut self, deadline: Instant) -> ZResult<Option<T>> {
+        match flume::Receiver::recv_deadline(self, deadline) {
+            Ok(t) => Ok(Some(t)),
+            Err(flume::RecvTimeoutError::Timeout) => Ok(None),
+            Err(flume::RecvTimeoutError::Disconnected) => Err(ChannelDisconnected.into()),
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<T> SampleReceiver<T> for std::sync::mpsc::Receiver<T> {
+    fn recv_deadline(&mut self, deadline: Instant) -> ZResult<Option<T>> {
+        use std::sync::mpsc::RecvTimeoutError;
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match std::sync::mpsc::Receiver::recv_timeout(self, timeout) {
+            Ok(t) => Ok(Some(t)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(ChannelDisconnected.into()),
+        }
+    }
+}
+
+#[cfg(all(feature = "unstable", feature = "crossbeam-channel"))]
+impl<T> SampleReceiver<T> for crossbeam_channel::Receiver<T> {
+    fn recv_deadline(&mut self, deadline: Instant) -> ZResult<Option<T>> {
+        use crossbeam_channel::RecvTimeoutError;
+        match crossbeam_channel::Receiver::recv_deadline(self, deadline) {
+            Ok(t) => Ok(Some(t)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(ChannelDisconnected.into()),
+        }
+    }
+}
+
+// The tokio receivers are awaited on the zenoh runtime, since the caller may not run on tokio
+#[zenoh_macros::unstable]
+impl<T: Send> SampleReceiver<T> for tokio::sync::mpsc::Receiver<T> {
+    fn recv_deadline(&mut self, deadline: Instant) -> ZResult<Option<T>> {
+        use tokio::sync::mpsc::error::TryRecvError;
+        match self.try_recv() {
+            Ok(t) => return Ok(Some(t)),
+            Err(TryRecvError::Disconnected) => return Err(ChannelDisconnected.into()),
+            Err(TryRecvError::Empty) => {}
+        }
+        let deadline = tokio::time::Instant::from_std(deadline);
+        match zenoh_runtime::ZRuntime::Application
+            .block_in_place(async { tokio::time::timeout_at(deadline, self.recv()).await })
+        {
+            Ok(Some(t)) => Ok(Some(t)),
+            Ok(None) => Err(ChannelDisconnected.into()),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+// The values missed by a lagging broadcast receiver are skipped, like its recv function does
+#[zenoh_macros::unstable]
+impl<T: Clone + Send> SampleReceiver<T> for tokio::sync::broadcast::Receiver<T> {
+    fn recv_deadline(&mut self, deadline: Instant) -> ZResult<Option<T>> {
+        use tokio::sync::broadcast::error::RecvError;
+        let deadline = tokio::time::Instant::from_std(deadline);
+        zenoh_runtime::ZRuntime::Application.block_in_place(async {
+            loop {
+                match tokio::time::timeout_at(deadline, self.recv()).await {
+                    Ok(Ok(t)) => return Ok(Some(t)),
+                    Ok(Err(RecvError::Lagged(skipped))) => {
+                        tracing::warn!("Broadcast receiver lagged: {} values skipped", skipped);
+                    }
+                    Ok(Err(RecvError::Closed)) => return Err(ChannelDisconnected.into()),
+                    Err(_) => return Ok(None),
+                }
+            }
+        })
+    }
+}
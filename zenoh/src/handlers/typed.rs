@@ -0,0 +1,66 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The handler deserializing the samples of the subscribers into typed values.
+use super::{Callback, DefaultHandler, IntoCallbackReceiverPair};
+use crate::prelude::{KeyExpr, Sample};
+use crate::serialization::SerializationRegistry;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use zenoh_result::ZResult;
+
+/// The [`Handler`](crate::prelude::IntoCallbackReceiverPair) of the subscribers declared
+/// with [`typed`](crate::subscriber::SubscriberBuilder::typed).
+#[zenoh_macros::unstable]
+pub struct TypedHandler<T> {
+    pub(crate) registry: Arc<SerializationRegistry>,
+    pub(crate) _type: PhantomData<fn() -> T>,
+}
+
+#[zenoh_macros::unstable]
+impl<T> fmt::Debug for TypedHandler<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedHandler")
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<T> IntoCallbackReceiverPair<'static, Sample> for TypedHandler<T>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    type Receiver = flume::Receiver<ZResult<(KeyExpr<'static>, T)>>;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = DefaultHandler.into_cb_receiver_pair();
+        let registry = self.registry;
+        (
+            Arc::new(move |sample: Sample| {
+                let value = registry.deserialize::<T>(&sample.value).map_err(|e| {
+                    zenoh_result::zerror!(
+                        "Unable to deserialize sample on {}: {}",
+                        sample.key_expr,
+                        e
+                    )
+                    .into()
+                });
+                callback(value.map(|value| (sample.key_expr, value)))
+            }),
+            receiver,
+        )
+    }
+}
@@ -228,4 +228,31 @@ impl SessionInfo<'_> {
             session: self.session.clone(),
         }
     }
+
+    /// Return the `max_payload_size` of the current zenoh [`Session`](crate::Session):
+    /// the maximum size in bytes of the payloads it puts and delivers, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let mut config = config::peer();
+    /// config.set_max_payload_size(Some(1024)).unwrap();
+    /// let session = zenoh::open(config).res().await.unwrap();
+    /// assert_eq!(session.info().max_payload_size(), Some(1024));
+    /// # }
+    /// ```
+    pub fn max_payload_size(&self) -> Option<usize> {
+        self.session.max_payload_size
+    }
+
+    /// Return the number of received samples the current zenoh [`Session`](crate::Session)
+    /// dropped because their payload exceeded its [`max_payload_size`](SessionInfo::max_payload_size).
+    pub fn oversized_payloads(&self) -> u64 {
+        self.session
+            .oversized_payloads
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
 }
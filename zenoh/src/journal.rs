@@ -0,0 +1,188 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The journal of the recent writes of a session, overlaid on the replies of its gets issued with
+//! [`read_your_writes`](crate::query::GetBuilder::read_your_writes).
+//!
+//! The journal keeps the last put or delete of each key expression the session wrote on, for a
+//! bounded number of key expressions and a bounded time, as read from the `read_your_writes`
+//! section of the configuration:
+//! ```json5
+//! read_your_writes: {
+//!   journal_size: 1000,
+//!   journal_ttl_ms: 10000,
+//! }
+//! ```
+use crate::handlers::Callback;
+use crate::prelude::{KeyExpr, Sample, SampleKind, Value};
+use crate::query::Reply;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uhlc::Timestamp;
+use zenoh_config::ReadYourWritesConf;
+use zenoh_core::zlock;
+use zenoh_protocol::core::ZenohId;
+
+const DEFAULT_JOURNAL_SIZE: usize = 1000;
+const DEFAULT_JOURNAL_TTL: Duration = Duration::from_secs(10);
+
+struct Written {
+    at: Instant,
+    sample: Sample,
+}
+
+/// The last writes of a session, per key expression.
+pub(crate) struct WriteJournal {
+    size: usize,
+    ttl: Duration,
+    writes: Mutex<HashMap<KeyExpr<'static>, Written>>,
+}
+
+impl WriteJournal {
+    pub(crate) fn new(conf: &ReadYourWritesConf) -> Self {
+        WriteJournal {
+            size: conf.journal_size().unwrap_or(DEFAULT_JOURNAL_SIZE),
+            ttl: conf
+                .journal_ttl_ms()
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_JOURNAL_TTL),
+            writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a write of the session, evicting the oldest one if the journal is full.
+    pub(crate) fn record(
+        &self,
+        key_expr: &KeyExpr<'_>,
+        value: &Value,
+        kind: SampleKind,
+        timestamp: Option<Timestamp>,
+    ) {
+        if self.size == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let mut sample = Sample::new(key_expr.clone().into_owned(), value.clone());
+        sample.kind = kind;
+        sample.timestamp = timestamp;
+        let mut writes = zlock!(self.writes);
+        if !writes.contains_key(&sample.key_expr) && writes.len() >= self.size {
+            writes.retain(|_, written| now.duration_since(written.at) < self.ttl);
+            if writes.len() >= self.size {
+                let oldest = writes
+                    .iter()
+                    .min_by_key(|(_, written)| written.at)
+                    .map(|(key_expr, _)| key_expr.clone());
+                if let Some(oldest) = oldest {
+                    writes.remove(&oldest);
+                }
+            }
+        }
+        writes.insert(sample.key_expr.clone(), Written { at: now, sample });
+    }
+
+    /// The unexpired writes on the key expressions intersecting `key_expr`.
+    fn matching(&self, key_expr: &KeyExpr<'_>) -> HashMap<KeyExpr<'static>, (Sample, bool)> {
+        let now = Instant::now();
+        let mut writes = zlock!(self.writes);
+        writes.retain(|_, written| now.duration_since(written.at) < self.ttl);
+        writes
+            .iter()
+            .filter(|(written_on, _)| written_on.intersects(key_expr))
+            .map(|(written_on, written)| (written_on.clone(), (written.sample.clone(), false)))
+            .collect()
+    }
+
+    /// Wrap the callback of a get on `key_expr` to overlay the matching writes on its replies.
+    pub(crate) fn overlay(
+        &self,
+        key_expr: &KeyExpr<'_>,
+        zid: ZenohId,
+        callback: Callback<'static, Reply>,
+    ) -> Callback<'static, Reply> {
+        let overlay = Overlay {
+            pending: Mutex::new(self.matching(key_expr)),
+            zid,
+            callback,
+        };
+        std::sync::Arc::new(move |reply| overlay.on_reply(reply))
+    }
+}
+
+/// Overlays the writes of a session on the replies of one of its gets.
+///
+/// The replies older than a write on their key expression are replaced by this write, delivered
+/// once; the writes not replacing any reply are delivered once the get is finalized.
+struct Overlay {
+    // The matching writes, and whether they were delivered or superseded
+    pending: Mutex<HashMap<KeyExpr<'static>, (Sample, bool)>>,
+    zid: ZenohId,
+    callback: Callback<'static, Reply>,
+}
+
+impl Overlay {
+    fn on_reply(&self, reply: Reply) {
+        let Ok(sample) = &reply.sample else {
+            return (self.callback)(reply);
+        };
+        let mut pending = zlock!(self.pending);
+        let Some((written, done)) = pending.get_mut(&sample.key_expr) else {
+            drop(pending);
+            return (self.callback)(reply);
+        };
+        // Without timestamps, the write is assumed to be more recent than the stored value
+        let newer = match (&written.timestamp, &sample.timestamp) {
+            (Some(written), Some(stored)) => written > stored,
+            _ => true,
+        };
+        if !newer {
+            *done = true;
+            drop(pending);
+            return (self.callback)(reply);
+        }
+        if std::mem::replace(done, true) || written.kind == SampleKind::Delete {
+            return;
+        }
+        let written = written.clone();
+        drop(pending);
+        (self.callback)(Reply {
+            sample: Ok(written),
+            replier_id: self.zid,
+        })
+    }
+}
+
+impl Drop for Overlay {
+    fn drop(&mut self) {
+        let pending = std::mem::take(&mut *zlock!(self.pending));
+        for (written, done) in pending.into_values() {
+            if !done && written.kind == SampleKind::Put {
+                (self.callback)(Reply {
+                    sample: Ok(written),
+                    replier_id: self.zid,
+                });
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for WriteJournal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteJournal")
+            .field("size", &self.size)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
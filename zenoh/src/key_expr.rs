@@ -682,6 +682,92 @@ impl AsyncResolve for KeyExprUndeclaration<'_> {
     }
 }
 
+/// A [`KeyExpr`] declared with [`Session::declare_keyexpr_guarded`], undeclared when the last of
+/// its clones is dropped.
+///
+/// The subscribers, publishers and writes given a `&DeclaredKeyExpr` send the numeric id it was
+/// declared with instead of the key expression, which only costs a few bytes per message.
+/// They must not outlive it: once undeclared, the id is no longer understood by the routers.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let key_expr = session
+///     .declare_keyexpr_guarded("key/expression")
+///     .res()
+///     .await
+///     .unwrap();
+/// let subscriber = session.declare_subscriber(&key_expr).res().await.unwrap();
+/// session.put(&key_expr, "value").res().await.unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct DeclaredKeyExpr(std::sync::Arc<DeclaredKeyExprInner>);
+
+#[zenoh_macros::unstable]
+struct DeclaredKeyExprInner {
+    session: Session,
+    key_expr: KeyExpr<'static>,
+}
+
+#[zenoh_macros::unstable]
+impl DeclaredKeyExpr {
+    pub(crate) fn new(session: &Session, key_expr: KeyExpr<'static>) -> Self {
+        DeclaredKeyExpr(std::sync::Arc::new(DeclaredKeyExprInner {
+            session: session.clone(),
+            key_expr,
+        }))
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Drop for DeclaredKeyExprInner {
+    fn drop(&mut self) {
+        // The mapping is gone with the session once closed
+        if zread!(self.session.state).primitives.is_none() {
+            return;
+        }
+        let key_expr = self.key_expr.clone();
+        if let Err(e) = self.session.undeclare(key_expr).res_sync() {
+            tracing::warn!("Failed to undeclare {}: {}", self.key_expr, e);
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::ops::Deref for DeclaredKeyExpr {
+    type Target = KeyExpr<'static>;
+    fn deref(&self) -> &Self::Target {
+        &self.0.key_expr
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> From<&'a DeclaredKeyExpr> for KeyExpr<'a> {
+    fn from(val: &'a DeclaredKeyExpr) -> Self {
+        (&val.0.key_expr).into()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::fmt::Debug for DeclaredKeyExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0.key_expr, f)
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::fmt::Display for DeclaredKeyExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0.key_expr, f)
+    }
+}
+
 #[cfg(target_pointer_width = "64")]
 #[allow(non_snake_case)]
 #[test]
@@ -695,3 +781,33 @@ fn size_of_KeyExpr() {
         4 * std::mem::size_of::<usize>()
     );
 }
+
+#[cfg(feature = "unstable")]
+#[test]
+fn declared_keyexpr_wire_size() {
+    use zenoh_buffers::writer::HasWriter;
+    use zenoh_codec::{WCodec, Zenoh080};
+
+    fn encoded_len(wire_expr: &WireExpr) -> usize {
+        let mut buffer = Vec::new();
+        let mut writer = (&mut buffer).writer();
+        Zenoh080::new().write(&mut writer, wire_expr).unwrap();
+        buffer.len()
+    }
+
+    let session = crate::open(crate::config::peer()).res_sync().unwrap();
+    let key_expr: KeyExpr = format!("test/declared/{}", "x".repeat(200))
+        .try_into()
+        .unwrap();
+    let declared = session
+        .declare_keyexpr_guarded(key_expr.clone())
+        .res_sync()
+        .unwrap();
+
+    // A key expression is sent as a null scope, the length of the key and the key itself,
+    // 217 bytes here; a declared one as its id alone, 1 byte for the first 127 ids
+    let plain = encoded_len(&key_expr.to_wire(&session));
+    let compact = encoded_len(&declared.to_wire(&session));
+    assert_eq!(plain, 1 + 2 + key_expr.len());
+    assert_eq!(compact, 1);
+}
@@ -111,8 +111,10 @@ pub const FEATURES: &str = concat_enabled_features!(
         "auth_pubkey",
         "auth_usrpwd",
         "complete_n",
+        "metrics",
         "shared-memory",
         "stats",
+        "testing",
         "transport_multilink",
         "transport_quic",
         "transport_serial",
@@ -136,13 +138,28 @@ pub use session::*;
 pub mod key_expr;
 pub(crate) mod net;
 pub use net::runtime;
+#[cfg(feature = "unstable")]
+pub mod bridge;
 pub mod selector;
+#[cfg(feature = "unstable")]
+pub mod serialization;
 #[deprecated = "This module is now a separate crate. Use the crate directly for shorter compile-times"]
 pub use zenoh_config as config;
+#[cfg(feature = "unstable")]
+pub mod events;
+pub mod fmt;
+#[cfg(feature = "unstable")]
+pub mod gap;
 pub mod handlers;
 pub mod info;
 #[cfg(feature = "unstable")]
+pub(crate) mod journal;
+#[cfg(feature = "unstable")]
 pub mod liveliness;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "unstable")]
+pub mod no_route;
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 pub mod plugins;
 pub mod prelude;
@@ -150,7 +167,15 @@ pub mod publication;
 pub mod query;
 pub mod queryable;
 pub mod sample;
+#[cfg(feature = "unstable")]
+pub(crate) mod scheduling;
+#[cfg(feature = "unstable")]
+pub mod scope;
 pub mod subscriber;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "unstable")]
+pub mod transform;
 pub mod value;
 #[cfg(feature = "shared-memory")]
 pub use zenoh_shm as shm;
@@ -179,7 +204,10 @@ pub mod time {
 /// A map of key/value (String,String) properties.
 pub mod properties {
     use super::prelude::Value;
-    pub use zenoh_collections::Properties;
+    pub use zenoh_collections::{
+        Case, MergePolicy, Properties, PropertiesDiff, PropertiesParseIssue,
+        PropertiesParseIssueKind, PropertyEntry, PropertyValueError, PropertyValues,
+    };
 
     /// Convert a set of [`Properties`] into a [`Value`].
     /// For instance, Properties: `[("k1", "v1"), ("k2, v2")]`
@@ -330,6 +358,45 @@ where
     }
 }
 
+/// Bridge two sessions, forwarding the key expressions selected by `rules` between them.
+///
+/// See the [`bridge`](mod@crate::bridge) module.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::bridge::{BridgeDirection, BridgeRule};
+/// use zenoh::prelude::r#async::*;
+///
+/// let session_a = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let session_b = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let bridge = zenoh::bridge(
+///     session_a,
+///     session_b,
+///     [BridgeRule::new("key/**", BridgeDirection::Both)],
+/// )
+/// .res()
+/// .await
+/// .unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub fn bridge<Rules>(
+    session_a: std::sync::Arc<Session>,
+    session_b: std::sync::Arc<Session>,
+    rules: Rules,
+) -> bridge::BridgeBuilder
+where
+    Rules: IntoIterator<Item = bridge::BridgeRule>,
+{
+    bridge::BridgeBuilder {
+        session_a,
+        session_b,
+        rules: rules.into_iter().collect(),
+    }
+}
+
 /// Initialize a Session with an existing Runtime.
 /// This operation is used by the plugins to share the same Runtime as the router.
 #[doc(hidden)]
@@ -24,7 +24,7 @@ use {
         handlers::locked,
         handlers::DefaultHandler,
         prelude::*,
-        subscriber::{Subscriber, SubscriberInner},
+        subscriber::{MetricsCounters, Subscriber, SubscriberInner},
         SessionRef, Undeclarable,
     },
     std::convert::TryInto,
@@ -555,6 +555,8 @@ where
         let key_expr = self.key_expr?;
         let session = self.session;
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let metrics = Arc::new(MetricsCounters::default());
+        let callback = metrics.clone().wrap(callback);
         session
             .declare_subscriber_inner(
                 &key_expr,
@@ -562,12 +564,23 @@ where
                 Locality::default(),
                 callback,
                 &SubscriberInfo::default(),
+                None,
             )
             .map(|sub_state| Subscriber {
                 subscriber: SubscriberInner {
+                    drop_timeout: session.subscribers_drop_timeout,
                     session,
                     state: sub_state,
                     alive: true,
+                    filtered: None,
+                    dropped: None,
+                    #[cfg(feature = "unstable")]
+                    occupancy: None,
+                    metrics,
+                    callback: None,
+                    also: Vec::new(),
+                    stream: Default::default(),
+                    matching: None,
                 },
                 receiver,
             })
@@ -0,0 +1,321 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! OpenMetrics exposition of the session statistics.
+//!
+//! This module is only available with the `metrics` feature, which enables the transport statistics.
+//!
+//! see [`Session::metrics`](crate::Session::metrics)
+use crate::prelude::{KeyExpr, KnownEncoding, Sample, Value};
+use crate::queryable::{Query, QueryableBuilder};
+use crate::{Session, SessionRef};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use zenoh_core::{zread, SyncResolve};
+use zenoh_transport::stats::DiscriminatedStatsReport;
+
+/// The maximum length of the `key_expr` label values: longer key expressions are truncated,
+/// to bound the cardinality of the metrics.
+pub const KEY_EXPR_LABEL_MAX_LEN: usize = 64;
+
+/// The metrics of a [`Session`], as returned by [`Session::metrics`](crate::Session::metrics).
+///
+/// The exposed metrics are:
+/// - `zenoh_build_info{version}`: always 1;
+/// - `zenoh_session_subscribers{key_expr}`: the number of subscribers of the session;
+/// - `zenoh_session_queryables{key_expr}`: the number of queryables of the session;
+/// - `zenoh_session_pending_queries`: the number of queries awaiting their final reply;
+/// - `zenoh_transport_{tx,rx}_{bytes,transport_messages,network_messages,dropped}_total{peer,whatami,protocol}`:
+///   the traffic counters of each unicast transport;
+/// - `zenoh_transport_{tx,rx}_{put,del,query,reply}_messages_total{peer,whatami,protocol,space}`:
+///   the zenoh messages counters of each unicast transport, for the `user` and `admin` spaces.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// println!("{}", session.metrics().encode_prometheus());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SessionMetrics<'a> {
+    pub(crate) session: &'a Session,
+}
+
+impl<'a> SessionMetrics<'a> {
+    /// Encode the metrics in the OpenMetrics text format.
+    pub fn encode_prometheus(&self) -> String {
+        encode(self.session)
+    }
+
+    /// Declare a [`Queryable`](crate::queryable::Queryable) replying to queries on `key_expr`
+    /// with the metrics of the session, encoded by [`encode_prometheus`](SessionMetrics::encode_prometheus).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let key_expr = format!("@/metrics/{}", session.zid());
+    /// let queryable = session.metrics().serve(key_expr).res().await.unwrap();
+    /// # }
+    /// ```
+    pub fn serve<'b, TryIntoKeyExpr>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QueryableBuilder<'a, 'b, impl Fn(Query) + Send + Sync + 'static>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        let key_expr = key_expr.try_into().map_err(Into::into);
+        let reply_key = key_expr
+            .as_ref()
+            .map(|key_expr: &KeyExpr| key_expr.clone().into_owned())
+            .ok();
+        let session = self.session.clone();
+        QueryableBuilder {
+            session: SessionRef::Borrow(self.session),
+            key_expr,
+            complete: true,
+            origin: Default::default(),
+            #[cfg(feature = "unstable")]
+            cache: None,
+            #[cfg(feature = "unstable")]
+            await_routed: None,
+            handler: move |query: Query| {
+                let key_expr = reply_key
+                    .clone()
+                    .unwrap_or_else(|| query.key_expr().clone());
+                let value = Value::from(encode(&session)).encoding(KnownEncoding::TextPlain.into());
+                if let Err(e) = query.reply(Ok(Sample::new(key_expr, value))).res_sync() {
+                    tracing::error!("Error replying to metrics query {}: {}", query, e);
+                }
+            },
+        }
+    }
+}
+
+fn encode(session: &Session) -> String {
+    let mut out = String::new();
+    family(
+        &mut out,
+        "zenoh_build_info",
+        "gauge",
+        "Information about zenoh.",
+    );
+    let _ = writeln!(
+        out,
+        "zenoh_build_info{{version=\"{}\"}} 1",
+        escape(crate::GIT_VERSION)
+    );
+
+    let state = zread!(session.state);
+    let mut subscribers = BTreeMap::new();
+    for sub in state.subscribers.values() {
+        *subscribers
+            .entry(key_expr_label(&sub.key_expr))
+            .or_insert(0) += 1;
+    }
+    let mut queryables = BTreeMap::new();
+    for qabl in state.queryables.values() {
+        if let Ok(key_expr) = state.local_wireexpr_to_expr(&qabl.key_expr) {
+            *queryables.entry(key_expr_label(&key_expr)).or_insert(0) += 1;
+        }
+    }
+    let pending_queries = state.queries.len();
+    drop(state);
+
+    family(
+        &mut out,
+        "zenoh_session_subscribers",
+        "gauge",
+        "Number of subscribers of the session.",
+    );
+    for (key_expr, count) in subscribers {
+        let _ = writeln!(
+            out,
+            "zenoh_session_subscribers{{key_expr=\"{key_expr}\"}} {count}"
+        );
+    }
+    family(
+        &mut out,
+        "zenoh_session_queryables",
+        "gauge",
+        "Number of queryables of the session.",
+    );
+    for (key_expr, count) in queryables {
+        let _ = writeln!(
+            out,
+            "zenoh_session_queryables{{key_expr=\"{key_expr}\"}} {count}"
+        );
+    }
+    family(
+        &mut out,
+        "zenoh_session_pending_queries",
+        "gauge",
+        "Number of queries of the session awaiting their final reply.",
+    );
+    let _ = writeln!(out, "zenoh_session_pending_queries {pending_queries}");
+
+    encode_transports(session, &mut out);
+    out.push_str("# EOF\n");
+    out
+}
+
+fn encode_transports(session: &Session, out: &mut String) {
+    let transports = zenoh_runtime::ZRuntime::Net
+        .block_in_place(session.runtime.manager().get_transports_unicast());
+    let reports: Vec<(String, _)> = transports
+        .iter()
+        .filter_map(|transport| {
+            let peer = transport.get_zid().ok()?;
+            let whatami = transport.get_whatami().ok()?;
+            let mut protocols: Vec<String> = transport
+                .get_links()
+                .unwrap_or_default()
+                .iter()
+                .map(|link| link.dst.protocol().as_str().to_string())
+                .collect();
+            protocols.sort();
+            protocols.dedup();
+            let labels = format!(
+                "peer=\"{peer}\",whatami=\"{whatami}\",protocol=\"{}\"",
+                escape(&protocols.join(","))
+            );
+            Some((labels, transport.get_stats().ok()?.report()))
+        })
+        .collect();
+
+    macro_rules! counter {
+        ($name:literal, $help:literal, $field:ident) => {
+            family(out, $name, "counter", $help);
+            for (labels, report) in &reports {
+                let _ = writeln!(out, "{}_total{{{}}} {}", $name, labels, report.$field);
+            }
+        };
+    }
+    macro_rules! space_counter {
+        ($name:literal, $help:literal, $field:ident) => {
+            family(out, $name, "counter", $help);
+            for (labels, report) in &reports {
+                let DiscriminatedStatsReport { user, admin } = &report.$field;
+                let _ = writeln!(out, "{}_total{{{},space=\"user\"}} {}", $name, labels, user);
+                let _ = writeln!(
+                    out,
+                    "{}_total{{{},space=\"admin\"}} {}",
+                    $name, labels, admin
+                );
+            }
+        };
+    }
+
+    counter!("zenoh_transport_tx_bytes", "Sent bytes.", tx_bytes);
+    counter!("zenoh_transport_rx_bytes", "Received bytes.", rx_bytes);
+    counter!(
+        "zenoh_transport_tx_transport_messages",
+        "Sent transport messages.",
+        tx_t_msgs
+    );
+    counter!(
+        "zenoh_transport_rx_transport_messages",
+        "Received transport messages.",
+        rx_t_msgs
+    );
+    counter!(
+        "zenoh_transport_tx_network_messages",
+        "Sent network messages.",
+        tx_n_msgs
+    );
+    counter!(
+        "zenoh_transport_rx_network_messages",
+        "Received network messages.",
+        rx_n_msgs
+    );
+    counter!(
+        "zenoh_transport_tx_dropped",
+        "Dropped outgoing network messages.",
+        tx_n_dropped
+    );
+    counter!(
+        "zenoh_transport_rx_dropped",
+        "Dropped incoming network messages.",
+        rx_n_dropped
+    );
+    space_counter!(
+        "zenoh_transport_tx_put_messages",
+        "Sent zenoh put messages.",
+        tx_z_put_msgs
+    );
+    space_counter!(
+        "zenoh_transport_rx_put_messages",
+        "Received zenoh put messages.",
+        rx_z_put_msgs
+    );
+    space_counter!(
+        "zenoh_transport_tx_del_messages",
+        "Sent zenoh del messages.",
+        tx_z_del_msgs
+    );
+    space_counter!(
+        "zenoh_transport_rx_del_messages",
+        "Received zenoh del messages.",
+        rx_z_del_msgs
+    );
+    space_counter!(
+        "zenoh_transport_tx_query_messages",
+        "Sent zenoh query messages.",
+        tx_z_query_msgs
+    );
+    space_counter!(
+        "zenoh_transport_rx_query_messages",
+        "Received zenoh query messages.",
+        rx_z_query_msgs
+    );
+    space_counter!(
+        "zenoh_transport_tx_reply_messages",
+        "Sent zenoh reply messages.",
+        tx_z_reply_msgs
+    );
+    space_counter!(
+        "zenoh_transport_rx_reply_messages",
+        "Received zenoh reply messages.",
+        rx_z_reply_msgs
+    );
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "# HELP {name} {help}");
+}
+
+fn key_expr_label(key_expr: &KeyExpr) -> String {
+    let key_expr = key_expr.as_str();
+    match key_expr.char_indices().nth(KEY_EXPR_LABEL_MAX_LEN) {
+        Some((idx, _)) => format!("{}...", escape(&key_expr[..idx])),
+        None => escape(key_expr),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
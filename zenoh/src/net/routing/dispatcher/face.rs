@@ -25,7 +25,10 @@ use tokio_util::sync::CancellationToken;
 use zenoh_protocol::zenoh::RequestBody;
 use zenoh_protocol::{
     core::{ExprId, WhatAmI, ZenohId},
-    network::{Mapping, Push, Request, RequestId, Response, ResponseFinal},
+    network::{
+        declare::subscriber::ext::PayloadFilterType, Mapping, Push, Request, RequestId, Response,
+        ResponseFinal,
+    },
 };
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TaskController;
@@ -42,6 +45,11 @@ pub struct FaceState {
     pub(crate) primitives: Arc<dyn crate::net::primitives::EPrimitives + Send + Sync>,
     pub(crate) local_mappings: HashMap<ExprId, Arc<Resource>>,
     pub(crate) remote_mappings: HashMap<ExprId, Arc<Resource>>,
+    /// The next id to declare a key expression to this face with: the ids are never reused.
+    pub(crate) next_local_id: Option<ExprId>,
+    /// The payload filters declared by the subscriptions of this face, by resource (see
+    /// [`payload_filter_key`](super::pubsub::payload_filter_key)).
+    pub(crate) payload_filters: HashMap<usize, PayloadFilterType>,
     pub(crate) next_qid: RequestId,
     pub(crate) pending_queries: HashMap<RequestId, (Arc<Query>, CancellationToken)>,
     pub(crate) mcast_group: Option<TransportMulticast>,
@@ -71,6 +79,8 @@ impl FaceState {
             primitives,
             local_mappings: HashMap::new(),
             remote_mappings: HashMap::new(),
+            next_local_id: Some(1),
+            payload_filters: HashMap::new(),
             next_qid: 0,
             pending_queries: HashMap::new(),
             mcast_group,
@@ -104,12 +114,18 @@ impl FaceState {
         }
     }
 
-    pub(crate) fn get_next_local_id(&self) -> ExprId {
-        let mut id = 1;
-        while self.local_mappings.contains_key(&id) || self.remote_mappings.contains_key(&id) {
-            id += 1;
+    /// Allocate an id to declare a key expression to this face with, if any is left.
+    ///
+    /// The ids undeclared by [`Resource::retire_local_mappings`] are not reused while the face
+    /// is alive, so that messages still in flight with them can't be mistaken for a later
+    /// declaration.
+    pub(crate) fn get_next_local_id(&mut self) -> Option<ExprId> {
+        let mut id = self.next_local_id?;
+        while self.remote_mappings.contains_key(&id) {
+            id = id.checked_add(1)?;
         }
-        id
+        self.next_local_id = id.checked_add(1);
+        Some(id)
     }
 
     pub(crate) fn update_interceptors_caches(&self, res: &mut Arc<Resource>) {
@@ -202,6 +218,7 @@ impl Primitives for Face {
                     &mut self.state.clone(),
                     &m.wire_expr,
                     &m.ext_info,
+                    m.ext_payload_filter.as_ref(),
                     msg.ext_nodeid.node_id,
                     &mut |p, m| declares.push((p.clone(), m)),
                 );
@@ -293,7 +310,13 @@ impl Primitives for Face {
                 );
             }
             RequestBody::Pull(_) => {
-                pull_data(&self.tables.tables, &self.state.clone(), msg.wire_expr);
+                pull_data(
+                    &self.tables.tables,
+                    &self.state.clone(),
+                    msg.id,
+                    msg.wire_expr,
+                    msg.ext_budget,
+                );
             }
             _ => {
                 tracing::error!("Unsupported request");
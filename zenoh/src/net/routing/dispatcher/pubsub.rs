@@ -15,27 +15,31 @@ use super::face::FaceState;
 use super::resource::{DataRoutes, Direction, PullCaches, Resource};
 use super::tables::{NodeId, Route, RoutingExpr, Tables, TablesLock};
 use crate::net::routing::hat::{HatTrait, SendDeclare};
+use crate::net::routing::RoutingContext;
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::num::NonZeroU32;
 use std::sync::RwLock;
+use std::sync::{Arc, Weak};
 use zenoh_core::zread;
 use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
-use zenoh_protocol::network::declare::subscriber::ext::SubscriberInfo;
+use zenoh_protocol::network::declare::subscriber::ext::{PayloadFilterType, SubscriberInfo};
 use zenoh_protocol::network::declare::Mode;
 use zenoh_protocol::{
     core::{WhatAmI, WireExpr},
-    network::{declare::ext, Push},
+    network::{declare::ext, response, Push, RequestId, ResponseFinal},
     zenoh::PushBody,
 };
 use zenoh_sync::get_mut_unchecked;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn declare_subscription(
     hat_code: &(dyn HatTrait + Send + Sync),
     tables: &TablesLock,
     face: &mut Arc<FaceState>,
     expr: &WireExpr,
     sub_info: &SubscriberInfo,
+    payload_filter: Option<&PayloadFilterType>,
     node_id: NodeId,
     send_declare: &mut SendDeclare,
 ) {
@@ -67,6 +71,20 @@ pub(crate) fn declare_subscription(
                     (res, wtables)
                 };
 
+            // The filter is registered before the subscription is propagated with it
+            match payload_filter {
+                Some(filter) => {
+                    get_mut_unchecked(face)
+                        .payload_filters
+                        .insert(payload_filter_key(&res), filter.clone());
+                }
+                None => {
+                    get_mut_unchecked(face)
+                        .payload_filters
+                        .remove(&payload_filter_key(&res));
+                }
+            }
+
             hat_code.declare_subscription(
                 &mut wtables,
                 face,
@@ -100,7 +118,7 @@ pub(crate) fn declare_subscription(
 
 pub(crate) fn undeclare_subscription(
     hat_code: &(dyn HatTrait + Send + Sync),
-    tables: &TablesLock,
+    tables: &Arc<TablesLock>,
     face: &mut Arc<FaceState>,
     expr: &WireExpr,
     node_id: NodeId,
@@ -121,7 +139,11 @@ pub(crate) fn undeclare_subscription(
                     node_id,
                     send_declare,
                 );
+                get_mut_unchecked(face)
+                    .payload_filters
+                    .remove(&payload_filter_key(&res));
 
+                Resource::retire_local_mappings(&mut wtables, &mut res, send_declare);
                 disable_matches_data_routes(&mut wtables, &mut res);
                 drop(wtables);
 
@@ -147,6 +169,63 @@ pub(crate) fn undeclare_subscription(
     }
 }
 
+/// The payload filter to declare to `dst_face` for the subscriptions of the other faces on `res`:
+/// the union of their filters, or `None` if any of them declared none.
+pub(crate) fn aggregated_payload_filter(
+    res: &Resource,
+    dst_face: &FaceState,
+) -> Option<PayloadFilterType> {
+    let key = payload_filter_key(res);
+    let mut prefixes = vec![];
+    for ctx in res.session_ctxs.values() {
+        if ctx.face.id != dst_face.id && ctx.subs.is_some() {
+            prefixes.extend_from_slice(&ctx.face.payload_filters.get(&key)?.prefixes);
+        }
+    }
+    (!prefixes.is_empty()).then_some(PayloadFilterType { prefixes })
+}
+
+/// The key of the payload filter of the subscription declared on `res` in
+/// [`FaceState::payload_filters`]: the filter is removed along with the subscription, so before
+/// the resource can be released.
+#[inline]
+pub(crate) fn payload_filter_key(res: &Resource) -> usize {
+    res as *const Resource as usize
+}
+
+/// Returns true if the subscriptions of `face` matching `res` accept `payload`: if any of them
+/// declared no payload filter or a filter matching it. The faces that declared no filter, or
+/// whose subscriptions aren't known, accept all the payloads, and so do all the faces for the
+/// deletions and the key expressions without matching context.
+fn accepts_payload(face: &FaceState, res: Option<&Arc<Resource>>, payload: &PushBody) -> bool {
+    if face.payload_filters.is_empty() {
+        return true;
+    }
+    let payload = match payload {
+        PushBody::Put(put) => &put.payload,
+        PushBody::Del(_) => return true,
+    };
+    let matches = match res.and_then(|res| res.context.as_ref()) {
+        Some(ctx) => &ctx.matches,
+        None => return true,
+    };
+    let mut subscribed = false;
+    for mres in matches.iter().filter_map(Weak::upgrade) {
+        if mres
+            .session_ctxs
+            .get(&face.id)
+            .map_or(false, |ctx| ctx.subs.is_some())
+        {
+            subscribed = true;
+            match face.payload_filters.get(&payload_filter_key(&mres)) {
+                Some(filter) if !filter.matches(payload) => (),
+                _ => return true,
+            }
+        }
+    }
+    !subscribed
+}
+
 fn compute_data_routes_(tables: &Tables, routes: &mut DataRoutes, expr: &mut RoutingExpr) {
     let indexes = tables.hat_code.get_data_routes_entries(tables);
 
@@ -342,6 +421,19 @@ pub(crate) fn get_local_data_route(
         })
 }
 
+/// Whether the data published by the sessions on `key_expr` is routed to another zenoh node.
+#[zenoh_macros::unstable]
+pub(crate) fn has_remote_data_route(tables: &Tables, key_expr: &str) -> bool {
+    let res = Resource::get_resource(&tables.root_res, key_expr);
+    get_local_data_route(
+        tables,
+        &res,
+        &mut RoutingExpr::new(&tables.root_res, key_expr),
+    )
+    .values()
+    .any(|(face, _, _)| face.zid != tables.zid)
+}
+
 fn compute_matching_pulls_(tables: &Tables, pull_caches: &mut PullCaches, expr: &mut RoutingExpr) {
     let ke = if let Ok(ke) = OwnedKeyExpr::try_from(expr.full_expr()) {
         ke
@@ -482,6 +574,7 @@ pub fn full_reentrant_route_data(
                         if tables
                             .hat_code
                             .egress_filter(&tables, face, outface, &mut expr)
+                            && accepts_payload(outface, res.as_ref(), &payload)
                         {
                             drop(tables);
                             #[cfg(feature = "stats")]
@@ -513,6 +606,7 @@ pub fn full_reentrant_route_data(
                                     tables
                                         .hat_code
                                         .egress_filter(&tables, face, outface, &mut expr)
+                                        && accepts_payload(outface, res.as_ref(), &payload)
                                 })
                                 .cloned()
                                 .collect::<Vec<Direction>>();
@@ -535,9 +629,17 @@ pub fn full_reentrant_route_data(
                                 })
                             }
                         } else {
+                            let rejected = route
+                                .values()
+                                .filter(|(outface, _key_expr, _context)| {
+                                    !accepts_payload(outface, res.as_ref(), &payload)
+                                })
+                                .map(|(outface, _key_expr, _context)| outface.id)
+                                .collect::<Vec<usize>>();
                             drop(tables);
                             for (outface, key_expr, context) in route.values() {
                                 if face.id != outface.id
+                                    && !rejected.contains(&outface.id)
                                     && match (
                                         face.mcast_group.as_ref(),
                                         outface.mcast_group.as_ref(),
@@ -573,7 +675,36 @@ pub fn full_reentrant_route_data(
     }
 }
 
-pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireExpr) {
+pub fn pull_data(
+    tables_ref: &RwLock<Tables>,
+    face: &Arc<FaceState>,
+    rid: RequestId,
+    expr: WireExpr,
+    max_samples: Option<NonZeroU32>,
+) {
+    let served = serve_pull(tables_ref, face, expr, max_samples);
+    // The pulls bounded with `max_samples` are answered with a final response once served,
+    // reporting the number of samples served
+    if max_samples.is_some() {
+        face.primitives
+            .clone()
+            .send_response_final(RoutingContext::new(ResponseFinal {
+                rid,
+                ext_qos: response::ext::QoSType::response_final_default(),
+                ext_tstamp: None,
+                ext_served: Some(served as u32),
+            }));
+    }
+}
+
+/// Push the samples kept for the pull subscription of `face` on `expr`, at most `max_samples`
+/// of them if given, returning the number of samples pushed.
+fn serve_pull(
+    tables_ref: &RwLock<Tables>,
+    face: &Arc<FaceState>,
+    expr: WireExpr,
+    max_samples: Option<NonZeroU32>,
+) -> usize {
     let tables = zread!(tables_ref);
     match tables.get_mapping(face, &expr.scope, expr.mapping) {
         Some(prefix) => match Resource::get_resource(prefix, expr.suffix.as_ref()) {
@@ -584,9 +715,14 @@ pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireE
                         Some(_subinfo) => {
                             // let reliability = subinfo.reliability;
                             let lock = zlock!(tables.pull_caches_lock);
-                            let route = get_mut_unchecked(ctx)
-                                .last_values
-                                .drain()
+                            // The oldest samples are delivered first, the ones beyond
+                            // `max_samples` are kept for the next pull
+                            let last_values = &mut get_mut_unchecked(ctx).last_values;
+                            let count = max_samples
+                                .map_or(usize::MAX, |max| max.get() as usize)
+                                .min(last_values.len());
+                            let route = last_values
+                                .drain(..count)
                                 .map(|(name, sample)| {
                                     (
                                         Resource::get_best_key(&tables.root_res, &name, face.id)
@@ -597,6 +733,7 @@ pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireE
                                 .collect::<Vec<(WireExpr, PushBody)>>();
                             drop(lock);
                             drop(tables);
+                            let served = route.len();
                             for (key_expr, payload) in route {
                                 face.primitives.send_push(Push {
                                     wire_expr: key_expr,
@@ -606,6 +743,7 @@ pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireE
                                     payload,
                                 });
                             }
+                            return served;
                         }
                         None => {
                             tracing::error!(
@@ -633,4 +771,5 @@ pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireE
             tracing::error!("Pull data with unknown scope {}!", expr.scope);
         }
     };
+    0
 }
@@ -25,7 +25,7 @@ use tokio_util::sync::CancellationToken;
 use zenoh_buffers::ZBuf;
 use zenoh_config::WhatAmI;
 use zenoh_protocol::core::key_expr::keyexpr;
-use zenoh_protocol::core::KnownEncoding;
+use zenoh_protocol::core::{KnownEncoding, SampleKind};
 use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
 use zenoh_protocol::zenoh;
 use zenoh_protocol::zenoh::ext::ValueType;
@@ -116,7 +116,7 @@ pub(crate) fn declare_queryable(
 
 pub(crate) fn undeclare_queryable(
     hat_code: &(dyn HatTrait + Send + Sync),
-    tables: &TablesLock,
+    tables: &Arc<TablesLock>,
     face: &mut Arc<FaceState>,
     expr: &WireExpr,
     node_id: NodeId,
@@ -131,6 +131,7 @@ pub(crate) fn undeclare_queryable(
 
                 hat_code.undeclare_queryable(&mut wtables, face, &mut res, node_id, send_declare);
 
+                Resource::retire_local_mappings(&mut wtables, &mut res, send_declare);
                 disable_matches_query_routes(&mut wtables, &mut res);
                 drop(wtables);
 
@@ -471,6 +472,24 @@ pub(crate) fn disable_matches_query_routes(_tables: &mut Tables, res: &mut Arc<R
     }
 }
 
+/// Whether the queries of the sessions on `key_expr` are routed to another zenoh node.
+#[zenoh_macros::unstable]
+pub(crate) fn has_remote_query_route(tables: &Tables, key_expr: &str) -> bool {
+    let res = Resource::get_resource(&tables.root_res, key_expr);
+    res.as_ref()
+        .and_then(|res| res.query_route(WhatAmI::Client, 0))
+        .unwrap_or_else(|| {
+            tables.hat_code.compute_query_route(
+                tables,
+                &mut RoutingExpr::new(&tables.root_res, key_expr),
+                0,
+                WhatAmI::Client,
+            )
+        })
+        .iter()
+        .any(|qabl| qabl.direction.0.zid != tables.zid)
+}
+
 #[inline]
 fn get_query_route(
     tables: &Tables,
@@ -623,6 +642,7 @@ pub fn route_query(
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment: None, // @TODO: expose it in the API
+                        ext_kind: SampleKind::Put,
                         ext_unknown: vec![],
                         payload,
                     });
@@ -664,6 +684,7 @@ pub fn route_query(
                                 rid: qid,
                                 ext_qos: response::ext::QoSType::response_final_default(),
                                 ext_tstamp: None,
+                                ext_served: None,
                             },
                             expr.full_expr().to_string(),
                         ));
@@ -740,6 +761,7 @@ pub fn route_query(
                             rid: qid,
                             ext_qos: response::ext::QoSType::response_final_default(),
                             ext_tstamp: None,
+                            ext_served: None,
                         },
                         expr.full_expr().to_string(),
                     ));
@@ -758,6 +780,7 @@ pub fn route_query(
                         rid: qid,
                         ext_qos: response::ext::QoSType::response_final_default(),
                         ext_tstamp: None,
+                        ext_served: None,
                     },
                     "".to_string(),
                 ));
@@ -867,6 +890,7 @@ pub(crate) fn finalize_pending_query(query: (Arc<Query>, CancellationToken)) {
                     rid: query.src_qid,
                     ext_qos: response::ext::QoSType::response_final_default(),
                     ext_tstamp: None,
+                    ext_served: None,
                 },
                 "".to_string(),
             ));
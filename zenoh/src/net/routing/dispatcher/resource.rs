@@ -14,7 +14,9 @@
 use super::face::FaceState;
 use super::tables::{Tables, TablesLock};
 use crate::net::routing::dispatcher::face::Face;
+use crate::net::routing::hat::SendDeclare;
 use crate::net::routing::RoutingContext;
+use indexmap::IndexMap;
 use std::any::Any;
 use std::collections::HashMap;
 use std::convert::TryInto;
@@ -30,7 +32,7 @@ use zenoh_protocol::{
     network::{
         declare::{
             ext, queryable::ext::QueryableInfo, subscriber::ext::SubscriberInfo, Declare,
-            DeclareBody, DeclareKeyExpr,
+            DeclareBody, DeclareKeyExpr, UndeclareKeyExpr,
         },
         Mapping,
     },
@@ -59,7 +61,9 @@ pub(crate) struct SessionContext {
     pub(crate) remote_expr_id: Option<ExprId>,
     pub(crate) subs: Option<SubscriberInfo>,
     pub(crate) qabl: Option<QueryableInfo>,
-    pub(crate) last_values: HashMap<String, PushBody>,
+    /// The last sample of each key kept for the next pull, in the order the keys were first
+    /// published since the previous pull.
+    pub(crate) last_values: IndexMap<String, PushBody>,
     pub(crate) in_interceptor_cache: Option<Box<dyn Any + Send + Sync>>,
     pub(crate) e_interceptor_cache: Option<Box<dyn Any + Send + Sync>>,
 }
@@ -316,6 +320,65 @@ impl Resource {
         }
     }
 
+    /// Returns true if `res` still has children, or subscriptions, queryables or key expression
+    /// declarations from any face, i.e. if its key expression may still be routed on.
+    fn in_use(res: &Arc<Resource>) -> bool {
+        !res.children.is_empty()
+            || res
+                .session_ctxs
+                .values()
+                .any(|ctx| ctx.subs.is_some() || ctx.qabl.is_some() || ctx.remote_expr_id.is_some())
+    }
+
+    /// Undeclare the key expressions declared to other faces for `res` (see
+    /// [`Resource::decl_key`]) if it is no longer in use.
+    ///
+    /// The UndeclareKeyExpr are sent through `send_declare`, so after the undeclarations that
+    /// still refer to the released ids. The ids are never reused while the face is alive (see
+    /// [`FaceState::get_next_local_id`]), so the messages still in flight with them can't be
+    /// mistaken for a later declaration.
+    pub(crate) fn retire_local_mappings(
+        tables: &mut Tables,
+        res: &mut Arc<Resource>,
+        send_declare: &mut SendDeclare,
+    ) {
+        if res.parent.is_none() || Resource::in_use(res) {
+            return;
+        }
+        let expr = res.expr();
+        for ctx in get_mut_unchecked(res).session_ctxs.values_mut() {
+            if let Some(expr_id) = get_mut_unchecked(ctx).local_expr_id.take() {
+                let mut face = ctx.face.clone();
+                if get_mut_unchecked(&mut face)
+                    .local_mappings
+                    .remove(&expr_id)
+                    .is_some()
+                    && tables.faces.contains_key(&face.id)
+                {
+                    tracing::debug!("Undeclare key expression {} for {}", expr, face);
+                    send_declare(
+                        &face.primitives,
+                        RoutingContext::with_expr(
+                            Declare {
+                                ext_qos: ext::QoSType::declare_default(),
+                                ext_tstamp: None,
+                                ext_nodeid: ext::NodeIdType::default(),
+                                body: DeclareBody::UndeclareKeyExpr(UndeclareKeyExpr {
+                                    id: expr_id,
+                                }),
+                            },
+                            expr.clone(),
+                        ),
+                    );
+                }
+            }
+        }
+        if let Some(context) = get_mut_unchecked(res).context.as_mut() {
+            context.disable_data_routes();
+            context.disable_query_routes();
+        }
+    }
+
     pub fn close(self: &mut Arc<Resource>) {
         let r = get_mut_unchecked(self);
         for c in r.children.values_mut() {
@@ -458,7 +521,7 @@ impl Resource {
                             remote_expr_id: None,
                             subs: None,
                             qabl: None,
-                            last_values: HashMap::new(),
+                            last_values: IndexMap::new(),
                             in_interceptor_cache: None,
                             e_interceptor_cache: None,
                         })
@@ -476,8 +539,7 @@ impl Resource {
                         suffix: wildsuffix.into(),
                         mapping: Mapping::Sender,
                     }
-                } else {
-                    let expr_id = face.get_next_local_id();
+                } else if let Some(expr_id) = get_mut_unchecked(face).get_next_local_id() {
                     get_mut_unchecked(ctx).local_expr_id = Some(expr_id);
                     get_mut_unchecked(face)
                         .local_mappings
@@ -500,6 +562,9 @@ impl Resource {
                         suffix: wildsuffix.into(),
                         mapping: Mapping::Sender,
                     }
+                } else {
+                    // No id left for this face: send the whole key expression
+                    res.expr().into()
                 }
             }
             None => wildsuffix.into(),
@@ -710,6 +775,7 @@ pub fn register_expr(
                 get_mut_unchecked(&mut res)
                     .session_ctxs
                     .entry(face.id)
+                    .and_modify(|ctx| get_mut_unchecked(ctx).remote_expr_id = Some(expr_id))
                     .or_insert_with(|| {
                         Arc::new(SessionContext {
                             face: face.clone(),
@@ -717,7 +783,7 @@ pub fn register_expr(
                             remote_expr_id: Some(expr_id),
                             subs: None,
                             qabl: None,
-                            last_values: HashMap::new(),
+                            last_values: IndexMap::new(),
                             in_interceptor_cache: None,
                             e_interceptor_cache: None,
                         })
@@ -735,10 +801,18 @@ pub fn register_expr(
     }
 }
 
-pub fn unregister_expr(tables: &TablesLock, face: &mut Arc<FaceState>, expr_id: ExprId) {
-    let wtables = zwrite!(tables.tables);
+pub fn unregister_expr(tables: &Arc<TablesLock>, face: &mut Arc<FaceState>, expr_id: ExprId) {
+    let mut wtables = zwrite!(tables.tables);
     match get_mut_unchecked(face).remote_mappings.remove(&expr_id) {
-        Some(mut res) => Resource::clean(&mut res),
+        Some(mut res) => {
+            if let Some(ctx) = get_mut_unchecked(&mut res).session_ctxs.get_mut(&face.id) {
+                if ctx.remote_expr_id == Some(expr_id) {
+                    get_mut_unchecked(ctx).remote_expr_id = None;
+                }
+            }
+            Resource::retire_local_mappings(&mut wtables, &mut res, &mut |p, m| p.send_declare(m));
+            Resource::clean(&mut res)
+        }
         None => tracing::error!("Undeclare unknown resource!"),
     }
     drop(wtables);
@@ -45,6 +45,7 @@ use std::{
 };
 use zenoh_config::WhatAmI;
 use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
+use zenoh_protocol::network::declare::subscriber::ext::PayloadFilterType;
 use zenoh_protocol::network::Oam;
 use zenoh_result::ZResult;
 use zenoh_sync::get_mut_unchecked;
@@ -295,7 +296,8 @@ impl HatContext {
 }
 
 struct HatFace {
-    local_subs: HashSet<Arc<Resource>>,
+    /// The subscriptions declared to the face, with the payload filter they were declared with.
+    local_subs: HashMap<Arc<Resource>, Option<PayloadFilterType>>,
     remote_subs: HashSet<Arc<Resource>>,
     local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     remote_qabls: HashSet<Arc<Resource>>,
@@ -304,7 +306,7 @@ struct HatFace {
 impl HatFace {
     fn new() -> Self {
         Self {
-            local_subs: HashSet::new(),
+            local_subs: HashMap::new(),
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
@@ -20,6 +20,7 @@ use crate::net::routing::dispatcher::tables::{QueryTargetQabl, QueryTargetQablSe
 use crate::net::routing::hat::{HatQueriesTrait, SendDeclare, Sources};
 use crate::net::routing::router::RoutesIndexes;
 use crate::net::routing::{RoutingContext, PREFIX_LIVELINESS};
+use indexmap::IndexMap;
 use ordered_float::OrderedFloat;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -132,7 +133,7 @@ fn register_client_queryable(
                 remote_expr_id: None,
                 subs: None,
                 qabl: None,
-                last_values: HashMap::new(),
+                last_values: IndexMap::new(),
                 in_interceptor_cache: None,
                 e_interceptor_cache: None,
             })
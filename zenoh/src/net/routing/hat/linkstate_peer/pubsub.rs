@@ -22,6 +22,7 @@ use crate::net::routing::dispatcher::tables::{Route, RoutingExpr};
 use crate::net::routing::hat::{HatPubSubTrait, SendDeclare, Sources};
 use crate::net::routing::router::RoutesIndexes;
 use crate::net::routing::{RoutingContext, PREFIX_LIVELINESS};
+use indexmap::IndexMap;
 use petgraph::graph::NodeIndex;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
@@ -66,6 +67,7 @@ fn send_sourced_subscription_to_net_children(
                                     id: 0, // TODO
                                     wire_expr: key_expr,
                                     ext_info: *sub_info,
+                                    ext_payload_filter: None,
                                 }),
                             },
                             res.expr(),
@@ -104,6 +106,7 @@ fn propagate_simple_subscription_to(
                         id: 0, // TODO
                         wire_expr: key_expr,
                         ext_info: *sub_info,
+                        ext_payload_filter: None,
                     }),
                 },
                 res.expr(),
@@ -221,16 +224,8 @@ fn register_client_subscription(
         let res = get_mut_unchecked(res);
         tracing::debug!("Register subscription {} for {}", res.expr(), face);
         match res.session_ctxs.get_mut(&face.id) {
-            Some(ctx) => match &ctx.subs {
-                Some(info) => {
-                    if Mode::Pull == info.mode {
-                        get_mut_unchecked(ctx).subs = Some(*sub_info);
-                    }
-                }
-                None => {
-                    get_mut_unchecked(ctx).subs = Some(*sub_info);
-                }
-            },
+            // A face declares each of its subscriptions once: declaring it again updates it
+            Some(ctx) => get_mut_unchecked(ctx).subs = Some(*sub_info),
             None => {
                 res.session_ctxs.insert(
                     face.id,
@@ -240,7 +235,7 @@ fn register_client_subscription(
                         remote_expr_id: None,
                         subs: Some(*sub_info),
                         qabl: None,
-                        last_values: HashMap::new(),
+                        last_values: IndexMap::new(),
                         in_interceptor_cache: None,
                         e_interceptor_cache: None,
                     }),
@@ -516,6 +511,7 @@ pub(super) fn pubsub_new_face(
                             id: 0, // TODO
                             wire_expr: key_expr,
                             ext_info: sub_info,
+                            ext_payload_filter: None,
                         }),
                     },
                     sub.expr(),
@@ -52,7 +52,10 @@ use zenoh_config::{unwrap_or_default, ModeDependent, WhatAmI, WhatAmIMatcher};
 use zenoh_protocol::network::Oam;
 use zenoh_protocol::{
     common::ZExtBody,
-    network::{declare::queryable::ext::QueryableInfo, oam::id::OAM_LINKSTATE},
+    network::{
+        declare::{queryable::ext::QueryableInfo, subscriber::ext::PayloadFilterType},
+        oam::id::OAM_LINKSTATE,
+    },
 };
 use zenoh_result::ZResult;
 use zenoh_sync::get_mut_unchecked;
@@ -368,7 +371,8 @@ impl HatContext {
 }
 
 struct HatFace {
-    local_subs: HashSet<Arc<Resource>>,
+    /// The subscriptions declared to the face, with the payload filter they were declared with.
+    local_subs: HashMap<Arc<Resource>, Option<PayloadFilterType>>,
     remote_subs: HashSet<Arc<Resource>>,
     local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     remote_qabls: HashSet<Arc<Resource>>,
@@ -377,7 +381,7 @@ struct HatFace {
 impl HatFace {
     fn new() -> Self {
         Self {
-            local_subs: HashSet::new(),
+            local_subs: HashMap::new(),
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
@@ -14,12 +14,14 @@
 use super::{face_hat, face_hat_mut, get_routes_entries};
 use super::{HatCode, HatFace};
 use crate::net::routing::dispatcher::face::FaceState;
+use crate::net::routing::dispatcher::pubsub::aggregated_payload_filter;
 use crate::net::routing::dispatcher::resource::{NodeId, Resource, SessionContext};
 use crate::net::routing::dispatcher::tables::Tables;
 use crate::net::routing::dispatcher::tables::{Route, RoutingExpr};
 use crate::net::routing::hat::{HatPubSubTrait, SendDeclare, Sources};
 use crate::net::routing::router::RoutesIndexes;
 use crate::net::routing::{RoutingContext, PREFIX_LIVELINESS};
+use indexmap::IndexMap;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -42,12 +44,16 @@ fn propagate_simple_subscription_to(
     src_face: &mut Arc<FaceState>,
     send_declare: &mut SendDeclare,
 ) {
+    let payload_filter = aggregated_payload_filter(res, dst_face);
+    let current_filter = face_hat!(dst_face).local_subs.get(res);
     if (src_face.id != dst_face.id
         || (dst_face.whatami == WhatAmI::Client && res.expr().starts_with(PREFIX_LIVELINESS)))
-        && !face_hat!(dst_face).local_subs.contains(res)
+        && (current_filter.is_none() || *current_filter.unwrap() != payload_filter)
         && (src_face.whatami == WhatAmI::Client || dst_face.whatami == WhatAmI::Client)
     {
-        face_hat_mut!(dst_face).local_subs.insert(res.clone());
+        face_hat_mut!(dst_face)
+            .local_subs
+            .insert(res.clone(), payload_filter.clone());
         let key_expr = Resource::decl_key(res, dst_face);
         send_declare(
             &dst_face.primitives,
@@ -60,6 +66,7 @@ fn propagate_simple_subscription_to(
                         id: 0, // @TODO use proper SubscriberId (#703)
                         wire_expr: key_expr,
                         ext_info: *sub_info,
+                        ext_payload_filter: payload_filter,
                     }),
                 },
                 res.expr(),
@@ -103,16 +110,8 @@ fn register_client_subscription(
         let res = get_mut_unchecked(res);
         tracing::debug!("Register subscription {} for {}", res.expr(), face);
         match res.session_ctxs.get_mut(&face.id) {
-            Some(ctx) => match &ctx.subs {
-                Some(info) => {
-                    if Mode::Pull == info.mode {
-                        get_mut_unchecked(ctx).subs = Some(*sub_info);
-                    }
-                }
-                None => {
-                    get_mut_unchecked(ctx).subs = Some(*sub_info);
-                }
-            },
+            // A face declares each of its subscriptions once: declaring it again updates it
+            Some(ctx) => get_mut_unchecked(ctx).subs = Some(*sub_info),
             None => {
                 res.session_ctxs.insert(
                     face.id,
@@ -122,7 +121,7 @@ fn register_client_subscription(
                         remote_expr_id: None,
                         subs: Some(*sub_info),
                         qabl: None,
-                        last_values: HashMap::new(),
+                        last_values: IndexMap::new(),
                         in_interceptor_cache: None,
                         e_interceptor_cache: None,
                     }),
@@ -160,6 +159,7 @@ fn declare_client_subscription(
                         id: 0, // @TODO use proper SubscriberId (#703)
                         wire_expr: res.expr().into(),
                         ext_info: *sub_info,
+                        ext_payload_filter: None,
                     }),
                 },
                 res.expr(),
@@ -187,7 +187,7 @@ fn propagate_forget_simple_subscription(
     send_declare: &mut SendDeclare,
 ) {
     for face in tables.faces.values_mut() {
-        if face_hat!(face).local_subs.contains(res) {
+        if face_hat!(face).local_subs.contains_key(res) {
             let wire_expr = Resource::get_best_key(res, "", face.id);
             send_declare(
                 &face.primitives,
@@ -227,7 +227,7 @@ pub(super) fn undeclare_client_subscription(
     }
     if client_subs.len() == 1 {
         let face = &mut client_subs[0];
-        if face_hat!(face).local_subs.contains(res)
+        if face_hat!(face).local_subs.contains_key(res)
             && !(face.whatami == WhatAmI::Client && res.expr().starts_with(PREFIX_LIVELINESS))
         {
             let wire_expr = Resource::get_best_key(res, "", face.id);
@@ -320,6 +320,7 @@ impl AdminSpace {
                 id: 0, // @TODO use proper SubscriberId (#703)
                 wire_expr: [&root_key, "/config/**"].concat().into(),
                 ext_info: SubscriberInfo::default(),
+                ext_payload_filter: None,
             }),
         });
     }
@@ -344,13 +345,17 @@ impl AdminSpace {
 impl Primitives for AdminSpace {
     fn send_declare(&self, msg: Declare) {
         tracing::trace!("Recv declare {:?}", msg);
-        if let DeclareBody::DeclareKeyExpr(m) = msg.body {
-            match self.key_expr_to_string(&m.wire_expr) {
+        match msg.body {
+            DeclareBody::DeclareKeyExpr(m) => match self.key_expr_to_string(&m.wire_expr) {
                 Ok(s) => {
                     zlock!(self.mappings).insert(m.id, s.into());
                 }
                 Err(e) => error!("Unknown expr_id {}! ({})", m.id, e),
+            },
+            DeclareBody::UndeclareKeyExpr(m) => {
+                zlock!(self.mappings).remove(&m.id);
             }
+            _ => {}
         }
     }
 
@@ -428,6 +433,7 @@ impl Primitives for AdminSpace {
                         rid: msg.id,
                         ext_qos: ext::QoSType::response_final_default(),
                         ext_tstamp: None,
+                        ext_served: None,
                     });
                     return;
                 }
@@ -441,6 +447,7 @@ impl Primitives for AdminSpace {
                         rid: msg.id,
                         ext_qos: ext::QoSType::response_final_default(),
                         ext_tstamp: None,
+                        ext_served: None,
                     });
                     return;
                 }
@@ -458,6 +465,8 @@ impl Primitives for AdminSpace {
                     qid: msg.id,
                     zid,
                     primitives,
+                    // The admin space reflects the declared key expressions, wildcards included
+                    allow_wildcard_writes: true,
                     #[cfg(feature = "unstable")]
                     attachment: query.ext_attachment.map(Into::into),
                 }),
@@ -583,7 +592,9 @@ fn local_data(context: &AdminContext, query: Query) {
         "metadata": context.metadata,
         "locators": locators,
         "sessions": transports,
+        "rejected_sessions": transport_mgr.get_rejected_transports_unicast(),
         "plugins": plugins,
+        "healthy": context.runtime.is_healthy(),
     });
 
     #[cfg(feature = "stats")]
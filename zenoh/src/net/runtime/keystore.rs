@@ -0,0 +1,75 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use advisory_lock::{AdvisoryFileLock, FileLockMode};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use zenoh_protocol::core::ZenohId;
+use zenoh_result::{zerror, ZResult};
+
+/// A file persisting the [`ZenohId`] of a runtime across restarts.
+///
+/// The file is exclusively locked for as long as the keystore is alive, preventing two
+/// processes from sharing the same identity simultaneously.
+pub(crate) struct Keystore {
+    path: PathBuf,
+    file: File,
+}
+
+impl Keystore {
+    /// Open the keystore at `path`, creating it with `zid` if it doesn't exist yet.
+    ///
+    /// Returns the keystore along with the [`ZenohId`] it stores.
+    pub(crate) fn open<P: AsRef<Path>>(path: P, zid: ZenohId) -> ZResult<(Self, ZenohId)> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(&path)
+            .map_err(|e| zerror!("Unable to open keystore {}: {}", path.display(), e))?;
+        file.try_lock(FileLockMode::Exclusive).map_err(|_| {
+            zerror!(
+                "Keystore {} is already in use by another process",
+                path.display()
+            )
+        })?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| zerror!("Unable to read keystore {}: {}", path.display(), e))?;
+        let zid = match content.trim() {
+            "" => {
+                tracing::info!("Storing ZID {} in new keystore {}", zid, path.display());
+                file.seek(SeekFrom::Start(0))
+                    .and_then(|_| writeln!(file, "{}", zid))
+                    .and_then(|_| file.sync_all())
+                    .map_err(|e| zerror!("Unable to write keystore {}: {}", path.display(), e))?;
+                zid
+            }
+            stored => stored
+                .parse()
+                .map_err(|e| zerror!("Invalid keystore {}: {}", path.display(), e))?,
+        };
+        Ok((Keystore { path, file }, zid))
+    }
+}
+
+impl Drop for Keystore {
+    fn drop(&mut self) {
+        if let Err(e) = self.file.unlock() {
+            tracing::warn!("Unable to unlock keystore {}: {}", self.path.display(), e);
+        }
+    }
+}
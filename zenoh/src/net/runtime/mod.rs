@@ -18,7 +18,10 @@
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 mod adminspace;
+#[cfg(feature = "keystore")]
+mod keystore;
 pub mod orchestrator;
+pub(crate) mod supervisor;
 
 use super::primitives::DeMux;
 use super::routing;
@@ -30,11 +33,16 @@ use crate::{GIT_VERSION, LONG_VERSION};
 pub use adminspace::AdminSpace;
 use futures::stream::StreamExt;
 use futures::Future;
+#[cfg(feature = "keystore")]
+use keystore::Keystore;
 use std::any::Any;
 use std::sync::{Arc, Weak};
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 use std::sync::{Mutex, MutexGuard};
 use std::time::Duration;
+use supervisor::Supervisor;
+#[cfg(feature = "unstable")]
+pub(crate) use supervisor::TaskFailureHandler;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use uhlc::{HLCBuilder, HLC};
@@ -50,6 +58,11 @@ use zenoh_transport::{
     TransportManager, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
 };
 
+/// The maximum number of times a recoverable internal task is restarted after a failure.
+const MAX_TASK_RESTARTS: usize = 3;
+/// The name of the supervised tasks reopening the listeners whose accept loop terminated.
+const ACCEPT_TASK: &str = "accept";
+
 pub(crate) struct RuntimeState {
     zid: ZenohId,
     whatami: WhatAmI,
@@ -59,8 +72,12 @@ pub(crate) struct RuntimeState {
     manager: TransportManager,
     transport_handlers: std::sync::RwLock<Vec<Arc<dyn TransportEventHandler>>>,
     locators: std::sync::RwLock<Vec<Locator>>,
+    listeners: std::sync::Mutex<Vec<EndPoint>>,
     hlc: Option<Arc<HLC>>,
     task_controller: TaskController,
+    supervisor: Arc<Supervisor>,
+    #[cfg(feature = "keystore")]
+    keystore: std::sync::Mutex<Option<Keystore>>,
     #[cfg(all(feature = "unstable", feature = "plugins"))]
     plugins_manager: Mutex<PluginsManager>,
 }
@@ -104,6 +121,28 @@ impl RuntimeBuilder {
         } = self;
 
         tracing::debug!("Zenoh Rust API {}", GIT_VERSION);
+        #[cfg(feature = "keystore")]
+        let (config, keystore) = {
+            let mut config = config;
+            let keystore = match config.keystore().clone() {
+                Some(path) => {
+                    let (keystore, zid) = Keystore::open(path, *config.id())?;
+                    config.set_id(zid).map_err(|_| {
+                        zenoh_result::zerror!("Unable to set ZID {} from keystore", zid)
+                    })?;
+                    Some(keystore)
+                }
+                None => None,
+            };
+            (config, keystore)
+        };
+        #[cfg(not(feature = "keystore"))]
+        if let Some(path) = config.keystore() {
+            bail!(
+                "Unable to open keystore {}: zenoh was built without the `keystore` feature",
+                path
+            );
+        }
         let zid = *config.id();
         tracing::info!("Using ZID: {}", zid);
 
@@ -132,6 +171,7 @@ impl RuntimeBuilder {
             .unwrap_or_else(|| crate::plugins::loader::load_plugins(&config));
         // Admin space creation flag
         let start_admin_space = *config.adminspace.enabled();
+        apply_logging_config(&config);
 
         let config = Notifier::new(config);
         let runtime = Runtime {
@@ -144,8 +184,12 @@ impl RuntimeBuilder {
                 manager: transport_manager,
                 transport_handlers: std::sync::RwLock::new(vec![]),
                 locators: std::sync::RwLock::new(vec![]),
+                listeners: std::sync::Mutex::new(vec![]),
                 hlc,
                 task_controller: TaskController::default(),
+                supervisor: Arc::new(Supervisor::new()),
+                #[cfg(feature = "keystore")]
+                keystore: std::sync::Mutex::new(keystore),
                 #[cfg(all(feature = "unstable", feature = "plugins"))]
                 plugins_manager: Mutex::new(plugins_manager),
             }),
@@ -163,27 +207,33 @@ impl RuntimeBuilder {
         crate::plugins::loader::start_plugins(&runtime);
 
         // Start notifier task
-        let receiver = config.subscribe();
         let token = runtime.get_cancellation_token();
-        runtime.spawn({
+        runtime.spawn_supervised("config_notifier", MAX_TASK_RESTARTS, {
             let runtime2 = runtime.clone();
-            async move {
-                let mut stream = receiver.into_stream();
-                loop {
-                    tokio::select! {
-                        res = stream.next() => {
-                            match res {
-                                Some(event) => {
-                                    if &*event == "connect/endpoints" {
-                                        if let Err(e) = runtime2.update_peers().await {
-                                            tracing::error!("Error updating peers: {}", e);
+            move || {
+                let runtime2 = runtime2.clone();
+                let token = token.clone();
+                let receiver = config.subscribe();
+                async move {
+                    let mut stream = receiver.into_stream();
+                    loop {
+                        tokio::select! {
+                            res = stream.next() => {
+                                match res {
+                                    Some(event) => {
+                                        if &*event == "connect/endpoints" {
+                                            if let Err(e) = runtime2.update_peers().await {
+                                                tracing::error!("Error updating peers: {}", e);
+                                            }
+                                        } else if event.trim_start_matches('/').starts_with("logging") {
+                                            apply_logging_config(&runtime2.config().lock());
                                         }
-                                    }
-                                },
-                                None => { break; }
+                                    },
+                                    None => { break; }
+                                }
                             }
+                            _ = token.cancelled() => { break; }
                         }
-                        _ = token.cancelled() => { break; }
                     }
                 }
             }
@@ -193,6 +243,14 @@ impl RuntimeBuilder {
     }
 }
 
+fn apply_logging_config(config: &Config) {
+    if let Some(directives) = config.logging.directives() {
+        if let Err(e) = zenoh_util::set_log_filter(&directives) {
+            tracing::warn!("Unable to apply the logging configuration: {}", e);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Runtime {
     state: Arc<RuntimeState>,
@@ -247,6 +305,9 @@ impl Runtime {
             .unwrap()
             .root_res
             .close();
+        // release the identity so that it can be reused by another runtime
+        #[cfg(feature = "keystore")]
+        self.state.keystore.lock().unwrap().take();
         Ok(())
     }
 
@@ -282,6 +343,55 @@ impl Runtime {
             .spawn_abortable_with_rt(zenoh_runtime::ZRuntime::Net, future)
     }
 
+    /// Spawns a supervised task within runtime.
+    /// If a future produced by `make` panics, the failure is reported to the registered
+    /// [`TaskFailureHandler`]s and a new future is spawned, at most `max_restarts` times.
+    /// If the task can't be recovered, the runtime is marked as unhealthy.
+    /// Upon runtime close the task will be automatically aborted.
+    pub(crate) fn spawn_supervised<F, Fut>(
+        &self,
+        task: &'static str,
+        max_restarts: usize,
+        make: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+    {
+        let supervisor = self.state.supervisor.clone();
+        self.spawn_abortable(async move { supervisor.supervise(task, max_restarts, make).await })
+    }
+
+    /// Returns false if one of the internal tasks of this runtime failed and couldn't be restarted.
+    pub fn is_healthy(&self) -> bool {
+        self.state.supervisor.is_healthy()
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn add_task_failure_handler(&self, handler: TaskFailureHandler) -> usize {
+        self.state.supervisor.add_failure_handler(handler)
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn remove_task_failure_handler(&self, id: usize) {
+        self.state.supervisor.remove_failure_handler(id)
+    }
+
+    /// Makes the supervised internal task `task` fail. Only intended for testing.
+    ///
+    /// Failing the `accept` task terminates the accept loops of the listeners.
+    #[cfg(feature = "testing")]
+    pub(crate) async fn inject_task_failure(&self, task: &str) {
+        if task == ACCEPT_TASK {
+            let listeners = self.state.listeners.lock().unwrap().clone();
+            for listener in listeners {
+                let _ = self.manager().del_listener(&listener).await;
+            }
+        } else {
+            self.state.supervisor.inject_failure(task)
+        }
+    }
+
     pub(crate) fn router(&self) -> Arc<Router> {
         self.state.router.clone()
     }
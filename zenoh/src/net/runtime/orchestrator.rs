@@ -11,10 +11,11 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::{Runtime, RuntimeSession};
+use super::{Runtime, RuntimeSession, ACCEPT_TASK, MAX_TASK_RESTARTS};
 use futures::prelude::*;
 use socket2::{Domain, Socket, Type};
 use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::UdpSocket;
 use zenoh_buffers::reader::DidntRead;
@@ -197,23 +198,37 @@ impl Runtime {
                 .collect();
             if !sockets.is_empty() {
                 let this = self.clone();
+                let mcast_socket = Arc::new(mcast_socket);
+                let sockets = Arc::new(sockets);
                 match (listen, autoconnect.is_empty()) {
                     (true, false) => {
-                        self.spawn_abortable(async move {
-                            tokio::select! {
-                                _ = this.responder(&mcast_socket, &sockets) => {},
-                                _ = this.connect_all(&sockets, autoconnect, &addr) => {},
+                        self.spawn_supervised("scouting", MAX_TASK_RESTARTS, move || {
+                            let this = this.clone();
+                            let mcast_socket = mcast_socket.clone();
+                            let sockets = sockets.clone();
+                            async move {
+                                tokio::select! {
+                                    _ = this.responder(&mcast_socket, &sockets) => {},
+                                    _ = this.connect_all(&sockets, autoconnect, &addr) => {},
+                                }
                             }
                         });
                     }
                     (true, true) => {
-                        self.spawn_abortable(async move {
-                            this.responder(&mcast_socket, &sockets).await;
+                        self.spawn_supervised("scouting", MAX_TASK_RESTARTS, move || {
+                            let this = this.clone();
+                            let mcast_socket = mcast_socket.clone();
+                            let sockets = sockets.clone();
+                            async move {
+                                this.responder(&mcast_socket, &sockets).await;
+                            }
                         });
                     }
                     (false, false) => {
-                        self.spawn_abortable(async move {
-                            this.connect_all(&sockets, autoconnect, &addr).await
+                        self.spawn_supervised("scouting", MAX_TASK_RESTARTS, move || {
+                            let this = this.clone();
+                            let sockets = sockets.clone();
+                            async move { this.connect_all(&sockets, autoconnect, &addr).await }
                         });
                     }
                     _ => {}
@@ -456,9 +471,14 @@ impl Runtime {
         retry_config: zenoh_config::ConnectionRetryConf,
     ) {
         let this = self.clone();
-        self.spawn(async move {
-            this.add_listener_retry(listener, retry_config).await;
-            this.print_locators();
+        self.spawn_supervised("listener", MAX_TASK_RESTARTS, move || {
+            let this = this.clone();
+            let listener = listener.clone();
+            let retry_config = retry_config.clone();
+            async move {
+                this.add_listener_retry(listener, retry_config).await;
+                this.print_locators();
+            }
         });
     }
 
@@ -479,7 +499,19 @@ impl Runtime {
     async fn add_listener(&self, listener: EndPoint) -> ZResult<()> {
         let endpoint = listener.clone();
         match self.manager().add_listener(endpoint).await {
-            Ok(listener) => tracing::debug!("Listener added: {}", listener),
+            Ok(locator) => {
+                tracing::debug!("Listener added: {}", locator);
+                // Keep the endpoint as opened, e.g. with its port if it was 0, to reopen it
+                let opened = self
+                    .manager()
+                    .get_listeners()
+                    .await
+                    .into_iter()
+                    .find(|endpoint| endpoint.to_locator() == locator)
+                    .unwrap_or(listener);
+                self.state.listeners.lock().unwrap().push(opened.clone());
+                self.supervise_listener(opened);
+            }
             Err(err) => {
                 tracing::warn!("Unable to open listener {}: {}", listener, err);
                 return Err(err);
@@ -488,6 +520,36 @@ impl Runtime {
         Ok(())
     }
 
+    /// Supervises the accept loop of the opened listener `listener`: its termination is
+    /// reported as a failure of the `accept` task, upon which the listener is reopened,
+    /// at most [`MAX_TASK_RESTARTS`] times before the runtime is marked as unhealthy.
+    fn supervise_listener(&self, listener: EndPoint) {
+        self.spawn_supervised(ACCEPT_TASK, MAX_TASK_RESTARTS, {
+            let runtime = self.clone();
+            move || {
+                let runtime = runtime.clone();
+                let listener = listener.clone();
+                async move { runtime.run_listener(listener).await }
+            }
+        });
+    }
+
+    async fn run_listener(&self, listener: EndPoint) {
+        if !self.manager().get_listeners().await.contains(&listener) {
+            match self.manager().add_listener(listener.clone()).await {
+                Ok(locator) => {
+                    tracing::warn!("Reopened listener {}", locator);
+                    self.print_locators();
+                }
+                Err(err) => panic!("unable to reopen listener {listener}: {err}"),
+            }
+        }
+        self.manager().listener_terminated(&listener).await;
+        if self.state.listeners.lock().unwrap().contains(&listener) {
+            panic!("the accept loop of listener {listener} terminated");
+        }
+    }
+
     fn print_locators(&self) {
         let mut locators = self.state.locators.write().unwrap();
         *locators = self.manager().get_locators();
@@ -665,7 +727,11 @@ impl Runtime {
             .await?
         {
             let this = self.clone();
-            self.spawn(async move { this.peer_connector_retry(peer).await });
+            self.spawn_supervised("peer_connector", MAX_TASK_RESTARTS, move || {
+                let this = this.clone();
+                let peer = peer.clone();
+                async move { this.peer_connector_retry(peer).await }
+            });
             Ok(())
         } else {
             bail!("Forbidden multicast endpoint in connect list!")
@@ -1037,16 +1103,24 @@ impl Runtime {
             WhatAmI::Client => {
                 let runtime = session.runtime.clone();
                 let cancellation_token = runtime.get_cancellation_token();
-                session.runtime.spawn(async move {
-                    let retry_config = runtime.get_global_connect_retry_config();
-                    let mut period = retry_config.period();
-                    while runtime.start_client().await.is_err() {
-                        tokio::select! {
-                            _ = tokio::time::sleep(period.next_duration()) => {}
-                            _ = cancellation_token.cancelled() => { break; }
+                session.runtime.spawn_supervised(
+                    "client_connector",
+                    MAX_TASK_RESTARTS,
+                    move || {
+                        let runtime = runtime.clone();
+                        let cancellation_token = cancellation_token.clone();
+                        async move {
+                            let retry_config = runtime.get_global_connect_retry_config();
+                            let mut period = retry_config.period();
+                            while runtime.start_client().await.is_err() {
+                                tokio::select! {
+                                    _ = tokio::time::sleep(period.next_duration()) => {}
+                                    _ = cancellation_token.cancelled() => { break; }
+                                }
+                            }
                         }
-                    }
-                });
+                    },
+                );
             }
             _ => {
                 if let Some(endpoint) = &*zread!(session.endpoint) {
@@ -1063,9 +1137,15 @@ impl Runtime {
                     if peers.contains(endpoint) {
                         let endpoint = endpoint.clone();
                         let runtime = session.runtime.clone();
-                        session
-                            .runtime
-                            .spawn(async move { runtime.peer_connector_retry(endpoint).await });
+                        session.runtime.spawn_supervised(
+                            "peer_connector",
+                            MAX_TASK_RESTARTS,
+                            move || {
+                                let runtime = runtime.clone();
+                                let endpoint = endpoint.clone();
+                                async move { runtime.peer_connector_retry(endpoint).await }
+                            },
+                        );
                     }
                 }
             }
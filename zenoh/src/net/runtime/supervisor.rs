@@ -0,0 +1,162 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use futures::FutureExt;
+use std::any::Any;
+#[cfg(feature = "testing")]
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+#[cfg(feature = "unstable")]
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "testing")]
+use std::sync::Mutex;
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "testing")]
+use tokio::sync::Notify;
+#[cfg(feature = "testing")]
+use zenoh_core::zlock;
+use zenoh_core::zread;
+#[cfg(feature = "unstable")]
+use zenoh_core::zwrite;
+
+/// Handler called with the name of a failed internal task and a description of its failure.
+pub(crate) type TaskFailureHandler = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// Watches the internal tasks of a runtime, reporting their failures and restarting them
+/// when they are recoverable.
+pub(crate) struct Supervisor {
+    healthy: AtomicBool,
+    #[cfg(feature = "unstable")]
+    handler_counter: AtomicUsize,
+    handlers: RwLock<Vec<(usize, TaskFailureHandler)>>,
+    #[cfg(feature = "testing")]
+    injections: Mutex<HashMap<String, Arc<Notify>>>,
+}
+
+impl Supervisor {
+    pub(crate) fn new() -> Self {
+        Supervisor {
+            healthy: AtomicBool::new(true),
+            #[cfg(feature = "unstable")]
+            handler_counter: AtomicUsize::new(0),
+            handlers: RwLock::new(vec![]),
+            #[cfg(feature = "testing")]
+            injections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn add_failure_handler(&self, handler: TaskFailureHandler) -> usize {
+        let id = self.handler_counter.fetch_add(1, Ordering::SeqCst);
+        zwrite!(self.handlers).push((id, handler));
+        id
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn remove_failure_handler(&self, id: usize) {
+        zwrite!(self.handlers).retain(|(handler_id, _)| *handler_id != id);
+    }
+
+    /// Makes the next (or current) run of the supervised task `task` panic.
+    #[cfg(feature = "testing")]
+    pub(crate) fn inject_failure(&self, task: &str) {
+        self.injection(task).notify_one();
+    }
+
+    #[cfg(feature = "testing")]
+    fn injection(&self, task: &str) -> Arc<Notify> {
+        zlock!(self.injections)
+            .entry(task.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Marks the runtime as unhealthy, after a task failed and couldn't be recovered.
+    pub(crate) fn mark_unhealthy(&self) {
+        self.healthy.store(false, Ordering::Relaxed);
+    }
+
+    pub(crate) fn report_failure(&self, task: &str, error: &str) {
+        tracing::error!("Internal task `{}` failed: {}", task, error);
+        let handlers = zread!(self.handlers)
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect::<Vec<_>>();
+        for handler in handlers {
+            handler(task, error);
+        }
+    }
+
+    /// Runs the futures produced by `make` until one of them completes, restarting
+    /// at most `max_restarts` times the ones that panic. If the task still fails after
+    /// its last restart, the runtime is marked as unhealthy.
+    pub(crate) async fn supervise<F, Fut>(&self, task: &'static str, max_restarts: usize, make: F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future,
+    {
+        #[cfg(feature = "testing")]
+        let injection = self.injection(task);
+        let mut restarts = 0;
+        loop {
+            #[cfg(feature = "testing")]
+            let run = async {
+                tokio::select! {
+                    _ = make() => {},
+                    _ = injection.notified() => panic!("Injected failure"),
+                }
+            };
+            #[cfg(not(feature = "testing"))]
+            let run = make().map(|_| ());
+            match AssertUnwindSafe(run).catch_unwind().await {
+                Ok(()) => break,
+                Err(panic) => {
+                    let error = panic_message(panic.as_ref());
+                    self.report_failure(task, &error);
+                    if restarts >= max_restarts {
+                        tracing::error!(
+                            "Internal task `{}` could not be recovered after {} restart(s)",
+                            task,
+                            restarts
+                        );
+                        self.mark_unhealthy();
+                        break;
+                    }
+                    restarts += 1;
+                    tracing::warn!(
+                        "Restarting internal task `{}` ({}/{})",
+                        task,
+                        restarts,
+                        max_restarts
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
@@ -25,10 +25,12 @@ use zenoh_protocol::core::Encoding;
 use zenoh_protocol::core::{
     key_expr::keyexpr, ExprId, Reliability, WhatAmI, WireExpr, ZenohId, EMPTY_EXPR_ID,
 };
-use zenoh_protocol::network::declare::subscriber::ext::SubscriberInfo;
+use zenoh_protocol::network::declare::subscriber::ext::{
+    PayloadFilterType, PayloadPrefix, SubscriberInfo,
+};
 use zenoh_protocol::network::declare::Mode;
 use zenoh_protocol::network::{ext, Declare, DeclareBody, DeclareKeyExpr};
-use zenoh_protocol::zenoh::{PushBody, Put};
+use zenoh_protocol::zenoh::{Del, PushBody, Put};
 
 #[test]
 fn base_test() {
@@ -68,6 +70,7 @@ fn base_test() {
         &mut face.upgrade().unwrap(),
         &WireExpr::from(1).with_suffix("four/five"),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -244,6 +247,7 @@ async fn clean_test() {
         &mut face0.upgrade().unwrap(),
         &"todrop1/todrop11".into(),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -259,6 +263,7 @@ async fn clean_test() {
         &mut face0.upgrade().unwrap(),
         &WireExpr::from(1).with_suffix("/todrop12"),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -309,6 +314,7 @@ async fn clean_test() {
         &mut face0.upgrade().unwrap(),
         &"todrop3".into(),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -340,6 +346,7 @@ async fn clean_test() {
         &mut face0.upgrade().unwrap(),
         &"todrop5".into(),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -349,6 +356,7 @@ async fn clean_test() {
         &mut face0.upgrade().unwrap(),
         &"todrop6".into(),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -527,6 +535,7 @@ fn client_test() {
         &mut face0.upgrade().unwrap(),
         &WireExpr::from(11).with_suffix("/**"),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -575,6 +584,7 @@ fn client_test() {
         &mut face1.upgrade().unwrap(),
         &WireExpr::from(21).with_suffix("/**"),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -623,6 +633,7 @@ fn client_test() {
         &mut face2.upgrade().unwrap(),
         &WireExpr::from(31).with_suffix("/**"),
         &sub_info,
+        None,
         NodeId::default(),
         &mut |p, m| p.send_declare(m),
     );
@@ -798,3 +809,303 @@ fn client_test() {
     // mapping strategy check
     // assert_eq!(primitives2.get_last_key().unwrap(), KeyExpr::IdWithSuffix(31, "/z2_pub1".to_string()));
 }
+
+#[test]
+fn payload_filter_test() {
+    let config = Config::default();
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+
+    let sub_info = SubscriberInfo {
+        reliability: Reliability::Reliable,
+        mode: Mode::Push,
+    };
+    let payload_filter = PayloadFilterType {
+        prefixes: vec![PayloadPrefix {
+            offset: 1,
+            bytes: vec![0x01],
+        }],
+    };
+
+    let primitives0 = Arc::new(ClientPrimitives::new());
+    let face0 = Arc::downgrade(&router.new_primitives(primitives0.clone()).state);
+    declare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/filter".into(),
+        &sub_info,
+        Some(&payload_filter),
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+
+    let primitives1 = Arc::new(ClientPrimitives::new());
+    let face1 = Arc::downgrade(&router.new_primitives(primitives1.clone()).state);
+
+    let push = |body: PushBody| {
+        primitives0.clear_data();
+        full_reentrant_route_data(
+            &tables,
+            &face1.upgrade().unwrap(),
+            &"test/filter".into(),
+            ext::QoSType::default(),
+            None,
+            body,
+            0,
+        );
+        primitives0.get_last_name()
+    };
+    let put_zbuf = |payload: ZBuf| {
+        push(PushBody::Put(Put {
+            timestamp: None,
+            encoding: Encoding::default(),
+            ext_sinfo: None,
+            #[cfg(feature = "shared-memory")]
+            ext_shm: None,
+            ext_unknown: vec![],
+            payload,
+            ext_attachment: None,
+        }))
+    };
+    let put = |payload: &[u8]| put_zbuf(payload.to_vec().into());
+
+    // Only the payloads matching the filter are routed to the face
+    assert_eq!(put(&[0x00, 0x01]).unwrap(), "test/filter");
+    assert!(put(&[0x01, 0x00]).is_none());
+    assert!(put(&[0x00]).is_none());
+
+    // The prefix is matched across the slices of the payload
+    let sliced = |slices: &[&[u8]]| {
+        let mut payload = ZBuf::empty();
+        for slice in slices {
+            payload.push_zslice(slice.to_vec().into());
+        }
+        payload
+    };
+    assert_eq!(
+        put_zbuf(sliced(&[&[0x00], &[0x01, 0x02]])).unwrap(),
+        "test/filter"
+    );
+    assert!(put_zbuf(sliced(&[&[0x00], &[0x02, 0x01]])).is_none());
+
+    // The deletions are routed whatever the filter
+    assert_eq!(
+        push(PushBody::Del(Del {
+            timestamp: None,
+            ext_sinfo: None,
+            ext_attachment: None,
+            ext_unknown: vec![],
+        }))
+        .unwrap(),
+        "test/filter"
+    );
+
+    // Any matching subscription of the face without filter accepts all the payloads
+    declare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/**".into(),
+        &sub_info,
+        None,
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+    assert_eq!(put(&[0x01, 0x00]).unwrap(), "test/filter");
+    undeclare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/**".into(),
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+    assert!(put(&[0x01, 0x00]).is_none());
+
+    // Declaring the subscription again without filter removes it
+    declare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/filter".into(),
+        &sub_info,
+        None,
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+    assert_eq!(put(&[0x01, 0x00]).unwrap(), "test/filter");
+}
+
+#[test]
+fn update_subscription_test() {
+    let config = Config::default();
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+
+    let primitives0 = Arc::new(ClientPrimitives::new());
+    let face0 = Arc::downgrade(&router.new_primitives(primitives0.clone()).state);
+    let primitives1 = Arc::new(ClientPrimitives::new());
+    let face1 = Arc::downgrade(&router.new_primitives(primitives1.clone()).state);
+
+    let declare = |reliability: Reliability, mode: Mode| {
+        declare_subscription(
+            zlock!(tables.ctrl_lock).as_ref(),
+            &tables,
+            &mut face0.upgrade().unwrap(),
+            &"test/update".into(),
+            &SubscriberInfo { reliability, mode },
+            None,
+            NodeId::default(),
+            &mut |p, m| p.send_declare(m),
+        );
+    };
+    let sub_info = || {
+        let tables = zread!(tables.tables);
+        let res = Resource::get_resource(&tables.root_res, "test/update").unwrap();
+        let ctx = res.session_ctxs.get(&face0.upgrade().unwrap().id).unwrap();
+        ctx.subs.unwrap()
+    };
+    let put = || {
+        primitives0.clear_data();
+        full_reentrant_route_data(
+            &tables,
+            &face1.upgrade().unwrap(),
+            &"test/update".into(),
+            ext::QoSType::default(),
+            None,
+            PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_unknown: vec![],
+                payload: ZBuf::empty(),
+                ext_attachment: None,
+            }),
+            0,
+        );
+        primitives0.get_last_name()
+    };
+
+    // Declaring the subscription again updates it in place
+    declare(Reliability::BestEffort, Mode::Push);
+    assert_eq!(sub_info().reliability, Reliability::BestEffort);
+    declare(Reliability::Reliable, Mode::Push);
+    assert_eq!(sub_info().reliability, Reliability::Reliable);
+    declare(Reliability::BestEffort, Mode::Push);
+    assert_eq!(sub_info().reliability, Reliability::BestEffort);
+    assert_eq!(put().unwrap(), "test/update");
+
+    // The samples are kept for the next pull once switched to pull mode
+    declare(Reliability::BestEffort, Mode::Pull);
+    assert_eq!(sub_info().mode, Mode::Pull);
+    assert!(put().is_none());
+    pull_data(
+        &tables.tables,
+        &face0.upgrade().unwrap(),
+        0,
+        "test/update".into(),
+        None,
+    );
+    assert_eq!(primitives0.get_last_name().unwrap(), "test/update");
+
+    // And pushed again once switched back to push mode
+    declare(Reliability::BestEffort, Mode::Push);
+    assert_eq!(sub_info().mode, Mode::Push);
+    assert_eq!(put().unwrap(), "test/update");
+}
+
+#[test]
+fn release_mappings_test() {
+    let config = Config::default();
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+
+    let sub_info = SubscriberInfo {
+        reliability: Reliability::Reliable,
+        mode: Mode::Push,
+    };
+
+    let primitives0 = Arc::new(DummyPrimitives {});
+    let face0 = Arc::downgrade(&router.new_primitives(primitives0).state);
+    let primitives1 = Arc::new(ClientPrimitives::new());
+    let face1 = Arc::downgrade(&router.new_primitives(primitives1.clone()).state);
+
+    declare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/release".into(),
+        &sub_info,
+        None,
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+    let res = Resource::get_resource(zread!(tables.tables)._get_root(), "test/release")
+        .map(|res| Arc::downgrade(&res))
+        .unwrap();
+    let expr_id = *face1
+        .upgrade()
+        .unwrap()
+        .local_mappings
+        .keys()
+        .next()
+        .unwrap();
+    assert_eq!(
+        zlock!(primitives1.mapping).get(&expr_id).unwrap(),
+        "test/release"
+    );
+
+    undeclare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/release".into(),
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+
+    // The id is undeclared right away and the resource released
+    assert!(!face1
+        .upgrade()
+        .unwrap()
+        .local_mappings
+        .contains_key(&expr_id));
+    assert!(!zlock!(primitives1.mapping).contains_key(&expr_id));
+    assert!(res.upgrade().is_none());
+
+    // But the id is not reused by a later declaration
+    declare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face0.upgrade().unwrap(),
+        &"test/other".into(),
+        &sub_info,
+        None,
+        NodeId::default(),
+        &mut |p, m| p.send_declare(m),
+    );
+    let face1 = face1.upgrade().unwrap();
+    assert_eq!(face1.local_mappings.len(), 1);
+    assert!(!face1.local_mappings.contains_key(&expr_id));
+}
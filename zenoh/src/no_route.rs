@@ -0,0 +1,299 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Handling of the puts and gets issued while they have no route.
+//!
+//! A put has no route while no other zenoh node subscribes to its key expression, as far as the
+//! session knows, and a get has no route while no other zenoh node has a matching queryable:
+//! e.g. while a client isn't connected to a router yet, or until the router forwarded it the
+//! matching subscriptions. The [`NoRoutePolicy`] of a session tells what happens to these puts
+//! and gets; deliveries to the subscribers and queryables of the same session are unaffected.
+//!
+//! The policy of a session is read from the `no_route` section of the configuration,
+//! and may be overridden per publisher (see [`PublisherBuilder::no_route`](crate::publication::PublisherBuilder::no_route)):
+//! ```json5
+//! no_route: {
+//!   policy: "buffer",
+//!   buffer_size: 1000,
+//!   buffer_timeout_ms: 10000,
+//! }
+//! ```
+use crate::net::primitives::Primitives;
+use crate::net::routing::dispatcher::{face::Face, pubsub, queries};
+use crate::net::runtime::Runtime;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_config::{NoRouteConf, NoRoutePolicyConf};
+use zenoh_core::{zlock, zread};
+use zenoh_protocol::network::{Push, Request};
+use zenoh_result::ZResult;
+
+const DEFAULT_BUFFER_SIZE: usize = 1000;
+const DEFAULT_BUFFER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What happens to the puts and gets issued while they have no route.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NoRoutePolicy {
+    /// The messages are dropped by the routing.
+    #[default]
+    Drop,
+    /// The puts and gets fail with a [`NoRoute`] error.
+    Fail,
+    /// Up to `size` messages are queued until their route appears, then sent in order.
+    /// The messages that are queued for longer than `timeout` are dropped, and the puts and gets
+    /// fail with a [`NoRoute`] error while the queue is full.
+    Buffer { size: usize, timeout: Duration },
+}
+
+impl From<&NoRouteConf> for NoRoutePolicy {
+    fn from(conf: &NoRouteConf) -> Self {
+        match conf.policy() {
+            None | Some(NoRoutePolicyConf::Drop) => NoRoutePolicy::Drop,
+            Some(NoRoutePolicyConf::Fail) => NoRoutePolicy::Fail,
+            Some(NoRoutePolicyConf::Buffer) => NoRoutePolicy::Buffer {
+                size: conf.buffer_size().unwrap_or(DEFAULT_BUFFER_SIZE),
+                timeout: conf
+                    .buffer_timeout_ms()
+                    .map(Duration::from_millis)
+                    .unwrap_or(DEFAULT_BUFFER_TIMEOUT),
+            },
+        }
+    }
+}
+
+/// The error of the puts and gets that have no route, according to their [`NoRoutePolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoRoute {
+    message: String,
+}
+
+impl fmt::Display for NoRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for NoRoute {}
+
+enum Pending {
+    Push(Push),
+    Request(Request),
+}
+
+struct PendingEntry {
+    deadline: Instant,
+    key_expr: String,
+    msg: Pending,
+}
+
+/// Applies the [`NoRoutePolicy`] to the messages a session sends to the network.
+pub(crate) struct NoRouteHandler {
+    policy: NoRoutePolicy,
+    runtime: Runtime,
+    // The queued messages, and whether the expiration task is running
+    pending: Mutex<(VecDeque<PendingEntry>, bool)>,
+}
+
+impl NoRouteHandler {
+    pub(crate) fn new(runtime: Runtime) -> Self {
+        let policy = NoRoutePolicy::from(&runtime.config().lock().no_route);
+        NoRouteHandler {
+            policy,
+            runtime,
+            pending: Mutex::new((VecDeque::new(), false)),
+        }
+    }
+
+    /// Returns an error if `policy` (or the policy of the session) is [`NoRoutePolicy::Fail`]
+    /// and the puts (or the gets, if `query` is true) on `key_expr` have no route.
+    pub(crate) fn check(
+        &self,
+        policy: Option<NoRoutePolicy>,
+        query: bool,
+        key_expr: &str,
+    ) -> ZResult<()> {
+        if policy.unwrap_or(self.policy) == NoRoutePolicy::Fail && !self.has_route(query, key_expr)
+        {
+            return Err(NoRoute {
+                message: format!(
+                    "No route for the {} on {}",
+                    if query { "queries" } else { "data" },
+                    key_expr
+                ),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the message if it must be sent now.
+    pub(crate) fn push(
+        self: &Arc<Self>,
+        policy: Option<NoRoutePolicy>,
+        primitives: &Arc<Face>,
+        key_expr: &str,
+        msg: Push,
+    ) -> ZResult<Option<Push>> {
+        match self.route(policy, primitives, key_expr, Pending::Push(msg))? {
+            Some(Pending::Push(msg)) => Ok(Some(msg)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Sends the request now, or queues it.
+    pub(crate) fn send_request(
+        self: &Arc<Self>,
+        primitives: &Arc<Face>,
+        key_expr: &str,
+        msg: Request,
+    ) -> ZResult<()> {
+        if let Some(Pending::Request(msg)) =
+            self.route(None, primitives, key_expr, Pending::Request(msg))?
+        {
+            primitives.send_request(msg);
+        }
+        Ok(())
+    }
+
+    fn has_route(&self, query: bool, key_expr: &str) -> bool {
+        let router = self.runtime.router();
+        let tables = zread!(router.tables.tables);
+        if query {
+            queries::has_remote_query_route(&tables, key_expr)
+        } else {
+            pubsub::has_remote_data_route(&tables, key_expr)
+        }
+    }
+
+    fn route(
+        self: &Arc<Self>,
+        policy: Option<NoRoutePolicy>,
+        primitives: &Arc<Face>,
+        key_expr: &str,
+        msg: Pending,
+    ) -> ZResult<Option<Pending>> {
+        // The failures are checked beforehand with `check`
+        let (size, timeout) = match policy.unwrap_or(self.policy) {
+            NoRoutePolicy::Drop | NoRoutePolicy::Fail => return Ok(Some(msg)),
+            NoRoutePolicy::Buffer { size, timeout } => (size, timeout),
+        };
+        let query = matches!(msg, Pending::Request(_));
+        let mut guard = zlock!(self.pending);
+        let (pending, expiring) = &mut *guard;
+        // The messages queued on the same key expression are sent first, to keep them in order
+        let queued = pending.iter().any(|entry| {
+            entry.key_expr == key_expr && matches!(entry.msg, Pending::Request(_)) == query
+        });
+        let routed = self.has_route(query, key_expr);
+        if !queued && routed {
+            return Ok(Some(msg));
+        }
+        if pending.len() >= size {
+            return Err(NoRoute {
+                message: format!(
+                    "No route for the {} on {} and {} messages are already queued",
+                    if query { "queries" } else { "data" },
+                    key_expr,
+                    pending.len()
+                ),
+            }
+            .into());
+        }
+        pending.push_back(PendingEntry {
+            deadline: Instant::now() + timeout,
+            key_expr: key_expr.to_string(),
+            msg,
+        });
+        if routed {
+            self.flush_pending(pending, primitives);
+        } else if !*expiring {
+            *expiring = true;
+            let handler = self.clone();
+            zenoh_runtime::ZRuntime::Net.spawn(async move { handler.expire().await });
+        }
+        Ok(None)
+    }
+
+    /// Sends the queued messages whose route appeared, in order.
+    ///
+    /// Called when the session is told of a new subscription or queryable.
+    pub(crate) fn route_declared(self: &Arc<Self>, primitives: &Arc<Face>) {
+        if zlock!(self.pending).0.is_empty() {
+            return;
+        }
+        let handler = self.clone();
+        let primitives = primitives.clone();
+        // The declarations may be received while the routing tables are locked
+        zenoh_runtime::ZRuntime::Net.spawn(async move {
+            handler.flush_pending(&mut zlock!(handler.pending).0, &primitives);
+        });
+    }
+
+    fn flush_pending(&self, pending: &mut VecDeque<PendingEntry>, primitives: &Face) {
+        let mut kept = VecDeque::with_capacity(pending.len());
+        for entry in pending.drain(..) {
+            let query = matches!(entry.msg, Pending::Request(_));
+            // The messages behind a message still queued on the same key expression stay queued
+            if kept.iter().any(|kept: &PendingEntry| {
+                kept.key_expr == entry.key_expr && matches!(kept.msg, Pending::Request(_)) == query
+            }) || !self.has_route(query, &entry.key_expr)
+            {
+                kept.push_back(entry);
+                continue;
+            }
+            match entry.msg {
+                Pending::Push(msg) => primitives.send_push(msg),
+                Pending::Request(msg) => primitives.send_request(msg),
+            }
+        }
+        *pending = kept;
+    }
+
+    // Drops the queued messages as their deadline expires, until the queue is empty
+    async fn expire(self: Arc<Self>) {
+        loop {
+            let next = {
+                let mut guard = zlock!(self.pending);
+                let (pending, expiring) = &mut *guard;
+                let now = Instant::now();
+                let len = pending.len();
+                pending.retain(|entry| entry.deadline > now);
+                if pending.len() < len {
+                    tracing::warn!(
+                        "Dropped {} messages queued for longer than their no-route timeout",
+                        len - pending.len()
+                    );
+                }
+                match pending.iter().map(|entry| entry.deadline).min() {
+                    Some(next) => next,
+                    None => {
+                        *expiring = false;
+                        return;
+                    }
+                }
+            };
+            tokio::time::sleep_until(next.into()).await;
+        }
+    }
+}
+
+impl fmt::Debug for NoRouteHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoRouteHandler")
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
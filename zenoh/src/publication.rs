@@ -17,12 +17,15 @@
 use crate::handlers::Callback;
 #[zenoh_macros::unstable]
 use crate::handlers::DefaultHandler;
+#[cfg(not(feature = "testing"))]
 use crate::net::primitives::Primitives;
 use crate::prelude::*;
 #[zenoh_macros::unstable]
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
+#[zenoh_macros::unstable]
+use crate::transform::TransformChain;
 use crate::Encoding;
 use crate::SessionRef;
 use crate::Undeclarable;
@@ -31,6 +34,7 @@ use zenoh_core::{zread, AsyncResolve, Resolvable, Resolve, SyncResolve};
 use zenoh_protocol::network::push::ext;
 use zenoh_protocol::network::Mapping;
 use zenoh_protocol::network::Push;
+use zenoh_protocol::zenoh::put::ext::SourceInfoType;
 use zenoh_protocol::zenoh::Del;
 use zenoh_protocol::zenoh::PushBody;
 use zenoh_protocol::zenoh::Put;
@@ -120,6 +124,15 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    /// Change how the data is handled while the session has no route to send it
+    /// (the policy of the session by default).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn no_route(mut self, policy: NoRoutePolicy) -> Self {
+        self.publisher = self.publisher.no_route(policy);
+        self
+    }
+
     pub fn kind(mut self, kind: SampleKind) -> Self {
         self.kind = kind;
         self
@@ -145,14 +158,32 @@ impl SyncResolve for PutBuilder<'_, '_> {
             congestion_control,
             priority,
             destination,
+            #[cfg(feature = "unstable")]
+            no_route,
+            ..
         } = self.publisher;
 
+        let key_expr = key_expr?;
         let publisher = Publisher {
+            #[cfg(feature = "unstable")]
+            id: session.next_publisher_id(),
             session,
-            key_expr: key_expr?,
+            key_expr,
             congestion_control,
             priority,
             destination,
+            #[cfg(feature = "unstable")]
+            no_route,
+            #[cfg(feature = "unstable")]
+            loop_guard: false,
+            #[cfg(feature = "unstable")]
+            max_provenance_hops: DEFAULT_MAX_PROVENANCE_HOPS,
+            #[cfg(feature = "unstable")]
+            transform_out: None,
+            #[cfg(feature = "unstable")]
+            flow: None,
+            #[cfg(feature = "unstable")]
+            announcement: None,
         };
 
         resolve_put(
@@ -179,6 +210,27 @@ use std::convert::TryInto;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use zenoh_result::Error;
+#[zenoh_macros::unstable]
+use {
+    crate::no_route::NoRoutePolicy,
+    crate::sample::{
+        AttachmentBuilder, ProvenanceHop, ACK_ATTACHMENT_KEY, PROVENANCE_ATTACHMENT_KEY,
+    },
+    crate::scheduling::FlowHandle,
+    crate::time::Timestamp,
+    std::collections::HashSet,
+    std::future::Future,
+    std::time::{Duration, Instant},
+    zenoh_config::unwrap_or_default,
+};
+
+#[zenoh_macros::unstable]
+pub(crate) const DEFAULT_MAX_PROVENANCE_HOPS: usize = 16;
+
+/// The prefix of the liveliness tokens announcing the publishers, followed by the [`ZenohId`] of
+/// their session, their [`EntityId`](crate::EntityId) and their key expression.
+#[zenoh_macros::unstable]
+pub(crate) const PREFIX_PUBLICATION: &str = "@/publication";
 
 #[zenoh_macros::unstable]
 #[derive(Clone)]
@@ -243,10 +295,25 @@ impl std::fmt::Debug for PublisherRef<'_> {
 #[derive(Debug, Clone)]
 pub struct Publisher<'a> {
     pub(crate) session: SessionRef<'a>,
+    #[cfg(feature = "unstable")]
+    pub(crate) id: Id,
     pub(crate) key_expr: KeyExpr<'a>,
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
     pub(crate) destination: Locality,
+    #[cfg(feature = "unstable")]
+    pub(crate) no_route: Option<NoRoutePolicy>,
+    #[cfg(feature = "unstable")]
+    pub(crate) loop_guard: bool,
+    #[cfg(feature = "unstable")]
+    pub(crate) max_provenance_hops: usize,
+    #[cfg(feature = "unstable")]
+    pub(crate) transform_out: Option<TransformChain>,
+    #[cfg(feature = "unstable")]
+    pub(crate) flow: Option<std::sync::Arc<FlowHandle>>,
+    /// The liveliness token announcing this publisher, if announced.
+    #[cfg(feature = "unstable")]
+    pub(crate) announcement: Option<std::sync::Arc<crate::liveliness::LivelinessToken<'a>>>,
 }
 
 impl<'a> Publisher<'a> {
@@ -254,6 +321,31 @@ impl<'a> Publisher<'a> {
         &self.key_expr
     }
 
+    /// Declare the liveliness token announcing this publisher.
+    #[zenoh_macros::unstable]
+    fn announced(mut self) -> ZResult<Self> {
+        let token_key = format!(
+            "{}/{}/{}/{}",
+            PREFIX_PUBLICATION,
+            self.session.zid(),
+            self.id,
+            self.key_expr
+        );
+        let liveliness = crate::liveliness::Liveliness {
+            session: self.session.clone(),
+        };
+        self.announcement = Some(std::sync::Arc::new(
+            liveliness.declare_token(token_key).res_sync()?,
+        ));
+        Ok(self)
+    }
+
+    /// Returns the [`EntityId`](crate::EntityId) of this Publisher, unique within its session.
+    #[zenoh_macros::unstable]
+    pub fn id(&self) -> crate::EntityId {
+        crate::EntityId(self.id)
+    }
+
     /// Change the `congestion_control` to apply when routing the data.
     #[inline]
     pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
@@ -277,6 +369,98 @@ impl<'a> Publisher<'a> {
         self
     }
 
+    /// Drop the samples [forwarded](Publisher::forward) by this `Publisher` whose provenance
+    /// chain already contains the [`ZenohId`] of its session.
+    ///
+    /// This breaks the loops formed by bridges re-publishing each other's samples.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn loop_guard(mut self, enabled: bool) -> Self {
+        self.loop_guard = enabled;
+        self
+    }
+
+    /// Change the maximum length of the provenance chain of the samples
+    /// [forwarded](Publisher::forward) by this `Publisher` (16 by default).
+    ///
+    /// The oldest hops are dropped from longer chains.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn max_provenance_hops(mut self, max: usize) -> Self {
+        self.max_provenance_hops = max;
+        self
+    }
+
+    /// Apply a [`TransformChain`] to the samples written by this `Publisher`
+    /// before they are sent.
+    ///
+    /// Writing a sample failing a transformer of the chain returns an error.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::transform::{NoopTransformer, TransformChain};
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .transform_out(TransformChain::new().then(NoopTransformer));
+    /// publisher.put("value").res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn transform_out(mut self, chain: TransformChain) -> Self {
+        self.transform_out = Some(chain);
+        self
+    }
+
+    /// Schedule the publications of this `Publisher` fairly with the other weighted publishers
+    /// of its session, given its `weight` (at least 1).
+    ///
+    /// The publications of weighted publishers are queued per publisher and sent in the order of
+    /// their priority; weighted publishers of the same priority then share the bandwidth in
+    /// proportion to their weights by deficit round robin. When the queue of a publisher is full,
+    /// its publications block or are dropped according to its congestion control.
+    /// The publications of the other publishers are sent immediately.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let bulk = session.declare_publisher("bulk").res().await.unwrap().weight(1);
+    /// let control = session.declare_publisher("control").res().await.unwrap().weight(10);
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn weight(mut self, weight: u8) -> Self {
+        self.flow = Some(self.session.scheduler.register(weight));
+        self
+    }
+
+    /// Returns the number of bytes published by this `Publisher` waiting to be scheduled
+    /// (see [`weight`](Publisher::weight)).
+    #[zenoh_macros::unstable]
+    pub fn queued_bytes(&self) -> usize {
+        self.flow.as_ref().map_or(0, |flow| flow.queued_bytes())
+    }
+
+    /// Returns the total number of bytes published by this `Publisher` once scheduled
+    /// (see [`weight`](Publisher::weight)).
+    #[zenoh_macros::unstable]
+    pub fn scheduled_bytes(&self) -> u64 {
+        self.flow.as_ref().map_or(0, |flow| flow.sent_bytes())
+    }
+
     /// Consumes the given `Publisher`, returning a thread-safe reference-counting
     /// pointer to it (`Arc<Publisher>`). This is equivalent to `Arc::new(Publisher)`.
     ///
@@ -361,6 +545,135 @@ impl<'a> Publisher<'a> {
         self._write(SampleKind::Delete, Value::empty())
     }
 
+    /// Put data and wait for it to be acknowledged by its receivers.
+    ///
+    /// The acknowledgments follow a reserved key expression convention: a temporary subscriber
+    /// waits on `<key>/@ack/<uuid>`, where `<key>` is the publisher's key expression and `<uuid>`
+    /// a random UUID unique to this publication. The publication names this key expression in
+    /// its [`ACK_ATTACHMENT_KEY`](crate::sample::ACK_ATTACHMENT_KEY) (`_ack`) attachment entry,
+    /// on which receivers publish their [`ZenohId`](crate::prelude::ZenohId) by calling
+    /// [`Sample::ack`]. The temporary subscriber is undeclared once the operation resolves,
+    /// whatever its outcome.
+    ///
+    /// The acknowledgments are de-duplicated by the [`ZenohId`](crate::prelude::ZenohId) they
+    /// carry: the subscribers of a same session acknowledging the publication count as a single
+    /// acknowledger. By default, the first acknowledgment wins; use [`PublicationWithAck::acks`]
+    /// to wait for several distinct acknowledging sessions.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// let acks = publisher
+    ///     .put_with_ack("value")
+    ///     .timeout(Duration::from_secs(1))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn put_with_ack<IntoValue>(&self, value: IntoValue) -> PublicationWithAck
+    where
+        IntoValue: Into<Value>,
+    {
+        let timeout = {
+            let conf = self.session.runtime.config().lock();
+            Duration::from_millis(unwrap_or_default!(conf.queries_default_timeout()))
+        };
+        PublicationWithAck {
+            publication: self._write(SampleKind::Put, value.into()),
+            timeout,
+            acks: 1,
+        }
+    }
+
+    /// Re-publish a received [`Sample`] on the key expression of this `Publisher`.
+    ///
+    /// The value, kind and attachment of the sample are preserved, and a [`ProvenanceHop`]
+    /// naming this session is appended to its provenance chain
+    /// (see [`Sample::provenance`]). If the [loop guard](Publisher::loop_guard) is enabled,
+    /// samples that already went through this session are dropped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let subscriber = session.declare_subscriber("src/key").res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("dst/key")
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .loop_guard(true);
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     publisher.forward(&sample).res().await.unwrap();
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn forward(&self, sample: &Sample) -> Forwarding {
+        self.forward_transformed(sample, |value| value)
+    }
+
+    /// Re-publish a received [`Sample`] on the key expression of this `Publisher`,
+    /// transforming its value with `transform`.
+    ///
+    /// See [`Publisher::forward`].
+    #[zenoh_macros::unstable]
+    pub fn forward_transformed<Transform>(
+        &self,
+        sample: &Sample,
+        transform: Transform,
+    ) -> Forwarding
+    where
+        Transform: FnOnce(Value) -> Value,
+    {
+        let zid = self.session.zid();
+        let mut hops = sample.provenance();
+        if self.loop_guard && hops.iter().any(|hop| hop.zid == zid) {
+            tracing::trace!(
+                "Dropping sample on {} looping back to {}",
+                sample.key_expr,
+                zid
+            );
+            return Forwarding { publication: None };
+        }
+        let time = *crate::time::new_reception_timestamp().get_time();
+        hops.push(ProvenanceHop {
+            zid,
+            timestamp: Timestamp::new(time, (&zid).into()),
+        });
+        if hops.len() > self.max_provenance_hops {
+            hops.drain(..hops.len() - self.max_provenance_hops);
+        }
+
+        let mut attachment = AttachmentBuilder::new();
+        if let Some(previous) = sample.attachment() {
+            for (key, value) in previous
+                .iter()
+                .filter(|(key, _)| key.as_slice() != PROVENANCE_ATTACHMENT_KEY.as_bytes())
+            {
+                attachment.insert(key.as_slice(), value.as_slice());
+            }
+        }
+        attachment.insert(PROVENANCE_ATTACHMENT_KEY, &ProvenanceHop::encode(&hops));
+
+        let mut publication = self._write(sample.kind, transform(sample.value.clone()));
+        publication.attachment = Some(attachment.build());
+        Forwarding {
+            publication: Some(publication),
+        }
+    }
+
     /// Return the [`MatchingStatus`] of the publisher.
     ///
     /// [`MatchingStatus::matching_subscribers`] will return true if there exist Subscribers
@@ -390,6 +703,31 @@ impl<'a> Publisher<'a> {
         })
     }
 
+    /// Return a [`FanoutReport`] estimating the number of copies of this publisher's publications.
+    ///
+    /// The report reflects the routing tables at the time of the call, restricted to the
+    /// publisher's [`Locality`](crate::prelude::Locality).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// let fanout = publisher.fanout().res().await.unwrap();
+    /// println!("{} remote routes", fanout.remote_routes);
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn fanout(&self) -> impl Resolve<ZResult<FanoutReport>> + '_ {
+        zenoh_core::ResolveClosure::new(move || {
+            self.session
+                .fanout_report(self.key_expr(), self.destination)
+        })
+    }
+
     /// Return a [`MatchingListener`] for this Publisher.
     ///
     /// The [`MatchingListener`] that will send a notification each time the [`MatchingStatus`] of
@@ -598,9 +936,21 @@ impl SyncResolve for PublisherUndeclaration<'_> {
         let Publisher {
             session, key_expr, ..
         } = &self.publisher;
+        #[cfg(feature = "unstable")]
+        session.undeclare_publisher_entity(self.publisher.id);
         session
             .undeclare_publication_intent(key_expr.clone())
             .res_sync()?;
+        // The announcement is shared with the clones of the publisher
+        #[cfg(feature = "unstable")]
+        if let Some(Ok(announcement)) = self
+            .publisher
+            .announcement
+            .take()
+            .map(std::sync::Arc::try_unwrap)
+        {
+            announcement.undeclare().res_sync()?;
+        }
         self.publisher.key_expr = unsafe { keyexpr::from_str_unchecked("") }.into();
         Ok(())
     }
@@ -617,6 +967,8 @@ impl AsyncResolve for PublisherUndeclaration<'_> {
 impl Drop for Publisher<'_> {
     fn drop(&mut self) {
         if !self.key_expr.is_empty() {
+            #[cfg(feature = "unstable")]
+            self.session.undeclare_publisher_entity(self.id);
             let _ = self
                 .session
                 .undeclare_publication_intent(self.key_expr.clone())
@@ -668,6 +1020,175 @@ impl AsyncResolve for Publication<'_> {
     }
 }
 
+/// A [`Resolvable`] returned by [`Publisher::forward()`](Publisher::forward) and
+/// [`Publisher::forward_transformed()`](Publisher::forward_transformed).
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct Forwarding<'a> {
+    // None if the sample was dropped by the loop guard
+    publication: Option<Publication<'a>>,
+}
+
+#[zenoh_macros::unstable]
+impl Resolvable for Forwarding<'_> {
+    type To = ZResult<()>;
+}
+
+#[zenoh_macros::unstable]
+impl SyncResolve for Forwarding<'_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        match self.publication {
+            Some(publication) => publication.res_sync(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl AsyncResolve for Forwarding<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A [`Resolvable`] returned by [`Publisher::put_with_ack()`](Publisher::put_with_ack).
+///
+/// It resolves to the number of distinct acknowledgers once the expected number of
+/// acknowledgments has been received, or to an error if the timeout expires first.
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct PublicationWithAck<'a> {
+    publication: Publication<'a>,
+    timeout: Duration,
+    acks: usize,
+}
+
+#[zenoh_macros::unstable]
+impl<'a> PublicationWithAck<'a> {
+    /// Set the maximum time to wait for the acknowledgments.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the number of distinct acknowledging sessions to wait for (`1` by default).
+    #[inline]
+    pub fn acks(mut self, acks: usize) -> Self {
+        self.acks = acks.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.publication.attachment = Some(attachment);
+        self
+    }
+
+    fn publish(self) -> ZResult<PendingAcks<'a>> {
+        let Publication {
+            publisher,
+            value,
+            kind,
+            attachment,
+        } = self.publication;
+        let ack_key: KeyExpr<'static> = format!(
+            "{}/@ack/{}",
+            publisher.key_expr,
+            uuid::Uuid::new_v4().simple()
+        )
+        .try_into()?;
+        let subscriber = publisher
+            .session
+            .declare_subscriber(ack_key.clone())
+            .with(flume::unbounded())
+            .res_sync()?;
+        let mut attachment = attachment.unwrap_or_default();
+        attachment.insert(ACK_ATTACHMENT_KEY, ack_key.as_str());
+        resolve_put(publisher, value, kind, Some(attachment))?;
+        Ok(PendingAcks {
+            subscriber,
+            deadline: Instant::now() + self.timeout,
+            expected: self.acks,
+            ackers: HashSet::new(),
+        })
+    }
+}
+
+#[zenoh_macros::unstable]
+struct PendingAcks<'a> {
+    subscriber: crate::subscriber::FlumeSubscriber<'a>,
+    deadline: Instant,
+    expected: usize,
+    ackers: HashSet<Vec<u8>>,
+}
+
+#[zenoh_macros::unstable]
+impl PendingAcks<'_> {
+    fn is_complete(&self) -> bool {
+        self.ackers.len() >= self.expected
+    }
+
+    fn on_ack(&mut self, ack: Sample) {
+        self.ackers
+            .insert(ack.value.payload.contiguous().into_owned());
+    }
+
+    fn finish(self) -> ZResult<usize> {
+        let received = self.ackers.len();
+        if self.is_complete() {
+            Ok(received)
+        } else {
+            bail!(
+                "Timeout waiting for acknowledgments on `{}` ({}/{} received)",
+                self.subscriber.key_expr(),
+                received,
+                self.expected
+            )
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Resolvable for PublicationWithAck<'_> {
+    type To = ZResult<usize>;
+}
+
+#[zenoh_macros::unstable]
+impl SyncResolve for PublicationWithAck<'_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let mut pending = self.publish()?;
+        while !pending.is_complete() {
+            match pending.subscriber.recv_deadline(pending.deadline) {
+                Ok(Some(ack)) => pending.on_ack(ack),
+                _ => break,
+            }
+        }
+        pending.finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> AsyncResolve for PublicationWithAck<'a> {
+    type Future = Pin<Box<dyn Future<Output = <Self as Resolvable>::To> + Send + 'a>>;
+
+    fn res_async(self) -> Self::Future {
+        Box::pin(async move {
+            let mut pending = self.publish()?;
+            while !pending.is_complete() {
+                let deadline = tokio::time::Instant::from_std(pending.deadline);
+                match tokio::time::timeout_at(deadline, pending.subscriber.recv_async()).await {
+                    Ok(Ok(ack)) => pending.on_ack(ack),
+                    _ => break,
+                }
+            }
+            pending.finish()
+        })
+    }
+}
+
 impl<'a, IntoValue> Sink<IntoValue> for Publisher<'a>
 where
     IntoValue: Into<Value>,
@@ -721,6 +1242,10 @@ pub struct PublisherBuilder<'a, 'b: 'a> {
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
     pub(crate) destination: Locality,
+    #[cfg(feature = "unstable")]
+    pub(crate) no_route: Option<NoRoutePolicy>,
+    #[cfg(feature = "unstable")]
+    pub(crate) announce: bool,
 }
 
 impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
@@ -734,6 +1259,10 @@ impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
             congestion_control: self.congestion_control,
             priority: self.priority,
             destination: self.destination,
+            #[cfg(feature = "unstable")]
+            no_route: self.no_route,
+            #[cfg(feature = "unstable")]
+            announce: self.announce,
         }
     }
 }
@@ -761,6 +1290,61 @@ impl<'a, 'b> PublisherBuilder<'a, 'b> {
         self.destination = destination;
         self
     }
+
+    /// Change how the data is handled while the session has no route to send it
+    /// (the policy of the session by default).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::no_route::NoRoutePolicy;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("key/expression")
+    ///     .no_route(NoRoutePolicy::Fail)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// assert!(publisher.put("value").res().await.is_err());
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn no_route(mut self, policy: NoRoutePolicy) -> Self {
+        self.no_route = Some(policy);
+        self
+    }
+
+    /// Announce this publisher to the subscribers with intersecting key expressions, until it is
+    /// undeclared, see [`SubscriberBuilder::matching_listener`](crate::subscriber::SubscriberBuilder::matching_listener).
+    ///
+    /// The publisher is announced with a liveliness token under `@/publication`, which the
+    /// session holds until the publisher is undeclared or the session is closed or lost.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("key/expression")
+    ///     .announce()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn announce(mut self) -> Self {
+        self.announce = true;
+        self
+    }
 }
 
 impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
@@ -770,6 +1354,7 @@ impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
 impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let mut key_expr = self.key_expr?;
+        check_wildcard_write(self.session.allow_wildcard_writes, &key_expr)?;
         if !key_expr.is_fully_optimized(&self.session) {
             let session_id = self.session.id;
             let expr_id = self.session.declare_prefix(key_expr.as_str()).res_sync();
@@ -803,12 +1388,42 @@ impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
         self.session
             .declare_publication_intent(key_expr.clone())
             .res_sync()?;
+        #[cfg(feature = "unstable")]
+        self.session
+            .register_scoped(crate::scope::ScopedEntity::Publisher(
+                key_expr.clone().into_owned(),
+            ))?;
         let publisher = Publisher {
+            #[cfg(feature = "unstable")]
+            id: self.session.declare_publisher_entity(
+                &key_expr,
+                self.congestion_control,
+                self.priority,
+                self.destination,
+            ),
             session: self.session,
             key_expr,
             congestion_control: self.congestion_control,
             priority: self.priority,
             destination: self.destination,
+            #[cfg(feature = "unstable")]
+            no_route: self.no_route,
+            #[cfg(feature = "unstable")]
+            loop_guard: false,
+            #[cfg(feature = "unstable")]
+            max_provenance_hops: DEFAULT_MAX_PROVENANCE_HOPS,
+            #[cfg(feature = "unstable")]
+            transform_out: None,
+            #[cfg(feature = "unstable")]
+            flow: None,
+            #[cfg(feature = "unstable")]
+            announcement: None,
+        };
+        #[cfg(feature = "unstable")]
+        let publisher = if self.announce {
+            publisher.announced()?
+        } else {
+            publisher
         };
         tracing::trace!("publish({:?})", publisher.key_expr);
         Ok(publisher)
@@ -823,6 +1438,74 @@ impl<'a, 'b> AsyncResolve for PublisherBuilder<'a, 'b> {
     }
 }
 
+/// The error of the writes on key expressions containing wildcards,
+/// unless allowed by the `allow_wildcard_writes` configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WildcardWriteError {
+    key_expr: OwnedKeyExpr,
+}
+
+impl WildcardWriteError {
+    /// The key expression of the rejected write.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+}
+
+impl std::fmt::Display for WildcardWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Attempted to write on `{}`, which contains wildcards (see the `allow_wildcard_writes` configuration)",
+            self.key_expr
+        )
+    }
+}
+
+impl std::error::Error for WildcardWriteError {}
+
+/// The error of the puts whose payload exceeds the `max_payload_size` configuration of the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    /// The size of the rejected payload, in bytes.
+    pub size: usize,
+    /// The `max_payload_size` of the session, in bytes.
+    pub limit: usize,
+}
+
+impl std::fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Attempted to put a payload of {} bytes, over the max_payload_size of {} bytes",
+            self.size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+fn check_payload_size(limit: Option<usize>, value: &Value) -> ZResult<()> {
+    match limit {
+        Some(limit) if value.payload.len() > limit => Err(PayloadTooLarge {
+            size: value.payload.len(),
+            limit,
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn check_wildcard_write(allowed: bool, key_expr: &keyexpr) -> ZResult<()> {
+    if !allowed && key_expr.is_wild() {
+        return Err(WildcardWriteError {
+            key_expr: key_expr.into(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 fn resolve_put(
     publisher: &Publisher<'_>,
     value: Value,
@@ -830,15 +1513,55 @@ fn resolve_put(
     #[cfg(feature = "unstable")] attachment: Option<Attachment>,
 ) -> ZResult<()> {
     tracing::trace!("write({:?}, [...])", &publisher.key_expr);
+    check_wildcard_write(publisher.session.allow_wildcard_writes, &publisher.key_expr)?;
+    #[cfg(feature = "unstable")]
+    let (value, attachment) = match &publisher.transform_out {
+        Some(chain) => {
+            let mut sample = Sample::new(publisher.key_expr.clone().into_owned(), value);
+            sample.kind = kind;
+            sample.attachment = attachment;
+            let sample = chain
+                .apply(sample)
+                .map_err(|e| zerror!("Unable to transform sample: {}", e))?;
+            (sample.value, sample.attachment)
+        }
+        None => (value, attachment),
+    };
+    // Empty attachments are not sent
+    #[cfg(feature = "unstable")]
+    let attachment = attachment.filter(|attachment| !attachment.is_empty());
+    check_payload_size(publisher.session.max_payload_size, &value)?;
+    #[cfg(feature = "unstable")]
+    if publisher.destination != Locality::SessionLocal {
+        publisher
+            .session
+            .no_route
+            .check(publisher.no_route, false, publisher.key_expr.as_str())?;
+    }
     let primitives = zread!(publisher.session.state)
         .primitives
         .as_ref()
         .unwrap()
         .clone();
     let timestamp = publisher.session.runtime.new_timestamp();
+    #[cfg(feature = "unstable")]
+    publisher
+        .session
+        .write_journal
+        .record(&publisher.key_expr, &value, kind, timestamp);
+    // The sequence number is taken even if the write is dropped before reaching the network,
+    // so that the subscribers detect the gap
+    #[cfg(feature = "unstable")]
+    let ext_sinfo = Some(SourceInfoType {
+        zid: publisher.session.zid(),
+        eid: publisher.id as u32,
+        sn: publisher.session.source_sequencer.next(&publisher.key_expr),
+    });
+    #[cfg(not(feature = "unstable"))]
+    let ext_sinfo: Option<SourceInfoType> = None;
 
     if publisher.destination != Locality::SessionLocal {
-        primitives.send_push(Push {
+        let push = Push {
             wire_expr: publisher.key_expr.to_wire(&publisher.session).to_owned(),
             ext_qos: ext::QoSType::new(
                 publisher.priority.into(),
@@ -860,7 +1583,7 @@ fn resolve_put(
                     PushBody::Put(Put {
                         timestamp,
                         encoding: value.encoding.clone(),
-                        ext_sinfo: None,
+                        ext_sinfo: ext_sinfo.clone(),
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment,
@@ -879,21 +1602,52 @@ fn resolve_put(
                     }
                     PushBody::Del(Del {
                         timestamp,
-                        ext_sinfo: None,
+                        ext_sinfo: ext_sinfo.clone(),
                         ext_attachment,
                         ext_unknown: vec![],
                     })
                 }
             },
-        });
+        };
+        #[cfg(feature = "unstable")]
+        let push = publisher.session.no_route.push(
+            publisher.no_route,
+            &primitives,
+            publisher.key_expr.as_str(),
+            push,
+        )?;
+        #[cfg(not(feature = "unstable"))]
+        let push = Some(push);
+        #[cfg(feature = "unstable")]
+        let push = match (&publisher.flow, push) {
+            (Some(flow), Some(push)) => {
+                flow.send_push(
+                    publisher.priority,
+                    publisher.congestion_control,
+                    primitives.clone(),
+                    push,
+                );
+                None
+            }
+            (_, push) => push,
+        };
+        if let Some(push) = push {
+            #[cfg(feature = "testing")]
+            publisher
+                .session
+                .fault_injector
+                .send_push(&primitives, push);
+            #[cfg(not(feature = "testing"))]
+            primitives.send_push(push);
+        }
     }
     if publisher.destination != Locality::Remote {
         let data_info = DataInfo {
             kind,
             encoding: Some(value.encoding),
             timestamp,
-            source_id: None,
-            source_sn: None,
+            source_id: ext_sinfo.as_ref().map(|i| i.zid),
+            source_sn: ext_sinfo.as_ref().map(|i| i.sn as u64),
             qos: QoS::from(ext::QoSType::new(
                 publisher.priority.into(),
                 publisher.congestion_control,
@@ -1039,6 +1793,31 @@ impl MatchingStatus {
     pub fn matching_subscribers(&self) -> bool {
         self.matching
     }
+
+    /// Return true if there exist announced Publishers matching the Subscriber's key expression,
+    /// see [`SubscriberBuilder::matching_listener`](crate::subscriber::SubscriberBuilder::matching_listener).
+    pub fn matching_publishers(&self) -> bool {
+        self.matching
+    }
+}
+
+/// An estimate of the number of copies of a publication on a key expression,
+/// as returned by [`Session::fanout`](crate::Session::fanout) and [`Publisher::fanout`].
+///
+/// This is a best-effort view derived from the local routing tables of the session:
+/// each remote route is a next hop (a face) the publication is sent to, which may itself
+/// forward it to further subscribers that aren't accounted for here.
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FanoutReport {
+    /// The number of subscribers of this session receiving the publication.
+    pub local_subscribers: usize,
+    /// The number of next hops the publication is sent to.
+    pub remote_routes: usize,
+    /// The number of copies of the publication delivered by this session,
+    /// i.e. one per local subscriber and one per remote route.
+    pub estimated_copies: usize,
 }
 
 /// A builder for initializing a [`MatchingListener`].
@@ -19,9 +19,13 @@ use crate::prelude::*;
 #[zenoh_macros::unstable]
 use crate::sample::Attachment;
 use crate::Session;
+#[zenoh_macros::unstable]
+use futures::{Sink, SinkExt};
 use std::collections::HashMap;
 use std::future::Ready;
 use std::time::Duration;
+#[zenoh_macros::unstable]
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
 use zenoh_result::ZResult;
 
@@ -86,8 +90,31 @@ pub struct Reply {
     pub replier_id: ZenohId,
 }
 
+/// An outstanding [`get`](Session::get), as listed by [`Session::pending_queries`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct PendingQuery {
+    /// The id of the query, to be passed to [`Session::cancel_query`].
+    pub qid: u32,
+    /// The selector of the query.
+    pub selector: Selector<'static>,
+    /// The time elapsed since the query was issued.
+    pub age: Duration,
+    /// The number of replies received so far.
+    pub replies: usize,
+}
+
+/// The error value of the final [`Reply`] of a query cancelled with [`Session::cancel_query`].
+#[zenoh_macros::unstable]
+pub const QUERY_CANCELLED: &str = "Cancelled";
+
 pub(crate) struct QueryState {
     pub(crate) nb_final: usize,
+    #[cfg(feature = "unstable")]
+    pub(crate) started: std::time::Instant,
+    #[cfg(feature = "unstable")]
+    pub(crate) nb_replies: usize,
     pub(crate) selector: Selector<'static>,
     pub(crate) scope: Option<KeyExpr<'static>>,
     pub(crate) reception_mode: ConsolidationMode,
@@ -131,6 +158,8 @@ pub struct GetBuilder<'a, 'b, Handler> {
     pub(crate) value: Option<Value>,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) read_your_writes: bool,
 }
 
 impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
@@ -167,6 +196,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            read_your_writes,
             handler: _,
         } = self;
         GetBuilder {
@@ -180,6 +211,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            read_your_writes,
             handler: callback,
         }
     }
@@ -252,6 +285,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            read_your_writes,
             handler: _,
         } = self;
         GetBuilder {
@@ -265,9 +300,49 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            read_your_writes,
             handler,
         }
     }
+
+    /// Send the samples of the replies to this query into `sink`, closing it once the query is
+    /// finalized.
+    ///
+    /// The error replies are passed to the [`on_error`](GetForward::on_error) callback if any,
+    /// and logged otherwise. Forwarding the samples into a [`Publisher`](crate::publication::Publisher)
+    /// republishes them.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("local/storage").res().await.unwrap();
+    /// session
+    ///     .get("remote/storage")
+    ///     .forward(publisher)
+    ///     .on_error(|error| eprintln!("Error reply: {}", error))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn forward<S>(self, sink: S) -> GetForward<'a, 'b, S>
+    where
+        S: Sink<Sample> + Send + Unpin,
+        S::Error: Into<zenoh_result::Error>,
+    {
+        GetForward {
+            get: self,
+            sink,
+            on_error: None,
+        }
+    }
 }
 impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
     /// Change the target of the query.
@@ -316,6 +391,42 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Overlay the recent writes of this session on the replies to this query (disabled by default).
+    ///
+    /// The replies older than a put or delete of this session on their key expression are replaced
+    /// by this put, or dropped for a delete, and the recent puts of this session on key expressions
+    /// intersecting the query's are delivered even if no queryable replied for them.
+    /// The writes are those kept by the journal of the session, sized by the `read_your_writes`
+    /// section of its configuration.
+    ///
+    /// This is a client-side overlay: the other sessions may still read the values the writes
+    /// of this session replaced until they are stored.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session.put("config/x", "new").res().await.unwrap();
+    /// let replies = session
+    ///     .get("config/x")
+    ///     .read_your_writes(true)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let reply = replies.recv_async().await.unwrap();
+    /// assert_eq!(reply.sample.unwrap().value.to_string(), "new");
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn read_your_writes(mut self, enabled: bool) -> Self {
+        self.read_your_writes = enabled;
+        self
+    }
+
     /// By default, `get` guarantees that it will only receive replies whose key expressions intersect
     /// with the queried key expression.
     ///
@@ -333,6 +444,7 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             timeout,
             value,
             attachment,
+            read_your_writes,
             handler,
         } = self;
         Self {
@@ -345,6 +457,7 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             timeout,
             value,
             attachment,
+            read_your_writes,
             handler,
         }
     }
@@ -362,6 +475,89 @@ pub enum ReplyKeyExpr {
     MatchingQuery,
 }
 
+/// A builder returned by [`GetBuilder::forward`] forwarding the replies to a query into a sink.
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct GetForward<'a, 'b, S> {
+    get: GetBuilder<'a, 'b, DefaultHandler>,
+    sink: S,
+    on_error: Option<Callback<'static, Value>>,
+}
+
+#[zenoh_macros::unstable]
+impl<S> GetForward<'_, '_, S> {
+    /// Pass the error replies to `callback` instead of logging them.
+    #[inline]
+    pub fn on_error<Callback>(mut self, callback: Callback) -> Self
+    where
+        Callback: Fn(Value) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<S> fmt::Debug for GetForward<'_, '_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GetForward")
+            .field("selector", &self.get.selector)
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<S> Resolvable for GetForward<'_, '_, S> {
+    type To = ZResult<()>;
+}
+
+#[zenoh_macros::unstable]
+impl<S> SyncResolve for GetForward<'_, '_, S>
+where
+    S: Sink<Sample> + Send + Unpin,
+    S::Error: Into<zenoh_result::Error>,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        zenoh_runtime::ZRuntime::Application.block_in_place(self.res_async())
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a, S> AsyncResolve for GetForward<'a, '_, S>
+where
+    S: Sink<Sample> + Send + Unpin + 'a,
+    S::Error: Into<zenoh_result::Error>,
+{
+    type Future = Pin<Box<dyn Future<Output = <Self as Resolvable>::To> + Send + 'a>>;
+
+    fn res_async(self) -> Self::Future {
+        let GetForward {
+            get,
+            mut sink,
+            on_error,
+        } = self;
+        let replies = get.res_sync();
+        Box::pin(async move {
+            let replies = replies?;
+            // The receiver is disconnected once the query is finalized
+            while let Ok(reply) = replies.recv_async().await {
+                match reply.sample {
+                    Ok(sample) => sink.send(sample).await.map_err(Into::into)?,
+                    Err(error) => match &on_error {
+                        Some(callback) => callback(error),
+                        None => tracing::warn!(
+                            "Error reply from {} not forwarded: {}",
+                            reply.replier_id,
+                            error
+                        ),
+                    },
+                }
+            }
+            sink.close().await.map_err(Into::into)
+        })
+    }
+}
+
 impl<Handler> Resolvable for GetBuilder<'_, '_, Handler>
 where
     Handler: IntoCallbackReceiverPair<'static, Reply> + Send,
@@ -377,10 +573,19 @@ where
 {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let selector = self.selector?;
+        #[cfg(feature = "unstable")]
+        let callback = if self.read_your_writes {
+            self.session
+                .write_journal
+                .overlay(&selector.key_expr, self.session.zid(), callback)
+        } else {
+            callback
+        };
 
         self.session
             .query(
-                &self.selector?,
+                &selector,
                 &self.scope?,
                 self.target,
                 self.consolidation,
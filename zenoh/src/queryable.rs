@@ -15,8 +15,11 @@
 //! Queryable primitives.
 
 use crate::handlers::{locked, DefaultHandler};
+#[zenoh_macros::unstable]
+use crate::handlers::{Callback, Dyn};
 use crate::net::primitives::Primitives;
 use crate::prelude::*;
+use crate::publication::check_wildcard_write;
 #[zenoh_macros::unstable]
 use crate::query::ReplyKeyExpr;
 #[zenoh_macros::unstable]
@@ -26,9 +29,16 @@ use crate::SessionRef;
 use crate::Undeclarable;
 
 use std::fmt;
+#[zenoh_macros::unstable]
+use std::future::Future;
 use std::future::Ready;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
+#[zenoh_macros::unstable]
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+#[zenoh_macros::unstable]
+use zenoh_core::zlock;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
 use zenoh_protocol::core::WireExpr;
 use zenoh_protocol::network::{response, Mapping, RequestId, Response, ResponseFinal};
@@ -48,6 +58,8 @@ pub(crate) struct QueryInner {
     pub(crate) qid: RequestId,
     pub(crate) zid: ZenohId,
     pub(crate) primitives: Arc<dyn Primitives>,
+    /// Whether the replies may use key expressions containing wildcards.
+    pub(crate) allow_wildcard_writes: bool,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
 }
@@ -58,6 +70,7 @@ impl Drop for QueryInner {
             rid: self.qid,
             ext_qos: response::ext::QoSType::response_final_default(),
             ext_tstamp: None,
+            ext_served: None,
         });
     }
 }
@@ -106,6 +119,9 @@ impl Query {
     /// By default, queries only accept replies whose key expression intersects with the query's.
     /// Unless the query has enabled disjoint replies (you can check this through [`Query::accepts_replies`]),
     /// replying on a disjoint key expression will result in an error when resolving the reply.
+    ///
+    /// The [`kind`](Sample::kind) of the sample is conveyed to the querier, so that deletions can be
+    /// replied as [`SampleKind::Delete`](crate::prelude::SampleKind::Delete) samples.
     #[inline(always)]
     pub fn reply(&self, result: Result<Sample, Value>) -> ReplyBuilder<'_> {
         ReplyBuilder {
@@ -188,6 +204,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                 {
                     bail!("Attempted to reply on `{}`, which does not intersect with query `{}`, despite query only allowing replies on matching key expressions", sample.key_expr, self.query.key_expr())
                 }
+                check_wildcard_write(self.query.inner.allow_wildcard_writes, &sample.key_expr)?;
                 let Sample {
                     key_expr,
                     value: Value { payload, encoding },
@@ -198,6 +215,8 @@ impl SyncResolve for ReplyBuilder<'_> {
                     source_info,
                     #[cfg(feature = "unstable")]
                     attachment,
+                    // Whether the sample was replayed isn't sent
+                    ..
                 } = sample;
                 #[allow(unused_mut)]
                 let mut data_info = DataInfo {
@@ -214,7 +233,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                 {
                     data_info.source_id = source_info.source_id;
                     data_info.source_sn = source_info.source_sn;
-                    if let Some(attachment) = attachment {
+                    if let Some(attachment) = attachment.filter(|a| !a.is_empty()) {
                         ext_attachment = Some(attachment.into());
                     }
                 }
@@ -242,6 +261,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment,
+                        ext_kind: data_info.kind,
                         ext_unknown: vec![],
                         payload,
                     }),
@@ -421,6 +441,10 @@ pub struct QueryableBuilder<'a, 'b, Handler> {
     pub(crate) key_expr: ZResult<KeyExpr<'b>>,
     pub(crate) complete: bool,
     pub(crate) origin: Locality,
+    #[cfg(feature = "unstable")]
+    pub(crate) cache: Option<(Duration, usize)>,
+    #[cfg(feature = "unstable")]
+    pub(crate) await_routed: Option<Duration>,
     pub(crate) handler: Handler,
 }
 
@@ -452,6 +476,10 @@ impl<'a, 'b> QueryableBuilder<'a, 'b, DefaultHandler> {
             key_expr,
             complete,
             origin,
+            #[cfg(feature = "unstable")]
+            cache,
+            #[cfg(feature = "unstable")]
+            await_routed,
             handler: _,
         } = self;
         QueryableBuilder {
@@ -459,6 +487,10 @@ impl<'a, 'b> QueryableBuilder<'a, 'b, DefaultHandler> {
             key_expr,
             complete,
             origin,
+            #[cfg(feature = "unstable")]
+            cache,
+            #[cfg(feature = "unstable")]
+            await_routed,
             handler: callback,
         }
     }
@@ -525,6 +557,10 @@ impl<'a, 'b> QueryableBuilder<'a, 'b, DefaultHandler> {
             key_expr,
             complete,
             origin,
+            #[cfg(feature = "unstable")]
+            cache,
+            #[cfg(feature = "unstable")]
+            await_routed,
             handler: _,
         } = self;
         QueryableBuilder {
@@ -532,6 +568,10 @@ impl<'a, 'b> QueryableBuilder<'a, 'b, DefaultHandler> {
             key_expr,
             complete,
             origin,
+            #[cfg(feature = "unstable")]
+            cache,
+            #[cfg(feature = "unstable")]
+            await_routed,
             handler,
         }
     }
@@ -544,6 +584,132 @@ impl<'a, 'b> QueryableBuilder<'a, 'b, DefaultHandler> {
         self.origin = origin;
         self
     }
+
+    /// Answer the queries of this Queryable with the result of an async function.
+    ///
+    /// `responder` is called with each query, and the future it returns is run on the
+    /// application runtime. Its `Ok` value is replied as a sample on the query's key expression,
+    /// its `Err` value as an error reply. The query is finalized once the future completes,
+    /// even if it panics (which is replied as an error) or is cancelled.
+    ///
+    /// Queries are answered concurrently; use [`concurrency`](QueryableBuilder::concurrency)
+    /// to bound the number of futures run at the same time.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let queryable = session
+    ///     .declare_queryable("key/expression")
+    ///     .respond_with(|query| async move {
+    ///         match query.parameters() {
+    ///             "" => Ok(Value::from("value")),
+    ///             _ => Err("unexpected parameters"),
+    ///         }
+    ///     })
+    ///     .concurrency(4)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    #[zenoh_macros::unstable]
+    pub fn respond_with<Responder, Fut, E>(
+        self,
+        responder: Responder,
+    ) -> QueryableBuilder<'a, 'b, RespondWith<Responder>>
+    where
+        Responder: Fn(Query) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, E>> + Send + 'static,
+        E: Into<Value> + Send,
+    {
+        self.with(RespondWith {
+            responder,
+            concurrency: None,
+        })
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a, 'b, Responder> QueryableBuilder<'a, 'b, RespondWith<Responder>> {
+    /// Limit the number of queries answered concurrently by the
+    /// [`respond_with`](QueryableBuilder::respond_with) function (unlimited by default).
+    ///
+    /// The other queries wait for one of the running futures to complete.
+    #[inline]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.handler.concurrency = Some(concurrency.max(1));
+        self
+    }
+}
+
+/// A handler answering queries with the result of an async function.
+///
+/// see [`QueryableBuilder::respond_with`]
+#[zenoh_macros::unstable]
+pub struct RespondWith<Responder> {
+    responder: Responder,
+    concurrency: Option<usize>,
+}
+
+#[zenoh_macros::unstable]
+impl<Responder> fmt::Debug for RespondWith<Responder> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RespondWith")
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<Responder, Fut, E> IntoCallbackReceiverPair<'static, Query> for RespondWith<Responder>
+where
+    Responder: Fn(Query) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Value, E>> + Send + 'static,
+    E: Into<Value> + Send,
+{
+    type Receiver = ();
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Query>, Self::Receiver) {
+        use futures::FutureExt;
+        use std::panic::AssertUnwindSafe;
+
+        let responder = Arc::new(self.responder);
+        let semaphore = self
+            .concurrency
+            .map(|concurrency| Arc::new(tokio::sync::Semaphore::new(concurrency)));
+        let callback = move |query: Query| {
+            let responder = responder.clone();
+            let semaphore = semaphore.clone();
+            // The query is finalized when its last clone is dropped, including when this task is cancelled
+            zenoh_runtime::ZRuntime::Application.spawn(async move {
+                let _permit = match semaphore {
+                    Some(semaphore) => semaphore.acquire_owned().await.ok(),
+                    None => None,
+                };
+                let response = AssertUnwindSafe(async { responder(query.clone()).await })
+                    .catch_unwind()
+                    .await;
+                let result = match response {
+                    Ok(Ok(value)) => Ok(Sample::new(query.key_expr().clone(), value)),
+                    Ok(Err(e)) => Err(e.into()),
+                    Err(panic) => {
+                        let error = crate::net::runtime::supervisor::panic_message(panic.as_ref());
+                        tracing::error!("Responder of query {} panicked: {}", query, error);
+                        Err(Value::from(format!("Responder panicked: {error}")))
+                    }
+                };
+                if let Err(e) = query.reply(result).res_async().await {
+                    tracing::error!("Error replying to query {}: {}", query, e);
+                }
+            });
+        };
+        (Dyn::new(callback), ())
+    }
 }
 impl<'a, 'b, Handler> QueryableBuilder<'a, 'b, Handler> {
     /// Change queryable completeness.
@@ -552,6 +718,199 @@ impl<'a, 'b, Handler> QueryableBuilder<'a, 'b, Handler> {
         self.complete = complete;
         self
     }
+
+    /// Cache the replies of this Queryable for `ttl`, keeping at most `max_entries` selectors.
+    ///
+    /// The replies to a query are cached by selector, its parameters being sorted so that
+    /// their order does not matter. Subsequent identical queries received within `ttl` are
+    /// answered with the same replies, in the same order, without reaching the handler.
+    /// When the cache is full, the oldest entry is evicted.
+    ///
+    /// Queries carrying a [`NOCACHE_PARAMETER`] parameter or a value always reach the handler,
+    /// and their replies are not cached. See also [`Queryable::invalidate`].
+    #[inline]
+    #[zenoh_macros::unstable]
+    pub fn cache(mut self, ttl: Duration, max_entries: usize) -> Self {
+        self.cache = Some((ttl, max_entries.max(1)));
+        self
+    }
+
+    /// Wait, when declaring this [`Queryable`], for its declaration to reach the connected
+    /// zenoh sessions, or for at most `timeout`.
+    ///
+    /// See [`Session::routed`](crate::Session::routed). The declaration resolves at once
+    /// when no other session is reachable.
+    #[inline]
+    #[zenoh_macros::unstable]
+    pub fn await_routed(mut self, timeout: Duration) -> Self {
+        self.await_routed = Some(timeout);
+        self
+    }
+}
+
+/// The selector parameter bypassing the cache of a [`Queryable`], see [`QueryableBuilder::cache`].
+#[zenoh_macros::unstable]
+pub const NOCACHE_PARAMETER: &str = "_nocache";
+
+/// The replies of a [`Queryable`] cached by selector.
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub(crate) struct ReplyCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, CachedReplies>>,
+}
+
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+struct CachedReplies {
+    expiry: Instant,
+    responses: Arc<Vec<Response>>,
+}
+
+#[zenoh_macros::unstable]
+impl ReplyCache {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        ReplyCache {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, selector: &str) -> Option<Arc<Vec<Response>>> {
+        let mut entries = zlock!(self.entries);
+        match entries.get(selector) {
+            Some(cached) if cached.expiry > Instant::now() => Some(cached.responses.clone()),
+            Some(_) => {
+                entries.remove(selector);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, selector: String, responses: Vec<Response>) {
+        let mut entries = zlock!(self.entries);
+        let now = Instant::now();
+        entries.retain(|_, cached| cached.expiry > now);
+        if !entries.contains_key(&selector) && entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.expiry)
+                .map(|(selector, _)| selector.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            selector,
+            CachedReplies {
+                expiry: now + self.ttl,
+                responses: Arc::new(responses),
+            },
+        );
+    }
+
+    fn invalidate(&self, selector_prefix: &str) {
+        zlock!(self.entries).retain(|selector, _| !selector.starts_with(selector_prefix));
+    }
+
+    /// The selector of `query` with its parameters sorted.
+    fn canonical_selector(query: &Query) -> String {
+        let mut parameters: Vec<&str> = query
+            .parameters()
+            .split('&')
+            .filter(|parameter| !parameter.is_empty())
+            .collect();
+        parameters.sort_unstable();
+        let mut selector = query.key_expr().as_str().to_string();
+        if !parameters.is_empty() {
+            selector.push('?');
+            selector.push_str(&parameters.join("&"));
+        }
+        selector
+    }
+
+    fn wrap(self: Arc<Self>, callback: Callback<'static, Query>) -> Callback<'static, Query> {
+        Dyn::new(move |query: Query| {
+            if query.value().is_some()
+                || query
+                    .selector()
+                    .decode()
+                    .any(|(key, _)| key == NOCACHE_PARAMETER)
+            {
+                return callback(query);
+            }
+            let selector = Self::canonical_selector(&query);
+            if let Some(responses) = self.get(&selector) {
+                for response in responses.iter() {
+                    let mut response = response.clone();
+                    response.rid = query.inner.qid;
+                    query.inner.primitives.send_response(response);
+                }
+                return;
+            }
+            // The handler answers a copy of the query recording its replies,
+            // the original query is finalized along with that copy
+            let inner = &query.inner;
+            let recorded = QueryInner {
+                key_expr: inner.key_expr.clone(),
+                parameters: inner.parameters.clone(),
+                value: None,
+                qid: inner.qid,
+                zid: inner.zid,
+                primitives: Arc::new(RecordingPrimitives {
+                    cache: self.clone(),
+                    selector,
+                    query: Mutex::new(Some(query.clone())),
+                    responses: Mutex::new(vec![]),
+                }),
+                allow_wildcard_writes: inner.allow_wildcard_writes,
+                attachment: inner.attachment.clone(),
+            };
+            callback(Query {
+                inner: Arc::new(recorded),
+            })
+        })
+    }
+}
+
+/// Forwards the replies to a query while recording them in a [`ReplyCache`].
+#[zenoh_macros::unstable]
+struct RecordingPrimitives {
+    cache: Arc<ReplyCache>,
+    selector: String,
+    query: Mutex<Option<Query>>,
+    responses: Mutex<Vec<Response>>,
+}
+
+#[zenoh_macros::unstable]
+impl Primitives for RecordingPrimitives {
+    fn send_declare(&self, _msg: zenoh_protocol::network::Declare) {}
+
+    fn send_push(&self, _msg: zenoh_protocol::network::Push) {}
+
+    fn send_request(&self, _msg: zenoh_protocol::network::Request) {}
+
+    fn send_response(&self, msg: Response) {
+        zlock!(self.responses).push(msg.clone());
+        let primitives = zlock!(self.query)
+            .as_ref()
+            .map(|query| query.inner.primitives.clone());
+        if let Some(primitives) = primitives {
+            primitives.send_response(msg);
+        }
+    }
+
+    fn send_response_final(&self, _msg: ResponseFinal) {
+        let responses = std::mem::take(&mut *zlock!(self.responses));
+        self.cache.insert(self.selector.clone(), responses);
+        let query = zlock!(self.query).take();
+        drop(query);
+    }
+
+    fn send_close(&self) {}
 }
 
 /// A queryable that provides data through a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
@@ -589,14 +948,43 @@ impl<'a, 'b, Handler> QueryableBuilder<'a, 'b, Handler> {
 #[derive(Debug)]
 pub struct Queryable<'a, Receiver> {
     pub(crate) queryable: CallbackQueryable<'a>,
+    #[cfg(feature = "unstable")]
+    pub(crate) cache: Option<Arc<ReplyCache>>,
     pub receiver: Receiver,
 }
 
 impl<'a, Receiver> Queryable<'a, Receiver> {
+    /// Returns the [`EntityId`](crate::EntityId) of this Queryable, unique within its session.
+    #[zenoh_macros::unstable]
+    pub fn id(&self) -> crate::EntityId {
+        crate::EntityId(self.queryable.state.id)
+    }
+
+    /// Wait for the declaration of this Queryable to reach the connected zenoh sessions,
+    /// or for at most `timeout`.
+    ///
+    /// Resolves to the number of sessions that acknowledged, see
+    /// [`Session::routed`](crate::Session::routed).
+    #[zenoh_macros::unstable]
+    pub fn routed(&self, timeout: Duration) -> impl Resolve<ZResult<usize>> + '_ {
+        self.queryable.session.routed(timeout)
+    }
+
     #[inline]
     pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
         Undeclarable::undeclare_inner(self, ())
     }
+
+    /// Drop the cached replies of the selectors starting with `selector_prefix`,
+    /// see [`QueryableBuilder::cache`].
+    ///
+    /// The parameters of the cached selectors are sorted.
+    #[zenoh_macros::unstable]
+    pub fn invalidate(&self, selector_prefix: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(selector_prefix);
+        }
+    }
 }
 
 impl<'a, T> Undeclarable<(), QueryableUndeclaration<'a>> for Queryable<'a, T> {
@@ -621,15 +1009,30 @@ where
     type To = ZResult<Queryable<'a, Handler::Receiver>>;
 }
 
-impl<'a, Handler> SyncResolve for QueryableBuilder<'a, '_, Handler>
+impl<'a, Handler> QueryableBuilder<'a, '_, Handler>
 where
     Handler: IntoCallbackReceiverPair<'static, Query> + Send,
     Handler::Receiver: Send,
 {
-    fn res_sync(self) -> <Self as Resolvable>::To {
+    /// Declare the queryable, and return it with the timeout to wait for the routing of its
+    /// declaration, if any.
+    fn declare(self) -> ZResult<(Queryable<'a, Handler::Receiver>, Option<Duration>)> {
+        #[cfg(feature = "unstable")]
+        let await_routed = self.await_routed;
+        #[cfg(not(feature = "unstable"))]
+        let await_routed = None;
         let session = self.session;
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
-        session
+        #[cfg(feature = "unstable")]
+        let cache = self
+            .cache
+            .map(|(ttl, max_entries)| Arc::new(ReplyCache::new(ttl, max_entries)));
+        #[cfg(feature = "unstable")]
+        let callback = match &cache {
+            Some(cache) => cache.clone().wrap(callback),
+            None => callback,
+        };
+        let queryable = session
             .declare_queryable_inner(
                 &self.key_expr?.to_wire(&session),
                 self.complete,
@@ -642,8 +1045,27 @@ where
                     state: qable_state,
                     alive: true,
                 },
+                #[cfg(feature = "unstable")]
+                cache,
                 receiver,
-            })
+            })?;
+        Ok((queryable, await_routed))
+    }
+}
+
+impl<'a, Handler> SyncResolve for QueryableBuilder<'a, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Query> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        #[allow(unused_variables)]
+        let (queryable, await_routed) = self.declare()?;
+        #[cfg(feature = "unstable")]
+        if let Some(timeout) = await_routed {
+            queryable.routed(timeout).res_sync()?;
+        }
+        Ok(queryable)
     }
 }
 
@@ -652,9 +1074,43 @@ where
     Handler: IntoCallbackReceiverPair<'static, Query> + Send,
     Handler::Receiver: Send,
 {
+    #[cfg(not(feature = "unstable"))]
     type Future = Ready<Self::To>;
+    #[cfg(feature = "unstable")]
+    type Future = crate::DeclarationFuture<Queryable<'a, Handler::Receiver>>;
 
+    #[cfg(not(feature = "unstable"))]
     fn res_async(self) -> Self::Future {
         std::future::ready(self.res_sync())
     }
+
+    #[cfg(feature = "unstable")]
+    fn res_async(self) -> Self::Future {
+        let (queryable, await_routed) = match self.declare() {
+            Ok(declared) => declared,
+            Err(e) => return crate::DeclarationFuture::ready(Err(e)),
+        };
+        let routed = await_routed.map(|timeout| queryable.queryable.session.routed_async(timeout));
+        crate::DeclarationFuture::new(queryable, routed)
+    }
+}
+
+/// A type-erased handle on a callback [`Queryable`] declared with
+/// [`Session::queryable_dyn`](crate::Session::queryable_dyn).
+///
+/// Unlike [`Queryable`], it isn't tied to the lifetime of the [`Session`](crate::Session) it was
+/// declared on, and can thus be handed over across `dyn Trait` boundaries.
+/// The queryable is automatically undeclared when the handle is dropped.
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct DynQueryableHandle {
+    pub(crate) queryable: Queryable<'static, ()>,
+}
+
+#[zenoh_macros::unstable]
+impl DynQueryableHandle {
+    /// Close the underlying Queryable.
+    pub fn close(self) -> ZResult<()> {
+        self.queryable.undeclare().res_sync()
+    }
 }
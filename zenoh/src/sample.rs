@@ -17,13 +17,21 @@ use crate::buffers::ZBuf;
 use crate::prelude::ZenohId;
 use crate::prelude::{KeyExpr, SampleKind, Value};
 use crate::query::Reply;
+#[zenoh_macros::unstable]
+use crate::time::NTP64;
 use crate::time::{new_reception_timestamp, Timestamp};
 use crate::Priority;
 #[zenoh_macros::unstable]
+use crate::Session;
+#[zenoh_macros::unstable]
 use serde::Serialize;
 use std::convert::{TryFrom, TryInto};
+#[zenoh_macros::unstable]
+use zenoh_core::{Resolve, ResolveClosure, SyncResolve};
 use zenoh_protocol::core::{CongestionControl, Encoding};
 use zenoh_protocol::network::push::ext::QoSType;
+#[zenoh_macros::unstable]
+use zenoh_result::ZResult;
 
 pub type SourceSn = u64;
 
@@ -45,6 +53,19 @@ pub(crate) enum Locality {
     Any,
 }
 
+impl Locality {
+    /// Whether a sample or query is allowed by this locality, `local` telling if it
+    /// originates from the same session.
+    #[inline]
+    pub(crate) fn allows(self, local: bool) -> bool {
+        match self {
+            Locality::Any => true,
+            Locality::SessionLocal => local,
+            Locality::Remote => !local,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub(crate) struct DataInfo {
     pub kind: SampleKind,
@@ -194,7 +215,7 @@ mod attachment {
             }
         }
         pub fn is_empty(&self) -> bool {
-            self.len() == 0
+            self.iter().next().is_none()
         }
         pub fn len(&self) -> usize {
             self.iter().count()
@@ -320,6 +341,62 @@ mod attachment {
 #[zenoh_macros::unstable]
 pub use attachment::{Attachment, AttachmentBuilder, AttachmentIterator};
 
+/// The [`Attachment`] key under which a publication carries the key expression
+/// on which its acknowledgments are expected.
+///
+/// See [`Publisher::put_with_ack`](crate::publication::Publisher::put_with_ack) and [`Sample::ack`].
+#[zenoh_macros::unstable]
+pub const ACK_ATTACHMENT_KEY: &str = "_ack";
+
+/// The [`Attachment`] key under which a publication carries its provenance chain.
+///
+/// See [`Publisher::forward`](crate::publication::Publisher::forward) and [`Sample::provenance`].
+#[zenoh_macros::unstable]
+pub const PROVENANCE_ATTACHMENT_KEY: &str = "_provenance";
+
+/// A re-publication of a [`Sample`] by a bridge, as recorded in its provenance chain.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProvenanceHop {
+    /// The [`ZenohId`] of the session that re-published the sample.
+    pub zid: ZenohId,
+    /// The time at which the sample was re-published.
+    pub timestamp: Timestamp,
+}
+
+#[zenoh_macros::unstable]
+impl ProvenanceHop {
+    // Each hop is encoded as the size of the zid, the zid and the NTP64 time in little endian
+    pub(crate) fn encode(hops: &[ProvenanceHop]) -> Vec<u8> {
+        let mut bytes = vec![];
+        for hop in hops {
+            let size = hop.zid.size();
+            bytes.push(size as u8);
+            bytes.extend_from_slice(&hop.zid.to_le_bytes()[..size]);
+            bytes.extend_from_slice(&hop.timestamp.get_time().as_u64().to_le_bytes());
+        }
+        bytes
+    }
+
+    pub(crate) fn decode(mut bytes: &[u8]) -> Option<Vec<ProvenanceHop>> {
+        let mut hops = vec![];
+        while let Some((&size, rest)) = bytes.split_first() {
+            let size = size as usize;
+            if rest.len() < size + 8 {
+                return None;
+            }
+            let zid = ZenohId::try_from(&rest[..size]).ok()?;
+            let time = u64::from_le_bytes(rest[size..size + 8].try_into().ok()?);
+            hops.push(ProvenanceHop {
+                zid,
+                timestamp: Timestamp::new(NTP64(time), (&zid).into()),
+            });
+            bytes = &rest[size + 8..];
+        }
+        Some(hops)
+    }
+}
+
 /// A zenoh sample.
 #[non_exhaustive]
 #[derive(Clone, Debug)]
@@ -343,6 +420,10 @@ pub struct Sample {
     /// </div>
     ///
     /// Infos on the source of this Sample.
+    ///
+    /// Every put and delete of a session carries its [`ZenohId`] and a sequence number, for both
+    /// push and pull subscribers: they are `None` for the samples written by sessions built
+    /// without the `unstable` feature.
     pub source_info: SourceInfo,
 
     #[cfg(feature = "unstable")]
@@ -353,7 +434,21 @@ pub struct Sample {
     /// </div>
     ///
     /// A map of key-value pairs, where each key and value are byte-slices.
+    ///
+    /// Attachments are forwarded unchanged by the routers. Empty attachments are not sent,
+    /// and are received as `None`.
     pub attachment: Option<Attachment>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// Whether this Sample was replayed from a cache or a storage rather than received live
+    /// (see [`Subscriber::resume_with_replay`](crate::subscriber::Subscriber::resume_with_replay)).
+    pub historical: bool,
 }
 
 impl Sample {
@@ -374,6 +469,8 @@ impl Sample {
             source_info: SourceInfo::empty(),
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            historical: false,
         }
     }
     /// Creates a new Sample.
@@ -397,6 +494,8 @@ impl Sample {
             source_info: SourceInfo::empty(),
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            historical: false,
         })
     }
 
@@ -422,6 +521,8 @@ impl Sample {
                 source_info: data_info.into(),
                 #[cfg(feature = "unstable")]
                 attachment: None,
+                #[cfg(feature = "unstable")]
+                historical: false,
             }
         } else {
             Sample {
@@ -434,6 +535,8 @@ impl Sample {
                 source_info: SourceInfo::empty(),
                 #[cfg(feature = "unstable")]
                 attachment: None,
+                #[cfg(feature = "unstable")]
+                historical: false,
             }
         }
     }
@@ -489,6 +592,61 @@ impl Sample {
         self.attachment = Some(attachment);
         self
     }
+
+    /// Returns the provenance chain of this Sample: the hops through which it was re-published
+    /// by bridges using [`Publisher::forward`](crate::publication::Publisher::forward),
+    /// from the oldest to the most recent.
+    ///
+    /// The chain is empty if this Sample was never re-published, or if its
+    /// [`PROVENANCE_ATTACHMENT_KEY`] attachment entry is malformed.
+    #[zenoh_macros::unstable]
+    pub fn provenance(&self) -> Vec<ProvenanceHop> {
+        self.attachment
+            .as_ref()
+            .and_then(|attachment| attachment.get(&PROVENANCE_ATTACHMENT_KEY))
+            .and_then(|bytes| ProvenanceHop::decode(bytes.as_slice()))
+            .unwrap_or_default()
+    }
+
+    /// Returns the key expression on which the publisher of this Sample expects an acknowledgment,
+    /// if it was published with [`Publisher::put_with_ack`](crate::publication::Publisher::put_with_ack).
+    #[zenoh_macros::unstable]
+    pub fn ack_key_expr(&self) -> Option<KeyExpr<'static>> {
+        let ack_key = self.attachment.as_ref()?.get(&ACK_ATTACHMENT_KEY)?;
+        let ack_key = String::from_utf8(ack_key.as_slice().to_vec()).ok()?;
+        KeyExpr::try_from(ack_key).ok()
+    }
+
+    /// Acknowledges this Sample to its publisher.
+    ///
+    /// The acknowledgment is published on the key expression carried by the Sample's
+    /// [`ACK_ATTACHMENT_KEY`] attachment entry, with the acknowledging session's [`ZenohId`]
+    /// as payload, so that the publisher counts the acknowledgments of a same session once.
+    /// Resolving fails if the Sample doesn't request an acknowledgment.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// # session.put("key/expression", "value").res().await.unwrap();
+    /// let sample = subscriber.recv_async().await.unwrap();
+    /// if sample.ack_key_expr().is_some() {
+    ///     sample.ack(&session).res().await.unwrap();
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn ack<'a>(&self, session: &'a Session) -> impl Resolve<ZResult<()>> + 'a {
+        let ack_key = self.ack_key_expr();
+        ResolveClosure::new(move || match ack_key {
+            Some(ack_key) => session.put(ack_key, session.zid().to_string()).res_sync(),
+            None => bail!("Sample doesn't request an acknowledgment"),
+        })
+    }
 }
 
 impl std::ops::Deref for Sample {
@@ -0,0 +1,275 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Fair scheduling of the publications of the weighted publishers of a session.
+//!
+//! The publications of the publishers given a [`weight`](crate::publication::Publisher::weight)
+//! are queued per publisher, and sent by a scheduling task: the publications of higher priority
+//! are sent first, and the publishers of the same priority are served by deficit round robin,
+//! each receiving a share of the bandwidth proportional to its weight while it has publications
+//! queued. The scheduling task is blocked along with the transports when they are congested, so
+//! that the queues of the publishers fill up: their publications then block or are dropped,
+//! according to their congestion control.
+#[cfg(not(feature = "testing"))]
+use crate::net::primitives::Primitives;
+use crate::net::routing::dispatcher::face::Face;
+use crate::publication::{CongestionControl, Priority};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use zenoh_buffers::buffer::Buffer;
+use zenoh_core::zlock;
+use zenoh_protocol::network::Push;
+use zenoh_protocol::zenoh::PushBody;
+
+// The number of bytes a publisher of weight 1 may send per round
+const QUANTUM: usize = 1024;
+// The number of bytes queued per publisher beyond which its publications block or are dropped
+const MAX_QUEUED_BYTES: usize = 1 << 20;
+const PRIORITIES: usize = Priority::Background as usize + 1;
+
+struct Queued {
+    primitives: Arc<Face>,
+    push: Push,
+    size: usize,
+}
+
+#[derive(Default)]
+struct Flow {
+    weight: usize,
+    queue: VecDeque<Queued>,
+    queued_bytes: usize,
+    sent_bytes: u64,
+    deficit: usize,
+    // Whether the flow is in the round of its priority
+    active: bool,
+    // Whether the flow was credited its quantum for the current round
+    credited: bool,
+    closed: bool,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    next_id: usize,
+    flows: HashMap<usize, Flow>,
+    // The flows with queued publications, per priority
+    active: [VecDeque<usize>; PRIORITIES],
+    running: bool,
+}
+
+impl SchedulerState {
+    /// Returns the next publication to send, following the priorities and deficit round robin.
+    fn next(&mut self) -> Option<Queued> {
+        loop {
+            let active = self.active.iter_mut().find(|active| !active.is_empty())?;
+            let id = *active.front()?;
+            let flow = self.flows.get_mut(&id)?;
+            if !flow.credited {
+                flow.deficit += QUANTUM * flow.weight;
+                flow.credited = true;
+            }
+            match flow.queue.front() {
+                Some(queued) if queued.size <= flow.deficit => {
+                    let queued = flow.queue.pop_front()?;
+                    flow.deficit -= queued.size;
+                    flow.queued_bytes -= queued.size;
+                    flow.sent_bytes += queued.size as u64;
+                    return Some(queued);
+                }
+                Some(_) => {
+                    flow.credited = false;
+                    active.rotate_left(1);
+                }
+                None => {
+                    flow.deficit = 0;
+                    flow.credited = false;
+                    flow.active = false;
+                    active.pop_front();
+                    if flow.closed {
+                        self.flows.remove(&id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The fair scheduler of the publications of a session.
+pub(crate) struct FairScheduler {
+    state: Mutex<SchedulerState>,
+    dequeued: Condvar,
+    #[cfg(feature = "testing")]
+    fault_injector: Arc<crate::testing::FaultInjector>,
+}
+
+impl FairScheduler {
+    pub(crate) fn new(
+        #[cfg(feature = "testing")] fault_injector: Arc<crate::testing::FaultInjector>,
+    ) -> Self {
+        FairScheduler {
+            state: Mutex::new(SchedulerState::default()),
+            dequeued: Condvar::new(),
+            #[cfg(feature = "testing")]
+            fault_injector,
+        }
+    }
+
+    /// Register the flow of a publisher with the given weight.
+    pub(crate) fn register(self: &Arc<Self>, weight: u8) -> Arc<FlowHandle> {
+        let mut state = zlock!(self.state);
+        let id = state.next_id;
+        state.next_id += 1;
+        state.flows.insert(
+            id,
+            Flow {
+                weight: weight.max(1) as usize,
+                ..Flow::default()
+            },
+        );
+        Arc::new(FlowHandle {
+            id,
+            scheduler: self.clone(),
+        })
+    }
+
+    fn enqueue(
+        self: &Arc<Self>,
+        flow: &FlowHandle,
+        priority: Priority,
+        congestion_control: CongestionControl,
+        primitives: Arc<Face>,
+        push: Push,
+    ) {
+        let size = match &push.payload {
+            PushBody::Put(put) => put.payload.len(),
+            PushBody::Del(_) => 0,
+        } + push.wire_expr.suffix.len();
+        let mut state = zlock!(self.state);
+        loop {
+            let Some(queued) = state.flows.get(&flow.id).map(|flow| flow.queued_bytes) else {
+                return;
+            };
+            if queued < MAX_QUEUED_BYTES {
+                break;
+            }
+            match congestion_control {
+                CongestionControl::Drop => {
+                    tracing::trace!("Dropping publication on {:?}: queue full", push.wire_expr);
+                    return;
+                }
+                CongestionControl::Block => {
+                    state = self.dequeued.wait(state).unwrap_or_else(|e| e.into_inner());
+                }
+            }
+        }
+        let state = &mut *state;
+        let Some(entry) = state.flows.get_mut(&flow.id) else {
+            return;
+        };
+        entry.queued_bytes += size;
+        entry.queue.push_back(Queued {
+            primitives,
+            push,
+            size,
+        });
+        if !entry.active {
+            entry.active = true;
+            state.active[priority as usize].push_back(flow.id);
+        }
+        if !state.running {
+            state.running = true;
+            let scheduler = self.clone();
+            zenoh_runtime::ZRuntime::Net.spawn_blocking(move || scheduler.run());
+        }
+    }
+
+    fn run(&self) {
+        loop {
+            let queued = {
+                let mut state = zlock!(self.state);
+                match state.next() {
+                    Some(queued) => queued,
+                    None => {
+                        state.running = false;
+                        return;
+                    }
+                }
+            };
+            self.dequeued.notify_all();
+            #[cfg(feature = "testing")]
+            self.fault_injector
+                .send_push(&queued.primitives, queued.push);
+            #[cfg(not(feature = "testing"))]
+            queued.primitives.send_push(queued.push);
+        }
+    }
+}
+
+impl std::fmt::Debug for FairScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FairScheduler")
+            .field("flows", &zlock!(self.state).flows.len())
+            .finish()
+    }
+}
+
+/// The flow of a weighted publisher, unregistered when dropped once its queue is sent.
+pub(crate) struct FlowHandle {
+    id: usize,
+    scheduler: Arc<FairScheduler>,
+}
+
+impl FlowHandle {
+    pub(crate) fn send_push(
+        &self,
+        priority: Priority,
+        congestion_control: CongestionControl,
+        primitives: Arc<Face>,
+        push: Push,
+    ) {
+        self.scheduler
+            .enqueue(self, priority, congestion_control, primitives, push)
+    }
+
+    pub(crate) fn queued_bytes(&self) -> usize {
+        let state = zlock!(self.scheduler.state);
+        state
+            .flows
+            .get(&self.id)
+            .map_or(0, |flow| flow.queued_bytes)
+    }
+
+    pub(crate) fn sent_bytes(&self) -> u64 {
+        let state = zlock!(self.scheduler.state);
+        state.flows.get(&self.id).map_or(0, |flow| flow.sent_bytes)
+    }
+}
+
+impl Drop for FlowHandle {
+    fn drop(&mut self) {
+        let mut state = zlock!(self.scheduler.state);
+        if let Some(flow) = state.flows.get_mut(&self.id) {
+            if flow.queue.is_empty() {
+                state.flows.remove(&self.id);
+            } else {
+                flow.closed = true;
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for FlowHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlowHandle").field("id", &self.id).finish()
+    }
+}
@@ -0,0 +1,216 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Scoped declarations.
+//!
+//! see [`Session::scope`](crate::Session::scope)
+use crate::handlers::DefaultHandler;
+use crate::info::SessionInfo;
+use crate::liveliness::Liveliness;
+use crate::prelude::{KeyExpr, SessionDeclarations};
+use crate::publication::PublisherBuilder;
+use crate::queryable::QueryableBuilder;
+use crate::subscriber::{PushMode, SubscriberBuilder};
+use crate::{Id, Session};
+use std::sync::{Arc, Mutex};
+use zenoh_core::{zlock, Resolve, ResolveClosure, SyncResolve};
+use zenoh_result::ZResult;
+
+/// An entity declared through an [`EntityScope`].
+#[derive(Clone)]
+pub(crate) enum ScopedEntity {
+    Subscriber(Id),
+    Queryable(Id),
+    Publisher(KeyExpr<'static>),
+    LivelinessToken(Id),
+    Scope(Arc<ScopeState>),
+}
+
+impl PartialEq for ScopedEntity {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ScopedEntity::Subscriber(l), ScopedEntity::Subscriber(r))
+            | (ScopedEntity::Queryable(l), ScopedEntity::Queryable(r))
+            | (ScopedEntity::LivelinessToken(l), ScopedEntity::LivelinessToken(r)) => l == r,
+            (ScopedEntity::Publisher(l), ScopedEntity::Publisher(r)) => l == r,
+            (ScopedEntity::Scope(l), ScopedEntity::Scope(r)) => Arc::ptr_eq(l, r),
+            _ => false,
+        }
+    }
+}
+
+impl ScopedEntity {
+    pub(crate) fn undeclare(self, session: &Session) -> ZResult<()> {
+        match self {
+            ScopedEntity::Subscriber(id) => session.unsubscribe(id),
+            ScopedEntity::Queryable(id) => session.close_queryable(id),
+            ScopedEntity::Publisher(key_expr) => {
+                session.undeclare_publication_intent(key_expr).res_sync()
+            }
+            ScopedEntity::LivelinessToken(id) => session.undeclare_liveliness(id),
+            ScopedEntity::Scope(scope) => {
+                scope.close(session);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The entities declared through an [`EntityScope`], or `None` once the scope is closed.
+pub(crate) struct ScopeState {
+    entities: Mutex<Option<Vec<ScopedEntity>>>,
+}
+
+impl ScopeState {
+    pub(crate) fn new() -> Self {
+        ScopeState {
+            entities: Mutex::new(Some(vec![])),
+        }
+    }
+
+    /// Returns false if the scope is already closed.
+    pub(crate) fn register(&self, entity: ScopedEntity) -> bool {
+        match zlock!(self.entities).as_mut() {
+            Some(entities) => {
+                entities.push(entity);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn forget(&self, entity: &ScopedEntity) {
+        if let Some(entities) = zlock!(self.entities).as_mut() {
+            if let Some(idx) = entities.iter().rposition(|e| e == entity) {
+                entities.remove(idx);
+            }
+        }
+    }
+
+    /// Undeclare the entities of the scope in the reverse order of their declaration.
+    pub(crate) fn close(&self, session: &Session) {
+        let entities = zlock!(self.entities).take();
+        for entity in entities.into_iter().flatten().rev() {
+            // Entities whose handle has been undeclared concurrently may already be gone
+            if let Err(e) = entity.undeclare(session) {
+                tracing::debug!("Error undeclaring scoped entity: {}", e);
+            }
+        }
+    }
+}
+
+/// A group of entities that are undeclared together.
+///
+/// Subscribers, queryables, publishers and liveliness tokens declared through an `EntityScope`
+/// are undeclared, in the reverse order of their declaration, when the scope is closed or
+/// dropped, even if their handles were leaked. Scopes can be nested with [`EntityScope::scope`].
+/// Entities declared directly on the [`Session`] are unaffected.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let scope = session.scope();
+/// let subscriber = scope.declare_subscriber("key/expression").res().await.unwrap();
+/// let publisher = scope.declare_publisher("key/expression").res().await.unwrap();
+/// // Undeclare both the subscriber and the publisher
+/// scope.close().res().await.unwrap();
+/// # }
+/// ```
+pub struct EntityScope {
+    pub(crate) session: Arc<Session>,
+    pub(crate) state: Arc<ScopeState>,
+    pub(crate) parent: Option<Arc<ScopeState>>,
+}
+
+impl EntityScope {
+    /// Create a nested [`EntityScope`], closed along with this one.
+    pub fn scope(&self) -> EntityScope {
+        self.session.scope()
+    }
+
+    /// Close this [`EntityScope`], undeclaring all the entities declared through it.
+    ///
+    /// Scopes are automatically closed when dropped, but you may want to use this function to
+    /// close them asynchronously.
+    pub fn close(self) -> impl Resolve<ZResult<()>> {
+        ResolveClosure::new(move || {
+            drop(self);
+            Ok(())
+        })
+    }
+}
+
+impl Drop for EntityScope {
+    fn drop(&mut self) {
+        self.state.close(&self.session);
+        if let Some(parent) = &self.parent {
+            parent.forget(&ScopedEntity::Scope(self.state.clone()));
+        }
+    }
+}
+
+impl std::fmt::Debug for EntityScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntityScope")
+            .field("session", &self.session.id)
+            .finish()
+    }
+}
+
+impl<'s> SessionDeclarations<'s, 'static> for EntityScope {
+    fn declare_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> SubscriberBuilder<'static, 'b, PushMode, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.session.declare_subscriber(key_expr)
+    }
+
+    fn declare_queryable<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QueryableBuilder<'static, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.session.declare_queryable(key_expr)
+    }
+
+    fn declare_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> PublisherBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.session.declare_publisher(key_expr)
+    }
+
+    fn liveliness(&'s self) -> Liveliness<'static> {
+        self.session.liveliness()
+    }
+
+    fn info(&'s self) -> SessionInfo<'static> {
+        self.session.info()
+    }
+}
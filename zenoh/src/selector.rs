@@ -14,8 +14,9 @@
 
 //! [Selector](https://github.com/eclipse-zenoh/roadmap/tree/main/rfcs/ALL/Selectors) to issue queries
 
+use zenoh_collections::PropertiesParseIssue;
 use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
-use zenoh_result::ZResult;
+use zenoh_result::{bail, ZResult};
 pub use zenoh_util::time_range::{TimeBound, TimeExpr, TimeRange};
 
 use crate::{prelude::KeyExpr, queryable::Query};
@@ -281,6 +282,19 @@ fn selector_accessors() {
         assert_eq!(selector.to_string(), without_any + "&other");
     }
 }
+#[test]
+fn selector_strict_parameters() {
+    for selector in ["a/b?", "a/b?x", "a/b?x=1&y", "a/b?x=1&", "a/b?x=a=b"] {
+        Selector::try_from(selector).unwrap();
+        Selector::try_from(selector.to_string()).unwrap();
+    }
+    let err = |s: &str| Selector::try_from(s).unwrap_err().to_string();
+    assert!(err("a/b?&x").contains("bare separator at byte 4"));
+    assert!(err("a/b?x&&y").contains("bare separator at byte 6"));
+    assert!(err("a/b?x&=1").contains("empty key at byte 6"));
+    assert!(err("a/b?=1&&").contains("empty key at byte 4, bare separator at byte 7"));
+}
+
 pub trait Parameter: Sized {
     type Name: AsRef<str> + Sized;
     type Value: AsRef<str> + Sized;
@@ -458,11 +472,32 @@ impl<'a> From<&Selector<'a>> for Selector<'a> {
     }
 }
 
+/// Rejects the parameters of `selector` starting at `start` that
+/// [`Properties::parse_strict_with`](zenoh_collections::Properties::parse_strict_with) reports.
+fn check_parameters(selector: &str, start: usize) -> ZResult<()> {
+    if let Err(issues) = zenoh_collections::Properties::parse_strict_with(&selector[start..], '&') {
+        let issues = issues
+            .into_iter()
+            .map(|issue| {
+                PropertiesParseIssue {
+                    offset: start + issue.offset,
+                    ..issue
+                }
+                .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!("Invalid parameters in selector `{}`: {}", selector, issues);
+    }
+    Ok(())
+}
+
 impl TryFrom<String> for Selector<'_> {
     type Error = zenoh_result::Error;
     fn try_from(mut s: String) -> Result<Self, Self::Error> {
         match s.find('?') {
             Some(qmark_position) => {
+                check_parameters(&s, qmark_position + 1)?;
                 let parameters = s[qmark_position + 1..].to_owned();
                 s.truncate(qmark_position);
                 Ok(KeyExpr::try_from(s)?.with_owned_parameters(parameters))
@@ -477,6 +512,7 @@ impl<'a> TryFrom<&'a str> for Selector<'a> {
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
         match s.find('?') {
             Some(qmark_position) => {
+                check_parameters(s, qmark_position + 1)?;
                 let params = &s[qmark_position + 1..];
                 Ok(KeyExpr::try_from(&s[..qmark_position])?.with_parameters(params))
             }
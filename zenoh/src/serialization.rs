@@ -0,0 +1,262 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Conversions between typed values and [`Value`]s, per [`Encoding`].
+//!
+//! see [`SerializationRegistry`]
+use crate::prelude::{Encoding, KnownEncoding, SplitBuffer, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+use zenoh_core::{zread, zwrite};
+use zenoh_result::{zerror, ZResult};
+
+/// The serializer and deserializer of a type `T` for an [`Encoding`].
+pub struct Codec<T> {
+    pub serialize: fn(&T) -> ZResult<Value>,
+    pub deserialize: fn(&Value) -> ZResult<T>,
+}
+
+impl<T> Clone for Codec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Codec<T> {}
+
+impl<T> fmt::Debug for Codec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Codec")
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+type Codecs = Vec<(Encoding, Box<dyn Any + Send + Sync>)>;
+
+lazy_static::lazy_static!(
+    static ref GLOBAL: SerializationRegistry = SerializationRegistry::new(None);
+);
+
+/// A registry of [`Codec`]s, keyed by type and [`Encoding`].
+///
+/// Each session has its own registry (see [`Session::serialization`](crate::Session::serialization)),
+/// whose codecs override the ones of the [global](SerializationRegistry::global) registry.
+/// When no codec is registered for a type and an encoding, JSON encodings fall back to `serde_json`.
+///
+/// Codecs can be registered concurrently with their use. Looking a codec up doesn't allocate.
+///
+/// # Examples
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use zenoh::prelude::sync::*;
+/// use zenoh::serialization::SerializationRegistry;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let session = zenoh::open(config::peer()).res().unwrap();
+/// let encoding = Encoding::APP_CUSTOM.with_suffix("point").unwrap();
+/// session.serialization().register::<Point>(
+///     encoding.clone(),
+///     |p| Ok(format!("{},{}", p.x, p.y).into()),
+///     |v| {
+///         let s = String::from_utf8(v.payload.contiguous().to_vec())?;
+///         let (x, y) = s.split_once(',').ok_or("Invalid point")?;
+///         Ok(Point { x: x.parse()?, y: y.parse()? })
+///     },
+/// );
+///
+/// let value = session.serialization().serialize(&Point { x: 1, y: 2 }, &encoding).unwrap();
+/// assert_eq!(value.encoding, encoding);
+/// let point: Point = session.serialization().deserialize(&value).unwrap();
+/// assert_eq!(point, Point { x: 1, y: 2 });
+/// ```
+pub struct SerializationRegistry {
+    codecs: RwLock<HashMap<TypeId, Codecs>>,
+    parent: Option<&'static SerializationRegistry>,
+}
+
+impl SerializationRegistry {
+    pub(crate) fn new(parent: Option<&'static SerializationRegistry>) -> Self {
+        SerializationRegistry {
+            codecs: RwLock::new(HashMap::new()),
+            parent,
+        }
+    }
+
+    /// Returns the global registry, shared by all sessions.
+    pub fn global() -> &'static SerializationRegistry {
+        &GLOBAL
+    }
+
+    /// Register the serializer and deserializer of `T` for `encoding`, replacing the previous ones.
+    ///
+    /// The values returned by `serialize` are given `encoding`.
+    pub fn register<T: 'static>(
+        &self,
+        encoding: Encoding,
+        serialize: fn(&T) -> ZResult<Value>,
+        deserialize: fn(&Value) -> ZResult<T>,
+    ) {
+        let codec: Box<dyn Any + Send + Sync> = Box::new(Codec {
+            serialize,
+            deserialize,
+        });
+        let mut codecs = zwrite!(self.codecs);
+        let codecs = codecs.entry(TypeId::of::<T>()).or_default();
+        match codecs.iter_mut().find(|(e, _)| *e == encoding) {
+            Some((_, c)) => *c = codec,
+            None => codecs.push((encoding, codec)),
+        }
+    }
+
+    /// Unregister the codec of `T` for `encoding`, returning true if there was one.
+    pub fn unregister<T: 'static>(&self, encoding: &Encoding) -> bool {
+        let mut codecs = zwrite!(self.codecs);
+        match codecs.get_mut(&TypeId::of::<T>()) {
+            Some(codecs) => {
+                let len = codecs.len();
+                codecs.retain(|(e, _)| e != encoding);
+                codecs.len() != len
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the codec of `T` for `encoding` registered in this registry or its parent, if any.
+    pub fn codec<T: 'static>(&self, encoding: &Encoding) -> Option<Codec<T>> {
+        let codec = zread!(self.codecs)
+            .get(&TypeId::of::<T>())
+            .and_then(|codecs| codecs.iter().find(|(e, _)| e == encoding))
+            .and_then(|(_, codec)| codec.downcast_ref::<Codec<T>>().copied());
+        codec.or_else(|| self.parent.and_then(|parent| parent.codec(encoding)))
+    }
+
+    /// Serialize `value` with `encoding`.
+    pub fn serialize<T>(&self, value: &T, encoding: &Encoding) -> ZResult<Value>
+    where
+        T: Serialize + 'static,
+    {
+        match self.codec::<T>(encoding) {
+            Some(codec) => Ok((codec.serialize)(value)?.encoding(encoding.clone())),
+            None => match encoding.prefix() {
+                KnownEncoding::AppJson | KnownEncoding::TextJson => {
+                    let payload = serde_json::to_vec(value)
+                        .map_err(|e| zerror!("Unable to serialize to {}: {}", encoding, e))?;
+                    Ok(Value::from(payload).encoding(encoding.clone()))
+                }
+                _ => Err(zerror!(
+                    "No codec registered for {} with encoding {}",
+                    std::any::type_name::<T>(),
+                    encoding
+                )
+                .into()),
+            },
+        }
+    }
+
+    /// Deserialize `value` according to its encoding.
+    pub fn deserialize<T>(&self, value: &Value) -> ZResult<T>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        match self.codec::<T>(&value.encoding) {
+            Some(codec) => (codec.deserialize)(value),
+            None => match value.encoding.prefix() {
+                KnownEncoding::AppJson | KnownEncoding::TextJson => {
+                    serde_json::from_slice(&value.payload.contiguous()).map_err(|e| {
+                        zerror!("Unable to deserialize from {}: {}", value.encoding, e).into()
+                    })
+                }
+                _ => Err(zerror!(
+                    "No codec registered for {} with encoding {}",
+                    std::any::type_name::<T>(),
+                    value.encoding
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for SerializationRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerializationRegistry")
+            .field(
+                "codecs",
+                &zread!(self.codecs).values().map(Vec::len).sum::<usize>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom() -> Encoding {
+        Encoding::APP_CUSTOM.with_suffix("u32").unwrap()
+    }
+
+    fn serialize_u32(v: &u32) -> ZResult<Value> {
+        Ok(v.to_le_bytes().to_vec().into())
+    }
+
+    fn deserialize_u32(v: &Value) -> ZResult<u32> {
+        let bytes: [u8; 4] = v.payload.contiguous()[..]
+            .try_into()
+            .map_err(|_| zerror!("Invalid u32"))?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    #[test]
+    fn registry_override_and_fallback() {
+        let global: &'static SerializationRegistry =
+            Box::leak(Box::new(SerializationRegistry::new(None)));
+        let registry = SerializationRegistry::new(Some(global));
+
+        // Built-in JSON
+        let value = registry.serialize(&42u32, &Encoding::APP_JSON).unwrap();
+        assert_eq!(value.to_string(), "42");
+        assert_eq!(registry.deserialize::<u32>(&value).unwrap(), 42);
+        assert!(registry.serialize(&42u32, &custom()).is_err());
+
+        // Codecs of the parent registry apply
+        global.register(custom(), serialize_u32, deserialize_u32);
+        let value = registry.serialize(&42u32, &custom()).unwrap();
+        assert_eq!(value.encoding, custom());
+        assert_eq!(value.payload.contiguous().len(), 4);
+        assert_eq!(registry.deserialize::<u32>(&value).unwrap(), 42);
+
+        // Codecs of the registry override the parent's
+        registry.register::<u32>(custom(), |_| Ok(Value::empty()), |_| Ok(0));
+        let value = registry.serialize(&42u32, &custom()).unwrap();
+        assert_eq!(registry.deserialize::<u32>(&value).unwrap(), 0);
+        assert!(registry.unregister::<u32>(&custom()));
+        assert!(!registry.unregister::<u32>(&custom()));
+        let value = registry.serialize(&42u32, &custom()).unwrap();
+        assert_eq!(registry.deserialize::<u32>(&value).unwrap(), 42);
+
+        // Codecs are registered per type
+        assert!(registry.serialize(&42u64, &custom()).is_err());
+    }
+}
@@ -15,6 +15,10 @@
 use crate::admin;
 use crate::config::Config;
 use crate::config::Notifier;
+#[zenoh_macros::unstable]
+use crate::events::SessionEventListenerBuilder;
+#[zenoh_macros::unstable]
+use crate::handlers::locked;
 use crate::handlers::{Callback, DefaultHandler};
 use crate::info::*;
 use crate::key_expr::KeyExprInner;
@@ -33,6 +37,8 @@ use crate::runtime::RuntimeBuilder;
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
+#[zenoh_macros::unstable]
+use crate::scope::{EntityScope, ScopeState, ScopedEntity};
 use crate::selector::TIME_RANGE_KEY;
 use crate::subscriber::*;
 use crate::Id;
@@ -41,18 +47,19 @@ use crate::Sample;
 use crate::SampleKind;
 use crate::Selector;
 use crate::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt;
+use std::num::NonZeroU32;
 use std::ops::Deref;
-use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU16, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::RwLock;
 use std::time::Duration;
 use tracing::{error, trace, warn};
 use uhlc::HLC;
-use zenoh_buffers::ZBuf;
+use zenoh_buffers::{buffer::Buffer, ZBuf};
 use zenoh_collections::SingleOrVec;
 use zenoh_config::unwrap_or_default;
 use zenoh_core::{zconfigurable, zread, Resolve, ResolveClosure, ResolveFuture, SyncResolve};
@@ -93,6 +100,66 @@ zconfigurable! {
     pub(crate) static ref API_OPEN_SESSION_DELAY: u64 = 500;
 }
 
+/// The future of an asynchronous declaration, ready unless it awaits the routing of the
+/// declaration, see [`SubscriberBuilder::await_routed`].
+#[zenoh_macros::unstable]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DeclarationFuture<T>(DeclarationState<T>);
+
+#[cfg(feature = "unstable")]
+enum DeclarationState<T> {
+    Ready(std::future::Ready<ZResult<T>>),
+    Routed(
+        std::pin::Pin<Box<dyn std::future::Future<Output = ZResult<usize>> + Send>>,
+        Option<T>,
+    ),
+}
+
+#[zenoh_macros::unstable]
+impl<T> DeclarationFuture<T> {
+    pub(crate) fn ready(declared: ZResult<T>) -> Self {
+        DeclarationFuture(DeclarationState::Ready(std::future::ready(declared)))
+    }
+
+    /// Resolves to `declared` once `routed` is, if given.
+    pub(crate) fn new<F>(declared: T, routed: Option<F>) -> Self
+    where
+        F: std::future::Future<Output = ZResult<usize>> + Send + 'static,
+    {
+        match routed {
+            Some(routed) => {
+                DeclarationFuture(DeclarationState::Routed(Box::pin(routed), Some(declared)))
+            }
+            None => DeclarationFuture::ready(Ok(declared)),
+        }
+    }
+}
+
+// The declared value is never pinned
+#[zenoh_macros::unstable]
+impl<T> Unpin for DeclarationFuture<T> {}
+
+#[zenoh_macros::unstable]
+impl<T> std::future::Future for DeclarationFuture<T> {
+    type Output = ZResult<T>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        match &mut self.get_mut().0 {
+            DeclarationState::Ready(ready) => std::pin::Pin::new(ready).poll(cx),
+            DeclarationState::Routed(routed, declared) => routed.as_mut().poll(cx).map(|routed| {
+                routed.map(|_| {
+                    declared
+                        .take()
+                        .expect("DeclarationFuture polled after completion")
+                })
+            }),
+        }
+    }
+}
+
 pub(crate) struct SessionState {
     pub(crate) primitives: Option<Arc<Face>>, // @TODO replace with MaybeUninit ??
     pub(crate) expr_id_counter: AtomicExprId, // @TODO: manage rollover and uniqueness
@@ -102,12 +169,18 @@ pub(crate) struct SessionState {
     pub(crate) remote_resources: HashMap<ExprId, Resource>,
     //pub(crate) publications: Vec<OwnedKeyExpr>,
     pub(crate) subscribers: HashMap<Id, Arc<SubscriberState>>,
+    pub(crate) subscriber_tree: SubscriberTree,
     pub(crate) queryables: HashMap<Id, Arc<QueryableState>>,
     #[cfg(feature = "unstable")]
     pub(crate) tokens: HashMap<Id, Arc<LivelinessTokenState>>,
     #[cfg(feature = "unstable")]
     pub(crate) matching_listeners: HashMap<Id, Arc<MatchingListenerState>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) publishers: HashMap<Id, PublicationInfo>,
     pub(crate) queries: HashMap<RequestId, QueryState>,
+    /// The bounded pulls waiting for their final response, see [`Session::pull_served`].
+    #[cfg(feature = "unstable")]
+    pub(crate) pulls: HashMap<RequestId, tokio::sync::oneshot::Sender<Option<u32>>>,
     pub(crate) aggregated_subscribers: Vec<OwnedKeyExpr>,
     //pub(crate) aggregated_publishers: Vec<OwnedKeyExpr>,
 }
@@ -126,12 +199,17 @@ impl SessionState {
             remote_resources: HashMap::new(),
             //publications: Vec::new(),
             subscribers: HashMap::new(),
+            subscriber_tree: SubscriberTree::default(),
             queryables: HashMap::new(),
             #[cfg(feature = "unstable")]
             tokens: HashMap::new(),
             #[cfg(feature = "unstable")]
             matching_listeners: HashMap::new(),
+            #[cfg(feature = "unstable")]
+            publishers: HashMap::new(),
             queries: HashMap::new(),
+            #[cfg(feature = "unstable")]
+            pulls: HashMap::new(),
             aggregated_subscribers,
             //aggregated_publishers,
         }
@@ -139,6 +217,27 @@ impl SessionState {
 }
 
 impl SessionState {
+    /// Remove the subscriber `sid` from the routing tables of the session.
+    fn remove_subscriber(&mut self, sid: Id) -> Option<Arc<SubscriberState>> {
+        let sub_state = self.subscribers.remove(&sid)?;
+        self.subscriber_tree.remove(&sub_state);
+        for res in self
+            .local_resources
+            .values_mut()
+            .filter_map(Resource::as_node_mut)
+        {
+            res.subscribers.retain(|sub| sub.id != sub_state.id);
+        }
+        for res in self
+            .remote_resources
+            .values_mut()
+            .filter_map(Resource::as_node_mut)
+        {
+            res.subscribers.retain(|sub| sub.id != sub_state.id);
+        }
+        Some(sub_state)
+    }
+
     #[inline]
     fn get_local_res(&self, id: &ExprId) -> Option<&Resource> {
         self.local_resources.get(id)
@@ -301,6 +400,36 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             reliability: Reliability::default(),
             mode: PushMode,
             origin: Locality::default(),
+            #[cfg(feature = "unstable")]
+            payload_filter: None,
+            #[cfg(feature = "unstable")]
+            transform: None,
+            #[cfg(feature = "unstable")]
+            filter: None,
+            #[cfg(feature = "unstable")]
+            deduplication: None,
+            #[cfg(feature = "unstable")]
+            on_gap: None,
+            #[cfg(feature = "unstable")]
+            ordering: None,
+            #[cfg(feature = "unstable")]
+            matching_listener: None,
+            #[cfg(feature = "unstable")]
+            on_drop: None,
+            #[cfg(feature = "unstable")]
+            dropped: None,
+            #[cfg(feature = "unstable")]
+            occupancy: None,
+            #[cfg(feature = "unstable")]
+            pull_period: None,
+            #[cfg(feature = "unstable")]
+            await_routed: None,
+            #[cfg(feature = "unstable")]
+            drop_timeout: None,
+            #[cfg(feature = "unstable")]
+            on_panic: PanicPolicy::default(),
+            #[cfg(feature = "unstable")]
+            also: Vec::new(),
             handler: DefaultHandler,
         }
     }
@@ -317,6 +446,10 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             key_expr: key_expr.try_into().map_err(Into::into),
             complete: false,
             origin: Locality::default(),
+            #[cfg(feature = "unstable")]
+            cache: None,
+            #[cfg(feature = "unstable")]
+            await_routed: None,
             handler: DefaultHandler,
         }
     }
@@ -334,6 +467,10 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
             destination: Locality::default(),
+            #[cfg(feature = "unstable")]
+            no_route: None,
+            #[cfg(feature = "unstable")]
+            announce: false,
         }
     }
     #[zenoh_macros::unstable]
@@ -387,6 +524,146 @@ where
     }
 }
 
+/// The identifier of a subscriber, queryable or publisher, unique within its [`Session`].
+///
+/// Identifiers are never reused during the lifetime of a session.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(pub(crate) Id);
+
+#[zenoh_macros::unstable]
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// The kind of an entity listed by [`Session::entities`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Subscriber,
+    Queryable,
+    Publisher,
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EntityKind::Subscriber => "subscriber",
+            EntityKind::Queryable => "queryable",
+            EntityKind::Publisher => "publisher",
+        })
+    }
+}
+
+/// An entity declared on a [`Session`], as listed by [`Session::entities`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityInfo {
+    /// The id of the entity, as returned by the `id` function of its handle.
+    pub id: EntityId,
+    /// The kind of the entity.
+    pub kind: EntityKind,
+    /// The key expression the entity was declared on.
+    pub key_expr: KeyExpr<'static>,
+}
+
+/// A subscriber declared on a [`Session`], as listed by [`Session::subscribers`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionInfo {
+    /// The id of the subscriber, as returned by [`Subscriber::id`](crate::subscriber::Subscriber::id).
+    pub id: EntityId,
+    /// The key expression the subscriber was declared on.
+    pub key_expr: KeyExpr<'static>,
+    /// The current [`SubMode`] of the subscriber.
+    pub mode: SubMode,
+    /// The current [`Reliability`] of the subscriber.
+    pub reliability: Reliability,
+    /// The [`Locality`] of the publications the subscriber receives.
+    pub origin: Locality,
+    /// The reception metrics of the subscriber, if it is not internal to the session.
+    pub metrics: Option<SubscriberMetrics>,
+}
+
+/// A publisher declared on a [`Session`], as listed by [`Session::publications`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicationInfo {
+    /// The id of the publisher, as returned by [`Publisher::id`](crate::publication::Publisher::id).
+    pub id: EntityId,
+    /// The key expression the publisher was declared on.
+    pub key_expr: KeyExpr<'static>,
+    /// The [`CongestionControl`] the publisher was declared with.
+    pub congestion_control: CongestionControl,
+    /// The [`Priority`] the publisher was declared with.
+    pub priority: Priority,
+    /// The [`Locality`] of the subscribers the publisher targets.
+    pub destination: Locality,
+}
+
+#[zenoh_macros::unstable]
+impl<'a> Undeclarable<&'a Session, EntityUndeclaration<'a>> for EntityId {
+    fn undeclare_inner(self, session: &'a Session) -> EntityUndeclaration<'a> {
+        EntityUndeclaration { session, id: self }
+    }
+}
+
+/// A [`Resolvable`](zenoh_core::Resolvable) returned when undeclaring an entity by its
+/// [`EntityId`] with [`Session::undeclare`].
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct EntityUndeclaration<'a> {
+    session: &'a Session,
+    id: EntityId,
+}
+
+#[zenoh_macros::unstable]
+impl zenoh_core::Resolvable for EntityUndeclaration<'_> {
+    type To = ZResult<()>;
+}
+
+#[zenoh_macros::unstable]
+impl SyncResolve for EntityUndeclaration<'_> {
+    fn res_sync(self) -> <Self as zenoh_core::Resolvable>::To {
+        let EntityId(id) = self.id;
+        let state = zread!(self.session.state);
+        if state.subscribers.contains_key(&id) {
+            drop(state);
+            self.session.unsubscribe(id)
+        } else if state.queryables.contains_key(&id) {
+            drop(state);
+            self.session.close_queryable(id)
+        } else if state.publishers.contains_key(&id) {
+            drop(state);
+            match self.session.undeclare_publisher_entity(id) {
+                Some(key_expr) => self
+                    .session
+                    .undeclare_publication_intent(key_expr)
+                    .res_sync(),
+                None => bail!("Unknown entity {}", self.id),
+            }
+        } else {
+            bail!("Unknown entity {}", self.id)
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl AsyncResolve for EntityUndeclaration<'_> {
+    type Future = std::future::Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
 /// A zenoh session.
 ///
 pub struct Session {
@@ -395,7 +672,29 @@ pub struct Session {
     pub(crate) id: u16,
     pub(crate) alive: bool,
     owns_runtime: bool,
-    task_controller: TaskController,
+    pub(crate) task_controller: TaskController,
+    pub(crate) allow_wildcard_writes: bool,
+    pub(crate) max_payload_size: Option<usize>,
+    pub(crate) oversized_payloads: Arc<AtomicU64>,
+    /// How long dropping a subscriber may wait for its undeclaration by default, if bounded.
+    #[cfg(feature = "unstable")]
+    pub(crate) subscribers_drop_timeout: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub(crate) events: Arc<crate::events::SessionEvents>,
+    #[cfg(feature = "unstable")]
+    pub(crate) entity_scope: Option<Arc<ScopeState>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) serialization: Arc<crate::serialization::SerializationRegistry>,
+    #[cfg(feature = "unstable")]
+    pub(crate) no_route: Arc<crate::no_route::NoRouteHandler>,
+    #[cfg(feature = "unstable")]
+    pub(crate) write_journal: Arc<crate::journal::WriteJournal>,
+    #[cfg(feature = "unstable")]
+    pub(crate) source_sequencer: Arc<crate::gap::SourceSequencer>,
+    #[cfg(feature = "unstable")]
+    pub(crate) scheduler: Arc<crate::scheduling::FairScheduler>,
+    #[cfg(feature = "testing")]
+    pub(crate) fault_injector: Arc<crate::testing::FaultInjector>,
 }
 
 static SESSION_ID_COUNTER: AtomicU16 = AtomicU16::new(0);
@@ -411,6 +710,20 @@ impl Session {
                 aggregated_subscribers,
                 aggregated_publishers,
             )));
+            let allow_wildcard_writes = runtime
+                .config()
+                .lock()
+                .allow_wildcard_writes()
+                .unwrap_or(false);
+            let max_payload_size = *runtime.config().lock().max_payload_size();
+            #[cfg(feature = "unstable")]
+            let subscribers_drop_timeout = runtime
+                .config()
+                .lock()
+                .subscribers_drop_timeout()
+                .map(Duration::from_millis);
+            #[cfg(feature = "testing")]
+            let fault_injector = Arc::new(crate::testing::FaultInjector::new(runtime.clone()));
             let session = Session {
                 runtime: runtime.clone(),
                 state: state.clone(),
@@ -418,6 +731,34 @@ impl Session {
                 alive: true,
                 owns_runtime: false,
                 task_controller: TaskController::default(),
+                allow_wildcard_writes,
+                max_payload_size,
+                oversized_payloads: Arc::new(AtomicU64::new(0)),
+                #[cfg(feature = "unstable")]
+                subscribers_drop_timeout,
+                #[cfg(feature = "unstable")]
+                events: Arc::new(crate::events::SessionEvents::default()),
+                #[cfg(feature = "unstable")]
+                entity_scope: None,
+                #[cfg(feature = "unstable")]
+                serialization: Arc::new(crate::serialization::SerializationRegistry::new(Some(
+                    crate::serialization::SerializationRegistry::global(),
+                ))),
+                #[cfg(feature = "unstable")]
+                no_route: Arc::new(crate::no_route::NoRouteHandler::new(runtime.clone())),
+                #[cfg(feature = "unstable")]
+                write_journal: Arc::new(crate::journal::WriteJournal::new(
+                    &runtime.config().lock().read_your_writes,
+                )),
+                #[cfg(feature = "unstable")]
+                source_sequencer: Arc::new(crate::gap::SourceSequencer::default()),
+                #[cfg(feature = "unstable")]
+                scheduler: Arc::new(crate::scheduling::FairScheduler::new(
+                    #[cfg(feature = "testing")]
+                    fault_injector.clone(),
+                )),
+                #[cfg(feature = "testing")]
+                fault_injector,
             };
 
             runtime.new_handler(Arc::new(admin::Handler::new(session.clone())));
@@ -502,6 +843,12 @@ impl Session {
         self.info().zid().res_sync()
     }
 
+    /// Returns the [`HLC`] timestamping the publications of this session, if any.
+    ///
+    /// Without timestamping (`timestamping: { enabled: false }` in the configuration, the default
+    /// for peers and clients), no HLC is instantiated and this returns `None`: the session then
+    /// never timestamps the samples it publishes or routes, and the timestamps of the samples
+    /// received from timestamping nodes are passed through untouched.
     pub fn hlc(&self) -> Option<&HLC> {
         self.runtime.hlc()
     }
@@ -525,6 +872,16 @@ impl Session {
         ResolveFuture::new(async move {
             trace!("close()");
             self.task_controller.terminate_all(Duration::from_secs(10));
+            // The remote nodes purge the declarations of the session when its transports are closed
+            let undeclare = !self.owns_runtime
+                || self
+                    .runtime
+                    .config()
+                    .lock()
+                    .undeclare_on_close()
+                    .unwrap_or(false);
+            #[allow(unused_variables)]
+            let undeclared = self.undeclare_all(undeclare);
             if self.owns_runtime {
                 self.runtime.close().await?;
             }
@@ -534,11 +891,119 @@ impl Session {
             state.queryables.clear();
             drop(state);
             primitives.as_ref().unwrap().send_close();
+            // The routing tables no longer hold the subscribers of the session
+            #[cfg(feature = "unstable")]
+            {
+                let listeners: Vec<Arc<MatchingListenerState>> = zread!(self.state)
+                    .matching_listeners
+                    .values()
+                    .filter(|msub| undeclared.iter().any(|k| k.intersects(&msub.key_expr)))
+                    .cloned()
+                    .collect();
+                for msub in listeners {
+                    self.notify_status_down(&msub);
+                }
+            }
             self.alive = false;
             Ok(())
         })
     }
 
+    /// Forget the remaining subscribers, queryables and liveliness tokens of the session,
+    /// sending their undeclarations if `send` is true.
+    ///
+    /// The undeclarations are de-duplicated, so that the declarations sharing a key expression
+    /// are undeclared once, but each key expression is still undeclared by its own message.
+    ///
+    /// Returns the key expressions of the forgotten subscribers.
+    fn undeclare_all(&self, send: bool) -> Vec<KeyExpr<'static>> {
+        let mut state = zwrite!(self.state);
+        let subscribers = std::mem::take(&mut state.subscribers);
+        state.subscriber_tree.clear();
+        let queryables = std::mem::take(&mut state.queryables);
+        #[cfg(feature = "unstable")]
+        let tokens = std::mem::take(&mut state.tokens);
+        let resources = &mut *state;
+        for res in resources
+            .local_resources
+            .values_mut()
+            .chain(resources.remote_resources.values_mut())
+            .filter_map(Resource::as_node_mut)
+        {
+            res.subscribers.clear();
+        }
+        let undeclared = subscribers
+            .values()
+            .map(|sub_state| sub_state.key_expr.clone())
+            .collect();
+        let primitives = match state.primitives.as_ref() {
+            Some(primitives) if send => primitives.clone(),
+            _ => return undeclared,
+        };
+
+        let mut undeclared_subscribers = HashSet::new();
+        for sub_state in subscribers.values() {
+            #[cfg(not(feature = "unstable"))]
+            let send_forget = sub_state.origin != Locality::SessionLocal;
+            #[cfg(feature = "unstable")]
+            let send_forget = sub_state.origin != Locality::SessionLocal
+                && !sub_state
+                    .key_expr
+                    .as_str()
+                    .starts_with(crate::liveliness::PREFIX_LIVELINESS);
+            if send_forget {
+                let wire_expr = match state
+                    .aggregated_subscribers
+                    .iter()
+                    .find(|s| s.includes(&sub_state.key_expr))
+                {
+                    Some(join_sub) => WireExpr::from(join_sub).to_owned(),
+                    None => sub_state.key_expr.to_wire(self).to_owned(),
+                };
+                undeclared_subscribers.insert(wire_expr);
+            }
+        }
+        #[cfg(feature = "unstable")]
+        for tok_state in tokens.values() {
+            undeclared_subscribers.insert(tok_state.key_expr.to_wire(self).to_owned());
+        }
+        let undeclared_queryables: HashSet<WireExpr<'static>> = queryables
+            .values()
+            .filter(|qable_state| qable_state.origin != Locality::SessionLocal)
+            .map(|qable_state| qable_state.key_expr.clone())
+            .collect();
+        drop(state);
+
+        trace!(
+            "undeclare_all({} subscribers, {} queryables)",
+            undeclared_subscribers.len(),
+            undeclared_queryables.len()
+        );
+        for wire_expr in undeclared_subscribers {
+            primitives.send_declare(Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::UndeclareSubscriber(UndeclareSubscriber {
+                    id: 0, // @TODO use proper SubscriberId (#703)
+                    ext_wire_expr: WireExprType { wire_expr },
+                }),
+            });
+        }
+        for wire_expr in undeclared_queryables {
+            primitives.send_declare(Declare {
+                ext_qos: declare::ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: declare::ext::NodeIdType::default(),
+                body: DeclareBody::UndeclareQueryable(UndeclareQueryable {
+                    id: 0, // @TODO use proper QueryableId (#703)
+                    ext_wire_expr: WireExprType { wire_expr },
+                }),
+            });
+        }
+        undeclared
+    }
+
     pub fn undeclare<'a, T, O>(&'a self, decl: T) -> O
     where
         O: Resolve<ZResult<()>>,
@@ -579,6 +1044,481 @@ impl Session {
     pub fn config(&self) -> &Notifier<Config> {
         self.runtime.config()
     }
+
+    /// Returns false if one of the internal tasks of the [`Session`](Session) failed and
+    /// couldn't be restarted, in which case the Session may no longer work as expected.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// assert!(session.is_healthy());
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn is_healthy(&self) -> bool {
+        self.runtime.is_healthy()
+    }
+
+    /// Create a [`SessionEventListener`](crate::events::SessionEventListener) that receives
+    /// the [`SessionEvent`](crate::events::SessionEvent)s of this Session.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let listener = session.event_listener().res().await.unwrap();
+    /// while let Ok(event) = listener.recv_async().await {
+    ///     println!("Session event: {:?}", event);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn event_listener(&self) -> SessionEventListenerBuilder<'_, DefaultHandler> {
+        SessionEventListenerBuilder {
+            session: SessionRef::Borrow(self),
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Declare a callback [`Subscriber`](crate::subscriber::Subscriber) through an object-safe
+    /// interface.
+    ///
+    /// This is meant for code that can't use the generic [`SubscriberBuilder`], such as plugins
+    /// registering subscriptions through a `dyn Trait`. The returned
+    /// [`DynSubscriberHandle`] is `Send + 'static`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let key_expr = KeyExpr::try_from("key/expression").unwrap();
+    /// let handle = session
+    ///     .subscribe_dyn(&key_expr, Box::new(|sample| println!("Received: {}", sample.value)))
+    ///     .unwrap();
+    /// handle.close().unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn subscribe_dyn(
+        &self,
+        key_expr: &KeyExpr<'_>,
+        callback: Box<dyn FnMut(Sample) + Send>,
+    ) -> ZResult<DynSubscriberHandle> {
+        let subscriber = Arc::new(self.clone())
+            .declare_subscriber(key_expr.clone().into_owned())
+            .callback(locked(callback))
+            .res_sync()?;
+        Ok(DynSubscriberHandle { subscriber })
+    }
+
+    /// Declare a callback [`Queryable`](crate::queryable::Queryable) through an object-safe
+    /// interface.
+    ///
+    /// This is the [`Queryable`](crate::queryable::Queryable) equivalent of
+    /// [`Session::subscribe_dyn`]. The returned [`DynQueryableHandle`] is `Send + 'static`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let key_expr = KeyExpr::try_from("key/expression").unwrap();
+    /// let handle = session
+    ///     .queryable_dyn(&key_expr, Box::new(|query| println!("Query: {}", query.selector())))
+    ///     .unwrap();
+    /// handle.close().unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn queryable_dyn(
+        &self,
+        key_expr: &KeyExpr<'_>,
+        callback: Box<dyn FnMut(Query) + Send>,
+    ) -> ZResult<DynQueryableHandle> {
+        let queryable = Arc::new(self.clone())
+            .declare_queryable(key_expr.clone().into_owned())
+            .callback(locked(callback))
+            .res_sync()?;
+        Ok(DynQueryableHandle { queryable })
+    }
+
+    /// Create an [`EntityScope`] through which entities can be declared and later undeclared
+    /// all together.
+    ///
+    /// Closing or dropping the scope undeclares every entity declared through it, including
+    /// the ones declared through its nested scopes, even if their handles were leaked.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let scope = session.scope();
+    /// let subscriber = scope.declare_subscriber("key/expression").res().await.unwrap();
+    /// std::mem::forget(subscriber);
+    /// // The leaked subscriber is undeclared along with the scope
+    /// scope.close().res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn scope(&self) -> EntityScope {
+        let state = Arc::new(ScopeState::new());
+        let parent = self.entity_scope.clone();
+        if let Some(parent) = &parent {
+            if !parent.register(ScopedEntity::Scope(state.clone())) {
+                // The parent scope is closed, so is the nested one
+                state.close(self);
+            }
+        }
+        let mut session = self.clone();
+        session.entity_scope = Some(state.clone());
+        EntityScope {
+            session: Arc::new(session),
+            state,
+            parent,
+        }
+    }
+
+    /// List the queries issued by this session with [`get`](Session::get) that are still
+    /// awaiting their final reply.
+    ///
+    /// The same information is available in the admin space under `@/session/<zid>/query/<qid>`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// for query in session.pending_queries() {
+    ///     println!("{} {} {:?} {}", query.qid, query.selector, query.age, query.replies);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn pending_queries(&self) -> Vec<PendingQuery> {
+        let state = zread!(self.state);
+        let mut queries: Vec<PendingQuery> = state
+            .queries
+            .iter()
+            .map(|(qid, query)| PendingQuery {
+                qid: *qid,
+                selector: query.selector.clone(),
+                age: query.started.elapsed(),
+                replies: query.nb_replies,
+            })
+            .collect();
+        queries.sort_by_key(|query| query.qid);
+        queries
+    }
+
+    /// List the subscribers, queryables and publishers currently declared on this session,
+    /// sorted by [`EntityId`].
+    ///
+    /// The same information is available in the admin space under `@/session/<zid>/entity/<id>`.
+    /// An entity may be undeclared by its id with [`undeclare`](Session::undeclare).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// assert!(session.entities().iter().any(|entity| entity.id == subscriber.id()));
+    /// session.undeclare(subscriber.id()).res().await.unwrap();
+    /// assert!(session.entities().iter().all(|entity| entity.id != subscriber.id()));
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn entities(&self) -> Vec<EntityInfo> {
+        let state = zread!(self.state);
+        let subscribers = state.subscribers.values().map(|sub| EntityInfo {
+            id: EntityId(sub.id),
+            kind: EntityKind::Subscriber,
+            key_expr: sub.key_expr.clone(),
+        });
+        let queryables = state.queryables.values().filter_map(|qable| {
+            let key_expr = state.local_wireexpr_to_expr(&qable.key_expr).ok()?;
+            Some(EntityInfo {
+                id: EntityId(qable.id),
+                kind: EntityKind::Queryable,
+                key_expr: key_expr.into_owned(),
+            })
+        });
+        let publishers = state.publishers.values().map(|publication| EntityInfo {
+            id: publication.id,
+            kind: EntityKind::Publisher,
+            key_expr: publication.key_expr.clone(),
+        });
+        let mut entities: Vec<EntityInfo> =
+            subscribers.chain(queryables).chain(publishers).collect();
+        entities.sort_by_key(|entity| entity.id);
+        entities
+    }
+
+    /// List the subscribers currently declared on this session, sorted by [`EntityId`].
+    ///
+    /// The list is a snapshot of the subscribers at the time of the call: subscribers declared
+    /// or undeclared afterwards aren't reflected in it. The same information is available in the
+    /// admin space under `@/session/<zid>/subscriber/<id>`, as a JSON value. This admin space is
+    /// only queryable by the other sessions when `adminspace.enabled` is set in the configuration.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .reliable()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let subscriptions = session.subscribers();
+    /// let subscription = subscriptions.iter().find(|sub| sub.id == subscriber.id()).unwrap();
+    /// assert_eq!(subscription.reliability, Reliability::Reliable);
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn subscribers(&self) -> Vec<SubscriptionInfo> {
+        // The subscribers declared by the session on its own admin space aren't listed
+        let admin_prefix = format!("@/session/{}/", self.zid());
+        let mut subscribers: Vec<SubscriptionInfo> = zread!(self.state)
+            .subscribers
+            .values()
+            .filter(|sub| !sub.key_expr.as_str().starts_with(&admin_prefix))
+            .map(|sub| SubscriptionInfo {
+                id: EntityId(sub.id),
+                key_expr: sub.key_expr.clone(),
+                mode: sub.mode(),
+                reliability: sub.info().reliability,
+                origin: sub.origin,
+                metrics: sub.metrics.get().map(MetricsSource::snapshot),
+            })
+            .collect();
+        subscribers.sort_by_key(|sub| sub.id);
+        subscribers
+    }
+
+    /// List the publishers currently declared on this session, sorted by [`EntityId`].
+    ///
+    /// The list is a snapshot of the publishers at the time of the call: publishers declared
+    /// or undeclared afterwards aren't reflected in it. The same information is available in the
+    /// admin space under `@/session/<zid>/publication/<id>`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// assert!(session.publications().iter().any(|publication| publication.id == publisher.id()));
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn publications(&self) -> Vec<PublicationInfo> {
+        let mut publications: Vec<PublicationInfo> =
+            zread!(self.state).publishers.values().cloned().collect();
+        publications.sort_by_key(|publication| publication.id);
+        publications
+    }
+
+    /// Wait until the declarations made so far by this session have reached the connected
+    /// zenoh sessions, or until `timeout` has passed.
+    ///
+    /// Resolves to the number of remote sessions that acknowledged. This is `0` when no other
+    /// session is reachable.
+    ///
+    /// The protocol has no acknowledgment for declarations: this sends a query to the
+    /// `@/session/<zid>/routed` queryable that every session declares, and which is answered
+    /// once the declarations sent before it have been processed. Routers without a session
+    /// don't answer, but forward the query to the sessions behind them.
+    ///
+    /// Routers thus never acknowledge anything themselves: a router with no session behind it
+    /// makes this resolve to `0`, and the count only covers the sessions that answered the
+    /// `@/session/*/routed` query, not the routes installed by the routers in between.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// let acknowledged = session.routed(Duration::from_secs(1)).res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn routed(&self, timeout: Duration) -> impl Resolve<ZResult<usize>> + '_ {
+        ResolveFuture::new(self.routed_async(timeout))
+    }
+
+    /// Issue the [`routed`](Session::routed) query, returning a future that doesn't borrow the
+    /// session so that it can be awaited while a declaration is being built.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn routed_async(
+        &self,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = ZResult<usize>> + Send + 'static {
+        let zid = self.zid();
+        let replies = self
+            .get(admin::ROUTED_SELECTOR)
+            .target(QueryTarget::All)
+            .consolidation(ConsolidationMode::None)
+            .allowed_destination(Locality::Remote)
+            .timeout(timeout)
+            .res_sync();
+        async move {
+            let replies = replies?;
+            let mut repliers = HashSet::new();
+            while let Ok(reply) = replies.recv_async().await {
+                if reply.sample.is_ok() && reply.replier_id != zid {
+                    repliers.insert(reply.replier_id);
+                }
+            }
+            Ok(repliers.len())
+        }
+    }
+
+    /// Cancel the pending query `qid`, as listed by [`pending_queries`](Session::pending_queries).
+    ///
+    /// The replies buffered for consolidation are delivered, followed by a final error [`Reply`]
+    /// whose value is [`QUERY_CANCELLED`], then the query's handler is dropped, closing its receiver.
+    /// The protocol has no cancellation message, so remote queryables aren't notified: the replies
+    /// they may still send for this query are dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let replies = session.get("key/expression").res().await.unwrap();
+    /// for query in session.pending_queries() {
+    ///     session.cancel_query(query.qid).res().await.unwrap();
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn cancel_query(&self, qid: RequestId) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            let mut state = zwrite!(self.state);
+            let query = state
+                .queries
+                .remove(&qid)
+                .ok_or_else(|| zerror!("Unknown query {}", qid))?;
+            drop(state);
+            tracing::debug!("Cancel query {}", qid);
+            if query.reception_mode == ConsolidationMode::Latest {
+                for (_, reply) in query.replies.unwrap().into_iter() {
+                    (query.callback)(reply);
+                }
+            }
+            (query.callback)(Reply {
+                sample: Err(QUERY_CANCELLED.into()),
+                replier_id: self.runtime.zid(),
+            });
+            Ok(())
+        })
+    }
+
+    /// Returns the [`SerializationRegistry`](crate::serialization::SerializationRegistry) of this session.
+    ///
+    /// Its codecs override the ones of the [global](crate::serialization::SerializationRegistry::global) registry.
+    #[zenoh_macros::unstable]
+    pub fn serialization(&self) -> &crate::serialization::SerializationRegistry {
+        &self.serialization
+    }
+
+    /// Estimate the number of copies of a publication on `key_expr`.
+    ///
+    /// The [`FanoutReport`] is a best-effort local view derived from the routing tables of this
+    /// session: it counts the matching subscribers of this session and the next hops a publication
+    /// would be sent to, but not the subscribers reached through them.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let fanout = session.fanout("key/expression").res().await.unwrap();
+    /// println!("{} estimated copies", fanout.estimated_copies);
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn fanout<'a, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> impl Resolve<ZResult<FanoutReport>> + 'a
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'a>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'a>>>::Error: Into<zenoh_result::Error>,
+    {
+        let key_expr: ZResult<KeyExpr<'a>> = key_expr.try_into().map_err(Into::into);
+        ResolveClosure::new(move || self.fanout_report(&key_expr?, Locality::Any))
+    }
+
+    /// Returns the [`SessionMetrics`](crate::metrics::SessionMetrics) of this session,
+    /// to be encoded in the OpenMetrics text format or served over zenoh.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let metrics: String = session.metrics().encode_prometheus();
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> crate::metrics::SessionMetrics<'_> {
+        crate::metrics::SessionMetrics { session: self }
+    }
+
+    /// Returns the [`FaultInjector`](crate::testing::FaultInjector) applied to the data messages
+    /// this session sends to the network.
+    #[cfg(feature = "testing")]
+    pub fn fault_injector(&self) -> &crate::testing::FaultInjector {
+        &self.fault_injector
+    }
+
+    /// Makes the internal task named `task` fail, as if it had panicked.
+    ///
+    /// This is a test hook to exercise the supervision of internal tasks, only available with
+    /// the `testing` feature. Failing the `accept` task terminates the accept loops of the
+    /// listeners of the Session.
+    #[doc(hidden)]
+    #[cfg(feature = "testing")]
+    pub fn inject_task_failure<'a>(&'a self, task: &'a str) -> impl Resolve<()> + 'a {
+        ResolveFuture::new(self.runtime.inject_task_failure(task))
+    }
 }
 
 impl<'a> SessionDeclarations<'a, 'a> for Session {
@@ -649,6 +1589,46 @@ impl Session {
         self._declare_keyexpr(key_expr)
     }
 
+    /// Declare `key_expr` like [`declare_keyexpr`](Session::declare_keyexpr), returning a
+    /// [`DeclaredKeyExpr`](crate::key_expr::DeclaredKeyExpr) which undeclares it when the last of
+    /// its clones is dropped.
+    ///
+    /// The subscribers, publishers and writes given the `DeclaredKeyExpr` send a numeric id
+    /// instead of the key expression in each message.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let key_expr = session
+    ///     .declare_keyexpr_guarded("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let publisher = session.declare_publisher(&key_expr).res().await.unwrap();
+    /// publisher.put("value").res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn declare_keyexpr_guarded<'a, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> impl Resolve<ZResult<crate::key_expr::DeclaredKeyExpr>> + 'a
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'a>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'a>>>::Error: Into<zenoh_result::Error>,
+    {
+        let key_expr: ZResult<KeyExpr> = key_expr.try_into().map_err(Into::into);
+        let declaration = self._declare_keyexpr(key_expr);
+        ResolveClosure::new(move || {
+            let key_expr = declaration.res_sync()?.into_owned();
+            Ok(crate::key_expr::DeclaredKeyExpr::new(self, key_expr))
+        })
+    }
+
     fn _declare_keyexpr<'a, 'b: 'a>(
         &'a self,
         key_expr: ZResult<KeyExpr<'b>>,
@@ -803,6 +1783,8 @@ impl Session {
             value: None,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            read_your_writes: false,
             handler: DefaultHandler,
         }
     }
@@ -817,13 +1799,34 @@ impl Session {
             alive: false,
             owns_runtime: self.owns_runtime,
             task_controller: self.task_controller.clone(),
+            allow_wildcard_writes: self.allow_wildcard_writes,
+            max_payload_size: self.max_payload_size,
+            oversized_payloads: self.oversized_payloads.clone(),
+            #[cfg(feature = "unstable")]
+            subscribers_drop_timeout: self.subscribers_drop_timeout,
+            #[cfg(feature = "unstable")]
+            events: self.events.clone(),
+            #[cfg(feature = "unstable")]
+            entity_scope: self.entity_scope.clone(),
+            #[cfg(feature = "unstable")]
+            serialization: self.serialization.clone(),
+            #[cfg(feature = "unstable")]
+            no_route: self.no_route.clone(),
+            #[cfg(feature = "unstable")]
+            write_journal: self.write_journal.clone(),
+            #[cfg(feature = "unstable")]
+            source_sequencer: self.source_sequencer.clone(),
+            #[cfg(feature = "unstable")]
+            scheduler: self.scheduler.clone(),
+            #[cfg(feature = "testing")]
+            fault_injector: self.fault_injector.clone(),
         }
     }
 
     #[allow(clippy::new_ret_no_self)]
     pub(super) fn new(config: Config) -> impl Resolve<ZResult<Session>> {
         ResolveFuture::new(async move {
-            tracing::debug!("Config: {:?}", &config);
+            tracing::debug!("Config: {}", &config);
             let aggregated_subscribers = config.aggregation().subscribers().clone();
             let aggregated_publishers = config.aggregation().publishers().clone();
             let mut runtime = RuntimeBuilder::new(config).build().await?;
@@ -862,11 +1865,7 @@ impl Session {
                         ..
                     }) = &mut res
                     {
-                        for sub in state.subscribers.values() {
-                            if key_expr.intersects(&sub.key_expr) {
-                                subscribers.push(sub.clone());
-                            }
-                        }
+                        subscribers.extend(state.subscriber_tree.matching(key_expr).cloned());
                     }
                     state.local_resources.insert(expr_id, res);
                     let primitives = state.primitives.as_ref().unwrap().clone();
@@ -939,6 +1938,8 @@ impl Session {
         _key_expr: KeyExpr<'a>,
     ) -> impl Resolve<ZResult<()>> + 'a {
         ResolveClosure::new(move || {
+            #[cfg(feature = "unstable")]
+            self.forget_scoped(&ScopedEntity::Publisher(_key_expr.clone().into_owned()));
             // let mut state = zwrite!(self.state);
             // if let Some(idx) = state.publications.iter().position(|p| **p == *key_expr) {
             //     trace!("undeclare_publication({:?})", key_expr);
@@ -970,6 +1971,68 @@ impl Session {
         })
     }
 
+    /// Register `entity` in the [`EntityScope`] this Session was obtained from, if any.
+    ///
+    /// The entity is undeclared if the scope is already closed.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn register_scoped(&self, entity: ScopedEntity) -> ZResult<()> {
+        match &self.entity_scope {
+            Some(scope) if !scope.register(entity.clone()) => {
+                let _ = entity.undeclare(self);
+                bail!("Unable to declare an entity in a closed scope")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn forget_scoped(&self, entity: &ScopedEntity) {
+        if let Some(scope) = &self.entity_scope {
+            scope.forget(entity);
+        }
+    }
+
+    /// Allocate the id of an undeclared publisher, e.g. the one of a [`put`](Session::put).
+    #[cfg(feature = "unstable")]
+    pub(crate) fn next_publisher_id(&self) -> Id {
+        zread!(self.state)
+            .decl_id_counter
+            .fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Allocate the id of a publisher declared on `key_expr`, listing it in
+    /// [`entities`](Session::entities) and [`publications`](Session::publications).
+    #[cfg(feature = "unstable")]
+    pub(crate) fn declare_publisher_entity(
+        &self,
+        key_expr: &KeyExpr,
+        congestion_control: CongestionControl,
+        priority: Priority,
+        destination: Locality,
+    ) -> Id {
+        let mut state = zwrite!(self.state);
+        let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst);
+        let publication = PublicationInfo {
+            id: EntityId(id),
+            key_expr: key_expr.clone().into_owned(),
+            congestion_control,
+            priority,
+            destination,
+        };
+        state.publishers.insert(id, publication);
+        id
+    }
+
+    /// Remove the publisher `id` from the [`entities`](Session::entities), returning its key
+    /// expression if it was listed.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn undeclare_publisher_entity(&self, id: Id) -> Option<KeyExpr<'static>> {
+        zwrite!(self.state)
+            .publishers
+            .remove(&id)
+            .map(|publication| publication.key_expr)
+    }
+
     pub(crate) fn declare_subscriber_inner(
         &self,
         key_expr: &KeyExpr,
@@ -977,6 +2040,7 @@ impl Session {
         origin: Locality,
         callback: Callback<'static, Sample>,
         info: &SubscriberInfo,
+        #[cfg(feature = "unstable")] payload_filter: Option<PayloadPrefixFilter>,
     ) -> ZResult<Arc<SubscriberState>> {
         let mut state = zwrite!(self.state);
         tracing::trace!("subscribe({:?})", key_expr);
@@ -986,12 +2050,21 @@ impl Session {
             None => key_expr.clone(),
         };
 
+        #[cfg(feature = "unstable")]
+        let (callback, pause) = crate::subscriber::SubscriberPause::wrap(callback);
         let sub_state = Arc::new(SubscriberState {
             id,
             key_expr: key_expr.clone().into_owned(),
             scope: scope.clone().map(|e| e.into_owned()),
             origin,
+            info: RwLock::new(*info),
             callback,
+            #[cfg(feature = "unstable")]
+            pause,
+            #[cfg(feature = "unstable")]
+            payload_filter,
+            #[cfg(feature = "unstable")]
+            metrics: Default::default(),
         });
 
         #[cfg(not(feature = "unstable"))]
@@ -1026,7 +2099,11 @@ impl Session {
             })
             .flatten();
 
+        // The subscriber is registered in all the routing tables used by `handle_data`
+        // under the same write lock, so that any local publication sequenced after this
+        // declaration is delivered to it.
         state.subscribers.insert(sub_state.id, sub_state.clone());
+        state.subscriber_tree.insert(sub_state.clone());
         for res in state
             .local_resources
             .values_mut()
@@ -1046,6 +2123,20 @@ impl Session {
             }
         }
 
+        // A subscriber sharing the subscription of others declares it again when payload filters
+        // are involved, with the union of their filters
+        #[cfg(feature = "unstable")]
+        let redeclare = declared_sub.is_none()
+            && origin != Locality::SessionLocal
+            && !key_expr
+                .as_str()
+                .starts_with(crate::liveliness::PREFIX_LIVELINESS)
+            && state.subscribers.values().any(|s| {
+                s.payload_filter.is_some()
+                    && s.origin != Locality::SessionLocal
+                    && s.key_expr.intersects(&key_expr)
+            });
+
         if let Some(key_expr) = declared_sub {
             let primitives = state.primitives.as_ref().unwrap().clone();
             drop(state);
@@ -1080,6 +2171,12 @@ impl Session {
                     id: id as u32,
                     wire_expr: key_expr.to_wire(self).to_owned(),
                     ext_info: *info,
+                    #[cfg(feature = "unstable")]
+                    ext_payload_filter: PayloadPrefixFilter::union([sub_state
+                        .payload_filter
+                        .as_ref()]),
+                    #[cfg(not(feature = "unstable"))]
+                    ext_payload_filter: None,
                 }),
             });
 
@@ -1088,29 +2185,120 @@ impl Session {
                 let state = zread!(self.state);
                 self.update_status_up(&state, &key_expr)
             }
+        } else {
+            drop(state);
+            #[cfg(feature = "unstable")]
+            if redeclare {
+                self.update_subscriber(sub_state.id, *info)?;
+            }
         }
 
+        #[cfg(feature = "unstable")]
+        self.register_scoped(ScopedEntity::Subscriber(sub_state.id))?;
+
         Ok(sub_state)
     }
 
+    /// Update the [`SubscriberInfo`] of the subscriber `sid`, declaring again the subscription
+    /// it shares with the subscribers on the same key expression.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn update_subscriber(&self, sid: Id, info: SubscriberInfo) -> ZResult<()> {
+        let state = zread!(self.state);
+        let sub_state = match state.subscribers.get(&sid) {
+            Some(sub_state) => sub_state.clone(),
+            None => bail!("Unknown subscriber {}", sid),
+        };
+        trace!("update_subscriber({:?}, {:?})", sub_state, info);
+        *zwrite!(sub_state.info) = info;
+        if sub_state.origin == Locality::SessionLocal
+            || sub_state
+                .key_expr
+                .as_str()
+                .starts_with(crate::liveliness::PREFIX_LIVELINESS)
+        {
+            return Ok(());
+        }
+
+        // The declaration is reliable if any of the subscribers sharing it is, and in push mode
+        // if any of them is. Its payload filter is the union of theirs.
+        let key_expr = &sub_state.key_expr;
+        let (key_expr, sharing): (KeyExpr, Vec<&Arc<SubscriberState>>) = match state
+            .aggregated_subscribers
+            .iter()
+            .find(|s| s.includes(key_expr))
+        {
+            Some(join_sub) => (
+                join_sub.clone().into(),
+                state
+                    .subscribers
+                    .values()
+                    .filter(|s| {
+                        s.origin != Locality::SessionLocal && join_sub.includes(&s.key_expr)
+                    })
+                    .collect(),
+            ),
+            None => (
+                key_expr.clone(),
+                state
+                    .subscribers
+                    .values()
+                    .filter(|s| s.origin != Locality::SessionLocal && s.key_expr == *key_expr)
+                    .collect(),
+            ),
+        };
+        let info = SubscriberInfo {
+            reliability: if sharing
+                .iter()
+                .any(|s| s.info().reliability == Reliability::Reliable)
+            {
+                Reliability::Reliable
+            } else {
+                Reliability::BestEffort
+            },
+            mode: if sharing.iter().any(|s| s.info().mode == declare::Mode::Push) {
+                declare::Mode::Push
+            } else {
+                declare::Mode::Pull
+            },
+        };
+        let payload_filter =
+            PayloadPrefixFilter::union(sharing.iter().map(|s| s.payload_filter.as_ref()));
+        let primitives = state.primitives.as_ref().unwrap().clone();
+        drop(state);
+        primitives.send_declare(Declare {
+            ext_qos: declare::ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: declare::ext::NodeIdType::default(),
+            body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                id: sid as u32,
+                wire_expr: key_expr.to_wire(self).to_owned(),
+                ext_info: info,
+                ext_payload_filter: payload_filter,
+            }),
+        });
+        Ok(())
+    }
+
+    /// Remove the subscriber `sid` from this session only, without undeclaring it to the
+    /// routers, which keep routing the matching samples to the session.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn forget_subscriber(&self, sid: Id) -> ZResult<()> {
+        self.forget_scoped(&ScopedEntity::Subscriber(sid));
+        match zwrite!(self.state).remove_subscriber(sid) {
+            Some(sub_state) => {
+                trace!("forget_subscriber({:?})", sub_state);
+                Ok(())
+            }
+            None => Err(zerror!("Unable to find subscriber").into()),
+        }
+    }
+
     pub(crate) fn unsubscribe(&self, sid: usize) -> ZResult<()> {
+        #[cfg(feature = "unstable")]
+        self.forget_scoped(&ScopedEntity::Subscriber(sid));
         let mut state = zwrite!(self.state);
-        if let Some(sub_state) = state.subscribers.remove(&sid) {
+        if let Some(sub_state) = state.remove_subscriber(sid) {
             trace!("unsubscribe({:?})", sub_state);
-            for res in state
-                .local_resources
-                .values_mut()
-                .filter_map(Resource::as_node_mut)
-            {
-                res.subscribers.retain(|sub| sub.id != sub_state.id);
-            }
-            for res in state
-                .remote_resources
-                .values_mut()
-                .filter_map(Resource::as_node_mut)
-            {
-                res.subscribers.retain(|sub| sub.id != sub_state.id);
-            }
 
             #[cfg(not(feature = "unstable"))]
             let send_forget = sub_state.origin != Locality::SessionLocal;
@@ -1258,6 +2446,9 @@ impl Session {
                 });
             }
         }
+        #[cfg(feature = "unstable")]
+        self.register_scoped(ScopedEntity::Queryable(qable_state.id))?;
+
         Ok(qable_state)
     }
 
@@ -1294,6 +2485,8 @@ impl Session {
     }
 
     pub(crate) fn close_queryable(&self, qid: usize) -> ZResult<()> {
+        #[cfg(feature = "unstable")]
+        self.forget_scoped(&ScopedEntity::Queryable(qid));
         let mut state = zwrite!(self.state);
         if let Some(qable_state) = state.queryables.remove(&qid) {
             trace!("close_queryable({:?})", qable_state);
@@ -1390,13 +2583,16 @@ impl Session {
                 id: id as u32,
                 wire_expr: key_expr.to_wire(self).to_owned(),
                 ext_info: SubscriberInfo::default(),
+                ext_payload_filter: None,
             }),
         });
+        self.register_scoped(ScopedEntity::LivelinessToken(tok_state.id))?;
         Ok(tok_state)
     }
 
     #[zenoh_macros::unstable]
     pub(crate) fn undeclare_liveliness(&self, tid: usize) -> ZResult<()> {
+        self.forget_scoped(&ScopedEntity::LivelinessToken(tid));
         let mut state = zwrite!(self.state);
         if let Some(tok_state) = state.tokens.remove(&tid) {
             trace!("undeclare_liveliness({:?})", tok_state);
@@ -1500,6 +2696,53 @@ impl Session {
         Ok(MatchingStatus { matching })
     }
 
+    #[zenoh_macros::unstable]
+    pub(crate) fn fanout_report(
+        &self,
+        key_expr: &KeyExpr,
+        destination: Locality,
+    ) -> ZResult<FanoutReport> {
+        use crate::net::routing::dispatcher::tables::RoutingExpr;
+        let router = self.runtime.router();
+        let tables = zread!(router.tables.tables);
+        let res = crate::net::routing::dispatcher::resource::Resource::get_resource(
+            &tables.root_res,
+            key_expr.as_str(),
+        );
+        let route = crate::net::routing::dispatcher::pubsub::get_local_data_route(
+            &tables,
+            &res,
+            &mut RoutingExpr::new(&tables.root_res, key_expr.as_str()),
+        );
+        drop(tables);
+
+        let state = zread!(self.state);
+        let remote_routes = match state.primitives.as_ref() {
+            Some(face) => route
+                .values()
+                .filter(|dir| !Arc::ptr_eq(&dir.0, &face.state))
+                .count(),
+            None => route.len(),
+        };
+        let local_subscribers = state
+            .subscribers
+            .values()
+            .filter(|sub| sub.origin != Locality::Remote && key_expr.intersects(&sub.key_expr))
+            .count();
+        drop(state);
+
+        let (local_subscribers, remote_routes) = match destination {
+            Locality::Any => (local_subscribers, remote_routes),
+            Locality::Remote => (0, remote_routes),
+            Locality::SessionLocal => (local_subscribers, 0),
+        };
+        Ok(FanoutReport {
+            local_subscribers,
+            remote_routes,
+            estimated_copies: local_subscribers + remote_routes,
+        })
+    }
+
     #[zenoh_macros::unstable]
     pub(crate) fn update_status_up(&self, state: &SessionState, key_expr: &KeyExpr) {
         for msub in state.matching_listeners.values() {
@@ -1548,34 +2791,35 @@ impl Session {
                     .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
                         let session = self.clone();
                         let msub = msub.clone();
-                        async move {
-                            match msub.current.lock() {
-                                Ok(mut current) => {
-                                    if *current {
-                                        if let Ok(status) = session
-                                            .matching_status(&msub.key_expr, msub.destination)
-                                        {
-                                            if !status.matching_subscribers() {
-                                                *current = false;
-                                                let callback = msub.callback.clone();
-                                                (callback)(status)
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    tracing::error!(
-                                        "Error trying to acquire MathginListener lock: {}",
-                                        e
-                                    );
-                                }
-                            }
-                        }
+                        async move { session.notify_status_down(&msub) }
                     });
             }
         }
     }
 
+    /// Notifies `msub` that it no longer matches any subscriber, if it was matching one until now.
+    ///
+    /// Must not be called while holding the session lock (`matching_status()` locks it).
+    #[zenoh_macros::unstable]
+    fn notify_status_down(&self, msub: &MatchingListenerState) {
+        match msub.current.lock() {
+            Ok(mut current) => {
+                if *current {
+                    if let Ok(status) = self.matching_status(&msub.key_expr, msub.destination) {
+                        if !status.matching_subscribers() {
+                            *current = false;
+                            let callback = msub.callback.clone();
+                            (callback)(status)
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("Error trying to acquire MathginListener lock: {}", e);
+            }
+        }
+    }
+
     #[zenoh_macros::unstable]
     pub(crate) fn undeclare_matches_listener_inner(&self, sid: usize) -> ZResult<()> {
         let mut state = zwrite!(self.state);
@@ -1587,6 +2831,30 @@ impl Session {
         }
     }
 
+    /// Returns false if a received payload of `size` bytes exceeds the `max_payload_size` of
+    /// the session, counting and reporting the dropped sample.
+    fn accept_payload(&self, key_expr: &WireExpr, size: usize) -> bool {
+        let Some(limit) = self.max_payload_size.filter(|limit| size > *limit) else {
+            return true;
+        };
+        self.oversized_payloads.fetch_add(1, Ordering::Relaxed);
+        let key_expr = zread!(self.state)
+            .remote_key_to_expr(key_expr)
+            .map_or_else(|_| key_expr.to_string(), |key_expr| key_expr.to_string());
+        warn!(
+            "Dropped a sample on {} with a payload of {} bytes, over the max_payload_size of {} bytes",
+            key_expr, size, limit
+        );
+        #[cfg(feature = "unstable")]
+        self.events
+            .emit(crate::events::SessionEvent::PayloadTooLarge {
+                key_expr,
+                size,
+                limit,
+            });
+        false
+    }
+
     pub(crate) fn handle_data(
         &self,
         local: bool,
@@ -1596,14 +2864,14 @@ impl Session {
         #[cfg(feature = "unstable")] attachment: Option<Attachment>,
     ) {
         let mut callbacks = SingleOrVec::default();
+        // Matching subscribers are collected under the state lock, which also sequences
+        // their declarations (see `declare_subscriber_inner`).
         let state = zread!(self.state);
         if key_expr.suffix.is_empty() {
             match state.get_res(&key_expr.scope, key_expr.mapping, local) {
                 Some(Resource::Node(res)) => {
                     for sub in &res.subscribers {
-                        if sub.origin == Locality::Any
-                            || (local == (sub.origin == Locality::SessionLocal))
-                        {
+                        if sub.origin.allows(local) {
                             match &sub.scope {
                                 Some(scope) => {
                                     if !res.key_expr.starts_with(&***scope) {
@@ -1650,11 +2918,8 @@ impl Session {
         } else {
             match state.wireexpr_to_keyexpr(key_expr, local) {
                 Ok(key_expr) => {
-                    for sub in state.subscribers.values() {
-                        if (sub.origin == Locality::Any
-                            || (local == (sub.origin == Locality::SessionLocal)))
-                            && key_expr.intersects(&sub.key_expr)
-                        {
+                    for sub in state.subscriber_tree.matching(&key_expr) {
+                        if sub.origin.allows(local) {
                             match &sub.scope {
                                 Some(scope) => {
                                     if !key_expr.starts_with(&***scope) {
@@ -1713,29 +2978,112 @@ impl Session {
         }
     }
 
-    pub(crate) fn pull<'a>(&'a self, key_expr: &'a KeyExpr) -> impl Resolve<ZResult<()>> + 'a {
+    /// Pull the samples kept by the routers for the subscription on `key_expr`, at most
+    /// `max_samples` of them if given.
+    pub(crate) fn pull<'a>(
+        &'a self,
+        key_expr: &'a KeyExpr,
+        max_samples: Option<NonZeroU32>,
+    ) -> impl Resolve<ZResult<()>> + 'a {
         ResolveClosure::new(move || {
-            trace!("pull({:?})", key_expr);
+            trace!("pull({:?}, {:?})", key_expr, max_samples);
             let state = zread!(self.state);
             let primitives = state.primitives.as_ref().unwrap().clone();
             drop(state);
-            primitives.send_request(Request {
-                id: 0, // @TODO compute a proper request ID
-                wire_expr: key_expr.to_wire(self).to_owned(),
-                ext_qos: ext::QoSType::request_default(),
-                ext_tstamp: None,
-                ext_nodeid: ext::NodeIdType::default(),
-                ext_target: request::ext::TargetType::default(),
-                ext_budget: None,
-                ext_timeout: None,
-                payload: RequestBody::Pull(Pull {
-                    ext_unknown: vec![],
-                }),
-            });
+            // @TODO compute a proper request ID
+            self.send_pull(&primitives, 0, key_expr, max_samples);
             Ok(())
         })
     }
 
+    /// Pull at most `max_samples` of the samples kept by the routers for the subscription on
+    /// `key_expr`, returning the number of samples they served, if known.
+    ///
+    /// The pulls are served by the routing tables of the session while they are sent: the
+    /// pulls bounded by a number of samples are answered with a final response reporting the
+    /// number of samples served, sent after them. The number is unknown, and not waited for,
+    /// when no such response was received once the pull is sent.
+    #[zenoh_macros::unstable]
+    pub(crate) fn pull_served(&self, key_expr: &KeyExpr, max_samples: NonZeroU32) -> Option<u32> {
+        trace!("pull_served({:?}, {:?})", key_expr, max_samples);
+        let (sender, mut served) = tokio::sync::oneshot::channel();
+        let mut state = zwrite!(self.state);
+        let rid = state.qid_counter.fetch_add(1, Ordering::SeqCst);
+        state.pulls.insert(rid, sender);
+        let primitives = state.primitives.as_ref().unwrap().clone();
+        drop(state);
+        self.send_pull(&primitives, rid, key_expr, Some(max_samples));
+        match served.try_recv() {
+            Ok(served) => served,
+            Err(_) => {
+                zwrite!(self.state).pulls.remove(&rid);
+                None
+            }
+        }
+    }
+
+    fn send_pull(
+        &self,
+        primitives: &Arc<Face>,
+        rid: RequestId,
+        key_expr: &KeyExpr,
+        max_samples: Option<NonZeroU32>,
+    ) {
+        primitives.send_request(Request {
+            id: rid,
+            wire_expr: key_expr.to_wire(self).to_owned(),
+            ext_qos: ext::QoSType::request_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            ext_target: request::ext::TargetType::default(),
+            ext_budget: max_samples,
+            ext_timeout: None,
+            payload: RequestBody::Pull(Pull {
+                ext_unknown: vec![],
+            }),
+        });
+    }
+
+    /// Spawn a task pulling the data of `key_exprs` at the period received from `period`,
+    /// until the returned token is cancelled, `period` is closed or the session is closed.
+    #[zenoh_macros::unstable]
+    pub(crate) fn pull_periodically(
+        &self,
+        key_exprs: Vec<KeyExpr<'static>>,
+        mut period: tokio::sync::watch::Receiver<PullPeriod>,
+    ) -> tokio_util::sync::CancellationToken {
+        let token = self.task_controller.get_cancellation_token();
+        self.task_controller
+            .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
+                let session = self.clone();
+                let token = token.clone();
+                async move {
+                    loop {
+                        let tick = period.borrow_and_update().as_duration();
+                        tokio::select! {
+                            _ = tokio::time::sleep(tick) => {
+                                if zread!(session.state).primitives.is_none() {
+                                    break;
+                                }
+                                for key_expr in &key_exprs {
+                                    if let Err(e) = session.pull(key_expr, None).res_sync() {
+                                        warn!("Periodic pull on {} failed: {}", key_expr, e);
+                                    }
+                                }
+                            }
+                            changed = period.changed() => {
+                                if changed.is_err() {
+                                    break;
+                                }
+                            }
+                            _ = token.cancelled() => break,
+                        }
+                    }
+                }
+            });
+        token
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn query(
         &self,
@@ -1750,6 +3098,14 @@ impl Session {
         callback: Callback<'static, Reply>,
     ) -> ZResult<()> {
         tracing::trace!("get({}, {:?}, {:?})", selector, target, consolidation);
+        #[cfg(feature = "unstable")]
+        if destination != Locality::SessionLocal {
+            let key_expr = match scope {
+                Some(scope) => scope / &*selector.key_expr,
+                None => selector.key_expr.clone(),
+            };
+            self.no_route.check(None, true, key_expr.as_str())?;
+        }
         let mut state = zwrite!(self.state);
         let consolidation = match consolidation.mode {
             Mode::Auto => {
@@ -1809,6 +3165,10 @@ impl Session {
             qid,
             QueryState {
                 nb_final,
+                #[cfg(feature = "unstable")]
+                started: std::time::Instant::now(),
+                #[cfg(feature = "unstable")]
+                nb_replies: 0,
                 selector: selector.clone().into_owned(),
                 scope: scope.clone().map(|e| e.into_owned()),
                 reception_mode: consolidation,
@@ -1825,11 +3185,11 @@ impl Session {
             let mut ext_attachment = None;
             #[cfg(feature = "unstable")]
             {
-                if let Some(attachment) = attachment.clone() {
+                if let Some(attachment) = attachment.clone().filter(|a| !a.is_empty()) {
                     ext_attachment = Some(attachment.into());
                 }
             }
-            primitives.send_request(Request {
+            let request = Request {
                 id: qid,
                 wire_expr: wexpr.clone(),
                 ext_qos: request::ext::QoSType::request_default(),
@@ -1851,7 +3211,17 @@ impl Session {
                     ext_attachment,
                     ext_unknown: vec![],
                 }),
-            });
+            };
+            #[cfg(feature = "unstable")]
+            if let Err(e) =
+                self.no_route
+                    .send_request(&primitives, selector.key_expr.as_str(), request)
+            {
+                zwrite!(self.state).queries.remove(&qid);
+                return Err(e);
+            }
+            #[cfg(not(feature = "unstable"))]
+            primitives.send_request(request);
         }
         if destination != Locality::Remote {
             self.handle_query(
@@ -1895,8 +3265,7 @@ impl Session {
                         .values()
                         .filter(
                             |queryable|
-                                (queryable.origin == Locality::Any
-                                    || (local == (queryable.origin == Locality::SessionLocal)))
+                                queryable.origin.allows(local)
                                 &&
                                 match state.local_wireexpr_to_expr(&queryable.key_expr) {
                                     Ok(qablname) => {
@@ -1945,6 +3314,7 @@ impl Session {
                 } else {
                     primitives
                 },
+                allow_wildcard_writes: self.allow_wildcard_writes,
                 #[cfg(feature = "unstable")]
                 attachment,
             }),
@@ -1994,6 +3364,36 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             reliability: Reliability::default(),
             mode: PushMode,
             origin: Locality::default(),
+            #[cfg(feature = "unstable")]
+            payload_filter: None,
+            #[cfg(feature = "unstable")]
+            transform: None,
+            #[cfg(feature = "unstable")]
+            filter: None,
+            #[cfg(feature = "unstable")]
+            deduplication: None,
+            #[cfg(feature = "unstable")]
+            on_gap: None,
+            #[cfg(feature = "unstable")]
+            ordering: None,
+            #[cfg(feature = "unstable")]
+            matching_listener: None,
+            #[cfg(feature = "unstable")]
+            on_drop: None,
+            #[cfg(feature = "unstable")]
+            dropped: None,
+            #[cfg(feature = "unstable")]
+            occupancy: None,
+            #[cfg(feature = "unstable")]
+            pull_period: None,
+            #[cfg(feature = "unstable")]
+            await_routed: None,
+            #[cfg(feature = "unstable")]
+            drop_timeout: None,
+            #[cfg(feature = "unstable")]
+            on_panic: PanicPolicy::default(),
+            #[cfg(feature = "unstable")]
+            also: Vec::new(),
             handler: DefaultHandler,
         }
     }
@@ -2039,6 +3439,10 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             key_expr: key_expr.try_into().map_err(Into::into),
             complete: false,
             origin: Locality::default(),
+            #[cfg(feature = "unstable")]
+            cache: None,
+            #[cfg(feature = "unstable")]
+            await_routed: None,
             handler: DefaultHandler,
         }
     }
@@ -2077,6 +3481,10 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
             destination: Locality::default(),
+            #[cfg(feature = "unstable")]
+            no_route: None,
+            #[cfg(feature = "unstable")]
+            announce: false,
         }
     }
 
@@ -2119,12 +3527,7 @@ impl Primitives for Session {
                 let state = &mut zwrite!(self.state);
                 match state.remote_key_to_expr(&m.wire_expr) {
                     Ok(key_expr) => {
-                        let mut subs = Vec::new();
-                        for sub in state.subscribers.values() {
-                            if key_expr.intersects(&sub.key_expr) {
-                                subs.push(sub.clone());
-                            }
-                        }
+                        let subs = state.subscriber_tree.matching(&key_expr).cloned().collect();
                         let res = Resource::Node(ResourceNode {
                             key_expr: key_expr.into(),
                             subscribers: subs,
@@ -2140,11 +3543,15 @@ impl Primitives for Session {
             }
             zenoh_protocol::network::DeclareBody::UndeclareKeyExpr(m) => {
                 trace!("recv UndeclareKeyExpr {}", m.id);
+                zwrite!(self.state).remote_resources.remove(&m.id);
             }
             zenoh_protocol::network::DeclareBody::DeclareSubscriber(m) => {
                 trace!("recv DeclareSubscriber {} {:?}", m.id, m.wire_expr);
                 #[cfg(feature = "unstable")]
                 {
+                    if let Some(primitives) = zread!(self.state).primitives.as_ref() {
+                        self.no_route.route_declared(primitives);
+                    }
                     let state = zread!(self.state);
                     match state.wireexpr_to_keyexpr(&m.wire_expr, false) {
                         Ok(expr) => {
@@ -2213,6 +3620,10 @@ impl Primitives for Session {
             }
             zenoh_protocol::network::DeclareBody::DeclareQueryable(m) => {
                 trace!("recv DeclareQueryable {} {:?}", m.id, m.wire_expr);
+                #[cfg(feature = "unstable")]
+                if let Some(primitives) = zread!(self.state).primitives.as_ref() {
+                    self.no_route.route_declared(primitives);
+                }
             }
             zenoh_protocol::network::DeclareBody::UndeclareQueryable(m) => {
                 trace!("recv UndeclareQueryable {:?}", m.id);
@@ -2229,6 +3640,9 @@ impl Primitives for Session {
         trace!("recv Push {:?}", msg);
         match msg.payload {
             PushBody::Put(m) => {
+                if !self.accept_payload(&msg.wire_expr, m.payload.len()) {
+                    return;
+                }
                 let info = DataInfo {
                     kind: SampleKind::Put,
                     encoding: Some(m.encoding),
@@ -2304,6 +3718,10 @@ impl Primitives for Session {
                 let mut state = zwrite!(self.state);
                 match state.queries.get_mut(&msg.rid) {
                     Some(query) => {
+                        #[cfg(feature = "unstable")]
+                        {
+                            query.nb_replies += 1;
+                        }
                         let callback = query.callback.clone();
                         std::mem::drop(state);
                         let value = match e.ext_body {
@@ -2384,8 +3802,12 @@ impl Primitives for Session {
                             }
                             None => key_expr,
                         };
+                        #[cfg(feature = "unstable")]
+                        {
+                            query.nb_replies += 1;
+                        }
                         let info = DataInfo {
-                            kind: SampleKind::Put,
+                            kind: m.ext_kind,
                             encoding: Some(m.encoding),
                             timestamp: m.timestamp,
                             qos: QoS::from(msg.ext_qos),
@@ -2498,6 +3920,11 @@ impl Primitives for Session {
     fn send_response_final(&self, msg: ResponseFinal) {
         trace!("recv ResponseFinal {:?}", msg);
         let mut state = zwrite!(self.state);
+        #[cfg(feature = "unstable")]
+        if let Some(served) = state.pulls.remove(&msg.rid) {
+            let _ = served.send(msg.ext_served);
+            return;
+        }
         match state.queries.get_mut(&msg.rid) {
             Some(query) => {
                 query.nb_final -= 1;
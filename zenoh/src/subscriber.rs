@@ -13,16 +13,66 @@
 //
 
 //! Subscribing primitives.
+#[zenoh_macros::unstable]
+use crate::gap::{GapDetector, GapInfo, OrderingBuffer};
 use crate::handlers::{locked, Callback, DefaultHandler};
+#[zenoh_macros::unstable]
+use crate::handlers::{
+    CacheHandler, CallbackAsync, ChannelDisconnected, ChannelOccupancy, Demux, DemuxHandler,
+    DispatchPolicy, MapHandler, OverflowPolicy, SampleCache, SampleChannel, SampleReceiver,
+    TypedHandler, DEFAULT_MAX_IN_FLIGHT,
+};
 use crate::prelude::Locality;
+#[zenoh_macros::unstable]
+use crate::prelude::SampleKind;
 use crate::prelude::{Id, IntoCallbackReceiverPair, KeyExpr, Sample};
+#[zenoh_macros::unstable]
+use crate::publication::MatchingStatus;
+#[zenoh_macros::unstable]
+use crate::time::Timestamp;
+#[zenoh_macros::unstable]
+use crate::transform::TransformChain;
 use crate::Undeclarable;
+#[zenoh_macros::unstable]
+use crate::{
+    query::ConsolidationMode,
+    selector::{Selector, TimeBound, TimeExpr, TimeRange},
+};
 use crate::{Result as ZResult, SessionRef};
+#[zenoh_macros::unstable]
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
 use std::fmt;
+#[zenoh_macros::unstable]
+use std::future::Future;
 use std::future::Ready;
+#[zenoh_macros::unstable]
+use std::marker::PhantomData;
+#[zenoh_macros::unstable]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
-use std::sync::Arc;
-use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
+#[zenoh_macros::unstable]
+use std::pin::Pin;
+#[zenoh_macros::unstable]
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+#[zenoh_macros::unstable]
+use std::task::{Context, Poll};
+use std::time::Duration;
+#[zenoh_macros::unstable]
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Mutex, OnceLock},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+#[zenoh_macros::unstable]
+use zenoh_buffers::{buffer::Buffer, ZBuf};
+#[zenoh_macros::unstable]
+use zenoh_core::{bail, zlock, zwrite, ResolveFuture};
+use zenoh_core::{zread, AsyncResolve, Resolvable, Resolve, ResolveClosure, SyncResolve};
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+#[zenoh_macros::unstable]
+use zenoh_protocol::network::declare::subscriber::ext::{PayloadFilterType, PayloadPrefix};
 use zenoh_protocol::network::declare::{subscriber::ext::SubscriberInfo, Mode};
 
 /// The subscription mode.
@@ -36,7 +86,29 @@ pub(crate) struct SubscriberState {
     pub(crate) key_expr: KeyExpr<'static>,
     pub(crate) scope: Option<KeyExpr<'static>>,
     pub(crate) origin: Locality,
+    pub(crate) info: RwLock<SubscriberInfo>,
     pub(crate) callback: Callback<'static, Sample>,
+    #[cfg(feature = "unstable")]
+    pub(crate) pause: Arc<SubscriberPause>,
+    /// The payload filter declared with the subscription, see [`SubscriberBuilder::payload_prefix`].
+    #[cfg(feature = "unstable")]
+    pub(crate) payload_filter: Option<PayloadPrefixFilter>,
+    /// The metrics of the subscriber, as reported by [`Session::subscribers`](crate::Session::subscribers).
+    #[cfg(feature = "unstable")]
+    pub(crate) metrics: OnceLock<MetricsSource>,
+}
+
+impl SubscriberState {
+    pub(crate) fn info(&self) -> SubscriberInfo {
+        *zread!(self.info)
+    }
+
+    pub(crate) fn mode(&self) -> SubMode {
+        match self.info().mode {
+            Mode::Push => SubMode::Push,
+            Mode::Pull => SubMode::Pull,
+        }
+    }
 }
 
 impl fmt::Debug for SubscriberState {
@@ -48,6 +120,329 @@ impl fmt::Debug for SubscriberState {
     }
 }
 
+/// What happens to the samples received by a paused subscriber.
+///
+/// See [`Subscriber::pause_with`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PausePolicy {
+    /// The samples are dropped.
+    #[default]
+    Drop,
+    /// Up to the given number of samples are buffered, and delivered in order on resume.
+    /// The samples received while the buffer is full are dropped.
+    Buffer(usize),
+}
+
+/// The paused state of a subscriber, checked before invoking its callback.
+#[zenoh_macros::unstable]
+pub(crate) struct SubscriberPause {
+    paused: AtomicBool,
+    state: Mutex<PauseState>,
+    callback: Callback<'static, Sample>,
+}
+
+#[zenoh_macros::unstable]
+#[derive(Default)]
+struct PauseState {
+    policy: Option<PausePolicy>,
+    buffer: VecDeque<Sample>,
+    paused_at: Option<SystemTime>,
+}
+
+#[zenoh_macros::unstable]
+impl SubscriberPause {
+    /// Wraps `callback` so that it isn't invoked while paused.
+    pub(crate) fn wrap(
+        callback: Callback<'static, Sample>,
+    ) -> (Callback<'static, Sample>, Arc<Self>) {
+        let pause = Arc::new(SubscriberPause {
+            paused: AtomicBool::new(false),
+            state: Mutex::new(PauseState::default()),
+            callback,
+        });
+        let callback = {
+            let pause = pause.clone();
+            Arc::new(move |sample| pause.deliver(sample))
+        };
+        (callback, pause)
+    }
+
+    fn deliver(&self, sample: Sample) {
+        if self.paused.load(Ordering::Acquire) {
+            let mut state = zlock!(self.state);
+            match state.policy {
+                Some(PausePolicy::Drop) => return,
+                Some(PausePolicy::Buffer(limit)) => {
+                    if state.buffer.len() < limit {
+                        state.buffer.push_back(sample);
+                    }
+                    return;
+                }
+                // Resumed in the meantime
+                None => {}
+            }
+        }
+        (self.callback)(sample)
+    }
+
+    fn pause(&self, policy: PausePolicy) {
+        let mut state = zlock!(self.state);
+        if state.policy.is_none() {
+            state.paused_at = Some(SystemTime::now());
+        }
+        state.policy = Some(policy);
+        if let PausePolicy::Buffer(limit) = policy {
+            state.buffer.truncate(limit);
+        }
+        self.paused.store(true, Ordering::Release);
+    }
+
+    fn resume(&self) {
+        // The samples received while flushing the buffer are buffered in turn,
+        // so that they are delivered after it
+        loop {
+            let buffer = {
+                let mut state = zlock!(self.state);
+                if state.buffer.is_empty() {
+                    state.policy = None;
+                    state.paused_at = None;
+                    self.paused.store(false, Ordering::Release);
+                    return;
+                }
+                std::mem::take(&mut state.buffer)
+            };
+            for sample in buffer {
+                (self.callback)(sample);
+            }
+        }
+    }
+
+    /// Buffers every sample received until resumed, whatever the policy, and returns when
+    /// this subscriber was paused, if it is.
+    fn hold(&self) -> Option<SystemTime> {
+        let mut state = zlock!(self.state);
+        let paused_at = state.paused_at?;
+        state.policy = Some(PausePolicy::Buffer(usize::MAX));
+        Some(paused_at)
+    }
+
+    /// Resumes after merging `replayed` into the buffer: the samples already buffered are
+    /// skipped, and the whole is delivered sorted by timestamp.
+    fn resume_with(&self, replayed: Vec<Sample>) {
+        {
+            let mut state = zlock!(self.state);
+            let mut seen: HashSet<(KeyExpr<'static>, uhlc::Timestamp)> = state
+                .buffer
+                .iter()
+                .filter_map(|sample| Some((sample.key_expr.clone(), sample.timestamp?)))
+                .collect();
+            let mut samples: Vec<Sample> = state.buffer.drain(..).collect();
+            samples.extend(replayed.into_iter().filter(|sample| {
+                sample.timestamp.map_or(true, |timestamp| {
+                    seen.insert((sample.key_expr.clone(), timestamp))
+                })
+            }));
+            samples.sort_by_key(|sample| sample.timestamp);
+            state.buffer = samples.into();
+        }
+        self.resume()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+}
+
+/// The user callback of a push mode subscriber, replaceable with [`Subscriber::set_callback`].
+#[zenoh_macros::unstable]
+pub(crate) struct CallbackSlot {
+    callback: RwLock<Callback<'static, Sample>>,
+    /// Whether the callback was ever replaced: until then, the samples are delivered
+    /// to the initial callback without taking the lock.
+    replaced: AtomicBool,
+}
+
+#[zenoh_macros::unstable]
+impl CallbackSlot {
+    /// Wraps `callback` so that it can be replaced.
+    pub(crate) fn wrap(
+        callback: Callback<'static, Sample>,
+    ) -> (Callback<'static, Sample>, Arc<Self>) {
+        let slot = Arc::new(CallbackSlot {
+            callback: RwLock::new(callback.clone()),
+            replaced: AtomicBool::new(false),
+        });
+        let callback = {
+            let slot = slot.clone();
+            // The lock isn't held while the callback runs, so that replacing it never waits
+            // for a delivery, and each sample is delivered entirely to either callback
+            Arc::new(move |sample| {
+                if slot.replaced.load(Ordering::Acquire) {
+                    let callback = zread!(slot.callback).clone();
+                    callback(sample)
+                } else {
+                    callback(sample)
+                }
+            })
+        };
+        (callback, slot)
+    }
+
+    fn set(&self, callback: Callback<'static, Sample>) {
+        *zwrite!(self.callback) = callback;
+        self.replaced.store(true, Ordering::Release);
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for CallbackSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackSlot").finish_non_exhaustive()
+    }
+}
+
+/// The subscribers of a session, indexed by key expression.
+///
+/// This is a trie of key expression chunks, whose verbatim chunks are hashed: finding the
+/// subscribers matching a key expression without wildcards costs in proportion to its depth,
+/// to the number of wild chunks along the way and to the number of matches, rather than to
+/// the total number of subscribers.
+#[derive(Default)]
+pub(crate) struct SubscriberTree {
+    root: SubscriberNode,
+}
+
+#[derive(Default)]
+struct SubscriberNode {
+    subscribers: Vec<Arc<SubscriberState>>,
+    verbatim: HashMap<Box<str>, SubscriberNode>,
+    wild: Vec<(OwnedKeyExpr, SubscriberNode)>,
+}
+
+impl SubscriberTree {
+    pub(crate) fn insert(&mut self, sub_state: Arc<SubscriberState>) {
+        let mut node = &mut self.root;
+        for chunk in sub_state.key_expr.as_str().split('/') {
+            node = if chunk.contains('*') {
+                match node.wild.iter().position(|(c, _)| c.as_str() == chunk) {
+                    Some(i) => &mut node.wild[i].1,
+                    None => {
+                        // SAFETY: the chunks of a key expression are key expressions.
+                        let chunk = unsafe { keyexpr::from_str_unchecked(chunk) };
+                        node.wild.push((chunk.into(), SubscriberNode::default()));
+                        &mut node.wild.last_mut().unwrap().1
+                    }
+                }
+            } else {
+                node.verbatim.entry(chunk.into()).or_default()
+            };
+        }
+        node.subscribers.push(sub_state);
+    }
+
+    pub(crate) fn remove(&mut self, sub_state: &SubscriberState) {
+        let chunks: Vec<&str> = sub_state.key_expr.as_str().split('/').collect();
+        self.root.remove(&chunks, sub_state.id);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.root = SubscriberNode::default();
+    }
+
+    /// Returns the subscribers whose key expression intersects with `key_expr`.
+    pub(crate) fn matching<'a>(
+        &'a self,
+        key_expr: &keyexpr,
+    ) -> impl Iterator<Item = &'a Arc<SubscriberState>> + 'a {
+        let mut nodes = vec![];
+        if key_expr.is_wild() {
+            self.root.intersecting(key_expr, &mut nodes);
+        } else {
+            let chunks: Vec<&str> = key_expr.as_str().split('/').collect();
+            self.root.including(&chunks, &mut nodes);
+            // A node may be reached through several paths when `**` is involved
+            nodes.sort_unstable_by_key(|node| *node as *const SubscriberNode);
+            nodes.dedup_by_key(|node| *node as *const SubscriberNode);
+        }
+        nodes.into_iter().flat_map(|node| node.subscribers.iter())
+    }
+}
+
+impl SubscriberNode {
+    fn is_empty(&self) -> bool {
+        self.subscribers.is_empty() && self.verbatim.is_empty() && self.wild.is_empty()
+    }
+
+    fn remove(&mut self, chunks: &[&str], id: Id) {
+        let Some((chunk, rest)) = chunks.split_first() else {
+            self.subscribers.retain(|sub| sub.id != id);
+            return;
+        };
+        if chunk.contains('*') {
+            if let Some(i) = self.wild.iter().position(|(c, _)| c.as_str() == *chunk) {
+                self.wild[i].1.remove(rest, id);
+                if self.wild[i].1.is_empty() {
+                    self.wild.swap_remove(i);
+                }
+            }
+        } else if let Some(node) = self.verbatim.get_mut(*chunk) {
+            node.remove(rest, id);
+            if node.is_empty() {
+                self.verbatim.remove(*chunk);
+            }
+        }
+    }
+
+    // Collect the nodes whose key expression includes the (non-wild) key expression made of `chunks`.
+    fn including<'a>(&'a self, chunks: &[&str], nodes: &mut Vec<&'a SubscriberNode>) {
+        let Some((chunk, rest)) = chunks.split_first() else {
+            nodes.push(self);
+            for (wild, node) in &self.wild {
+                if wild.as_str() == "**" {
+                    node.including(chunks, nodes);
+                }
+            }
+            return;
+        };
+        if let Some(node) = self.verbatim.get(*chunk) {
+            node.including(rest, nodes);
+        }
+        for (wild, node) in &self.wild {
+            if wild.as_str() == "**" {
+                // `**` matches any number of chunks, except the ones starting with `@`
+                for i in 0..=chunks.len() {
+                    node.including(&chunks[i..], nodes);
+                    if chunks.get(i).map_or(false, |c| c.starts_with('@')) {
+                        break;
+                    }
+                }
+            } else {
+                // SAFETY: the chunks of a key expression are key expressions.
+                let chunk = unsafe { keyexpr::from_str_unchecked(chunk) };
+                if wild.intersects(chunk) {
+                    node.including(rest, nodes);
+                }
+            }
+        }
+    }
+
+    // Collect the nodes whose key expression intersects with `key_expr`.
+    fn intersecting<'a>(&'a self, key_expr: &keyexpr, nodes: &mut Vec<&'a SubscriberNode>) {
+        if let Some(sub) = self.subscribers.first() {
+            if sub.key_expr.intersects(key_expr) {
+                nodes.push(self);
+            }
+        }
+        for node in self.verbatim.values() {
+            node.intersecting(key_expr, nodes);
+        }
+        for (_, node) in &self.wild {
+            node.intersecting(key_expr, nodes);
+        }
+    }
+}
+
 /// A subscriber that provides data through a callback.
 ///
 /// CallbackSubscribers can be created from a zenoh [`Session`](crate::Session)
@@ -77,6 +472,28 @@ pub(crate) struct SubscriberInner<'a> {
     pub(crate) session: SessionRef<'a>,
     pub(crate) state: Arc<SubscriberState>,
     pub(crate) alive: bool,
+    #[cfg(feature = "unstable")]
+    pub(crate) filtered: Option<Arc<AtomicUsize>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) dropped: Option<Arc<AtomicUsize>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) occupancy: Option<Arc<ChannelOccupancy>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) metrics: Arc<MetricsCounters>,
+    #[cfg(feature = "unstable")]
+    pub(crate) callback: Option<Arc<CallbackSlot>>,
+    /// The subscriptions to the key expressions added with [`SubscriberBuilder::also`].
+    #[cfg(feature = "unstable")]
+    pub(crate) also: Vec<Arc<SubscriberState>>,
+    /// The stream of the samples of a [`FlumeSubscriber`], once polled.
+    #[cfg(feature = "unstable")]
+    pub(crate) stream: SampleStream,
+    /// How long dropping this subscriber may wait for its undeclaration, if bounded.
+    #[cfg(feature = "unstable")]
+    pub(crate) drop_timeout: Option<Duration>,
+    /// The liveliness subscriber tracking the announced publishers matching this subscriber.
+    #[cfg(feature = "unstable")]
+    pub(crate) matching: Option<Box<SubscriberInner<'a>>>,
 }
 
 /// A [`PullMode`] subscriber that provides data through a callback.
@@ -110,6 +527,135 @@ pub(crate) struct SubscriberInner<'a> {
 /// ```
 pub(crate) struct PullSubscriberInner<'a> {
     inner: SubscriberInner<'a>,
+    #[cfg(feature = "unstable")]
+    periodic: Mutex<Option<PeriodicPull>>,
+    /// The period of the automatic pulls stopped while the subscriber is in push mode.
+    #[cfg(feature = "unstable")]
+    suspended_period: Mutex<Option<Duration>>,
+}
+
+/// The shortest period at which a [`PullSubscriber`] may pull its data automatically,
+/// the resolution of the timers.
+#[zenoh_macros::unstable]
+pub const MIN_PULL_PERIOD: Duration = Duration::from_millis(1);
+
+/// The period at which a [`PullSubscriber`] pulls its data automatically, at least
+/// [`MIN_PULL_PERIOD`].
+///
+/// It is displayed in human units, e.g. `1min 30s` or `250ms`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use zenoh::subscriber::PullPeriod;
+///
+/// let period = PullPeriod::try_new(Duration::from_millis(1500)).unwrap();
+/// assert_eq!(period.to_string(), "1s 500ms");
+/// assert!(PullPeriod::try_new(Duration::ZERO).is_err());
+/// assert_eq!(PullPeriod::saturating_from(Duration::ZERO).to_string(), "1ms");
+/// ```
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PullPeriod(Duration);
+
+#[zenoh_macros::unstable]
+impl PullPeriod {
+    /// Returns the [`PullPeriod`] of `period`, or an [`InvalidPullPeriod`] error if it's
+    /// shorter than [`MIN_PULL_PERIOD`].
+    pub fn try_new(period: Duration) -> Result<Self, InvalidPullPeriod> {
+        if period < MIN_PULL_PERIOD {
+            return Err(InvalidPullPeriod { period });
+        }
+        Ok(PullPeriod(period))
+    }
+
+    /// Returns the [`PullPeriod`] of `period`, or of [`MIN_PULL_PERIOD`] if it's shorter.
+    pub fn saturating_from(period: Duration) -> Self {
+        PullPeriod(period.max(MIN_PULL_PERIOD))
+    }
+
+    /// Returns the period as a [`Duration`].
+    #[inline]
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
+#[zenoh_macros::unstable]
+impl TryFrom<Duration> for PullPeriod {
+    type Error = InvalidPullPeriod;
+
+    fn try_from(period: Duration) -> Result<Self, Self::Error> {
+        PullPeriod::try_new(period)
+    }
+}
+
+#[zenoh_macros::unstable]
+impl From<PullPeriod> for Duration {
+    fn from(period: PullPeriod) -> Self {
+        period.0
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Display for PullPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let nanos = self.0.subsec_nanos() as u64;
+        let units = [
+            (secs / 86_400, "d"),
+            (secs / 3_600 % 24, "h"),
+            (secs / 60 % 60, "min"),
+            (secs % 60, "s"),
+            (nanos / 1_000_000, "ms"),
+            (nanos / 1_000 % 1_000, "µs"),
+            (nanos % 1_000, "ns"),
+        ];
+        let mut separator = "";
+        for (value, unit) in units {
+            if value != 0 {
+                write!(f, "{separator}{value}{unit}")?;
+                separator = " ";
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error of the pull periods shorter than [`MIN_PULL_PERIOD`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidPullPeriod {
+    /// The rejected period.
+    pub period: Duration,
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Display for InvalidPullPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid pull period: {:?} is shorter than {:?}",
+            self.period, MIN_PULL_PERIOD
+        )
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::error::Error for InvalidPullPeriod {}
+
+/// The task pulling the data of a [`PullSubscriber`] periodically, stopped when dropped.
+#[zenoh_macros::unstable]
+struct PeriodicPull {
+    period: tokio::sync::watch::Sender<PullPeriod>,
+    token: tokio_util::sync::CancellationToken,
+}
+
+#[zenoh_macros::unstable]
+impl Drop for PeriodicPull {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
 }
 
 impl<'a> PullSubscriberInner<'a> {
@@ -135,7 +681,92 @@ impl<'a> PullSubscriberInner<'a> {
     /// ```
     #[inline]
     pub fn pull(&self) -> impl Resolve<ZResult<()>> + '_ {
-        self.inner.session.pull(&self.inner.state.key_expr)
+        ResolveClosure::new(move || {
+            for state in self.inner.states() {
+                self.inner.session.pull(&state.key_expr, None).res_sync()?;
+            }
+            Ok(())
+        })
+    }
+
+    #[zenoh_macros::unstable]
+    fn pull_max(&self, max_samples: usize) -> impl Resolve<ZResult<usize>> + '_ {
+        ResolveClosure::new(move || {
+            // Bounds beyond the range of the pull messages are unbounded
+            let mut budget = u32::try_from(max_samples).unwrap_or(u32::MAX);
+            let mut delivered = 0;
+            // The key expressions share the budget: each one is pulled with what the previous
+            // ones left
+            for state in self.inner.states() {
+                let max_samples = match NonZeroU32::new(budget) {
+                    Some(max_samples) => max_samples,
+                    None => break,
+                };
+                match self.inner.session.pull_served(&state.key_expr, max_samples) {
+                    Some(served) => {
+                        budget = budget.saturating_sub(served);
+                        delivered += served as usize;
+                    }
+                    // The remaining budget is unknown
+                    None => break,
+                }
+            }
+            Ok(delivered)
+        })
+    }
+
+    #[zenoh_macros::unstable]
+    fn set_pull_period(&self, period: Option<Duration>) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            let period = period.map(PullPeriod::try_new).transpose()?;
+            let mut periodic = zlock!(self.periodic);
+            match period {
+                Some(period) => match periodic.as_ref() {
+                    Some(running) if !running.token.is_cancelled() => {
+                        running.period.send_replace(period);
+                    }
+                    _ => {
+                        let (sender, receiver) = tokio::sync::watch::channel(period);
+                        let key_exprs = self
+                            .inner
+                            .states()
+                            .map(|state| state.key_expr.clone())
+                            .collect();
+                        let token = self.inner.session.pull_periodically(key_exprs, receiver);
+                        *periodic = Some(PeriodicPull {
+                            period: sender,
+                            token,
+                        });
+                    }
+                },
+                None => *periodic = None,
+            }
+            Ok(())
+        })
+    }
+
+    /// Update the subscriptions of this subscriber, stopping the automatic pulls while it is in
+    /// push mode and restarting them when it's back in pull mode.
+    #[zenoh_macros::unstable]
+    fn update(&self, reliability: Reliability, mode: SubMode) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            self.inner.update(reliability, mode)?;
+            match mode {
+                SubMode::Push => {
+                    if let Some(running) = zlock!(self.periodic).take() {
+                        *zlock!(self.suspended_period) =
+                            Some(running.period.borrow().as_duration());
+                    }
+                }
+                SubMode::Pull => {
+                    let suspended = zlock!(self.suspended_period).take();
+                    if suspended.is_some() {
+                        self.set_pull_period(suspended).res_sync()?;
+                    }
+                }
+            }
+            Ok(())
+        })
     }
 
     /// Close a [`CallbackPullSubscriber`](CallbackPullSubscriber).
@@ -168,6 +799,272 @@ impl<'a> PullSubscriberInner<'a> {
 }
 
 impl<'a> SubscriberInner<'a> {
+    /// The subscriptions of this subscriber, starting with the one it was declared with.
+    fn states(&self) -> impl Iterator<Item = &Arc<SubscriberState>> {
+        #[cfg(feature = "unstable")]
+        let also = self.also.iter();
+        #[cfg(not(feature = "unstable"))]
+        let also = std::iter::empty();
+        std::iter::once(&self.state).chain(also)
+    }
+
+    /// Subscribe to the key expressions added with [`SubscriberBuilder::also`].
+    ///
+    /// On error, the subscriptions already declared are undeclared when this is dropped.
+    #[cfg(feature = "unstable")]
+    fn subscribe_also(
+        &mut self,
+        key_exprs: Vec<KeyExpr>,
+        origin: Locality,
+        callback: Callback<'static, Sample>,
+        info: &SubscriberInfo,
+        payload_filter: Option<PayloadPrefixFilter>,
+    ) -> ZResult<()> {
+        for key_expr in key_exprs {
+            let state = self.session.declare_subscriber_inner(
+                &key_expr,
+                &None,
+                origin,
+                callback.clone(),
+                info,
+                payload_filter.clone(),
+            )?;
+            self.also.push(state);
+        }
+        Ok(())
+    }
+
+    /// Track the announced publishers matching this subscriber, calling `callback` when its
+    /// matching status changes.
+    #[cfg(feature = "unstable")]
+    fn listen_matching(&mut self, callback: Callback<'static, MatchingStatus>) -> ZResult<()> {
+        let key_expr = format!(
+            "{}/*/*/{}",
+            crate::publication::PREFIX_PUBLICATION,
+            self.state.key_expr
+        );
+        let tracker = Arc::new(PublicationTracker {
+            publications: Mutex::new(HashSet::new()),
+            callback,
+        });
+        let liveliness = crate::liveliness::Liveliness {
+            session: self.session.clone(),
+        };
+        let listener = liveliness
+            .declare_subscriber(key_expr.clone())
+            .callback({
+                let tracker = tracker.clone();
+                move |sample| tracker.update(sample)
+            })
+            .res_sync()?;
+        // The publishers announced before the subscriber was declared are found by querying their tokens
+        liveliness
+            .get(key_expr)
+            .callback(move |reply| {
+                if let Ok(sample) = reply.sample {
+                    tracker.update(sample)
+                }
+            })
+            .res_sync()?;
+        self.matching = Some(Box::new(listener.subscriber));
+        Ok(())
+    }
+
+    /// Re-declare all the subscriptions of this subscriber with `reliability` and `mode`.
+    #[cfg(feature = "unstable")]
+    fn update(&self, reliability: Reliability, mode: SubMode) -> ZResult<()> {
+        let info = SubscriberInfo {
+            reliability,
+            mode: match mode {
+                SubMode::Push => Mode::Push,
+                SubMode::Pull => Mode::Pull,
+            },
+        };
+        for state in self.states() {
+            self.session.update_subscriber(state.id, info)?;
+        }
+        Ok(())
+    }
+
+    /// Undeclare all the subscriptions of this subscriber on the zenoh runtime, returning a future
+    /// waiting for at most `timeout` for it to complete.
+    #[cfg(feature = "unstable")]
+    fn unsubscribe_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> impl Future<Output = ZResult<()>> + Send + 'static {
+        self.alive = false;
+        let session = crate::Session::clone(&self.session);
+        let ids: Vec<Id> = self.states().map(|state| state.id).collect();
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        self.session
+            .task_controller
+            .spawn_with_rt(zenoh_runtime::ZRuntime::Net, async move {
+                let mut result = Ok(());
+                for id in ids {
+                    let unsubscribed = session.unsubscribe(id);
+                    if result.is_ok() {
+                        result = unsubscribed;
+                    }
+                }
+                let _ = sender.send(result);
+            });
+        let key_expr = self.state.key_expr.clone();
+        async move {
+            match tokio::time::timeout(timeout, receiver).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(_)) => bail!(
+                    "Undeclaring the subscriber on {} was aborted with its session",
+                    key_expr
+                ),
+                Err(_) => bail!(
+                    "Undeclaring the subscriber on {} timed out after {:?}",
+                    key_expr,
+                    timeout
+                ),
+            }
+        }
+    }
+
+    /// Remove all the subscriptions of this subscriber from its session, without undeclaring
+    /// them to the routers.
+    #[cfg(feature = "unstable")]
+    fn forget(&mut self) -> ZResult<()> {
+        self.alive = false;
+        let mut result = Ok(());
+        for state in self.states() {
+            let forgotten = self.session.forget_subscriber(state.id);
+            if result.is_ok() {
+                result = forgotten;
+            }
+        }
+        result
+    }
+
+    /// Undeclare all the subscriptions of this subscriber, returning the first error.
+    fn unsubscribe(&self) -> ZResult<()> {
+        let mut result = Ok(());
+        for state in self.states() {
+            let unsubscribed = self.session.unsubscribe(state.id);
+            if result.is_ok() {
+                result = unsubscribed;
+            }
+        }
+        result
+    }
+
+    #[cfg(feature = "unstable")]
+    fn filtered(&self) -> usize {
+        self.filtered
+            .as_ref()
+            .map_or(0, |filtered| filtered.load(Ordering::Relaxed))
+    }
+
+    #[cfg(feature = "unstable")]
+    fn dropped_samples(&self) -> usize {
+        self.dropped
+            .as_ref()
+            .map_or(0, |dropped| dropped.load(Ordering::Relaxed))
+    }
+
+    #[cfg(feature = "unstable")]
+    fn metrics_source(&self) -> MetricsSource {
+        MetricsSource {
+            counters: self.metrics.clone(),
+            dropped: self.dropped.clone(),
+            occupancy: self.occupancy.clone(),
+        }
+    }
+
+    #[cfg(feature = "unstable")]
+    fn metrics(&self) -> SubscriberMetrics {
+        self.metrics_source().snapshot()
+    }
+
+    /// Make the metrics of this subscriber available to [`Session::subscribers`](crate::Session::subscribers).
+    #[cfg(feature = "unstable")]
+    fn publish_metrics(&self) {
+        let _ = self.state.metrics.set(self.metrics_source());
+    }
+
+    #[cfg(feature = "unstable")]
+    fn pause(&self, policy: PausePolicy) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            for state in self.states() {
+                state.pause.pause(policy);
+            }
+            Ok(())
+        })
+    }
+
+    #[cfg(feature = "unstable")]
+    fn resume(&self) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            for state in self.states() {
+                state.pause.resume();
+            }
+            Ok(())
+        })
+    }
+
+    #[cfg(feature = "unstable")]
+    fn resume_with_replay(&self) -> impl Resolve<ZResult<()>> + '_ {
+        let session = &self.session;
+        let state = &self.state;
+        ResolveFuture::new(async move {
+            let Some(paused_at) = state.pause.hold() else {
+                return Ok(());
+            };
+            let mut selector = Selector::from(&state.key_expr);
+            selector.with_time_range(TimeRange(
+                TimeBound::Inclusive(TimeExpr::Fixed(paused_at)),
+                TimeBound::Unbounded,
+            ));
+            let replies = match session
+                .get(selector)
+                .consolidation(ConsolidationMode::None)
+                .allowed_destination(state.origin)
+                .res_async()
+                .await
+            {
+                Ok(replies) => replies,
+                Err(e) => {
+                    state.pause.resume();
+                    return Err(e);
+                }
+            };
+            let mut replayed = Vec::new();
+            while let Ok(reply) = replies.recv_async().await {
+                let Ok(mut sample) = reply.sample else {
+                    continue;
+                };
+                // The time range is only as precise as its RFC3339 form
+                if !sample
+                    .timestamp
+                    .is_some_and(|timestamp| timestamp.get_time().to_system_time() >= paused_at)
+                {
+                    continue;
+                }
+                if let Some(scope) = &state.scope {
+                    match sample
+                        .key_expr
+                        .as_str()
+                        .strip_prefix(scope.as_str())
+                        .and_then(|suffix| suffix.strip_prefix('/'))
+                        .map(KeyExpr::try_from)
+                    {
+                        Some(Ok(key_expr)) => sample.key_expr = key_expr.into_owned(),
+                        _ => continue,
+                    }
+                }
+                sample.historical = true;
+                replayed.push(sample);
+            }
+            state.pause.resume_with(replayed);
+            Ok(())
+        })
+    }
+
     /// Close a [`CallbackSubscriber`](CallbackSubscriber).
     ///
     /// `CallbackSubscribers` are automatically closed when dropped, but you may want to use this function to handle errors or
@@ -231,9 +1128,7 @@ impl Resolvable for SubscriberUndeclaration<'_> {
 impl SyncResolve for SubscriberUndeclaration<'_> {
     fn res_sync(mut self) -> <Self as Resolvable>::To {
         self.subscriber.alive = false;
-        self.subscriber
-            .session
-            .unsubscribe(self.subscriber.state.id)
+        self.subscriber.unsubscribe()
     }
 }
 
@@ -248,7 +1143,15 @@ impl AsyncResolve for SubscriberUndeclaration<'_> {
 impl Drop for SubscriberInner<'_> {
     fn drop(&mut self) {
         if self.alive {
-            let _ = self.session.unsubscribe(self.state.id);
+            #[cfg(feature = "unstable")]
+            if let Some(timeout) = self.drop_timeout {
+                let unsubscribed = self.unsubscribe_timeout(timeout);
+                if let Err(e) = zenoh_runtime::ZRuntime::Application.block_in_place(unsubscribed) {
+                    tracing::warn!("{}", e);
+                }
+                return;
+            }
+            let _ = self.unsubscribe();
         }
     }
 }
@@ -289,6 +1192,10 @@ impl From<PushMode> for Mode {
 
 /// A builder for initializing a [`FlumeSubscriber`].
 ///
+/// Once the builder is resolved, the subscriber is guaranteed to receive any publication
+/// made afterwards on the same [`Session`](crate::Session) on a matching key expression,
+/// whatever the subscribers concurrently declared or undeclared on this Session.
+///
 /// # Examples
 /// ```
 /// # #[tokio::main]
@@ -333,6 +1240,62 @@ pub struct SubscriberBuilder<'a, 'b, Mode, Handler> {
     #[cfg(not(feature = "unstable"))]
     pub(crate) origin: Locality,
 
+    #[cfg(feature = "unstable")]
+    pub payload_filter: Option<PayloadPrefixFilter>,
+
+    #[cfg(feature = "unstable")]
+    pub transform: Option<TransformChain>,
+
+    #[cfg(feature = "unstable")]
+    pub filter: Option<SampleFilter>,
+
+    #[cfg(feature = "unstable")]
+    pub deduplication: Option<Deduplication>,
+
+    /// The detection of the samples missed by the subscriber, if any.
+    #[cfg(feature = "unstable")]
+    pub on_gap: Option<GapDetector>,
+
+    /// The buffer delivering the samples in their publication order, if any.
+    #[cfg(feature = "unstable")]
+    pub ordering: Option<OrderingBuffer>,
+
+    /// The callback notified of the changes of the matching status of the subscriber, if any.
+    #[cfg(feature = "unstable")]
+    pub matching_listener: Option<MatchingHook>,
+
+    /// The hook called with the samples dropped by the handler, if any.
+    #[cfg(feature = "unstable")]
+    pub on_drop: Option<DropHook>,
+
+    /// The count of the samples dropped by the [`SampleChannel`] handler, if any.
+    #[cfg(feature = "unstable")]
+    pub dropped: Option<Arc<AtomicUsize>>,
+
+    /// The occupancy of the channel of the [`SampleChannel`] handler, if any.
+    #[cfg(feature = "unstable")]
+    pub occupancy: Option<Arc<ChannelOccupancy>>,
+
+    /// The period at which a pull-mode subscriber pulls its data, if any.
+    #[cfg(feature = "unstable")]
+    pub pull_period: Option<Duration>,
+
+    /// How long to wait for the declaration to reach the connected sessions, if at all.
+    #[cfg(feature = "unstable")]
+    pub await_routed: Option<Duration>,
+
+    /// How long dropping the subscriber may wait for its undeclaration, if bounded.
+    #[cfg(feature = "unstable")]
+    pub drop_timeout: Option<Duration>,
+
+    /// What the subscriber does when its callback panics.
+    #[cfg(feature = "unstable")]
+    pub on_panic: PanicPolicy,
+
+    /// The key expressions subscribed to in addition to `key_expr`.
+    #[cfg(feature = "unstable")]
+    pub also: Vec<ZResult<KeyExpr<'b>>>,
+
     #[cfg(feature = "unstable")]
     pub handler: Handler,
     #[cfg(not(feature = "unstable"))]
@@ -362,22 +1325,7 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
     where
         Callback: Fn(Sample) + Send + Sync + 'static,
     {
-        let SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode,
-            origin,
-            handler: _,
-        } = self;
-        SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode,
-            origin,
-            handler: callback,
-        }
+        self.map_parts(|mode| mode, |_| callback)
     }
 
     /// Receive the samples for this subscription with a mutable callback.
@@ -412,6 +1360,48 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
         self.callback(locked(callback))
     }
 
+    /// Receive the samples for this subscription with an async callback.
+    ///
+    /// The futures returned by the callback are driven by a dedicated task of the session,
+    /// at most [`max_in_flight`](SubscriberBuilder::max_in_flight) at a time (16 by default):
+    /// the delivery of samples is blocked while too many futures are pending, like with
+    /// [`OverflowPolicy::Block`]. A future that panics is logged and doesn't affect the others.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .callback_async(|sample| async move {
+    ///         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    ///         println!("Stored: {} {}", sample.key_expr, sample.value);
+    ///     })
+    ///     .max_in_flight(4)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn callback_async<F, Fut>(
+        self,
+        callback: F,
+    ) -> SubscriberBuilder<'a, 'b, Mode, CallbackAsync<F>>
+    where
+        F: Fn(Sample) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.with(CallbackAsync {
+            callback,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+        })
+    }
+
     /// Receive the samples for this subscription with a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
     ///
     /// # Examples
@@ -437,316 +1427,2705 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
     where
         Handler: crate::prelude::IntoCallbackReceiverPair<'static, Sample>,
     {
-        let SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode,
-            origin,
-            handler: _,
-        } = self;
-        SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode,
-            origin,
-            handler,
+        self.map_parts(|mode| mode, |_| handler)
+    }
+
+    /// Change the capacity of the channel receiving the samples for this subscription
+    /// (256 by default).
+    ///
+    /// Once the channel is full, new samples are handled according to the [`OverflowPolicy`]
+    /// (see [`on_full`](SubscriberBuilder::on_full)). A capacity of 0 is rejected when the subscriber
+    /// is declared: use [`unbounded`](SubscriberBuilder::unbounded) for a channel that is never full.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .channel_size(4096)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn channel_size(self, capacity: usize) -> SubscriberBuilder<'a, 'b, Mode, SampleChannel> {
+        self.sample_channel().channel_size(capacity)
+    }
+
+    /// Receive the samples for this subscription through an unbounded channel.
+    ///
+    /// The delivery of samples never blocks, at the cost of an unbounded memory usage
+    /// if the samples are not received as fast as they arrive.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn unbounded(self) -> SubscriberBuilder<'a, 'b, Mode, SampleChannel> {
+        self.sample_channel().unbounded()
+    }
+
+    /// Change how the samples for this subscription are handled when its channel is full
+    /// ([`OverflowPolicy::Block`] by default).
+    ///
+    /// The number of dropped samples is returned by [`Subscriber::dropped_samples`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::handlers::OverflowPolicy;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .channel_size(16)
+    ///     .on_full(OverflowPolicy::DropOldest)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn on_full(self, policy: OverflowPolicy) -> SubscriberBuilder<'a, 'b, Mode, SampleChannel> {
+        self.sample_channel().on_full(policy)
+    }
+
+    /// Apply backpressure to the publishers of this subscription when its channel fills up.
+    ///
+    /// Once the channel holds [`high_watermark`](SubscriberBuilder::high_watermark) samples,
+    /// the session stops reading the samples of its transports until the channel is drained
+    /// down to [`low_watermark`](SubscriberBuilder::low_watermark) samples. With a
+    /// [`Reliability::Reliable`] subscription, no sample is dropped: the reliable channels of
+    /// the transports fill up in turn, until the publishers using
+    /// [`CongestionControl::Block`](crate::publication::CongestionControl::Block) are blocked.
+    ///
+    /// The watermarks default to the capacity of the channel and to its half.
+    ///
+    /// This is head-of-line blocking: while the session stops reading, none of the samples
+    /// received on the same transports are delivered, including the samples of the other
+    /// subscribers of the session, and of the subscriptions sharing the same links.
+    ///
+    /// The current number of samples in the channel is reported by
+    /// [`SubscriberMetrics::queued`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .reliable()
+    ///     .flow_control()
+    ///     .high_watermark(64)
+    ///     .low_watermark(16)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn flow_control(self) -> SubscriberBuilder<'a, 'b, Mode, SampleChannel> {
+        self.sample_channel().flow_control()
+    }
+
+    #[zenoh_macros::unstable]
+    fn sample_channel(self) -> SubscriberBuilder<'a, 'b, Mode, SampleChannel> {
+        let channel = SampleChannel::default();
+        let dropped = channel.dropped.clone();
+        let occupancy = channel.occupancy.clone();
+        let mut builder = self.with(channel);
+        builder.dropped = Some(dropped);
+        builder.occupancy = Some(occupancy);
+        builder
+    }
+
+    /// Receive the samples for this subscription deserialized as `T`.
+    ///
+    /// The samples are deserialized according to their encoding, by the
+    /// [`SerializationRegistry`] of the [`Session`](crate::Session).
+    /// Samples that fail to be deserialized are received as errors, the subscriber keeps running.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .typed::<Vec<u32>>()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(received) = subscriber.recv_async().await {
+    ///     match received {
+    ///         Ok((key_expr, value)) => println!("Received: {} {:?}", key_expr, value),
+    ///         Err(e) => eprintln!("Invalid sample: {}", e),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn typed<T>(self) -> SubscriberBuilder<'a, 'b, Mode, TypedHandler<T>>
+    where
+        T: serde::de::DeserializeOwned + Send + 'static,
+    {
+        let handler = TypedHandler {
+            registry: self.session.serialization.clone(),
+            _type: PhantomData,
+        };
+        self.with(handler)
+    }
+
+    /// Keep the latest sample received on each key matching this subscription in a [`SampleCache`].
+    ///
+    /// Delete samples remove their key from the cache. The samples can still be forwarded to a
+    /// callback, given with `callback` after `with_cache`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("device/**/temp")
+    ///     .with_cache()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// // ...
+    /// for sample in subscriber.snapshot() {
+    ///     println!("{}: {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn with_cache(self) -> SubscriberBuilder<'a, 'b, Mode, CacheHandler> {
+        self.with(CacheHandler { callback: None })
+    }
+
+    /// Dispatch the samples for this subscription to callbacks according to their key.
+    ///
+    /// The callbacks are registered for key expressions with `route` after `demux`, and the samples
+    /// matching none of them are delivered to the `default` callback, if any. A single subscription
+    /// is declared, and the routes can be changed once the subscriber is declared through its [`Demux`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("vehicle/*/gps")
+    ///     .demux()
+    ///     .route("vehicle/1/gps", |sample| println!("Vehicle 1: {}", sample.value))
+    ///     .route("vehicle/2/gps", |sample| println!("Vehicle 2: {}", sample.value))
+    ///     .default(|sample| println!("Other vehicle: {}", sample.key_expr))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber
+    ///     .add_route("vehicle/3/gps", |sample| println!("Vehicle 3: {}", sample.value))
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn demux(self) -> SubscriberBuilder<'a, 'b, Mode, DemuxHandler> {
+        self.with(DemuxHandler {
+            demux: Demux::default(),
+        })
+    }
+
+    /// Map the samples for this subscription to values of another type before they are delivered.
+    ///
+    /// The values are delivered to the [`Handler`](crate::prelude::IntoCallbackReceiverPair) given
+    /// with `with` after `map`, or to a channel by default. The map runs on the reception thread,
+    /// within the subscriber's [`on_panic`](SubscriberBuilder::on_panic) policy: a sample whose
+    /// mapping panics is never delivered.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .map(|sample| sample.key_expr.to_string())
+    ///     .with(flume::bounded(32))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(key_expr) = subscriber.recv_async().await {
+    ///     println!("Received a sample on {}", key_expr);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn map<F, T>(self, map: F) -> SubscriberBuilder<'a, 'b, Mode, MapHandler<F, DefaultHandler>>
+    where
+        F: Fn(Sample) -> T + Send + Sync + 'static,
+        T: Send + 'static,
+    {
+        self.with(MapHandler {
+            map,
+            handler: DefaultHandler,
+        })
+    }
+}
+#[zenoh_macros::unstable]
+impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, SampleChannel> {
+    /// Change the capacity of the channel receiving the samples for this subscription.
+    ///
+    /// see [`channel_size`](SubscriberBuilder::channel_size)
+    #[inline]
+    pub fn channel_size(mut self, capacity: usize) -> Self {
+        if capacity == 0 {
+            self.key_expr = Err(zenoh_result::zerror!(
+                "Invalid subscriber channel size 0: the size must be at least 1"
+            )
+            .into());
+        }
+        self.handler.capacity = Some(capacity);
+        self
+    }
+
+    /// Receive the samples for this subscription through an unbounded channel.
+    ///
+    /// see [`unbounded`](SubscriberBuilder::unbounded)
+    #[inline]
+    pub fn unbounded(mut self) -> Self {
+        self.handler.capacity = None;
+        self
+    }
+
+    /// Change how the samples for this subscription are handled when its channel is full.
+    ///
+    /// see [`on_full`](SubscriberBuilder::on_full)
+    #[inline]
+    pub fn on_full(mut self, policy: OverflowPolicy) -> Self {
+        self.handler.on_full = policy;
+        self
+    }
+
+    /// Apply backpressure to the publishers of this subscription when its channel fills up.
+    ///
+    /// see [`flow_control`](SubscriberBuilder::flow_control)
+    #[inline]
+    pub fn flow_control(mut self) -> Self {
+        self.handler.flow_control = true;
+        self
+    }
+
+    /// Change the number of queued samples from which the session stops reading its transports.
+    ///
+    /// Implies [`flow_control`](SubscriberBuilder::flow_control).
+    #[inline]
+    pub fn high_watermark(mut self, high: usize) -> Self {
+        if high == 0 {
+            self.key_expr = Err(zenoh_result::zerror!(
+                "Invalid subscriber high watermark 0: the watermark must be at least 1"
+            )
+            .into());
+        }
+        self.handler.flow_control = true;
+        self.handler.high_watermark = Some(high);
+        self.check_watermarks()
+    }
+
+    /// Change the number of queued samples from which the session resumes reading its transports.
+    ///
+    /// Implies [`flow_control`](SubscriberBuilder::flow_control).
+    #[inline]
+    pub fn low_watermark(mut self, low: usize) -> Self {
+        self.handler.flow_control = true;
+        self.handler.low_watermark = Some(low);
+        self.check_watermarks()
+    }
+
+    fn check_watermarks(mut self) -> Self {
+        let (high, low) = self.handler.watermarks();
+        if low >= high && self.key_expr.is_ok() {
+            self.key_expr = Err(zenoh_result::zerror!(
+                "Invalid subscriber watermarks: the low watermark ({}) must be lower than the high watermark ({})",
+                low,
+                high
+            )
+            .into());
         }
+        self
     }
 }
-impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
-    /// Change the subscription reliability.
+
+#[zenoh_macros::unstable]
+impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DemuxHandler> {
+    /// Deliver the samples matching `key_expr` to `callback`.
+    ///
+    /// see [`Demux::add_route`]
     #[inline]
-    pub fn reliability(mut self, reliability: Reliability) -> Self {
-        self.reliability = reliability;
+    pub fn route<TryIntoKeyExpr, Callback>(
+        mut self,
+        key_expr: TryIntoKeyExpr,
+        callback: Callback,
+    ) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        if let Err(e) = self.handler.demux.add_route(key_expr, callback) {
+            self.key_expr = Err(e);
+        }
         self
     }
 
-    /// Change the subscription reliability to `Reliable`.
+    /// Deliver the samples matching none of the routes to `callback`.
+    ///
+    /// see [`Demux::set_default`]
     #[inline]
-    pub fn reliable(mut self) -> Self {
-        self.reliability = Reliability::Reliable;
+    pub fn default<Callback>(self, callback: Callback) -> Self
+    where
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.handler.demux.set_default(callback);
         self
     }
 
-    /// Change the subscription reliability to `BestEffort`.
+    /// Change whether the samples are delivered to the first matching route or to all of them
+    /// ([`DispatchPolicy::First`] by default).
     #[inline]
-    pub fn best_effort(mut self) -> Self {
-        self.reliability = Reliability::BestEffort;
+    pub fn dispatch(self, policy: DispatchPolicy) -> Self {
+        self.handler.demux.set_policy(policy);
         self
     }
+}
 
-    /// Restrict the matching publications that will be receive by this [`Subscriber`]
-    /// to the ones that have the given [`Locality`](crate::prelude::Locality).
-    #[zenoh_macros::unstable]
+#[zenoh_macros::unstable]
+impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, CacheHandler> {
+    /// Forward the samples to `callback` once the cache is updated.
     #[inline]
-    pub fn allowed_origin(mut self, origin: Locality) -> Self {
-        self.origin = origin;
+    pub fn callback<Callback>(mut self, callback: Callback) -> Self
+    where
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.handler.callback = Some(Arc::new(callback));
         self
     }
+}
 
-    /// Change the subscription mode to Pull.
+#[zenoh_macros::unstable]
+impl<'a, 'b, Mode, F> SubscriberBuilder<'a, 'b, Mode, MapHandler<F, DefaultHandler>> {
+    /// Receive the mapped values for this subscription with a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
+    ///
+    /// see [`map`](SubscriberBuilder::map)
     #[inline]
-    pub fn pull_mode(self) -> SubscriberBuilder<'a, 'b, PullMode, Handler> {
-        let SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode: _,
-            origin,
-            handler,
-        } = self;
-        SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode: PullMode,
-            origin,
-            handler,
+    pub fn with<Handler, T>(
+        self,
+        handler: Handler,
+    ) -> SubscriberBuilder<'a, 'b, Mode, MapHandler<F, Handler>>
+    where
+        F: Fn(Sample) -> T + Send + Sync + 'static,
+        Handler: IntoCallbackReceiverPair<'static, T>,
+    {
+        self.map_parts(
+            |mode| mode,
+            |MapHandler { map, .. }| MapHandler { map, handler },
+        )
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a, 'b, Mode, F> SubscriberBuilder<'a, 'b, Mode, CallbackAsync<F>> {
+    /// Limit the number of futures of the [`callback_async`](SubscriberBuilder::callback_async)
+    /// function that are pending at the same time (at least 1).
+    ///
+    /// With a limit of 1, the samples are processed one after the other, in their order of reception.
+    #[inline]
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.handler.max_in_flight = max_in_flight.max(1);
+        self
+    }
+}
+
+impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
+    /// Change the mode and the handler of this builder, keeping all its other settings.
+    fn map_parts<NewMode, NewHandler>(
+        self,
+        mode: impl FnOnce(Mode) -> NewMode,
+        handler: impl FnOnce(Handler) -> NewHandler,
+    ) -> SubscriberBuilder<'a, 'b, NewMode, NewHandler> {
+        SubscriberBuilder {
+            session: self.session,
+            key_expr: self.key_expr,
+            reliability: self.reliability,
+            mode: mode(self.mode),
+            origin: self.origin,
+            #[cfg(feature = "unstable")]
+            payload_filter: self.payload_filter,
+            #[cfg(feature = "unstable")]
+            transform: self.transform,
+            #[cfg(feature = "unstable")]
+            filter: self.filter,
+            #[cfg(feature = "unstable")]
+            deduplication: self.deduplication,
+            #[cfg(feature = "unstable")]
+            on_gap: self.on_gap,
+            #[cfg(feature = "unstable")]
+            ordering: self.ordering,
+            #[cfg(feature = "unstable")]
+            matching_listener: self.matching_listener,
+            #[cfg(feature = "unstable")]
+            on_drop: self.on_drop,
+            #[cfg(feature = "unstable")]
+            dropped: self.dropped,
+            #[cfg(feature = "unstable")]
+            occupancy: self.occupancy,
+            #[cfg(feature = "unstable")]
+            pull_period: self.pull_period,
+            #[cfg(feature = "unstable")]
+            await_routed: self.await_routed,
+            #[cfg(feature = "unstable")]
+            drop_timeout: self.drop_timeout,
+            #[cfg(feature = "unstable")]
+            on_panic: self.on_panic,
+            #[cfg(feature = "unstable")]
+            also: self.also,
+            handler: handler(self.handler),
+        }
+    }
+
+    /// Change the subscription reliability.
+    #[inline]
+    pub fn reliability(mut self, reliability: Reliability) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    /// Change the subscription reliability to `Reliable`.
+    #[inline]
+    pub fn reliable(mut self) -> Self {
+        self.reliability = Reliability::Reliable;
+        self
+    }
+
+    /// Change the subscription reliability to `BestEffort`.
+    #[inline]
+    pub fn best_effort(mut self) -> Self {
+        self.reliability = Reliability::BestEffort;
+        self
+    }
+
+    /// Restrict the matching publications that will be receive by this [`Subscriber`]
+    /// to the ones that have the given [`Locality`](crate::prelude::Locality).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn allowed_origin(mut self, origin: Locality) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Restrict the matching publications that will be receive by this [`Subscriber`]
+    /// to the ones of its own [`Session`](crate::Session).
+    ///
+    /// This is a shorthand for
+    /// [`allowed_origin(Locality::SessionLocal)`](SubscriberBuilder::allowed_origin).
+    /// Use [`Locality::Remote`](crate::prelude::Locality::Remote) instead to ignore them.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn local(self) -> Self {
+        self.allowed_origin(Locality::SessionLocal)
+    }
+
+    /// Wait, when declaring this [`Subscriber`], for its declaration to reach the connected
+    /// zenoh sessions, or for at most `timeout`.
+    ///
+    /// See [`Session::routed`](crate::Session::routed). The declaration resolves at once
+    /// when no other session is reachable.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .await_routed(Duration::from_secs(1))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn await_routed(mut self, timeout: Duration) -> Self {
+        self.await_routed = Some(timeout);
+        self
+    }
+
+    /// Wait for at most `timeout` for the undeclaration of the [`Subscriber`] when it's dropped.
+    ///
+    /// Past `timeout`, the subscriber is dropped anyway and its undeclaration completes in the
+    /// background, see [`Subscriber::close_timeout`]. Overrides the `subscribers_drop_timeout`
+    /// of the session configuration.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .drop_timeout(Duration::from_millis(100))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn drop_timeout(mut self, timeout: Duration) -> Self {
+        self.drop_timeout = Some(timeout);
+        self
+    }
+
+    /// Choose what the [`Subscriber`] does when its callback panics, [`PanicPolicy::Continue`]
+    /// by default.
+    ///
+    /// The panics are caught on each delivery, filters and transformers included, so that they
+    /// never unwind into the session. They are logged with the key of the sample and counted in
+    /// the [`SubscriberMetrics`] of the subscriber.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::subscriber::PanicPolicy;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .callback(|sample| {
+    ///         let value: i64 = sample.value.try_into().unwrap();
+    ///         println!("Received: {}", value);
+    ///     })
+    ///     .on_panic(PanicPolicy::CloseSubscriber)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn on_panic(mut self, policy: PanicPolicy) -> Self {
+        self.on_panic = policy;
+        self
+    }
+
+    /// Also subscribe to `key_expr` with this [`Subscriber`].
+    ///
+    /// The samples matching any of the key expressions are delivered to the same callback or
+    /// handler, and undeclaring the subscriber undeclares all of them. A sample matching
+    /// several of the key expressions is delivered once per key expression. In pull mode,
+    /// [`pull`](PullSubscriber::pull) pulls on all of them.
+    ///
+    /// Each key expression is listed as an entity of its own by
+    /// [`Session::entities`](crate::Session::entities).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("robot/*/pose")
+    ///     .also("robot/*/status")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn also<TryIntoKeyExpr>(mut self, key_expr: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.also.push(key_expr.try_into().map_err(Into::into));
+        self
+    }
+
+    /// Only deliver to this [`Subscriber`] the samples whose payload contains `prefix` at `offset`.
+    ///
+    /// This is an experimental feature: the filter is declared with the subscription, and the
+    /// peers supporting it don't send the samples it rejects to the session of the subscriber.
+    /// The subscriptions on the same key expression of a session are declared with the union of
+    /// their filters. The peers that don't support it send all the samples, the filter is then
+    /// evaluated by the session of the subscriber before calling its callback, the rejected
+    /// samples being counted by [`filtered`](Subscriber::filtered).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// // Only receive the messages of type 0x01
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .payload_prefix(0, [0x01])
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn payload_prefix<IntoBytes>(mut self, offset: usize, prefix: IntoBytes) -> Self
+    where
+        IntoBytes: Into<Vec<u8>>,
+    {
+        self.payload_filter = Some(PayloadPrefixFilter::new(offset, prefix));
+        self
+    }
+
+    /// Apply a [`TransformChain`] to the samples received by this [`Subscriber`],
+    /// before they are delivered to its handler.
+    ///
+    /// Samples failing a transformer of the chain are dropped and reported to the chain's
+    /// error callback, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::transform::{NoopTransformer, TransformChain};
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .transform(TransformChain::new().then(NoopTransformer))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn transform(mut self, chain: TransformChain) -> Self {
+        self.transform = Some(chain);
+        self
+    }
+
+    /// Only deliver to this [`Subscriber`] the samples for which `predicate` returns true.
+    ///
+    /// The predicate is evaluated on the reception path, after the [transformers](SubscriberBuilder::transform),
+    /// so rejected samples never reach the callback nor take room in the channel of the subscriber.
+    /// The number of rejected samples is returned by [`Subscriber::filtered`].
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("robot/**")
+    ///     .filter(|sample| sample.value.payload.len() > 8)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn filter<Predicate>(mut self, predicate: Predicate) -> Self
+    where
+        Predicate: Fn(&Sample) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(SampleFilter::new(predicate));
+        self
+    }
+
+    /// Drop the samples already received by this [`Subscriber`], e.g. through redundant routers.
+    ///
+    /// The timestamp of the last sample received on each key is kept for `window`: a sample
+    /// whose timestamp is not newer is dropped before the callback or the channel of the
+    /// subscriber. Duplicates arriving more than `window` after the original are not detected.
+    /// Samples without timestamp are delivered unchanged,
+    /// see [`deduplicate_strict`](SubscriberBuilder::deduplicate_strict) to drop them.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .deduplicate(Duration::from_secs(10))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn deduplicate(mut self, window: Duration) -> Self {
+        self.deduplication = Some(Deduplication::new(window));
+        self
+    }
+
+    /// Like [`deduplicate`](SubscriberBuilder::deduplicate), but also drop the samples without
+    /// timestamp, which cannot be deduplicated.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn deduplicate_strict(mut self, window: Duration) -> Self {
+        self.deduplication = Some(Deduplication::new(window).strict(true));
+        self
+    }
+
+    /// Call `on_gap` when this [`Subscriber`] detects that it missed samples, e.g. after a
+    /// transport was lost and re-established.
+    ///
+    /// The detection relies on the sequence numbers of the samples published on each key by
+    /// each session (see [`SourceInfo`](crate::sample::SourceInfo)): `on_gap` is called before
+    /// delivering the sample received after the missing ones. Samples lost after the last one
+    /// received from a source are only detected when this source publishes again.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .reliable()
+    ///     .on_gap(|gap| println!("Missed {} samples on {}", gap.missed(), gap.key_expr))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn on_gap<OnGap>(mut self, on_gap: OnGap) -> Self
+    where
+        OnGap: Fn(GapInfo) + Send + Sync + 'static,
+    {
+        self.on_gap = Some(GapDetector::new(on_gap));
+        self
+    }
+
+    /// Deliver the samples of each source and key in their publication order, holding back the
+    /// samples received ahead of missing ones for at most the window of `buffer`.
+    ///
+    /// The order relies on the same sequence numbers as [`on_gap`](SubscriberBuilder::on_gap),
+    /// which then detects the missing samples skipped once the window elapsed. The buffer holds
+    /// at most a given number of samples per source (see [`OrderingBuffer::capacity`]): past it,
+    /// the samples held for the longest are delivered.
+    ///
+    /// Holding samples back adds latency whenever some are reordered or lost, so the samples are
+    /// delivered on reception by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .ordered(Duration::from_millis(100))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ordered<Buffer>(mut self, buffer: Buffer) -> Self
+    where
+        Buffer: Into<OrderingBuffer>,
+    {
+        self.ordering = Some(buffer.into());
+        self
+    }
+
+    /// Call `callback` when the matching status of this [`Subscriber`] changes, i.e. when the
+    /// first publisher with a key expression intersecting its own appears, or when the last one
+    /// disappears.
+    ///
+    /// Only the publishers declared with [`announce`](crate::publication::PublisherBuilder::announce)
+    /// are tracked, the ones of the key expressions added with [`also`](SubscriberBuilder::also)
+    /// excluded. They disappear when undeclared, or when their session is closed or lost.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .matching_listener(|status| {
+    ///         if !status.matching_publishers() {
+    ///             println!("No data source connected");
+    ///         }
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn matching_listener<Listener>(mut self, listener: Listener) -> Self
+    where
+        Listener: Fn(MatchingStatus) + Send + Sync + 'static,
+    {
+        self.matching_listener = Some(MatchingHook::new(listener));
+        self
+    }
+
+    /// Call `on_drop` with each sample the handler of this [`Subscriber`] fails to deliver,
+    /// e.g. to write it to a spill file.
+    ///
+    /// Samples are dropped by the channels whose receiver was dropped, and by the
+    /// [`SampleChannel`] handler when it is full with a dropping [`OverflowPolicy`].
+    /// Handlers that never drop samples, like callbacks, never call `on_drop`.
+    ///
+    /// `on_drop` is called on the reception path of the session, like the callbacks, and should
+    /// thus be cheap.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::handlers::OverflowPolicy;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .channel_size(16)
+    ///     .on_full(OverflowPolicy::DropOldest)
+    ///     .on_drop(|sample| eprintln!("Dropped sample on {}", sample.key_expr))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn on_drop<OnDrop>(mut self, on_drop: OnDrop) -> Self
+    where
+        OnDrop: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.on_drop = Some(DropHook::new(on_drop));
+        self
+    }
+
+    /// Change the subscription mode to Pull.
+    #[inline]
+    pub fn pull_mode(self) -> SubscriberBuilder<'a, 'b, PullMode, Handler> {
+        self.map_parts(|_| PullMode, |handler| handler)
+    }
+
+    /// Change the subscription mode to Pull, pulling the data automatically every `period`.
+    ///
+    /// The data may still be pulled in between with [`pull`](PullSubscriber::pull),
+    /// and the period changed with [`set_pull_period`](PullSubscriber::set_pull_period).
+    /// The subscriber isn't declared, and an [`InvalidPullPeriod`] error is returned, if
+    /// `period` is shorter than [`MIN_PULL_PERIOD`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .pull_mode_periodic(Duration::from_millis(100))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn pull_mode_periodic(
+        self,
+        period: Duration,
+    ) -> SubscriberBuilder<'a, 'b, PullMode, Handler> {
+        let mut builder = self.pull_mode();
+        builder.pull_period = Some(period);
+        builder
+    }
+
+    /// Change the subscription mode to Push.
+    #[inline]
+    pub fn push_mode(self) -> SubscriberBuilder<'a, 'b, PushMode, Handler> {
+        #[cfg(feature = "unstable")]
+        let builder = SubscriberBuilder {
+            pull_period: None,
+            ..self
+        };
+        #[cfg(not(feature = "unstable"))]
+        let builder = self;
+        builder.map_parts(|_| PushMode, |handler| handler)
+    }
+}
+
+impl<'a, SubscriberMode, Handler> SubscriberBuilder<'a, '_, SubscriberMode, Handler>
+where
+    SubscriberMode: Into<Mode>,
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    /// Declare the subscriber with all the settings of this builder but the automatic pulls
+    /// and the wait for the routing of its declaration, which depend on its mode.
+    ///
+    /// The callback of the subscriber can only be replaced afterwards if `swappable`.
+    #[cfg_attr(not(feature = "unstable"), allow(unused_variables))]
+    fn declare(self, swappable: bool) -> ZResult<(SubscriberInner<'a>, Handler::Receiver)> {
+        let key_expr = self.key_expr?;
+        #[cfg(feature = "unstable")]
+        let also = self.also.into_iter().collect::<ZResult<Vec<_>>>()?;
+        let session = self.session;
+        #[cfg(feature = "unstable")]
+        let (callback, receiver) = match self.on_drop {
+            Some(hook) => self.handler.into_cb_receiver_pair_with_drop(hook.0),
+            None => self.handler.into_cb_receiver_pair(),
+        };
+        #[cfg(not(feature = "unstable"))]
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        #[cfg(feature = "unstable")]
+        let (callback, slot) = match swappable {
+            true => {
+                let (callback, slot) = CallbackSlot::wrap(callback);
+                (callback, Some(slot))
+            }
+            false => (callback, None),
+        };
+        #[cfg(feature = "unstable")]
+        let metrics = Arc::new(MetricsCounters::default());
+        #[cfg(feature = "unstable")]
+        let callback = metrics.clone().wrap(callback);
+        #[cfg(feature = "unstable")]
+        let filtered = match (&self.filter, &self.payload_filter) {
+            (Some(filter), _) => Some(filter.filtered.clone()),
+            (None, Some(_)) => Some(Arc::new(AtomicUsize::new(0))),
+            (None, None) => None,
+        };
+        #[cfg(feature = "unstable")]
+        let callback = match self.filter {
+            Some(filter) => filter.wrap(callback),
+            None => callback,
+        };
+        // The payload filter is also declared with the subscription, the publishers supporting it
+        // don't send the samples it rejects. It still applies to the others.
+        #[cfg(feature = "unstable")]
+        let callback = match (&self.payload_filter, &filtered) {
+            (Some(filter), Some(filtered)) => filter.clone().wrap(callback, filtered.clone()),
+            _ => callback,
+        };
+        // Transformers (e.g. decompression) run before the payload filter
+        #[cfg(feature = "unstable")]
+        let callback = match self.transform {
+            Some(chain) => chain.wrap(callback),
+            None => callback,
+        };
+        // Duplicates are dropped before being transformed
+        #[cfg(feature = "unstable")]
+        let callback = match self.deduplication {
+            Some(deduplication) => deduplication.wrap(callback),
+            None => callback,
+        };
+        // Gaps are detected on all the received samples, duplicates included
+        #[cfg(feature = "unstable")]
+        let callback = match self.on_gap {
+            Some(detector) => detector.wrap(callback),
+            None => callback,
+        };
+        #[cfg(feature = "unstable")]
+        let callback = metrics.clone().catch_panics(callback, self.on_panic);
+        // The held samples may be delivered from another task, so the panics are caught within
+        #[cfg(feature = "unstable")]
+        let callback = match self.ordering {
+            Some(buffer) => buffer.wrap(callback),
+            None => callback,
+        };
+        #[cfg(feature = "unstable")]
+        let also_callback = callback.clone();
+        let info = SubscriberInfo {
+            reliability: self.reliability,
+            mode: self.mode.into(),
+        };
+        let state = session.declare_subscriber_inner(
+            &key_expr,
+            &None,
+            self.origin,
+            callback,
+            &info,
+            #[cfg(feature = "unstable")]
+            self.payload_filter.clone(),
+        )?;
+        #[cfg(feature = "unstable")]
+        let drop_timeout = self.drop_timeout.or(session.subscribers_drop_timeout);
+        #[allow(unused_mut)]
+        let mut subscriber = SubscriberInner {
+            session,
+            state,
+            alive: true,
+            #[cfg(feature = "unstable")]
+            filtered,
+            #[cfg(feature = "unstable")]
+            dropped: self.dropped,
+            #[cfg(feature = "unstable")]
+            occupancy: self.occupancy,
+            #[cfg(feature = "unstable")]
+            metrics,
+            #[cfg(feature = "unstable")]
+            callback: slot,
+            #[cfg(feature = "unstable")]
+            also: Vec::new(),
+            #[cfg(feature = "unstable")]
+            stream: Default::default(),
+            #[cfg(feature = "unstable")]
+            drop_timeout,
+            #[cfg(feature = "unstable")]
+            matching: None,
+        };
+        #[cfg(feature = "unstable")]
+        subscriber.publish_metrics();
+        #[cfg(feature = "unstable")]
+        subscriber.subscribe_also(also, self.origin, also_callback, &info, self.payload_filter)?;
+        #[cfg(feature = "unstable")]
+        if let Some(listener) = self.matching_listener {
+            subscriber.listen_matching(listener.0)?;
+        }
+        Ok((subscriber, receiver))
+    }
+}
+
+// Push mode
+impl<'a, Handler> Resolvable for SubscriberBuilder<'a, '_, PushMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<Subscriber<'a, Handler::Receiver>>;
+}
+
+impl<'a, Handler> SyncResolve for SubscriberBuilder<'a, '_, PushMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        #[cfg(feature = "unstable")]
+        let await_routed = self.await_routed;
+        let (subscriber, receiver) = self.declare(true)?;
+        let subscriber = Subscriber {
+            subscriber,
+            receiver,
+        };
+        #[cfg(feature = "unstable")]
+        if let Some(timeout) = await_routed {
+            subscriber.routed(timeout).res_sync()?;
+        }
+        Ok(subscriber)
+    }
+}
+
+impl<'a, Handler> AsyncResolve for SubscriberBuilder<'a, '_, PushMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    #[cfg(not(feature = "unstable"))]
+    type Future = Ready<Self::To>;
+    #[cfg(feature = "unstable")]
+    type Future = crate::DeclarationFuture<Subscriber<'a, Handler::Receiver>>;
+
+    #[cfg(not(feature = "unstable"))]
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+
+    #[cfg(feature = "unstable")]
+    fn res_async(self) -> Self::Future {
+        let await_routed = self.await_routed;
+        let subscriber = match self.declare(true) {
+            Ok((subscriber, receiver)) => Subscriber {
+                subscriber,
+                receiver,
+            },
+            Err(e) => return crate::DeclarationFuture::ready(Err(e)),
+        };
+        let routed =
+            await_routed.map(|timeout| subscriber.subscriber.session.routed_async(timeout));
+        crate::DeclarationFuture::new(subscriber, routed)
+    }
+}
+
+// Pull mode
+impl<'a, Handler> Resolvable for SubscriberBuilder<'a, '_, PullMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<PullSubscriber<'a, Handler::Receiver>>;
+}
+
+impl<'a, Handler> SubscriberBuilder<'a, '_, PullMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    /// Declare the pull subscriber, and return it with the timeout to wait for the routing of
+    /// its declaration, if any.
+    fn declare_pull(self) -> ZResult<(PullSubscriber<'a, Handler::Receiver>, Option<Duration>)> {
+        #[cfg(feature = "unstable")]
+        let (pull_period, await_routed) = (self.pull_period, self.await_routed);
+        #[cfg(not(feature = "unstable"))]
+        let await_routed = None;
+        #[cfg(feature = "unstable")]
+        if let Some(period) = pull_period {
+            PullPeriod::try_new(period)?;
+        }
+        let (inner, receiver) = self.declare(false)?;
+        let subscriber = PullSubscriber {
+            subscriber: PullSubscriberInner {
+                inner,
+                #[cfg(feature = "unstable")]
+                periodic: Mutex::new(None),
+                #[cfg(feature = "unstable")]
+                suspended_period: Mutex::new(None),
+            },
+            receiver,
+        };
+        #[cfg(feature = "unstable")]
+        if pull_period.is_some() {
+            subscriber.set_pull_period(pull_period).res_sync()?;
+        }
+        Ok((subscriber, await_routed))
+    }
+}
+
+impl<'a, Handler> SyncResolve for SubscriberBuilder<'a, '_, PullMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        #[allow(unused_variables)]
+        let (subscriber, await_routed) = self.declare_pull()?;
+        #[cfg(feature = "unstable")]
+        if let Some(timeout) = await_routed {
+            subscriber.routed(timeout).res_sync()?;
+        }
+        Ok(subscriber)
+    }
+}
+
+impl<'a, Handler> AsyncResolve for SubscriberBuilder<'a, '_, PullMode, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    #[cfg(not(feature = "unstable"))]
+    type Future = Ready<Self::To>;
+    #[cfg(feature = "unstable")]
+    type Future = crate::DeclarationFuture<PullSubscriber<'a, Handler::Receiver>>;
+
+    #[cfg(not(feature = "unstable"))]
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+
+    #[cfg(feature = "unstable")]
+    fn res_async(self) -> Self::Future {
+        let (subscriber, await_routed) = match self.declare_pull() {
+            Ok(declared) => declared,
+            Err(e) => return crate::DeclarationFuture::ready(Err(e)),
+        };
+        let routed =
+            await_routed.map(|timeout| subscriber.subscriber.inner.session.routed_async(timeout));
+        crate::DeclarationFuture::new(subscriber, routed)
+    }
+}
+
+/// Implements the methods common to [`Subscriber`], [`PullSubscriber`] and [`SharedSubscriber`],
+/// in an impl block providing `fn inner(&self) -> &SubscriberInner`.
+macro_rules! subscriber_methods {
+    () => {
+        /// Returns the [`KeyExpr`] this subscriber subscribes to.
+        pub fn key_expr(&self) -> &KeyExpr<'static> {
+            &self.inner().state.key_expr
+        }
+
+        /// Returns the [`EntityId`](crate::EntityId) of this subscriber, unique within its session.
+        #[zenoh_macros::unstable]
+        pub fn id(&self) -> crate::EntityId {
+            crate::EntityId(self.inner().state.id)
+        }
+
+        /// Returns the [`Reliability`] of this subscriber.
+        #[zenoh_macros::unstable]
+        pub fn reliability(&self) -> Reliability {
+            self.inner().state.info().reliability
+        }
+
+        /// Wait for the declaration of this subscriber to reach the connected zenoh sessions,
+        /// or for at most `timeout`.
+        ///
+        /// Resolves to the number of sessions that acknowledged, see
+        /// [`Session::routed`](crate::Session::routed).
+        #[zenoh_macros::unstable]
+        pub fn routed(&self, timeout: Duration) -> impl Resolve<ZResult<usize>> + '_ {
+            self.inner().session.routed(timeout)
+        }
+
+        /// Returns the number of samples rejected by the [`filter`](SubscriberBuilder::filter)
+        /// or the [`payload_prefix`](SubscriberBuilder::payload_prefix) of this subscriber, if any.
+        #[zenoh_macros::unstable]
+        pub fn filtered(&self) -> usize {
+            self.inner().filtered()
+        }
+
+        /// Returns the number of samples dropped because the channel of this subscriber was full
+        /// (see [`on_full`](SubscriberBuilder::on_full)).
+        #[zenoh_macros::unstable]
+        pub fn dropped_samples(&self) -> usize {
+            self.inner().dropped_samples()
+        }
+
+        /// Returns the reception metrics of this subscriber.
+        ///
+        /// # Examples
+        /// ```
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// use zenoh::prelude::r#async::*;
+        ///
+        /// let session = zenoh::open(config::peer()).res().await.unwrap();
+        /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+        /// session.put("key/expression", "value").res().await.unwrap();
+        /// assert_eq!(subscriber.metrics().samples_delivered, 1);
+        /// # }
+        /// ```
+        #[zenoh_macros::unstable]
+        pub fn metrics(&self) -> SubscriberMetrics {
+            self.inner().metrics()
+        }
+
+        /// Stop delivering samples to this subscriber, dropping them until [`resume`](Self::resume).
+        ///
+        /// Unlike undeclaring it, pausing a subscriber keeps its subscription:
+        /// publishers keep routing the samples to it in the meantime.
+        /// A [`SharedSubscriber`] is paused for all the clones of the handle.
+        ///
+        /// # Examples
+        /// ```
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// use zenoh::prelude::r#async::*;
+        ///
+        /// let session = zenoh::open(config::peer()).res().await.unwrap();
+        /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+        /// subscriber.pause().res().await.unwrap();
+        /// // Reconfigure the application
+        /// subscriber.resume().res().await.unwrap();
+        /// # }
+        /// ```
+        #[zenoh_macros::unstable]
+        pub fn pause(&self) -> impl Resolve<ZResult<()>> + '_ {
+            self.inner().pause(PausePolicy::Drop)
+        }
+
+        /// Stop delivering samples to this subscriber until [`resume`](Self::resume),
+        /// handling them according to `policy`.
+        ///
+        /// Pausing an already paused subscriber changes its policy, truncating its buffer if needed.
+        #[zenoh_macros::unstable]
+        pub fn pause_with(&self, policy: PausePolicy) -> impl Resolve<ZResult<()>> + '_ {
+            self.inner().pause(policy)
+        }
+
+        /// Resume delivering samples to this subscriber.
+        ///
+        /// The samples buffered while paused (see [`PausePolicy::Buffer`]) are first delivered in
+        /// order from the resuming task, which must thus not hold a lock needed by the callback.
+        #[zenoh_macros::unstable]
+        pub fn resume(&self) -> impl Resolve<ZResult<()>> + '_ {
+            self.inner().resume()
+        }
+
+        /// Resume delivering samples to this subscriber, after replaying the ones published
+        /// while it was paused.
+        ///
+        /// The samples published on its key expression since it was paused are queried from the
+        /// publication caches and storages, with a [time range](crate::selector::TimeRange).
+        /// The replies, marked [`historical`](Sample::historical), are merged with the samples
+        /// buffered while paused, skipping those already buffered, and delivered sorted by
+        /// timestamp before the live samples. The replies without a timestamp are ignored.
+        ///
+        /// Resuming a subscriber which isn't paused doesn't query anything.
+        ///
+        /// # Examples
+        /// ```
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// use zenoh::prelude::r#async::*;
+        ///
+        /// let session = zenoh::open(config::peer()).res().await.unwrap();
+        /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+        /// subscriber.pause().res().await.unwrap();
+        /// // Reconfigure the application
+        /// subscriber.resume_with_replay().res().await.unwrap();
+        /// # }
+        /// ```
+        #[zenoh_macros::unstable]
+        pub fn resume_with_replay(&self) -> impl Resolve<ZResult<()>> + '_ {
+            self.inner().resume_with_replay()
+        }
+
+        /// Returns whether this subscriber is paused.
+        #[zenoh_macros::unstable]
+        pub fn is_paused(&self) -> bool {
+            self.inner().state.pause.is_paused()
+        }
+    };
+}
+
+/// Implements the methods of [`Subscriber`] and [`PullSubscriber`] waiting for their samples.
+macro_rules! receiver_methods {
+    () => {
+        /// Wait for a sample for at most `timeout`, whichever the handler of this subscriber.
+        ///
+        /// Returns `Ok(None)` if `timeout` elapses, and a
+        /// [`ChannelDisconnected`](crate::handlers::ChannelDisconnected) error if the channel of
+        /// this subscriber is disconnected.
+        ///
+        /// # Examples
+        /// ```
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// use std::time::Duration;
+        /// use zenoh::prelude::r#async::*;
+        ///
+        /// let session = zenoh::open(config::peer()).res().await.unwrap();
+        /// let mut subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+        /// while let Some(sample) = subscriber.recv_timeout(Duration::from_millis(100)).unwrap() {
+        ///     println!("Received: {} {}", sample.key_expr, sample.value);
+        /// }
+        /// # }
+        /// ```
+        #[zenoh_macros::unstable]
+        pub fn recv_timeout<T>(&mut self, timeout: Duration) -> ZResult<Option<T>>
+        where
+            Receiver: SampleReceiver<T>,
+        {
+            self.receiver.recv_timeout(timeout)
+        }
+
+        /// Wait for a sample until `deadline`, whichever the handler of this subscriber.
+        ///
+        /// Returns `Ok(None)` if `deadline` is reached, and a
+        /// [`ChannelDisconnected`](crate::handlers::ChannelDisconnected) error if the channel of
+        /// this subscriber is disconnected.
+        #[zenoh_macros::unstable]
+        pub fn recv_deadline<T>(&mut self, deadline: Instant) -> ZResult<Option<T>>
+        where
+            Receiver: SampleReceiver<T>,
+        {
+            self.receiver.recv_deadline(deadline)
+        }
+    };
+}
+
+/// A subscriber that provides data through a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
+///
+/// Subscribers can be created from a zenoh [`Session`](crate::Session)
+/// with the [`declare_subscriber`](crate::SessionDeclarations::declare_subscriber) function
+/// and the [`with`](SubscriberBuilder::with) function
+/// of the resulting builder.
+///
+/// Subscribers are automatically undeclared when dropped.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber("key/expression")
+///     .with(flume::bounded(32))
+///     .res()
+///     .await
+///     .unwrap();
+/// while let Ok(sample) = subscriber.recv_async().await {
+///     println!("Received: {} {}", sample.key_expr, sample.value);
+/// }
+/// # }
+/// ```
+#[non_exhaustive]
+pub struct Subscriber<'a, Receiver> {
+    pub(crate) subscriber: SubscriberInner<'a>,
+    pub receiver: Receiver,
+}
+
+impl<Receiver> fmt::Debug for Subscriber<'_, Receiver> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = &self.subscriber.state;
+        f.debug_struct("Subscriber")
+            .field("id", &state.id)
+            .field("key_expr", &state.key_expr)
+            .field("reliability", &state.info().reliability)
+            .field("mode", &state.mode())
+            .finish()
+    }
+}
+
+/// A [`PullMode`] subscriber that provides data through a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
+///
+/// PullSubscribers only provide data when explicitly pulled by the
+/// application with the [`pull`](PullSubscriber::pull) function.
+/// PullSubscribers can be created from a zenoh [`Session`](crate::Session)
+/// with the [`declare_subscriber`](crate::SessionDeclarations::declare_subscriber) function,
+/// the [`with`](SubscriberBuilder::with) function
+/// and the [`pull_mode`](SubscriberBuilder::pull_mode) function
+/// of the resulting builder.
+///
+/// Subscribers are automatically undeclared when dropped.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber("key/expression")
+///     .with(flume::bounded(32))
+///     .pull_mode()
+///     .res()
+///     .await
+///     .unwrap();
+/// subscriber.pull();
+/// # }
+/// ```
+#[non_exhaustive]
+pub struct PullSubscriber<'a, Receiver> {
+    pub(crate) subscriber: PullSubscriberInner<'a>,
+    pub receiver: Receiver,
+}
+
+impl<Receiver> fmt::Debug for PullSubscriber<'_, Receiver> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = &self.subscriber.inner.state;
+        f.debug_struct("PullSubscriber")
+            .field("id", &state.id)
+            .field("key_expr", &state.key_expr)
+            .field("reliability", &state.info().reliability)
+            .finish()
+    }
+}
+
+impl<'a, Receiver> Deref for PullSubscriber<'a, Receiver> {
+    type Target = Receiver;
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<'a, Receiver> DerefMut for PullSubscriber<'a, Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+impl<'a, Receiver> PullSubscriber<'a, Receiver> {
+    fn inner(&self) -> &SubscriberInner<'a> {
+        &self.subscriber.inner
+    }
+
+    subscriber_methods!();
+    receiver_methods!();
+
+    /// Returns the [`SubMode`] of this PullSubscriber, [`SubMode::Pull`] unless changed with
+    /// [`update`](PullSubscriber::update).
+    #[zenoh_macros::unstable]
+    pub fn mode(&self) -> SubMode {
+        self.subscriber.inner.state.mode()
+    }
+
+    /// Change the [`Reliability`] and [`SubMode`] of this PullSubscriber, without undeclaring it.
+    ///
+    /// The subscription is declared again, with the same id and key expression, so that the
+    /// routers update it in place. In push mode, the samples are delivered without being pulled
+    /// and the automatic pulls set with [`set_pull_period`](PullSubscriber::set_pull_period) are
+    /// stopped; they restart with the same period when switching back to pull mode.
+    ///
+    /// Some samples may be lost during the transition:
+    /// - switching to push mode loses the samples the routers kept for the next pull,
+    /// - switching to best effort may drop the samples in flight.
+    ///
+    /// Switching to pull mode or to reliable loses no sample.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::subscriber::SubMode;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber
+    ///     .update(Reliability::Reliable, SubMode::Push)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn update(
+        &self,
+        reliability: Reliability,
+        mode: SubMode,
+    ) -> impl Resolve<ZResult<()>> + '_ {
+        self.subscriber.update(reliability, mode)
+    }
+
+    /// Pull available data for a [`PullSubscriber`].
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::subscriber::SubMode;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .with(flume::bounded(32))
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber.pull();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn pull(&self) -> impl Resolve<ZResult<()>> + '_ {
+        self.subscriber.pull()
+    }
+
+    /// Pull at most `max_samples` of the available data for this PullSubscriber, the others
+    /// being kept for the next pulls.
+    ///
+    /// The routers keep the last sample of each key matching the subscription: `max_samples`
+    /// bounds the number of keys whose sample is delivered, oldest first. The key expressions
+    /// subscribed to share it: each of them is pulled, in turn, with what the previous ones
+    /// left.
+    ///
+    /// Resolves to the number of samples the routers delivered for this pull. Routers that
+    /// don't report it are assumed to have used the whole bound: the pull resolves to the
+    /// samples counted so far, without pulling the next key expressions.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .with(flume::bounded(32))
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let delivered = subscriber.pull_max(16).res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn pull_max(&self, max_samples: usize) -> impl Resolve<ZResult<usize>> + '_ {
+        self.subscriber.pull_max(max_samples)
+    }
+
+    /// Pull the data of this subscriber automatically every `period`, or stop pulling it
+    /// automatically if `period` is `None`.
+    ///
+    /// The periodic pulls stop when the subscriber is undeclared or dropped.
+    /// A `period` shorter than [`MIN_PULL_PERIOD`] is rejected with an [`InvalidPullPeriod`]
+    /// error.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber
+    ///     .set_pull_period(Some(Duration::from_secs(1)))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn set_pull_period(&self, period: Option<Duration>) -> impl Resolve<ZResult<()>> + '_ {
+        self.subscriber.set_pull_period(period)
+    }
+
+    /// Close a [`PullSubscriber`].
+    ///
+    /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
+    /// close the Subscriber asynchronously.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber.undeclare().res().await.unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        self.subscriber.undeclare()
+    }
+
+    /// Close this PullSubscriber, waiting for at most `timeout` for its undeclaration.
+    ///
+    /// Past `timeout`, an error is returned but the PullSubscriber is closed anyway: its undeclaration
+    /// completes in the background, and isn't attempted again.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber
+    ///     .close_timeout(Duration::from_secs(1))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn close_timeout(self, timeout: Duration) -> impl Resolve<ZResult<()>> + 'a {
+        let mut subscriber = self.subscriber.inner;
+        ResolveFuture::new(async move { subscriber.unsubscribe_timeout(timeout).await })
+    }
+
+    /// Close this PullSubscriber locally, without undeclaring it, so that it never waits on the network.
+    ///
+    /// The routers keep sending the matching samples to the session, which discards them, until
+    /// the session is closed or the last subscriber on the same key expression is undeclared.
+    #[zenoh_macros::unstable]
+    pub fn force_close(self) -> impl Resolve<ZResult<()>> + 'a {
+        let mut subscriber = self.subscriber.inner;
+        ResolveClosure::new(move || subscriber.forget())
+    }
+
+    /// Split this PullSubscriber into a handle on its subscription and its receiver, which can
+    /// then be owned independently, e.g. moved to another task.
+    ///
+    /// The handle keeps [`pull`](PullSubscriber::pull) available, and dropping it undeclares the
+    /// subscription: the receiver then only yields the data already received.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let (handle, receiver) = subscriber.split();
+    /// tokio::spawn(async move {
+    ///     while let Ok(sample) = receiver.recv_async().await {
+    ///         println!("Received: {} {}", sample.key_expr, sample.value);
+    ///     }
+    /// });
+    /// handle.pull().res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn split(self) -> (PullSubscriber<'a, ()>, Receiver) {
+        let PullSubscriber {
+            subscriber,
+            receiver,
+        } = self;
+        (
+            PullSubscriber {
+                subscriber,
+                receiver: (),
+            },
+            receiver,
+        )
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> PullSubscriber<'a, flume::Receiver<Sample>> {
+    /// Undeclare this PullSubscriber, then return the samples still queued for it, in their
+    /// arrival order.
+    ///
+    /// No final [`pull`](PullSubscriber::pull) is made: only the samples already received by
+    /// previous pulls are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .pull_mode()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber.pull().res().await.unwrap();
+    /// for sample in subscriber.close_and_drain().res().await.unwrap() {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    pub fn close_and_drain(self) -> impl Resolve<ZResult<Vec<Sample>>> + 'a {
+        let PullSubscriber {
+            subscriber,
+            receiver,
+        } = self;
+        let undeclaration = subscriber.undeclare();
+        ResolveClosure::new(move || {
+            undeclaration.res_sync()?;
+            Ok(receiver.drain().collect())
+        })
+    }
+}
+
+impl<'a, Receiver> Subscriber<'a, Receiver> {
+    fn inner(&self) -> &SubscriberInner<'a> {
+        &self.subscriber
+    }
+
+    subscriber_methods!();
+    receiver_methods!();
+
+    /// Returns the [`SubMode`] of this Subscriber, [`SubMode::Push`] unless changed with
+    /// [`update`](Subscriber::update).
+    #[zenoh_macros::unstable]
+    pub fn mode(&self) -> SubMode {
+        self.subscriber.state.mode()
+    }
+
+    /// Change the [`Reliability`] and [`SubMode`] of this Subscriber, without undeclaring it.
+    ///
+    /// The subscription is declared again, with the same id and key expression, so that the
+    /// routers update it in place. A Subscriber in pull mode receives no sample, as it can't
+    /// pull them: use a [`PullSubscriber`] to switch between pull and push modes.
+    ///
+    /// Switching to best effort may drop the samples in flight during the transition,
+    /// switching to reliable loses no sample.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::subscriber::SubMode;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .best_effort()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber
+    ///     .update(Reliability::Reliable, SubMode::Push)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(subscriber.reliability(), Reliability::Reliable);
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn update(
+        &self,
+        reliability: Reliability,
+        mode: SubMode,
+    ) -> impl Resolve<ZResult<()>> + '_ {
+        // Only capture the inner subscriber, which is Send whatever the receiver
+        let subscriber = &self.subscriber;
+        ResolveClosure::new(move || subscriber.update(reliability, mode))
+    }
+
+    /// Close a [`Subscriber`].
+    ///
+    /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
+    /// close the Subscriber asynchronously.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber.undeclare().res().await.unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn undeclare(self) -> SubscriberUndeclaration<'a> {
+        self.subscriber.undeclare()
+    }
+
+    /// Close this Subscriber, waiting for at most `timeout` for its undeclaration.
+    ///
+    /// Past `timeout`, an error is returned but the Subscriber is closed anyway: its undeclaration
+    /// completes in the background, and isn't attempted again.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber
+    ///     .close_timeout(Duration::from_secs(1))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn close_timeout(self, timeout: Duration) -> impl Resolve<ZResult<()>> + 'a {
+        let mut subscriber = self.subscriber;
+        ResolveFuture::new(async move { subscriber.unsubscribe_timeout(timeout).await })
+    }
+
+    /// Close this Subscriber locally, without undeclaring it, so that it never waits on the network.
+    ///
+    /// The routers keep sending the matching samples to the session, which discards them, until
+    /// the session is closed or the last subscriber on the same key expression is undeclared.
+    #[zenoh_macros::unstable]
+    pub fn force_close(self) -> impl Resolve<ZResult<()>> + 'a {
+        let mut subscriber = self.subscriber;
+        ResolveClosure::new(move || subscriber.forget())
+    }
+
+    /// Split this Subscriber into a handle on its subscription and its receiver, which can
+    /// then be owned independently, e.g. moved to another task.
+    ///
+    /// Dropping the handle undeclares the subscription: the receiver then only yields the samples
+    /// already received.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let (handle, receiver) = subscriber.split();
+    /// tokio::spawn(async move {
+    ///     while let Ok(sample) = receiver.recv_async().await {
+    ///         println!("Received: {} {}", sample.key_expr, sample.value);
+    ///     }
+    /// });
+    /// handle.undeclare().res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn split(self) -> (Subscriber<'a, ()>, Receiver) {
+        let Subscriber {
+            subscriber,
+            receiver,
+        } = self;
+        (
+            Subscriber {
+                subscriber,
+                receiver: (),
+            },
+            receiver,
+        )
+    }
+
+    /// Return the receiver of this Subscriber, leaking its subscription on purpose.
+    ///
+    /// The subscription is never undeclared: the samples keep being delivered to the receiver
+    /// until the session is closed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let receiver = session.declare_subscriber("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .into_receiver();
+    /// while let Ok(sample) = receiver.recv_async().await {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn into_receiver(self) -> Receiver {
+        let Subscriber {
+            mut subscriber,
+            receiver,
+        } = self;
+        subscriber.alive = false;
+        receiver
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Subscriber<'_, ()> {
+    /// Replace the callback of this callback subscriber, without undeclaring it.
+    ///
+    /// Each sample is delivered entirely to either the previous or the new callback: the samples
+    /// received once this function returns are delivered to the new callback, but the previous
+    /// one may still be running for the samples received before.
+    ///
+    /// The callback given when declaring the subscriber is only dropped once the subscriber is
+    /// undeclared, so that delivering the samples to it never takes a lock.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .callback(|sample| println!("Buffering: {}", sample.value))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber.set_callback(|sample| println!("Processing: {}", sample.value));
+    /// # }
+    /// ```
+    pub fn set_callback<F>(&self, callback: F)
+    where
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        if let Some(slot) = &self.subscriber.callback {
+            slot.set(Arc::new(callback));
+        }
+    }
+
+    /// Replace the callback of this callback subscriber with a mutable one, without undeclaring
+    /// it, see [`set_callback`](Subscriber::set_callback).
+    ///
+    /// The callback is invoked by one thread at a time.
+    pub fn set_callback_mut<F>(&self, callback: F)
+    where
+        F: FnMut(Sample) + Send + Sync + 'static,
+    {
+        self.set_callback(locked(callback))
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Subscriber<'static, ()> {
+    /// Convert this callback subscriber into a [`SharedSubscriber`], a clonable handle
+    /// keeping its subscription declared until the last of its clones is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .callback(|sample| println!("Received: {} {}", sample.key_expr, sample.value))
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .into_shared();
+    /// let keepalive = subscriber.clone();
+    /// drop(subscriber);
+    /// // Still subscribed until `keepalive` is dropped
+    /// # }
+    /// ```
+    pub fn into_shared(self) -> SharedSubscriber {
+        SharedSubscriber(Arc::new(PullSubscriberInner {
+            inner: self.subscriber,
+            periodic: Mutex::new(None),
+            suspended_period: Mutex::new(None),
+        }))
+    }
+}
+
+#[zenoh_macros::unstable]
+impl PullSubscriber<'static, ()> {
+    /// Convert this callback pull subscriber into a [`SharedSubscriber`], a clonable handle
+    /// keeping its subscription declared until the last of its clones is dropped.
+    ///
+    /// see [`Subscriber::into_shared`]
+    pub fn into_shared(self) -> SharedSubscriber {
+        SharedSubscriber(Arc::new(self.subscriber))
+    }
+}
+
+/// A clonable handle to the subscription of a callback subscriber, which is undeclared
+/// once, when the last clone of the handle is dropped.
+///
+/// Combined with [`Session::into_arc`](crate::Session::into_arc), it gives the subscription
+/// a `'static` owner that can be shared between several components.
+///
+/// See [`Subscriber::into_shared`] and [`PullSubscriber::into_shared`].
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct SharedSubscriber(Arc<PullSubscriberInner<'static>>);
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for SharedSubscriber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedSubscriber")
+            .field("id", &self.0.inner.state.id)
+            .field("key_expr", &self.0.inner.state.key_expr)
+            .finish()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl SharedSubscriber {
+    fn inner(&self) -> &SubscriberInner<'static> {
+        &self.0.inner
+    }
+
+    subscriber_methods!();
+
+    /// Returns the [`SubMode`] of this subscriber.
+    pub fn mode(&self) -> SubMode {
+        self.0.inner.state.mode()
+    }
+
+    /// Pull available data for this subscriber, if it was declared in pull mode.
+    ///
+    /// see [`PullSubscriber::pull`]
+    #[inline]
+    pub fn pull(&self) -> impl Resolve<ZResult<()>> + '_ {
+        self.0.pull()
+    }
+
+    /// Pull at most `max_samples` of the available data for this subscriber, if it was
+    /// declared in pull mode.
+    ///
+    /// see [`PullSubscriber::pull_max`]
+    pub fn pull_max(&self, max_samples: usize) -> impl Resolve<ZResult<usize>> + '_ {
+        self.0.pull_max(max_samples)
+    }
+
+    /// Returns the number of clones of this handle, including itself.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+}
+
+impl<'a, T> Undeclarable<(), SubscriberUndeclaration<'a>> for Subscriber<'a, T> {
+    fn undeclare_inner(self, _: ()) -> SubscriberUndeclaration<'a> {
+        Undeclarable::undeclare_inner(self.subscriber, ())
+    }
+}
+
+impl<Receiver> Deref for Subscriber<'_, Receiver> {
+    type Target = Receiver;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+impl<Receiver> DerefMut for Subscriber<'_, Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+/// The reception metrics of a subscriber, see [`Subscriber::metrics`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubscriberMetrics {
+    /// The number of samples delivered to the handler of the subscriber, once filtered.
+    pub samples_delivered: u64,
+    /// The total payload size of the samples delivered to the handler, in bytes.
+    pub bytes_delivered: u64,
+    /// The number of delivered samples dropped because the channel of the subscriber was full
+    /// (see [`on_full`](SubscriberBuilder::on_full)).
+    pub samples_dropped: u64,
+    /// The number of samples whose delivery panicked (see [`on_panic`](SubscriberBuilder::on_panic)).
+    pub panics: u64,
+    /// The number of samples currently queued in the channel of the subscriber, waiting to be
+    /// received (see [`flow_control`](SubscriberBuilder::flow_control)).
+    pub queued: u64,
+    /// The reception time of the last sample delivered to the handler.
+    pub last_received: Option<SystemTime>,
+}
+
+/// The counters and gauges behind the [`SubscriberMetrics`] of a subscriber.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone)]
+pub(crate) struct MetricsSource {
+    counters: Arc<MetricsCounters>,
+    dropped: Option<Arc<AtomicUsize>>,
+    occupancy: Option<Arc<ChannelOccupancy>>,
+}
+
+#[zenoh_macros::unstable]
+impl MetricsSource {
+    pub(crate) fn snapshot(&self) -> SubscriberMetrics {
+        SubscriberMetrics {
+            samples_dropped: self
+                .dropped
+                .as_ref()
+                .map_or(0, |dropped| dropped.load(Ordering::Relaxed) as u64),
+            queued: self
+                .occupancy
+                .as_ref()
+                .map_or(0, |occupancy| occupancy.get() as u64),
+            ..self.counters.snapshot()
+        }
+    }
+}
+
+/// The counters behind the [`SubscriberMetrics`] of a subscriber.
+#[zenoh_macros::unstable]
+#[derive(Debug, Default)]
+pub(crate) struct MetricsCounters {
+    delivered: AtomicU64,
+    bytes: AtomicU64,
+    panics: AtomicU64,
+    // In nanoseconds since the UNIX epoch, 0 until a sample is delivered
+    last_received: AtomicU64,
+}
+
+#[zenoh_macros::unstable]
+impl MetricsCounters {
+    fn snapshot(&self) -> SubscriberMetrics {
+        let last_received = self.last_received.load(Ordering::Relaxed);
+        SubscriberMetrics {
+            samples_delivered: self.delivered.load(Ordering::Relaxed),
+            bytes_delivered: self.bytes.load(Ordering::Relaxed),
+            samples_dropped: 0,
+            panics: self.panics.load(Ordering::Relaxed),
+            queued: 0,
+            last_received: (last_received != 0)
+                .then(|| UNIX_EPOCH + Duration::from_nanos(last_received)),
+        }
+    }
+
+    pub(crate) fn wrap(
+        self: Arc<Self>,
+        callback: Callback<'static, Sample>,
+    ) -> Callback<'static, Sample> {
+        Arc::new(move |sample: Sample| {
+            self.delivered.fetch_add(1, Ordering::Relaxed);
+            self.bytes
+                .fetch_add(sample.value.payload.len() as u64, Ordering::Relaxed);
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(1, |now| now.as_nanos() as u64);
+            self.last_received.store(now, Ordering::Relaxed);
+            callback(sample)
+        })
+    }
+
+    /// Catch the panics of `callback`, counting them and applying `policy`.
+    pub(crate) fn catch_panics(
+        self: Arc<Self>,
+        callback: Callback<'static, Sample>,
+        policy: PanicPolicy,
+    ) -> Callback<'static, Sample> {
+        let closed = AtomicBool::new(false);
+        Arc::new(move |sample: Sample| {
+            if closed.load(Ordering::Relaxed) {
+                return;
+            }
+            let key_expr = sample.key_expr.clone();
+            let delivery =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(sample)));
+            if delivery.is_err() {
+                self.panics.fetch_add(1, Ordering::Relaxed);
+                tracing::error!("Subscriber callback panicked on a sample for {}", key_expr);
+                match policy {
+                    PanicPolicy::Continue => {}
+                    PanicPolicy::CloseSubscriber => closed.store(true, Ordering::Relaxed),
+                    PanicPolicy::Abort => std::process::abort(),
+                }
+            }
+        })
+    }
+}
+
+/// What a subscriber does when its callback panics, see [`SubscriberBuilder::on_panic`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// The sample is dropped, the next ones are delivered.
+    #[default]
+    Continue,
+    /// The sample and all the next ones are dropped, as if the subscriber was closed.
+    CloseSubscriber,
+    /// The process is aborted.
+    Abort,
+}
+
+/// A filter on the bytes found at a given offset of the payload of samples.
+///
+/// See [`SubscriberBuilder::payload_prefix`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadPrefixFilter {
+    prefix: PayloadPrefix,
+}
+
+#[zenoh_macros::unstable]
+impl PayloadPrefixFilter {
+    /// Create a filter matching the payloads that contain `prefix` at `offset`.
+    pub fn new<IntoBytes>(offset: usize, prefix: IntoBytes) -> Self
+    where
+        IntoBytes: Into<Vec<u8>>,
+    {
+        PayloadPrefixFilter {
+            prefix: PayloadPrefix {
+                offset: offset as u64,
+                bytes: prefix.into(),
+            },
+        }
+    }
+
+    /// Returns true if `payload` contains the prefix of this filter at its offset.
+    pub fn matches(&self, payload: &ZBuf) -> bool {
+        self.prefix.matches(payload)
+    }
+
+    /// The union of the filters of the subscribers sharing a subscription, declared with it, or
+    /// `None` if any of them has no filter.
+    pub(crate) fn union<'a>(
+        filters: impl IntoIterator<Item = Option<&'a PayloadPrefixFilter>>,
+    ) -> Option<PayloadFilterType> {
+        let prefixes = filters
+            .into_iter()
+            .map(|filter| filter.map(|filter| filter.prefix.clone()))
+            .collect::<Option<Vec<_>>>()?;
+        (!prefixes.is_empty()).then_some(PayloadFilterType { prefixes })
+    }
+
+    fn wrap(
+        self,
+        callback: Callback<'static, Sample>,
+        filtered: Arc<AtomicUsize>,
+    ) -> Callback<'static, Sample> {
+        Arc::new(move |sample: Sample| {
+            if self.matches(&sample.value.payload) {
+                callback(sample)
+            } else {
+                filtered.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+    }
+}
+
+/// A predicate selecting the samples delivered to a [`Subscriber`], see [`SubscriberBuilder::filter`].
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct SampleFilter {
+    predicate: Arc<dyn Fn(&Sample) -> bool + Send + Sync>,
+    filtered: Arc<AtomicUsize>,
+}
+
+#[zenoh_macros::unstable]
+impl SampleFilter {
+    /// Create a filter selecting the samples for which `predicate` returns true.
+    pub fn new<Predicate>(predicate: Predicate) -> Self
+    where
+        Predicate: Fn(&Sample) -> bool + Send + Sync + 'static,
+    {
+        SampleFilter {
+            predicate: Arc::new(predicate),
+            filtered: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns true if `sample` is selected by this filter.
+    pub fn matches(&self, sample: &Sample) -> bool {
+        (self.predicate)(sample)
+    }
+
+    /// Returns the number of samples rejected by this filter.
+    pub fn filtered(&self) -> usize {
+        self.filtered.load(Ordering::Relaxed)
+    }
+
+    fn wrap(self, callback: Callback<'static, Sample>) -> Callback<'static, Sample> {
+        Arc::new(move |sample: Sample| {
+            if self.matches(&sample) {
+                callback(sample)
+            } else {
+                self.filtered.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for SampleFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleFilter")
+            .field("filtered", &self.filtered())
+            .finish()
+    }
+}
+
+/// The hook called with the samples dropped by the handler of a [`Subscriber`],
+/// see [`SubscriberBuilder::on_drop`].
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct DropHook(Callback<'static, Sample>);
+
+#[zenoh_macros::unstable]
+impl DropHook {
+    /// Create a hook calling `on_drop` with each dropped sample.
+    pub fn new<OnDrop>(on_drop: OnDrop) -> Self
+    where
+        OnDrop: Fn(Sample) + Send + Sync + 'static,
+    {
+        DropHook(Arc::new(on_drop))
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for DropHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DropHook").finish_non_exhaustive()
+    }
+}
+
+/// The deduplication of the samples received by a [`Subscriber`],
+/// see [`SubscriberBuilder::deduplicate`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deduplication {
+    window: Duration,
+    strict: bool,
+}
+
+#[zenoh_macros::unstable]
+impl Deduplication {
+    /// Deduplicate the samples received at most `window` after the previous one on their key.
+    pub fn new(window: Duration) -> Self {
+        Deduplication {
+            window,
+            strict: false,
         }
     }
 
-    /// Change the subscription mode to Push.
-    #[inline]
-    pub fn push_mode(self) -> SubscriberBuilder<'a, 'b, PushMode, Handler> {
-        let SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode: _,
-            origin,
-            handler,
-        } = self;
-        SubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            mode: PushMode,
-            origin,
-            handler,
-        }
+    /// Whether to drop the samples without timestamp.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
     }
-}
 
-// Push mode
-impl<'a, Handler> Resolvable for SubscriberBuilder<'a, '_, PushMode, Handler>
-where
-    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
-    Handler::Receiver: Send,
-{
-    type To = ZResult<Subscriber<'a, Handler::Receiver>>;
+    fn wrap(self, callback: Callback<'static, Sample>) -> Callback<'static, Sample> {
+        let seen = Mutex::new(SeenTimestamps {
+            last: HashMap::new(),
+            evicted: Instant::now(),
+        });
+        Arc::new(move |sample: Sample| {
+            let Some(timestamp) = sample.timestamp else {
+                if !self.strict {
+                    callback(sample);
+                }
+                return;
+            };
+            let now = Instant::now();
+            let mut seen = zlock!(seen);
+            // Evict at most once per window, so that entries live between 1 and 2 windows
+            if now.duration_since(seen.evicted) >= self.window {
+                seen.last
+                    .retain(|_, (_, received)| now.duration_since(*received) < self.window);
+                seen.evicted = now;
+            }
+            let fresh = match seen.last.get_mut(sample.key_expr.as_keyexpr()) {
+                Some((last, _)) if timestamp <= *last => false,
+                Some(entry) => {
+                    *entry = (timestamp, now);
+                    true
+                }
+                None => {
+                    seen.last
+                        .insert(sample.key_expr.as_keyexpr().to_owned(), (timestamp, now));
+                    true
+                }
+            };
+            drop(seen);
+            if fresh {
+                callback(sample)
+            }
+        })
+    }
 }
 
-impl<'a, Handler> SyncResolve for SubscriberBuilder<'a, '_, PushMode, Handler>
-where
-    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
-    Handler::Receiver: Send,
-{
-    fn res_sync(self) -> <Self as Resolvable>::To {
-        let key_expr = self.key_expr?;
-        let session = self.session;
-        let (callback, receiver) = self.handler.into_cb_receiver_pair();
-        session
-            .declare_subscriber_inner(
-                &key_expr,
-                &None,
-                self.origin,
-                callback,
-                &SubscriberInfo {
-                    reliability: self.reliability,
-                    mode: self.mode.into(),
-                },
-            )
-            .map(|sub_state| Subscriber {
-                subscriber: SubscriberInner {
-                    session,
-                    state: sub_state,
-                    alive: true,
-                },
-                receiver,
-            })
+/// The callback notified of the changes of the matching status of a [`Subscriber`],
+/// see [`SubscriberBuilder::matching_listener`].
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct MatchingHook(Callback<'static, MatchingStatus>);
+
+#[zenoh_macros::unstable]
+impl MatchingHook {
+    /// Create a hook calling `listener` with each new matching status.
+    pub fn new<Listener>(listener: Listener) -> Self
+    where
+        Listener: Fn(MatchingStatus) + Send + Sync + 'static,
+    {
+        MatchingHook(Arc::new(listener))
     }
 }
 
-impl<'a, Handler> AsyncResolve for SubscriberBuilder<'a, '_, PushMode, Handler>
-where
-    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
-    Handler::Receiver: Send,
-{
-    type Future = Ready<Self::To>;
-
-    fn res_async(self) -> Self::Future {
-        std::future::ready(self.res_sync())
+#[zenoh_macros::unstable]
+impl fmt::Debug for MatchingHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MatchingHook").finish_non_exhaustive()
     }
 }
 
-// Pull mode
-impl<'a, Handler> Resolvable for SubscriberBuilder<'a, '_, PullMode, Handler>
-where
-    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
-    Handler::Receiver: Send,
-{
-    type To = ZResult<PullSubscriber<'a, Handler::Receiver>>;
+/// The announced publishers matching a subscriber, see [`SubscriberBuilder::matching_listener`].
+#[zenoh_macros::unstable]
+struct PublicationTracker {
+    publications: Mutex<HashSet<OwnedKeyExpr>>,
+    callback: Callback<'static, MatchingStatus>,
 }
 
-impl<'a, Handler> SyncResolve for SubscriberBuilder<'a, '_, PullMode, Handler>
-where
-    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
-    Handler::Receiver: Send,
-{
-    fn res_sync(self) -> <Self as Resolvable>::To {
-        let key_expr = self.key_expr?;
-        let session = self.session;
-        let (callback, receiver) = self.handler.into_cb_receiver_pair();
-        session
-            .declare_subscriber_inner(
-                &key_expr,
-                &None,
-                self.origin,
-                callback,
-                &SubscriberInfo {
-                    reliability: self.reliability,
-                    mode: self.mode.into(),
-                },
-            )
-            .map(|sub_state| PullSubscriber {
-                subscriber: PullSubscriberInner {
-                    inner: SubscriberInner {
-                        session,
-                        state: sub_state,
-                        alive: true,
-                    },
-                },
-                receiver,
-            })
+#[zenoh_macros::unstable]
+impl PublicationTracker {
+    fn update(&self, sample: Sample) {
+        let mut publications = zlock!(self.publications);
+        let matching = !publications.is_empty();
+        match sample.kind {
+            SampleKind::Put => publications.insert(sample.key_expr.as_keyexpr().to_owned()),
+            SampleKind::Delete => publications.remove(sample.key_expr.as_keyexpr()),
+        };
+        // Notified under the lock, so that the notifications are never reordered
+        if publications.is_empty() == matching {
+            (self.callback)(MatchingStatus {
+                matching: !matching,
+            });
+        }
     }
 }
 
-impl<'a, Handler> AsyncResolve for SubscriberBuilder<'a, '_, PullMode, Handler>
-where
-    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
-    Handler::Receiver: Send,
-{
-    type Future = Ready<Self::To>;
+/// The last timestamps received by a deduplicating subscriber, per key.
+#[zenoh_macros::unstable]
+struct SeenTimestamps {
+    last: HashMap<OwnedKeyExpr, (Timestamp, Instant)>,
+    evicted: Instant,
+}
 
-    fn res_async(self) -> Self::Future {
-        std::future::ready(self.res_sync())
+/// The stream of the samples of a [`FlumeSubscriber`] or a flume [`PullSubscriber`],
+/// created on its first poll.
+#[zenoh_macros::unstable]
+#[derive(Default)]
+pub(crate) struct SampleStream(Option<flume::r#async::RecvStream<'static, Sample>>);
+
+#[zenoh_macros::unstable]
+impl SampleStream {
+    fn poll_next(
+        &mut self,
+        receiver: &flume::Receiver<Sample>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Sample>> {
+        self.0
+            .get_or_insert_with(|| receiver.clone().into_stream())
+            .poll_next_unpin(cx)
     }
 }
 
-/// A subscriber that provides data through a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
-///
-/// Subscribers can be created from a zenoh [`Session`](crate::Session)
-/// with the [`declare_subscriber`](crate::SessionDeclarations::declare_subscriber) function
-/// and the [`with`](SubscriberBuilder::with) function
-/// of the resulting builder.
-///
-/// Subscribers are automatically undeclared when dropped.
-///
-/// # Examples
-/// ```no_run
-/// # #[tokio::main]
-/// # async fn main() {
-/// use zenoh::prelude::r#async::*;
-///
-/// let session = zenoh::open(config::peer()).res().await.unwrap();
-/// let subscriber = session
-///     .declare_subscriber("key/expression")
-///     .with(flume::bounded(32))
-///     .res()
-///     .await
-///     .unwrap();
-/// while let Ok(sample) = subscriber.recv_async().await {
-///     println!("Received: {} {}", sample.key_expr, sample.value);
-/// }
-/// # }
-/// ```
-#[non_exhaustive]
-#[derive(Debug)]
-pub struct Subscriber<'a, Receiver> {
-    pub(crate) subscriber: SubscriberInner<'a>,
-    pub receiver: Receiver,
+#[zenoh_macros::unstable]
+impl fmt::Debug for SampleStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleStream")
+            .field("polled", &self.0.is_some())
+            .finish()
+    }
 }
 
-/// A [`PullMode`] subscriber that provides data through a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
-///
-/// PullSubscribers only provide data when explicitly pulled by the
-/// application with the [`pull`](PullSubscriber::pull) function.
-/// PullSubscribers can be created from a zenoh [`Session`](crate::Session)
-/// with the [`declare_subscriber`](crate::SessionDeclarations::declare_subscriber) function,
-/// the [`with`](SubscriberBuilder::with) function
-/// and the [`pull_mode`](SubscriberBuilder::pull_mode) function
-/// of the resulting builder.
-///
-/// Subscribers are automatically undeclared when dropped.
-///
-/// # Examples
-/// ```
-/// # #[tokio::main]
-/// # async fn main() {
-/// use zenoh::prelude::r#async::*;
-///
-/// let session = zenoh::open(config::peer()).res().await.unwrap();
-/// let subscriber = session
-///     .declare_subscriber("key/expression")
-///     .with(flume::bounded(32))
-///     .pull_mode()
-///     .res()
-///     .await
-///     .unwrap();
-/// subscriber.pull();
-/// # }
-/// ```
-#[non_exhaustive]
-pub struct PullSubscriber<'a, Receiver> {
-    pub(crate) subscriber: PullSubscriberInner<'a>,
-    pub receiver: Receiver,
-}
+/// The samples of the subscriber, until it is undeclared.
+#[zenoh_macros::unstable]
+impl Stream for FlumeSubscriber<'_> {
+    type Item = Sample;
 
-impl<'a, Receiver> Deref for PullSubscriber<'a, Receiver> {
-    type Target = Receiver;
-    fn deref(&self) -> &Self::Target {
-        &self.receiver
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Sample>> {
+        let this = self.get_mut();
+        this.subscriber.stream.poll_next(&this.receiver, cx)
     }
 }
 
-impl<'a, Receiver> DerefMut for PullSubscriber<'a, Receiver> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.receiver
+/// The samples of the subscriber, as they are pulled, until it is undeclared.
+#[zenoh_macros::unstable]
+impl Stream for PullSubscriber<'_, flume::Receiver<Sample>> {
+    type Item = Sample;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Sample>> {
+        let this = self.get_mut();
+        this.subscriber.inner.stream.poll_next(&this.receiver, cx)
     }
 }
 
-impl<'a, Receiver> PullSubscriber<'a, Receiver> {
-    /// Pull available data for a [`PullSubscriber`].
+/// A [`Subscriber`] that provides data through a `flume` channel.
+pub type FlumeSubscriber<'a> = Subscriber<'a, flume::Receiver<Sample>>;
+
+#[zenoh_macros::unstable]
+impl<'a> FlumeSubscriber<'a> {
+    /// Convert this subscriber into a stream of its samples, which keeps the subscription
+    /// declared until it is dropped.
+    ///
+    /// FlumeSubscribers are streams themselves: polling a `&mut FlumeSubscriber` doesn't
+    /// undeclare the subscription once dropped.
     ///
     /// # Examples
     /// ```
     /// # #[tokio::main]
     /// # async fn main() {
+    /// use futures::prelude::*;
     /// use zenoh::prelude::r#async::*;
-    /// use zenoh::subscriber::SubMode;
     ///
-    /// let session = zenoh::open(config::peer()).res().await.unwrap();
-    /// let subscriber = session
-    ///     .declare_subscriber("key/expression")
-    ///     .with(flume::bounded(32))
-    ///     .pull_mode()
-    ///     .res()
-    ///     .await
-    ///     .unwrap();
-    /// subscriber.pull();
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// tokio::spawn(subscriber.into_stream().for_each(|sample| async move {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }));
     /// # }
     /// ```
-    #[inline]
-    pub fn pull(&self) -> impl Resolve<ZResult<()>> + '_ {
-        self.subscriber.pull()
+    pub fn into_stream(self) -> impl Stream<Item = Sample> + Send + Unpin + 'a {
+        self
     }
 
-    /// Close a [`PullSubscriber`].
-    ///
-    /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
-    /// close the Subscriber asynchronously.
+    /// Returns up to `max` of the samples currently queued for this subscriber,
+    /// in their arrival order, without blocking.
     ///
     /// # Examples
     /// ```
@@ -755,30 +4134,53 @@ impl<'a, Receiver> PullSubscriber<'a, Receiver> {
     /// use zenoh::prelude::r#async::*;
     ///
     /// let session = zenoh::open(config::peer()).res().await.unwrap();
-    /// let subscriber = session.declare_subscriber("key/expression")
-    ///     .pull_mode()
-    ///     .res()
-    ///     .await
-    ///     .unwrap();
-    /// subscriber.undeclare().res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// for sample in subscriber.drain(64) {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
     /// # }
     /// ```
-    #[inline]
-    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
-        self.subscriber.undeclare()
+    pub fn drain(&self, max: usize) -> Vec<Sample> {
+        let mut samples = Vec::with_capacity(max.min(self.receiver.len()));
+        self.drain_into(&mut samples, max);
+        samples
     }
-}
 
-impl<'a, Receiver> Subscriber<'a, Receiver> {
-    /// Returns the [`KeyExpr`] this Subscriber subscribes to.
-    pub fn key_expr(&self) -> &KeyExpr<'static> {
-        &self.subscriber.state.key_expr
+    /// Appends up to `max` of the samples currently queued for this subscriber to `samples`,
+    /// in their arrival order, without blocking.
+    ///
+    /// Returns the number of samples appended.
+    pub fn drain_into(&self, samples: &mut Vec<Sample>, max: usize) -> usize {
+        let len = samples.len();
+        samples.extend(self.receiver.try_iter().take(max));
+        samples.len() - len
     }
 
-    /// Close a [`Subscriber`].
+    /// Waits for a sample for at most `timeout`, then returns it along with up to `max - 1`
+    /// of the samples queued after it.
     ///
-    /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
-    /// close the Subscriber asynchronously.
+    /// Returns an empty `Vec` if `timeout` elapses, and an error if the channel of this
+    /// subscriber is disconnected.
+    pub fn drain_timeout(&self, max: usize, timeout: Duration) -> ZResult<Vec<Sample>> {
+        if max == 0 {
+            return Ok(vec![]);
+        }
+        let first = match self.receiver.recv_timeout(timeout) {
+            Ok(first) => first,
+            Err(flume::RecvTimeoutError::Timeout) => return Ok(vec![]),
+            Err(flume::RecvTimeoutError::Disconnected) => return Err(ChannelDisconnected.into()),
+        };
+        let mut samples = Vec::with_capacity(max.min(self.receiver.len() + 1));
+        samples.push(first);
+        self.drain_into(&mut samples, max - 1);
+        Ok(samples)
+    }
+
+    /// Undeclare this subscriber, then return the samples still queued for it, in their
+    /// arrival order.
+    ///
+    /// This is the graceful-shutdown counterpart of [`undeclare`](Subscriber::undeclare),
+    /// which drops the queued samples along with the receiver.
     ///
     /// # Examples
     /// ```
@@ -787,37 +4189,200 @@ impl<'a, Receiver> Subscriber<'a, Receiver> {
     /// use zenoh::prelude::r#async::*;
     ///
     /// let session = zenoh::open(config::peer()).res().await.unwrap();
-    /// let subscriber = session.declare_subscriber("key/expression")
-    ///     .res()
-    ///     .await
-    ///     .unwrap();
-    /// subscriber.undeclare().res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// for sample in subscriber.close_and_drain().res().await.unwrap() {
+    ///     println!("Received: {} {}", sample.key_expr, sample.value);
+    /// }
     /// # }
     /// ```
-    #[inline]
-    pub fn undeclare(self) -> SubscriberUndeclaration<'a> {
-        self.subscriber.undeclare()
+    pub fn close_and_drain(self) -> impl Resolve<ZResult<Vec<Sample>>> + 'a {
+        let Subscriber {
+            subscriber,
+            receiver,
+        } = self;
+        let undeclaration = subscriber.undeclare();
+        ResolveClosure::new(move || {
+            undeclaration.res_sync()?;
+            Ok(receiver.drain().collect())
+        })
     }
 }
 
-impl<'a, T> Undeclarable<(), SubscriberUndeclaration<'a>> for Subscriber<'a, T> {
-    fn undeclare_inner(self, _: ()) -> SubscriberUndeclaration<'a> {
-        Undeclarable::undeclare_inner(self.subscriber, ())
-    }
+/// A [`Subscriber`] that provides data through a `crossbeam_channel` channel.
+#[cfg(feature = "crossbeam-channel")]
+pub type CrossbeamSubscriber<'a> = Subscriber<'a, crossbeam_channel::Receiver<Sample>>;
+
+/// A [`Subscriber`] that provides samples deserialized as `T` through a `flume` channel.
+#[zenoh_macros::unstable]
+pub type TypedSubscriber<'a, T> = Subscriber<'a, flume::Receiver<ZResult<(KeyExpr<'static>, T)>>>;
+
+/// A [`Subscriber`] dispatching its samples to callbacks according to their key with a [`Demux`].
+#[zenoh_macros::unstable]
+pub type DemuxSubscriber<'a> = Subscriber<'a, Demux>;
+
+/// A [`Subscriber`] that keeps the latest sample received on each key in a [`SampleCache`].
+#[zenoh_macros::unstable]
+pub type CachedSubscriber<'a> = Subscriber<'a, SampleCache>;
+
+/// A type-erased handle on a callback [`Subscriber`] declared with
+/// [`Session::subscribe_dyn`](crate::Session::subscribe_dyn).
+///
+/// Unlike [`Subscriber`], it isn't tied to the lifetime of the [`Session`](crate::Session) it was
+/// declared on, and can thus be handed over across `dyn Trait` boundaries.
+/// The subscriber is automatically undeclared when the handle is dropped.
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct DynSubscriberHandle {
+    pub(crate) subscriber: Subscriber<'static, ()>,
 }
 
-impl<Receiver> Deref for Subscriber<'_, Receiver> {
-    type Target = Receiver;
+#[zenoh_macros::unstable]
+impl DynSubscriberHandle {
+    /// Returns the [`KeyExpr`] the underlying Subscriber subscribes to.
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.receiver
+    /// Close the underlying Subscriber.
+    pub fn close(self) -> ZResult<()> {
+        self.subscriber.undeclare().res_sync()
     }
 }
-impl<Receiver> DerefMut for Subscriber<'_, Receiver> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.receiver
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use std::collections::{BTreeSet, HashMap};
+    use zenoh_keyexpr::fuzzer::KeyExprFuzzer;
+
+    fn sub_state(id: Id, key_expr: zenoh_keyexpr::OwnedKeyExpr) -> Arc<SubscriberState> {
+        Arc::new(SubscriberState {
+            id,
+            key_expr: key_expr.into(),
+            scope: None,
+            origin: Locality::Any,
+            info: RwLock::new(SubscriberInfo::default()),
+            callback: Arc::new(|_| {}),
+            #[cfg(feature = "unstable")]
+            pause: SubscriberPause::wrap(Arc::new(|_| {})).1,
+            #[cfg(feature = "unstable")]
+            payload_filter: None,
+            #[cfg(feature = "unstable")]
+            metrics: OnceLock::new(),
+        })
     }
-}
 
-/// A [`Subscriber`] that provides data through a `flume` channel.
-pub type FlumeSubscriber<'a> = Subscriber<'a, flume::Receiver<Sample>>;
+    #[test]
+    fn subscriber_tree_matches_linear_scan() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let keys: Vec<_> = KeyExprFuzzer(rand::rngs::StdRng::seed_from_u64(7))
+            .take(1000)
+            .collect();
+        let mut tree = SubscriberTree::default();
+        let mut linear: HashMap<Id, Arc<SubscriberState>> = HashMap::new();
+        for (id, key_expr) in keys.iter().enumerate() {
+            let sub = sub_state(id, key_expr.clone());
+            tree.insert(sub.clone());
+            linear.insert(id, sub);
+            // Several subscribers on the same key expression
+            if rng.gen_bool(0.1) {
+                let sub = sub_state(id + keys.len(), key_expr.clone());
+                tree.insert(sub.clone());
+                linear.insert(sub.id, sub);
+            }
+        }
+
+        let check = |tree: &SubscriberTree, linear: &HashMap<Id, Arc<SubscriberState>>| {
+            // Publications are mostly on key expressions without wildcards
+            let concrete = KeyExprFuzzer(rand::rngs::StdRng::seed_from_u64(17))
+                .filter(|key_expr| !key_expr.is_wild())
+                .take(300);
+            let mut matches = 0;
+            for target in KeyExprFuzzer(rand::rngs::StdRng::seed_from_u64(13))
+                .take(300)
+                .chain(concrete)
+                .chain(keys.iter().take(100).cloned())
+            {
+                let expected: BTreeSet<Id> = linear
+                    .values()
+                    .filter(|sub| sub.key_expr.intersects(&target))
+                    .map(|sub| sub.id)
+                    .collect();
+                let mut matched = vec![];
+                matched.extend(tree.matching(&target).map(|sub| sub.id));
+                assert_eq!(matched.len(), expected.len(), "on {target}");
+                assert_eq!(matched.into_iter().collect::<BTreeSet<_>>(), expected);
+                matches += expected.len();
+            }
+            assert!(matches > 0);
+        };
+        check(&tree, &linear);
+
+        // Removals, including the pruning of the emptied nodes
+        let ids: Vec<Id> = linear.keys().copied().collect();
+        for id in ids {
+            if rng.gen_bool(0.7) {
+                let sub = linear.remove(&id).unwrap();
+                tree.remove(&sub);
+            }
+        }
+        check(&tree, &linear);
+
+        for (id, key_expr) in keys.iter().enumerate().take(200) {
+            let sub = sub_state(id + 2 * keys.len(), key_expr.clone());
+            tree.insert(sub.clone());
+            linear.insert(sub.id, sub);
+        }
+        check(&tree, &linear);
+
+        tree.clear();
+        assert_eq!(tree.matching(&keys[0]).count(), 0);
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn pull_period() {
+        // Zero
+        assert_eq!(
+            PullPeriod::try_new(Duration::ZERO),
+            Err(InvalidPullPeriod {
+                period: Duration::ZERO
+            })
+        );
+        assert_eq!(
+            PullPeriod::saturating_from(Duration::ZERO).as_duration(),
+            MIN_PULL_PERIOD
+        );
+
+        // Sub-microsecond
+        let period = Duration::from_nanos(500);
+        assert_eq!(
+            PullPeriod::try_from(period),
+            Err(InvalidPullPeriod { period })
+        );
+        assert_eq!(
+            Duration::from(PullPeriod::saturating_from(period)),
+            MIN_PULL_PERIOD
+        );
+        assert_eq!(PullPeriod::saturating_from(period).to_string(), "1ms");
+
+        // Over u32::MAX seconds
+        let period = Duration::new(u32::MAX as u64 + 1, 1_001_000);
+        assert_eq!(PullPeriod::try_new(period).unwrap().as_duration(), period);
+        assert_eq!(PullPeriod::saturating_from(period).as_duration(), period);
+        assert_eq!(
+            PullPeriod::try_new(period).unwrap().to_string(),
+            "49710d 6h 28min 16s 1ms 1µs"
+        );
+        assert!(PullPeriod::try_new(Duration::MAX).is_ok());
+
+        assert_eq!(MIN_PULL_PERIOD.try_into(), Ok(PullPeriod(MIN_PULL_PERIOD)));
+        assert_eq!(
+            PullPeriod::try_new(Duration::from_secs(90))
+                .unwrap()
+                .to_string(),
+            "1min 30s"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Fault injection for chaos testing.
+//!
+//! This module is only available with the `testing` feature, meant for the integration tests
+//! of applications built on zenoh. Without this feature, no fault injection code is compiled in.
+//!
+//! The [`FaultInjector`] of a session (see [`Session::fault_injector`](crate::Session::fault_injector))
+//! applies [`Faults`] to the data messages (puts and deletes) the session sends to the network.
+//! Deliveries to the subscribers of the same session are unaffected.
+//!
+//! The initial faults are read from the `fault_injection` section of the configuration:
+//! ```json5
+//! fault_injection: {
+//!   drop_rate: 0.1,
+//!   duplicate_rate: 0.05,
+//!   delay_min_ms: 10,
+//!   delay_max_ms: 50,
+//!   close_after: 1000,
+//! }
+//! ```
+use crate::net::primitives::Primitives;
+use crate::net::routing::dispatcher::face::Face;
+use crate::net::runtime::Runtime;
+use rand::Rng;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use zenoh_config::FaultInjectionConf;
+use zenoh_core::{zread, zwrite};
+use zenoh_protocol::network::Push;
+
+/// The delay applied to the outgoing data messages.
+///
+/// Delayed messages are sent from a separate task, so they may be reordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delay {
+    /// Delay every message by the same duration.
+    Fixed(Duration),
+    /// Delay every message by a duration uniformly distributed between `min` and `max`.
+    Uniform { min: Duration, max: Duration },
+}
+
+impl Delay {
+    fn sample(&self) -> Duration {
+        match *self {
+            Delay::Fixed(delay) => delay,
+            Delay::Uniform { min, max } if min < max => rand::thread_rng().gen_range(min..=max),
+            Delay::Uniform { min, .. } => min,
+        }
+    }
+}
+
+/// The faults applied by a [`FaultInjector`].
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use zenoh::testing::{Delay, Faults};
+///
+/// let faults = Faults::default()
+///     .drop_rate(0.1)
+///     .delay(Delay::Fixed(Duration::from_millis(10)))
+///     .close_after(1000);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Faults {
+    drop_rate: f64,
+    duplicate_rate: f64,
+    delay: Option<Delay>,
+    close_after: Option<usize>,
+}
+
+impl Faults {
+    /// Drop outgoing data messages with the given probability, between 0 and 1 (0 by default).
+    #[inline]
+    pub fn drop_rate(mut self, rate: f64) -> Self {
+        self.drop_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Send outgoing data messages twice with the given probability, between 0 and 1 (0 by default).
+    #[inline]
+    pub fn duplicate_rate(mut self, rate: f64) -> Self {
+        self.duplicate_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Delay outgoing data messages (not delayed by default).
+    #[inline]
+    pub fn delay(mut self, delay: Delay) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Close the transports of the session once it sent `count` data messages (never by default).
+    #[inline]
+    pub fn close_after(mut self, count: usize) -> Self {
+        self.close_after = Some(count);
+        self
+    }
+}
+
+impl From<&FaultInjectionConf> for Faults {
+    fn from(conf: &FaultInjectionConf) -> Self {
+        let mut faults = Faults::default()
+            .drop_rate(conf.drop_rate().unwrap_or(0.0))
+            .duplicate_rate(conf.duplicate_rate().unwrap_or(0.0));
+        match (conf.delay_min_ms(), conf.delay_max_ms()) {
+            (None, None) => {}
+            (min, max) => {
+                let min = Duration::from_millis(min.unwrap_or(0));
+                let max = Duration::from_millis(max.unwrap_or(0)).max(min);
+                faults = faults.delay(Delay::Uniform { min, max });
+            }
+        }
+        if let Some(count) = conf.close_after() {
+            faults = faults.close_after(*count);
+        }
+        faults
+    }
+}
+
+/// Applies [`Faults`] to the outgoing data messages of a session.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::testing::Faults;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// session.fault_injector().set(Faults::default().drop_rate(0.5));
+/// // Half of the publications are lost
+/// session.put("key/expression", "value").res().await.unwrap();
+/// session.fault_injector().clear();
+/// # }
+/// ```
+pub struct FaultInjector {
+    faults: RwLock<Faults>,
+    sent: AtomicUsize,
+    runtime: Runtime,
+}
+
+impl FaultInjector {
+    pub(crate) fn new(runtime: Runtime) -> Self {
+        let faults = Faults::from(&runtime.config().lock().fault_injection);
+        FaultInjector {
+            faults: RwLock::new(faults),
+            sent: AtomicUsize::new(0),
+            runtime,
+        }
+    }
+
+    /// Replace the applied faults, resetting the count of sent messages.
+    pub fn set(&self, faults: Faults) {
+        *zwrite!(self.faults) = faults;
+        self.sent.store(0, Ordering::SeqCst);
+    }
+
+    /// Stop applying faults.
+    pub fn clear(&self) {
+        self.set(Faults::default())
+    }
+
+    /// Returns the applied faults.
+    pub fn faults(&self) -> Faults {
+        *zread!(self.faults)
+    }
+
+    /// Returns the number of data messages sent since the faults were last set.
+    pub fn sent(&self) -> usize {
+        self.sent.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn send_push(&self, primitives: &Arc<Face>, msg: Push) {
+        let faults = self.faults();
+        let sent = self.sent.fetch_add(1, Ordering::SeqCst) + 1;
+        if faults == Faults::default() {
+            return primitives.send_push(msg);
+        }
+        let mut rng = rand::thread_rng();
+        if faults.close_after == Some(sent) {
+            tracing::debug!(
+                "Fault injection: closing transports after {} messages",
+                sent
+            );
+            let manager = self.runtime.manager().clone();
+            zenoh_runtime::ZRuntime::Net.spawn(async move {
+                for transport in manager.get_transports_unicast().await {
+                    let _ = transport.close().await;
+                }
+                for transport in manager.get_transports_multicast().await {
+                    let _ = transport.close().await;
+                }
+            });
+        }
+        if rng.gen_bool(faults.drop_rate) {
+            tracing::trace!("Fault injection: dropping {:?}", msg.wire_expr);
+            return;
+        }
+        let copies = if rng.gen_bool(faults.duplicate_rate) {
+            2
+        } else {
+            1
+        };
+        match faults.delay {
+            Some(delay) => {
+                for _ in 0..copies {
+                    let primitives = primitives.clone();
+                    let msg = msg.clone();
+                    let delay = delay.sample();
+                    zenoh_runtime::ZRuntime::Net.spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        primitives.send_push(msg);
+                    });
+                }
+            }
+            None => {
+                for _ in 1..copies {
+                    primitives.send_push(msg.clone());
+                }
+                primitives.send_push(msg);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for FaultInjector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FaultInjector")
+            .field("faults", &self.faults())
+            .field("sent", &self.sent())
+            .finish()
+    }
+}
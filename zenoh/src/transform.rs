@@ -0,0 +1,264 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Sample transformers.
+//!
+//! A [`TransformChain`] applies an ordered list of [`SampleTransformer`]s to the samples
+//! received by a subscriber (see [`SubscriberBuilder::transform`](crate::subscriber::SubscriberBuilder::transform))
+//! or sent by a publisher (see [`Publisher::transform_out`](crate::publication::Publisher::transform_out)).
+use crate::handlers::Callback;
+use crate::prelude::Sample;
+#[cfg(feature = "transform_deflate")]
+use crate::prelude::{Encoding, SplitBuffer};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The encoding suffix marking payloads compressed with [`DeflateCompressor`].
+#[cfg(feature = "transform_deflate")]
+pub const DEFLATE_ENCODING_SUFFIX: &str = ";deflate";
+
+/// An error returned by a [`SampleTransformer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformError {
+    message: String,
+}
+
+impl TransformError {
+    pub fn new<T: fmt::Display>(message: T) -> Self {
+        TransformError {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+/// A transformation of [`Sample`]s, such as decompression or decryption.
+pub trait SampleTransformer: Send + Sync {
+    fn transform(&self, sample: Sample) -> Result<Sample, TransformError>;
+}
+
+impl<F> SampleTransformer for F
+where
+    F: Fn(Sample) -> Result<Sample, TransformError> + Send + Sync,
+{
+    fn transform(&self, sample: Sample) -> Result<Sample, TransformError> {
+        self(sample)
+    }
+}
+
+/// An ordered chain of [`SampleTransformer`]s.
+///
+/// Samples failing one of the transformers are dropped. The failures are counted
+/// (see [`TransformChain::errors`]) and reported to the [error callback](TransformChain::on_error), if any.
+/// Clones of a `TransformChain` share the same error count.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::transform::{NoopTransformer, TransformChain};
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let chain = TransformChain::new()
+///     .then(NoopTransformer)
+///     .on_error(|e| eprintln!("Dropped sample: {}", e));
+/// let subscriber = session
+///     .declare_subscriber("key/expression")
+///     .transform(chain.clone())
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct TransformChain {
+    transformers: Vec<Arc<dyn SampleTransformer>>,
+    errors: Arc<AtomicUsize>,
+    on_error: Option<Arc<dyn Fn(TransformError) + Send + Sync>>,
+}
+
+impl TransformChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `transformer` to this chain.
+    pub fn then<T>(mut self, transformer: T) -> Self
+    where
+        T: SampleTransformer + 'static,
+    {
+        self.transformers.push(Arc::new(transformer));
+        self
+    }
+
+    /// Set a callback receiving the errors of the transformers of this chain.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(TransformError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    /// Returns the number of samples that failed to be transformed by this chain.
+    pub fn errors(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Apply the transformers of this chain to `sample`, in order.
+    pub fn apply(&self, sample: Sample) -> Result<Sample, TransformError> {
+        let result = self
+            .transformers
+            .iter()
+            .try_fold(sample, |sample, transformer| transformer.transform(sample));
+        if let Err(e) = &result {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            if let Some(on_error) = &self.on_error {
+                on_error(e.clone());
+            }
+        }
+        result
+    }
+
+    pub(crate) fn wrap(self, callback: Callback<'static, Sample>) -> Callback<'static, Sample> {
+        Arc::new(move |sample: Sample| {
+            if let Ok(sample) = self.apply(sample) {
+                callback(sample)
+            }
+        })
+    }
+}
+
+impl fmt::Debug for TransformChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformChain")
+            .field("transformers", &self.transformers.len())
+            .field("errors", &self.errors())
+            .finish()
+    }
+}
+
+/// A [`SampleTransformer`] compressing payloads with deflate.
+///
+/// [`DEFLATE_ENCODING_SUFFIX`] is appended to the encoding of the compressed samples.
+/// Requires the `transform_deflate` feature.
+#[cfg(feature = "transform_deflate")]
+#[derive(Debug, Clone, Copy)]
+pub struct DeflateCompressor {
+    level: u8,
+}
+
+#[cfg(feature = "transform_deflate")]
+impl DeflateCompressor {
+    /// Create a compressor with the given compression level, between 0 (no compression)
+    /// and 10 (best compression).
+    pub fn new(level: u8) -> Self {
+        DeflateCompressor {
+            level: level.min(10),
+        }
+    }
+}
+
+#[cfg(feature = "transform_deflate")]
+impl Default for DeflateCompressor {
+    fn default() -> Self {
+        DeflateCompressor::new(6)
+    }
+}
+
+#[cfg(feature = "transform_deflate")]
+impl SampleTransformer for DeflateCompressor {
+    fn transform(&self, mut sample: Sample) -> Result<Sample, TransformError> {
+        let payload = sample.value.payload.contiguous();
+        let compressed = miniz_oxide::deflate::compress_to_vec(&payload, self.level);
+        let encoding = sample
+            .value
+            .encoding
+            .clone()
+            .with_suffix(DEFLATE_ENCODING_SUFFIX)
+            .map_err(TransformError::new)?;
+        sample.value.payload = compressed.into();
+        sample.value.encoding = encoding;
+        Ok(sample)
+    }
+}
+
+/// A [`SampleTransformer`] decompressing the payloads compressed with [`DeflateCompressor`].
+///
+/// Samples whose encoding doesn't end with [`DEFLATE_ENCODING_SUFFIX`] are left untouched.
+/// Requires the `transform_deflate` feature.
+#[cfg(feature = "transform_deflate")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeflateDecompressor;
+
+#[cfg(feature = "transform_deflate")]
+impl SampleTransformer for DeflateDecompressor {
+    fn transform(&self, mut sample: Sample) -> Result<Sample, TransformError> {
+        let Some(suffix) = sample
+            .value
+            .encoding
+            .suffix()
+            .strip_suffix(DEFLATE_ENCODING_SUFFIX)
+        else {
+            return Ok(sample);
+        };
+        let encoding = Encoding::new(*sample.value.encoding.prefix() as u8, suffix.to_string())
+            .map_err(TransformError::new)?;
+        let payload = miniz_oxide::inflate::decompress_to_vec(&sample.value.payload.contiguous())
+            .map_err(|e| {
+            TransformError::new(format!(
+                "Unable to decompress sample on {}: {}",
+                sample.key_expr, e
+            ))
+        })?;
+        sample.value.payload = payload.into();
+        sample.value.encoding = encoding;
+        Ok(sample)
+    }
+}
+
+/// A [`SampleTransformer`] leaving samples untouched.
+///
+/// This is meant as a template for user-defined transformers, such as encryption:
+/// ```
+/// use zenoh::prelude::sync::*;
+/// use zenoh::transform::{SampleTransformer, TransformError};
+///
+/// struct Xor(u8);
+///
+/// impl SampleTransformer for Xor {
+///     fn transform(&self, mut sample: Sample) -> Result<Sample, TransformError> {
+///         let payload: Vec<u8> = sample.value.payload.contiguous().iter().map(|b| b ^ self.0).collect();
+///         sample.value.payload = payload.into();
+///         Ok(sample)
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopTransformer;
+
+impl SampleTransformer for NoopTransformer {
+    fn transform(&self, sample: Sample) -> Result<Sample, TransformError> {
+        Ok(sample)
+    }
+}
@@ -17,13 +17,14 @@
 use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
 use std::borrow::Cow;
 use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
 #[cfg(feature = "shared-memory")]
 use std::sync::Arc;
 
 use zenoh_collections::Properties;
 use zenoh_result::ZError;
 
-use crate::buffers::ZBuf;
+use crate::buffers::{buffer::Buffer, ZBuf};
 use crate::prelude::{Encoding, KnownEncoding, Sample, SplitBuffer};
 #[cfg(feature = "shared-memory")]
 use zenoh_shm::SharedMemoryBuf;
@@ -61,6 +62,37 @@ impl Value {
         self.encoding = encoding;
         self
     }
+
+    /// Returns the length of the payload of this zenoh Value, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.payload.len()
+    }
+
+    /// Returns true if the payload of this zenoh Value is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+}
+
+/// Two [`Value`]s are equal if their encodings and the bytes of their payloads are equal,
+/// regardless of how their payloads are fragmented.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoding == other.encoding && self.payload == other.payload
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self.encoding.prefix() as u8).hash(state);
+        self.encoding.suffix().hash(state);
+        // Hash the payload as a whole, so that fragmented payloads hash like contiguous ones
+        self.payload.contiguous().hash(state);
+    }
 }
 
 impl std::fmt::Debug for Value {
@@ -703,3 +735,49 @@ impl TryFrom<Value> for Properties {
         Self::try_from(&v)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use zenoh_buffers::ZSlice;
+
+    fn hash(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn fragmented(slices: &[&[u8]]) -> ZBuf {
+        let mut zbuf = ZBuf::empty();
+        for slice in slices {
+            zbuf.push_zslice(ZSlice::from(slice.to_vec()));
+        }
+        zbuf
+    }
+
+    #[test]
+    fn value_eq_ignores_fragmentation() {
+        let contiguous = Value::from(b"hello world".to_vec());
+        let fragmented = Value::new(fragmented(&[b"hel", b"", b"lo w", b"orld"]));
+        assert_eq!(contiguous, fragmented);
+        assert_eq!(hash(&contiguous), hash(&fragmented));
+        assert_eq!(fragmented.len(), 11);
+        assert!(!fragmented.is_empty());
+        assert!(Value::empty().is_empty());
+    }
+
+    #[test]
+    fn value_eq_compares_encoding() {
+        let octets = Value::from(b"hello".to_vec());
+        let text = octets.clone().encoding(Encoding::TEXT_PLAIN);
+        assert_ne!(octets, text);
+        assert_ne!(
+            text,
+            text.clone()
+                .encoding(Encoding::TEXT_PLAIN.with_suffix(";charset=utf-8").unwrap())
+        );
+        assert_ne!(octets, Value::from(b"hellO".to_vec()));
+        assert_ne!(octets, Value::from(b"hell".to_vec()));
+    }
+}
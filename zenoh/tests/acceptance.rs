@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+async fn open_listener(endpoint: &str, config: &mut Config) -> Session {
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.set_enabled(true).unwrap();
+    ztimeout!(zenoh::open(config.clone()).res_async()).unwrap()
+}
+
+async fn open_connecter(endpoint: &str) -> Session {
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async()).unwrap()
+}
+
+async fn peers(session: &Session) -> Vec<ZenohId> {
+    ztimeout!(session.info().peers_zid().res_async()).collect()
+}
+
+async fn rejected_sessions(session: &Session) -> u64 {
+    let replies = ztimeout!(session.get(format!("@/peer/{}", session.zid())).res_async()).unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    let info: serde_json::Value =
+        serde_json::from_slice(&reply.sample.unwrap().value.payload.contiguous()).unwrap();
+    info["rejected_sessions"].as_u64().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn max_sessions_refuses_extra_peer() {
+    zenoh_util::try_init_log_from_env();
+    let endpoint = "tcp/127.0.0.1:17480";
+
+    let mut config = config::peer();
+    config.transport.unicast.set_max_sessions(1).unwrap();
+    let listener = open_listener(endpoint, &mut config).await;
+
+    let peer01 = open_connecter(endpoint).await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(peers(&listener).await, vec![peer01.zid()]);
+
+    let peer02 = open_connecter(endpoint).await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(peers(&listener).await, vec![peer01.zid()]);
+    assert!(peers(&peer02).await.is_empty());
+    assert!(rejected_sessions(&listener).await >= 1);
+
+    // The accepted peer keeps communicating with the listener
+    let subscriber = ztimeout!(peer01.declare_subscriber("test/acceptance").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(listener.put("test/acceptance", "value").res_async()).unwrap();
+    assert!(ztimeout!(subscriber.recv_async()).is_ok());
+    ztimeout!(subscriber.undeclare().res_async()).unwrap();
+
+    ztimeout!(peer02.close().res_async()).unwrap();
+    ztimeout!(peer01.close().res_async()).unwrap();
+    ztimeout!(listener.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acceptance_policy_refuses_denied_peer() {
+    zenoh_util::try_init_log_from_env();
+    let endpoint = "tcp/127.0.0.1:17481";
+
+    let denied = ZenohId::rand();
+    let mut config = config::peer();
+    config
+        .transport
+        .unicast
+        .acceptance
+        .set_denied_zids(vec![denied])
+        .unwrap();
+    let listener = open_listener(endpoint, &mut config).await;
+
+    let mut peer_config = config::peer();
+    peer_config.set_id(denied).unwrap();
+    peer_config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    peer_config
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let peer01 = ztimeout!(zenoh::open(peer_config).res_async()).unwrap();
+    let peer02 = open_connecter(endpoint).await;
+    tokio::time::sleep(SLEEP).await;
+
+    assert_eq!(peers(&listener).await, vec![peer02.zid()]);
+    assert!(peers(&peer01).await.is_empty());
+    assert!(rejected_sessions(&listener).await >= 1);
+
+    ztimeout!(peer02.close().res_async()).unwrap();
+    ztimeout!(peer01.close().res_async()).unwrap();
+    ztimeout!(listener.close().res_async()).unwrap();
+}
@@ -0,0 +1,64 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn put_with_ack() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    let zenoh = zenoh::open(Config::default()).res().unwrap().into_arc();
+    let acker = zenoh.clone();
+    let sub = zenoh
+        .declare_subscriber("test/ack")
+        .callback(move |sample| {
+            assert!(sample.ack_key_expr().is_some());
+            sample.ack(&acker).res().unwrap();
+        })
+        .res()
+        .unwrap();
+    let publisher = zenoh.declare_publisher("test/ack").res().unwrap();
+
+    let acks = publisher
+        .put_with_ack("value")
+        .timeout(Duration::from_secs(1))
+        .res()
+        .unwrap();
+    assert_eq!(acks, 1);
+
+    // A single acknowledging session can't satisfy two distinct acknowledgers.
+    assert!(publisher
+        .put_with_ack("value")
+        .acks(2)
+        .timeout(Duration::from_millis(500))
+        .res()
+        .is_err());
+
+    sub.undeclare().res().unwrap();
+    assert!(publisher
+        .put_with_ack("value")
+        .timeout(Duration::from_millis(500))
+        .res()
+        .is_err());
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn ack_without_request() {
+    use zenoh::prelude::sync::*;
+
+    let zenoh = zenoh::open(Config::default()).res().unwrap();
+    let sample = Sample::try_from("test/ack", "value").unwrap();
+    assert!(sample.ack_key_expr().is_none());
+    assert!(sample.ack(&zenoh).res().is_err());
+}
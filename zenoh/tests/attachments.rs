@@ -123,3 +123,73 @@ fn queries() {
         }
     }
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn through_router() {
+    use std::time::Duration;
+    use zenoh::{prelude::r#async::*, sample::Attachment};
+    use zenoh_core::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const KEY_EXPR: &str = "test/attachment/routed";
+
+    zenoh_util::try_init_log_from_env();
+    let endpoint: EndPoint = "tcp/127.0.0.1:18003".parse().unwrap();
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec![endpoint.clone()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let router = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let publisher = ztimeout!(zenoh::open(config::client([endpoint.clone()])).res_async()).unwrap();
+    let subscriber = ztimeout!(zenoh::open(config::client([endpoint])).res_async()).unwrap();
+
+    let sub = ztimeout!(subscriber
+        .declare_subscriber(KEY_EXPR)
+        .await_routed(TIMEOUT)
+        .res_async())
+    .unwrap();
+
+    // The entries are forwarded unchanged, in their order, duplicated keys included
+    let mut attachment = Attachment::new();
+    attachment.insert("trace-id", "0af7651916cd43dd8448eb211c80319c");
+    attachment.insert("claim", "read");
+    attachment.insert("claim", "write");
+    ztimeout!(publisher
+        .put(KEY_EXPR, "with attachment")
+        .with_attachment(attachment)
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    let entries: Vec<(Vec<u8>, Vec<u8>)> = sample
+        .attachment()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| (k.as_slice().to_vec(), v.as_slice().to_vec()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (
+                b"trace-id".to_vec(),
+                b"0af7651916cd43dd8448eb211c80319c".to_vec()
+            ),
+            (b"claim".to_vec(), b"read".to_vec()),
+            (b"claim".to_vec(), b"write".to_vec()),
+        ]
+    );
+
+    // Empty attachments are not sent
+    ztimeout!(publisher
+        .put(KEY_EXPR, "with empty attachment")
+        .with_attachment(Attachment::new())
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.attachment().is_none());
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    ztimeout!(subscriber.close().res_async()).unwrap();
+    ztimeout!(publisher.close().res_async()).unwrap();
+    ztimeout!(router.close().res_async()).unwrap();
+}
@@ -0,0 +1,158 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod bridge {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::bridge::{BridgeDirection, BridgeRule, BridgeRuleStats};
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    fn open(listen: Option<&str>, connect: Option<&str>) -> Arc<Session> {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        if let Some(endpoint) = listen {
+            config.listen.endpoints = vec![endpoint.parse().unwrap()];
+        }
+        if let Some(endpoint) = connect {
+            config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        }
+        zenoh::open(config).res().unwrap().into_arc()
+    }
+
+    #[test]
+    fn forward_one_direction() {
+        const ENDPOINT_A: &str = "tcp/127.0.0.1:17520";
+        const ENDPOINT_B: &str = "tcp/127.0.0.1:17521";
+
+        // Two networks of two sessions each, bridged by one session of each network
+        let bridge_a = open(Some(ENDPOINT_A), None);
+        let bridge_b = open(Some(ENDPOINT_B), None);
+        let app_a = open(None, Some(ENDPOINT_A));
+        let app_b = open(None, Some(ENDPOINT_B));
+        let bridge = zenoh::bridge(
+            bridge_a,
+            bridge_b,
+            [BridgeRule::new("test/bridge/**", BridgeDirection::AToB)
+                .remap("test/bridge", "test/bridged")],
+        )
+        .res()
+        .unwrap();
+
+        let mut subscriber_a = app_a.declare_subscriber("test/**").res().unwrap();
+        let mut subscriber_b = app_b.declare_subscriber("test/**").res().unwrap();
+        std::thread::sleep(SLEEP);
+
+        app_a.put("test/bridge/key", "a").res().unwrap();
+        let sample = subscriber_b.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.key_expr.as_str(), "test/bridged/key");
+        assert_eq!(sample.value.to_string(), "a");
+        assert_eq!(
+            subscriber_a
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .unwrap()
+                .value
+                .to_string(),
+            "a"
+        );
+
+        // Nothing is forwarded from the second network to the first one
+        app_b.put("test/bridge/key", "b").res().unwrap();
+        assert_eq!(
+            subscriber_b
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .unwrap()
+                .value
+                .to_string(),
+            "b"
+        );
+        std::thread::sleep(SLEEP);
+        assert!(subscriber_a.is_empty());
+        assert!(subscriber_b.is_empty());
+        assert_eq!(
+            bridge.stats(),
+            [BridgeRuleStats {
+                forwarded: 1,
+                looped: 0,
+                failed: 0
+            }]
+        );
+
+        // The bridge keeps forwarding once a session of the second network reconnects
+        drop(subscriber_b);
+        drop(app_b);
+        let app_b = open(None, Some(ENDPOINT_B));
+        let mut subscriber_b = app_b.declare_subscriber("test/**").res().unwrap();
+        std::thread::sleep(SLEEP);
+        app_a.put("test/bridge/key", "a").res().unwrap();
+        let sample = subscriber_b.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.key_expr.as_str(), "test/bridged/key");
+
+        bridge.close().res().unwrap();
+        app_a.put("test/bridge/key", "a").res().unwrap();
+        std::thread::sleep(SLEEP);
+        assert!(subscriber_b.is_empty());
+    }
+
+    #[test]
+    fn both_directions_without_loop() {
+        let session_a = open(None, None);
+        let session_b = open(None, None);
+        let bridge = zenoh::bridge(
+            session_a.clone(),
+            session_b.clone(),
+            [BridgeRule::new("test/loop/**", BridgeDirection::Both)],
+        )
+        .res()
+        .unwrap();
+        let mut subscriber_a = session_a.declare_subscriber("test/loop/**").res().unwrap();
+        let mut subscriber_b = session_b.declare_subscriber("test/loop/**").res().unwrap();
+
+        session_a.put("test/loop/key", "a").res().unwrap();
+        assert_eq!(
+            subscriber_a
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .unwrap()
+                .value
+                .to_string(),
+            "a"
+        );
+        assert_eq!(
+            subscriber_b
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .unwrap()
+                .value
+                .to_string(),
+            "a"
+        );
+        std::thread::sleep(SLEEP);
+        // The sample bridged into the second session isn't sent back to the first one
+        assert!(subscriber_a.is_empty());
+        assert!(subscriber_b.is_empty());
+        assert_eq!(
+            bridge.stats(),
+            [BridgeRuleStats {
+                forwarded: 1,
+                looped: 1,
+                failed: 0
+            }]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod callback_async {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_millis(100);
+    const SAMPLES: usize = 32;
+
+    async fn wait_for(counter: &AtomicUsize, expected: usize) {
+        tokio::time::timeout(TIMEOUT, async {
+            while counter.load(Ordering::SeqCst) < expected {
+                tokio::time::sleep(SLEEP).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn bounded_in_flight() {
+        let session = zenoh::open(config::peer()).res_async().await.unwrap();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicUsize::new(0));
+
+        let _subscriber = session
+            .declare_subscriber("test/callback_async/bounded")
+            .callback_async({
+                let (in_flight, max_in_flight, done) =
+                    (in_flight.clone(), max_in_flight.clone(), done.clone());
+                move |_sample| {
+                    let (in_flight, max_in_flight, done) =
+                        (in_flight.clone(), max_in_flight.clone(), done.clone());
+                    async move {
+                        let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(current, Ordering::SeqCst);
+                        tokio::time::sleep(SLEEP).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        done.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+            .max_in_flight(4)
+            .res_async()
+            .await
+            .unwrap();
+
+        for i in 0..SAMPLES {
+            session
+                .put("test/callback_async/bounded", i as u64)
+                .res_async()
+                .await
+                .unwrap();
+        }
+        wait_for(&done, SAMPLES).await;
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 4);
+        assert!(max_in_flight.load(Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn panics_are_isolated() {
+        let session = zenoh::open(config::peer()).res_async().await.unwrap();
+        let done = Arc::new(AtomicUsize::new(0));
+
+        let _subscriber = session
+            .declare_subscriber("test/callback_async/panic")
+            .callback_async({
+                let done = done.clone();
+                move |sample| {
+                    let done = done.clone();
+                    async move {
+                        if sample.value.to_string() == "panic" {
+                            panic!("the sample asked for it");
+                        }
+                        done.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+            .max_in_flight(1)
+            .res_async()
+            .await
+            .unwrap();
+
+        for value in ["first", "panic", "second", "panic", "third"] {
+            session
+                .put("test/callback_async/panic", value)
+                .res_async()
+                .await
+                .unwrap();
+        }
+        wait_for(&done, 3).await;
+
+        // The session keeps working
+        let subscriber = session
+            .declare_subscriber("test/callback_async/after")
+            .res_async()
+            .await
+            .unwrap();
+        session
+            .put("test/callback_async/after", "value")
+            .res_async()
+            .await
+            .unwrap();
+        let sample = subscriber.recv_async().await.unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+}
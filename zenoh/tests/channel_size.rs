@@ -0,0 +1,82 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn channel_size_per_subscriber() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const SLEEP: Duration = Duration::from_secs(1);
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const BURST: usize = 64;
+
+    let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+    let mut small = session
+        .declare_subscriber("test/channel_size/small")
+        .channel_size(4)
+        .res()
+        .unwrap();
+    let large = session
+        .declare_subscriber("test/channel_size/large")
+        .channel_size(BURST)
+        .res()
+        .unwrap();
+    let unbounded = session
+        .declare_subscriber("test/channel_size/unbounded")
+        .unbounded()
+        .res()
+        .unwrap();
+
+    let burst = |key_expr: &'static str| {
+        let session = session.clone();
+        std::thread::spawn(move || {
+            for i in 0..BURST {
+                session.put(key_expr, i as u64).res().unwrap();
+            }
+        })
+    };
+    let small_publisher = burst("test/channel_size/small");
+    let large_publisher = burst("test/channel_size/large");
+    let unbounded_publisher = burst("test/channel_size/unbounded");
+    std::thread::sleep(SLEEP);
+
+    // The large channels keep up with the burst
+    large_publisher.join().unwrap();
+    unbounded_publisher.join().unwrap();
+    assert_eq!(large.len(), BURST);
+    assert_eq!(unbounded.len(), BURST);
+
+    // The small channel blocks the burst until its samples are received
+    assert_eq!(small.len(), 4);
+    assert!(!small_publisher.is_finished());
+    for i in 0..BURST {
+        let sample = small.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), i.to_string());
+    }
+    small_publisher.join().unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn channel_size_zero() {
+    use zenoh::prelude::sync::*;
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let error = session
+        .declare_subscriber("test/channel_size/zero")
+        .channel_size(0)
+        .res()
+        .unwrap_err();
+    assert!(error.to_string().contains("channel size"));
+}
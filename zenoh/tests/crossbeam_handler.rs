@@ -0,0 +1,95 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "crossbeam-channel")]
+mod crossbeam_handler {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::CrossbeamSubscriber;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const SAMPLES: usize = 16;
+
+    fn receive_all(subscriber: &CrossbeamSubscriber) {
+        for i in 0..SAMPLES {
+            let sample = subscriber.receiver.recv_timeout(TIMEOUT).unwrap();
+            assert_eq!(sample.value.to_string(), i.to_string());
+        }
+    }
+
+    #[test]
+    fn bounded_channel() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let subscriber = session
+            .declare_subscriber("test/crossbeam/bounded")
+            .with(crossbeam_channel::bounded(4))
+            .res()
+            .unwrap();
+
+        let publisher = {
+            let session = session.clone();
+            std::thread::spawn(move || {
+                for i in 0..SAMPLES {
+                    session
+                        .put("test/crossbeam/bounded", i as u64)
+                        .res()
+                        .unwrap();
+                }
+            })
+        };
+        std::thread::sleep(SLEEP);
+        // The puts wait while the channel is full
+        assert_eq!(subscriber.len(), 4);
+        receive_all(&subscriber);
+        publisher.join().unwrap();
+    }
+
+    #[test]
+    fn unbounded_channel() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/crossbeam/unbounded")
+            .with(crossbeam_channel::unbounded())
+            .res()
+            .unwrap();
+
+        for i in 0..SAMPLES {
+            session
+                .put("test/crossbeam/unbounded", i as u64)
+                .res()
+                .unwrap();
+        }
+        assert_eq!(subscriber.len(), SAMPLES);
+        receive_all(&subscriber);
+    }
+
+    #[test]
+    fn undeclared_on_drop() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/crossbeam/drop")
+            .with(crossbeam_channel::unbounded())
+            .res()
+            .unwrap();
+        let receiver = subscriber.receiver.clone();
+
+        drop(subscriber);
+        session.put("test/crossbeam/drop", "value").res().unwrap();
+        // The subscription and its sender are gone, while the receiver is still held
+        assert!(matches!(
+            receiver.recv_timeout(TIMEOUT),
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected)
+        ));
+    }
+}
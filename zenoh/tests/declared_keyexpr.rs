@@ -0,0 +1,57 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod declared_keyexpr {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn subscribe_and_publish() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17537".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+
+        let key = format!("test/declared/{}", "x".repeat(200));
+        let sub_key_expr = subscribing.declare_keyexpr_guarded(&key).res().unwrap();
+        let mut subscriber = subscribing
+            .declare_subscriber(&sub_key_expr)
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        let pub_key_expr = publishing.declare_keyexpr_guarded(&key).res().unwrap();
+        let publisher = publishing.declare_publisher(&pub_key_expr).res().unwrap();
+
+        publisher.put("declared").res().unwrap();
+        publishing.put(&pub_key_expr, "put").res().unwrap();
+        for value in ["declared", "put"] {
+            let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+            assert_eq!(sample.key_expr.as_str(), key);
+            assert_eq!(sample.value.to_string(), value);
+        }
+
+        // Undeclared once the last clone is dropped, after the publisher using it
+        drop(publisher);
+        drop(pub_key_expr);
+        drop(subscriber);
+        drop(sub_key_expr);
+    }
+}
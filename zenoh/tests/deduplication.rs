@@ -0,0 +1,89 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod deduplication {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::FlumeSubscriber;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    fn open(timestamping: bool) -> Session {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .insert_json5("timestamping/enabled", &timestamping.to_string())
+            .unwrap();
+        zenoh::open(config).res().unwrap()
+    }
+
+    fn values(subscriber: &FlumeSubscriber) -> Vec<String> {
+        subscriber
+            .drain(usize::MAX)
+            .into_iter()
+            .map(|sample| sample.value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn duplicates_dropped() {
+        let session = open(true);
+        // Overlapping key expressions deliver each matching sample twice
+        let duplicating = session
+            .declare_subscriber("test/dedup/*")
+            .also("test/dedup/a")
+            .res()
+            .unwrap();
+        let deduplicating = session
+            .declare_subscriber("test/dedup/*")
+            .also("test/dedup/a")
+            .deduplicate(WINDOW)
+            .res()
+            .unwrap();
+
+        for value in ["first", "second"] {
+            session.put("test/dedup/a", value).res().unwrap();
+        }
+        session.put("test/dedup/b", "other").res().unwrap();
+        assert_eq!(
+            values(&duplicating),
+            ["first", "first", "second", "second", "other"]
+        );
+        assert_eq!(values(&deduplicating), ["first", "second", "other"]);
+    }
+
+    #[test]
+    fn without_timestamp() {
+        let session = open(false);
+        let lenient = session
+            .declare_subscriber("test/dedup/untimestamped")
+            .deduplicate(WINDOW)
+            .res()
+            .unwrap();
+        let strict = session
+            .declare_subscriber("test/dedup/untimestamped")
+            .deduplicate_strict(WINDOW)
+            .res()
+            .unwrap();
+
+        for value in ["first", "first"] {
+            session
+                .put("test/dedup/untimestamped", value)
+                .res()
+                .unwrap();
+        }
+        assert_eq!(values(&lenient), ["first", "first"]);
+        assert!(values(&strict).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod demux {
+    use std::sync::{Arc, Mutex};
+    use zenoh::handlers::DispatchPolicy;
+    use zenoh::prelude::sync::*;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn logger(log: &Log, route: &'static str) -> impl Fn(Sample) + Send + Sync + 'static {
+        let log = log.clone();
+        move |sample| {
+            log.lock()
+                .unwrap()
+                .push(format!("{route}:{}", sample.key_expr))
+        }
+    }
+
+    fn take(log: &Log) -> Vec<String> {
+        std::mem::take(&mut *log.lock().unwrap())
+    }
+
+    #[test]
+    fn routes() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let log = Log::default();
+        let subscriber = session
+            .declare_subscriber("test/demux/*/gps")
+            .demux()
+            .route("test/demux/1/gps", logger(&log, "one"))
+            .route("test/demux/*/gps", logger(&log, "any"))
+            .default(logger(&log, "default"))
+            .res()
+            .unwrap();
+
+        session.put("test/demux/1/gps", "").res().unwrap();
+        session.put("test/demux/2/gps", "").res().unwrap();
+        assert_eq!(take(&log), ["one:test/demux/1/gps", "any:test/demux/2/gps"]);
+
+        subscriber.set_policy(DispatchPolicy::All);
+        session.put("test/demux/1/gps", "").res().unwrap();
+        assert_eq!(take(&log), ["one:test/demux/1/gps", "any:test/demux/1/gps"]);
+
+        // Routes can be changed on the declared subscriber
+        assert!(subscriber.remove_route(keyexpr::new("test/demux/*/gps").unwrap()));
+        assert!(!subscriber.remove_route(keyexpr::new("test/demux/*/gps").unwrap()));
+        subscriber
+            .add_route("test/demux/3/gps", logger(&log, "three"))
+            .unwrap();
+        assert!(subscriber.add_route("test/demux//gps", |_| ()).is_err());
+        for key_expr in ["test/demux/1/gps", "test/demux/2/gps", "test/demux/3/gps"] {
+            session.put(key_expr, "").res().unwrap();
+        }
+        assert_eq!(
+            take(&log),
+            [
+                "one:test/demux/1/gps",
+                "default:test/demux/2/gps",
+                "three:test/demux/3/gps"
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_route() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/demux/invalid")
+            .demux()
+            .route("test/demux/invalid/", |_| ())
+            .res();
+        assert!(subscriber.is_err());
+    }
+}
@@ -0,0 +1,70 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn dyn_declarations() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    fn assert_send_static<T: Send + 'static>(t: T) -> T {
+        t
+    }
+
+    let zenoh = zenoh::open(Config::default()).res().unwrap();
+    let key_expr = KeyExpr::try_from("test/dyn").unwrap();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let subscriber = assert_send_static(
+        zenoh
+            .subscribe_dyn(&key_expr, {
+                let received = received.clone();
+                Box::new(move |_| {
+                    received.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .unwrap(),
+    );
+    assert_eq!(subscriber.key_expr(), &key_expr);
+    let queryable = assert_send_static(
+        zenoh
+            .queryable_dyn(
+                &key_expr,
+                Box::new(|query| {
+                    query
+                        .reply(Ok(Sample::new(query.key_expr().clone(), "reply")))
+                        .res()
+                        .unwrap()
+                }),
+            )
+            .unwrap(),
+    );
+
+    zenoh.put(&key_expr, "value").res().unwrap();
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+    let replies = zenoh.get(&key_expr).res().unwrap();
+    assert!(replies
+        .recv_timeout(Duration::from_secs(1))
+        .unwrap()
+        .sample
+        .is_ok());
+
+    subscriber.close().unwrap();
+    queryable.close().unwrap();
+    zenoh.put(&key_expr, "value").res().unwrap();
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+    let replies = zenoh.get(&key_expr).res().unwrap();
+    assert!(replies.recv_timeout(Duration::from_secs(1)).is_err());
+}
@@ -0,0 +1,227 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod entity_id {
+    use std::collections::HashSet;
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::{EntityId, EntityKind};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn kind_of(session: &Session, id: EntityId) -> Option<EntityKind> {
+        session
+            .entities()
+            .into_iter()
+            .find(|entity| entity.id == id)
+            .map(|entity| entity.kind)
+    }
+
+    #[test]
+    fn ids_are_never_reused() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut ids = HashSet::new();
+        // Undeclaring the entities right away, so that their ids could be reused
+        for i in 0..10_000 {
+            let id = match i % 3 {
+                0 => session
+                    .declare_subscriber("test/entity/unique")
+                    .res()
+                    .unwrap()
+                    .id(),
+                1 => session
+                    .declare_queryable("test/entity/unique")
+                    .res()
+                    .unwrap()
+                    .id(),
+                _ => session
+                    .declare_publisher("test/entity/unique")
+                    .res()
+                    .unwrap()
+                    .id(),
+            };
+            assert!(ids.insert(id), "{id} was reused");
+        }
+        assert!(session
+            .entities()
+            .iter()
+            .all(|entity| !ids.contains(&entity.id)));
+    }
+
+    #[test]
+    fn entities_match_handles() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session.declare_subscriber("test/entity/sub").res().unwrap();
+        let queryable = session
+            .declare_queryable("test/entity/qable")
+            .res()
+            .unwrap();
+        let publisher = session.declare_publisher("test/entity/pub").res().unwrap();
+
+        let entities = session.entities();
+        for (id, kind, key_expr) in [
+            (subscriber.id(), EntityKind::Subscriber, "test/entity/sub"),
+            (queryable.id(), EntityKind::Queryable, "test/entity/qable"),
+            (publisher.id(), EntityKind::Publisher, "test/entity/pub"),
+        ] {
+            let entity = entities.iter().find(|entity| entity.id == id).unwrap();
+            assert_eq!(entity.kind, kind);
+            assert_eq!(entity.key_expr.as_str(), key_expr);
+        }
+
+        let publisher_id = publisher.id();
+        drop(publisher);
+        assert_eq!(kind_of(&session, publisher_id), None);
+        assert_eq!(
+            kind_of(&session, subscriber.id()),
+            Some(EntityKind::Subscriber)
+        );
+    }
+
+    #[test]
+    fn undeclare_by_id() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/entity/undeclare")
+            .res()
+            .unwrap();
+        let queryable = session
+            .declare_queryable("test/entity/undeclare")
+            .res()
+            .unwrap();
+        let publisher = session
+            .declare_publisher("test/entity/undeclare")
+            .res()
+            .unwrap();
+
+        for id in [subscriber.id(), queryable.id(), publisher.id()] {
+            session.undeclare(id).res().unwrap();
+            assert_eq!(kind_of(&session, id), None);
+            assert!(session.undeclare(id).res().is_err());
+        }
+
+        // The undeclared subscriber no longer receives the publications
+        publisher.put("value").res().unwrap();
+        assert!(subscriber
+            .recv_timeout(Duration::from_millis(100))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn admin_space() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/entity/admin")
+            .res()
+            .unwrap();
+
+        let key_expr = format!("@/session/{}/entity/{}", session.zid(), subscriber.id());
+        let replies = session.get(&key_expr).timeout(TIMEOUT).res().unwrap();
+        let sample = replies.recv().unwrap().sample.unwrap();
+        assert_eq!(sample.key_expr.as_str(), key_expr);
+        let value: serde_json::Value =
+            serde_json::from_slice(&sample.payload.contiguous()).unwrap();
+        assert_eq!(value["kind"], "subscriber");
+        assert_eq!(value["key_expr"], "test/entity/admin");
+    }
+
+    #[test]
+    fn subscriber_accessors() {
+        use zenoh::subscriber::{Reliability, SubMode};
+
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/entity/accessors")
+            .reliable()
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.key_expr().as_str(), "test/entity/accessors");
+        assert_eq!(subscriber.reliability(), Reliability::Reliable);
+        assert_eq!(subscriber.mode(), SubMode::Push);
+        let debug = format!("{subscriber:?}");
+        assert!(debug.contains("test/entity/accessors"), "{debug}");
+
+        let subscriber = session
+            .declare_subscriber("test/entity/accessors/pull")
+            .best_effort()
+            .pull_mode()
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.key_expr().as_str(), "test/entity/accessors/pull");
+        assert_eq!(subscriber.reliability(), Reliability::BestEffort);
+        assert_eq!(subscriber.mode(), SubMode::Pull);
+        assert_eq!(
+            kind_of(&session, subscriber.id()),
+            Some(EntityKind::Subscriber)
+        );
+    }
+
+    #[test]
+    fn subscriptions_and_publications() {
+        use zenoh::publication::{CongestionControl, Priority};
+        use zenoh::subscriber::{Reliability, SubMode};
+
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/entity/listed/sub")
+            .reliable()
+            .allowed_origin(Locality::SessionLocal)
+            .res()
+            .unwrap();
+        let publisher = session
+            .declare_publisher("test/entity/listed/pub")
+            .congestion_control(CongestionControl::Block)
+            .priority(Priority::RealTime)
+            .allowed_destination(Locality::Remote)
+            .res()
+            .unwrap();
+        // Puts are not declared publishers
+        session
+            .put("test/entity/listed/put", "value")
+            .res()
+            .unwrap();
+
+        let subscribers = session.subscribers();
+        let subscription = subscribers
+            .iter()
+            .find(|sub| sub.id == subscriber.id())
+            .unwrap();
+        assert_eq!(subscription.key_expr.as_str(), "test/entity/listed/sub");
+        assert_eq!(subscription.mode, SubMode::Push);
+        assert_eq!(subscription.reliability, Reliability::Reliable);
+        assert_eq!(subscription.origin, Locality::SessionLocal);
+
+        let publications = session.publications();
+        assert_eq!(publications.len(), 1);
+        assert_eq!(publications[0].id, publisher.id());
+        assert_eq!(publications[0].key_expr.as_str(), "test/entity/listed/pub");
+        assert_eq!(publications[0].congestion_control, CongestionControl::Block);
+        assert_eq!(publications[0].priority, Priority::RealTime);
+        assert_eq!(publications[0].destination, Locality::Remote);
+
+        // The lists are snapshots, unaffected by later undeclarations
+        drop(publisher);
+        assert_eq!(publications.len(), 1);
+        assert!(session.publications().is_empty());
+
+        let key_expr = format!("@/session/{}/subscriber/{}", session.zid(), subscriber.id());
+        let replies = session.get(&key_expr).timeout(TIMEOUT).res().unwrap();
+        let sample = replies.recv().unwrap().sample.unwrap();
+        let value: serde_json::Value =
+            serde_json::from_slice(&sample.payload.contiguous()).unwrap();
+        assert_eq!(value["key_expr"], "test/entity/listed/sub");
+        assert_eq!(value["reliability"], "Reliable");
+    }
+}
@@ -0,0 +1,109 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod fair_scheduling {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::publication::{CongestionControl, Publisher};
+
+    const SLEEP: Duration = Duration::from_secs(1);
+    const PAYLOAD: usize = 8 * 1024;
+
+    fn open(endpoint: &str, listen: bool) -> Arc<Session> {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        if listen {
+            config.listen.endpoints = vec![endpoint.parse().unwrap()];
+        } else {
+            config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        }
+        zenoh::open(config).res().unwrap().into_arc()
+    }
+
+    #[test]
+    fn weighted_bandwidth_share() {
+        const ENDPOINT: &str = "tcp/127.0.0.1:17522";
+
+        let publishing = open(ENDPOINT, true);
+        let subscribing = open(ENDPOINT, false);
+        // A slow subscriber saturates the link
+        let _subscriber = subscribing
+            .declare_subscriber("test/fair/*")
+            .callback(|_| std::thread::sleep(Duration::from_micros(200)))
+            .reliable()
+            .res()
+            .unwrap();
+        std::thread::sleep(SLEEP);
+
+        let declare = |key_expr: &'static str, weight| {
+            Arc::new(
+                publishing
+                    .declare_publisher(key_expr)
+                    .congestion_control(CongestionControl::Block)
+                    .res()
+                    .unwrap()
+                    .weight(weight),
+            )
+        };
+        let heavy = declare("test/fair/heavy", 10);
+        let light = declare("test/fair/light", 1);
+
+        let running = Arc::new(AtomicBool::new(true));
+        let publish = |publisher: &Arc<Publisher<'static>>| {
+            let publisher = publisher.clone();
+            let running = running.clone();
+            std::thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    publisher.put(vec![0u8; PAYLOAD]).res().unwrap();
+                }
+            })
+        };
+        let publishers = [publish(&heavy), publish(&light)];
+
+        // Measured once the queues of both publishers are backlogged
+        std::thread::sleep(2 * SLEEP);
+        assert!(heavy.queued_bytes() > 0);
+        assert!(light.queued_bytes() > 0);
+        let (heavy_start, light_start) = (heavy.scheduled_bytes(), light.scheduled_bytes());
+        std::thread::sleep(3 * SLEEP);
+        let heavy_sent = heavy.scheduled_bytes() - heavy_start;
+        let light_sent = light.scheduled_bytes() - light_start;
+
+        running.store(false, Ordering::Relaxed);
+        drop(_subscriber);
+        for publisher in publishers {
+            publisher.join().unwrap();
+        }
+
+        let ratio = heavy_sent as f64 / light_sent.max(1) as f64;
+        assert!(
+            (7.0..=14.0).contains(&ratio),
+            "heavy: {heavy_sent} bytes, light: {light_sent} bytes"
+        );
+    }
+
+    #[test]
+    fn unweighted_publishers_unaffected() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session.declare_subscriber("test/fair/local").res().unwrap();
+        let publisher = session.declare_publisher("test/fair/local").res().unwrap();
+
+        publisher.put("value").res().unwrap();
+        assert_eq!(subscriber.recv().unwrap().value.to_string(), "value");
+        assert_eq!(publisher.queued_bytes(), 0);
+        assert_eq!(publisher.scheduled_bytes(), 0);
+    }
+}
@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fanout_through_router() {
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+    use zenoh_core::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const KEY_EXPR: &str = "test/fanout/key";
+
+    zenoh_util::try_init_log_from_env();
+    let endpoint: EndPoint = "tcp/127.0.0.1:17495".parse().unwrap();
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec![endpoint.clone()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let router = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let client01 = ztimeout!(zenoh::open(config::client([endpoint.clone()])).res_async()).unwrap();
+    let client02 = ztimeout!(zenoh::open(config::client([endpoint])).res_async()).unwrap();
+
+    let report = ztimeout!(router.fanout(KEY_EXPR).res_async()).unwrap();
+    assert_eq!(report.remote_routes, 0);
+    assert_eq!(report.estimated_copies, 0);
+
+    let sub01 = ztimeout!(client01.declare_subscriber(KEY_EXPR).res_async()).unwrap();
+    let sub02 = ztimeout!(client02.declare_subscriber("test/fanout/*").res_async()).unwrap();
+    let local = ztimeout!(router.declare_subscriber(KEY_EXPR).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let report = ztimeout!(router.fanout(KEY_EXPR).res_async()).unwrap();
+    assert_eq!(report.local_subscribers, 1);
+    assert_eq!(report.remote_routes, 2);
+    assert_eq!(report.estimated_copies, 3);
+
+    // The publisher's report is restricted to its destination
+    let publisher = ztimeout!(router
+        .declare_publisher(KEY_EXPR)
+        .allowed_destination(Locality::Remote)
+        .res_async())
+    .unwrap();
+    let report = ztimeout!(publisher.fanout().res_async()).unwrap();
+    assert_eq!(report.local_subscribers, 0);
+    assert_eq!(report.remote_routes, 2);
+
+    // The report follows the routing changes
+    ztimeout!(sub01.undeclare().res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    let report = ztimeout!(publisher.fanout().res_async()).unwrap();
+    assert_eq!(report.remote_routes, 1);
+
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    ztimeout!(local.undeclare().res_async()).unwrap();
+    ztimeout!(sub02.undeclare().res_async()).unwrap();
+    ztimeout!(client02.close().res_async()).unwrap();
+    ztimeout!(client01.close().res_async()).unwrap();
+    ztimeout!(router.close().res_async()).unwrap();
+}
@@ -0,0 +1,161 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "testing")]
+use std::time::{Duration, Instant};
+use zenoh::prelude::r#async::*;
+use zenoh::testing::{Delay, Faults};
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+const KEY_EXPR: &str = "test/fault_injection";
+
+async fn open_pair(port: u16, publisher_config: Option<Config>) -> (Session, Session) {
+    let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+    let mut config = publisher_config.unwrap_or_else(config::peer);
+    config.listen.endpoints = vec![endpoint.clone()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let publisher = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let subscriber = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    (publisher, subscriber)
+}
+
+async fn links(session: &Session) -> Vec<String> {
+    let replies = ztimeout!(session
+        .get(format!(
+            "@/session/{}/transport/unicast/*/link/*",
+            session.zid()
+        ))
+        .res_async())
+    .unwrap();
+    let mut links = vec![];
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        links.push(reply.sample.unwrap().key_expr.to_string());
+    }
+    links.sort();
+    links
+}
+
+async fn close_pair(publisher: Session, subscriber: Session) {
+    ztimeout!(subscriber.close().res_async()).unwrap();
+    ztimeout!(publisher.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fault_injection_drop_and_duplicate() {
+    zenoh_util::try_init_log_from_env();
+    let (publisher, subscriber) = open_pair(17498, None).await;
+    let sub = ztimeout!(subscriber.declare_subscriber(KEY_EXPR).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    publisher
+        .fault_injector()
+        .set(Faults::default().drop_rate(1.0));
+    ztimeout!(publisher.put(KEY_EXPR, "dropped").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(sub.try_recv().is_err());
+    assert_eq!(publisher.fault_injector().sent(), 1);
+
+    publisher
+        .fault_injector()
+        .set(Faults::default().duplicate_rate(1.0));
+    ztimeout!(publisher.put(KEY_EXPR, "duplicated").res_async()).unwrap();
+    for _ in 0..2 {
+        let sample = ztimeout!(sub.recv_async()).unwrap();
+        assert_eq!(sample.value.to_string(), "duplicated");
+    }
+    tokio::time::sleep(SLEEP).await;
+    assert!(sub.try_recv().is_err());
+
+    publisher.fault_injector().clear();
+    ztimeout!(publisher.put(KEY_EXPR, "value").res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "value");
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    close_pair(publisher, subscriber).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fault_injection_delay() {
+    zenoh_util::try_init_log_from_env();
+    const DELAY: Duration = Duration::from_millis(500);
+
+    let (publisher, subscriber) = open_pair(17499, None).await;
+    let sub = ztimeout!(subscriber.declare_subscriber(KEY_EXPR).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    publisher
+        .fault_injector()
+        .set(Faults::default().delay(Delay::Fixed(DELAY)));
+    let start = Instant::now();
+    ztimeout!(publisher.put(KEY_EXPR, "delayed").res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "delayed");
+    assert!(start.elapsed() >= DELAY);
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    close_pair(publisher, subscriber).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fault_injection_close_after() {
+    zenoh_util::try_init_log_from_env();
+    let (publisher, subscriber) = open_pair(17500, None).await;
+    let sub = ztimeout!(subscriber.declare_subscriber(KEY_EXPR).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    publisher
+        .fault_injector()
+        .set(Faults::default().close_after(3));
+    let links_before = links(&publisher).await;
+    assert!(!links_before.is_empty());
+    for _ in 0..3 {
+        ztimeout!(publisher.put(KEY_EXPR, "value").res_async()).unwrap();
+    }
+    tokio::time::sleep(SLEEP).await;
+    // The subscriber reconnects to the publisher through a new link
+    assert_ne!(links(&publisher).await, links_before);
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    close_pair(publisher, subscriber).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fault_injection_from_config() {
+    zenoh_util::try_init_log_from_env();
+    let mut config = config::peer();
+    config
+        .insert_json5("fault_injection", r#"{ drop_rate: 1.0 }"#)
+        .unwrap();
+    let (publisher, subscriber) = open_pair(17501, Some(config)).await;
+    assert_eq!(
+        publisher.fault_injector().faults(),
+        Faults::default().drop_rate(1.0)
+    );
+    let sub = ztimeout!(subscriber.declare_subscriber(KEY_EXPR).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(publisher.put(KEY_EXPR, "dropped").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(sub.try_recv().is_err());
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    close_pair(publisher, subscriber).await;
+}
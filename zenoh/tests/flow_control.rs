@@ -0,0 +1,80 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod flow_control {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::sync::*;
+
+    const SLEEP: Duration = Duration::from_secs(1);
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn backpressure_between_watermarks() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let mut subscriber = session
+            .declare_subscriber("test/flow_control")
+            .flow_control()
+            .high_watermark(4)
+            .low_watermark(1)
+            .res()
+            .unwrap();
+
+        let publisher = {
+            let session = Arc::clone(&session);
+            thread::spawn(move || {
+                for i in 0..10 {
+                    session.put("test/flow_control", i as u64).res().unwrap();
+                }
+            })
+        };
+
+        // The delivery stops at the high watermark
+        thread::sleep(SLEEP);
+        assert!(!publisher.is_finished());
+        assert_eq!(subscriber.metrics().queued, 4);
+
+        // And resumes once the channel is drained down to the low watermark
+        let mut received = Vec::new();
+        let deadline = Instant::now() + TIMEOUT;
+        while received.len() < 10 {
+            let sample: Sample = subscriber.recv_deadline(deadline).unwrap().unwrap();
+            received.push(sample.value.to_string());
+            thread::sleep(Duration::from_millis(10));
+        }
+        publisher.join().unwrap();
+        assert_eq!(received, (0..10).map(|i| i.to_string()).collect::<Vec<_>>());
+        assert_eq!(subscriber.dropped_samples(), 0);
+        assert_eq!(subscriber.metrics().queued, 0);
+    }
+
+    #[test]
+    fn invalid_watermarks() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        assert!(session
+            .declare_subscriber("test/flow_control/invalid")
+            .flow_control()
+            .high_watermark(4)
+            .low_watermark(4)
+            .res()
+            .is_err());
+        assert!(session
+            .declare_subscriber("test/flow_control/invalid")
+            .flow_control()
+            .high_watermark(0)
+            .res()
+            .is_err());
+    }
+}
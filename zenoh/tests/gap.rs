@@ -0,0 +1,211 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+fn open_pair(port: u16) -> (Session, Session) {
+    let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![endpoint.clone()];
+    let publishing = zenoh::open(config).res().unwrap();
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.connect.endpoints = vec![endpoint];
+    let subscribing = zenoh::open(config).res().unwrap();
+    (publishing, subscribing)
+}
+
+#[test]
+fn source_info() {
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let subscriber = session.declare_subscriber("test/gap/sn/*").res().unwrap();
+    for key_expr in ["test/gap/sn/a", "test/gap/sn/a", "test/gap/sn/b"] {
+        session.put(key_expr, "value").res().unwrap();
+    }
+    // The sequence numbers are counted per key
+    let sns: Vec<_> = subscriber
+        .drain(usize::MAX)
+        .into_iter()
+        .map(|sample| {
+            assert_eq!(sample.source_info.source_id, Some(session.zid()));
+            sample.source_info.source_sn.unwrap()
+        })
+        .collect();
+    assert_eq!(sns, [0, 1, 0]);
+}
+
+#[test]
+fn source_info_pulled() {
+    let (publishing, subscribing) = open_pair(17542);
+    let mut subscriber = subscribing
+        .declare_subscriber("test/gap/pulled")
+        .pull_mode()
+        .await_routed(TIMEOUT)
+        .res()
+        .unwrap();
+
+    publishing.put("test/gap/pulled", "value").res().unwrap();
+    std::thread::sleep(Duration::from_secs(1));
+    subscriber.pull().res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert_eq!(sample.source_info.source_id, Some(publishing.zid()));
+    assert_eq!(sample.source_info.source_sn, Some(0));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn missed_samples() {
+    use zenoh::gap::GapInfo;
+    use zenoh::testing::Faults;
+
+    let (publishing, subscribing) = open_pair(17534);
+    let gaps = Arc::new(Mutex::new(Vec::<GapInfo>::new()));
+    let subscriber = subscribing
+        .declare_subscriber("test/gap/missed")
+        .reliable()
+        .on_gap({
+            let gaps = gaps.clone();
+            move |gap| gaps.lock().unwrap().push(gap)
+        })
+        .await_routed(TIMEOUT)
+        .res()
+        .unwrap();
+
+    publishing.put("test/gap/missed", "first").res().unwrap();
+    publishing
+        .fault_injector()
+        .set(Faults::default().drop_rate(1.0));
+    for _ in 0..3 {
+        publishing.put("test/gap/missed", "lost").res().unwrap();
+    }
+    publishing.fault_injector().clear();
+    publishing.put("test/gap/missed", "last").res().unwrap();
+
+    for value in ["first", "last"] {
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), value);
+    }
+    let gaps = gaps.lock().unwrap();
+    assert_eq!(
+        *gaps,
+        [GapInfo {
+            source: publishing.zid(),
+            key_expr: "test/gap/missed".try_into().unwrap(),
+            expected: 1,
+            received: 4,
+        }]
+    );
+    assert_eq!(gaps[0].missed(), 3);
+}
+
+#[test]
+fn no_gap() {
+    let (publishing, subscribing) = open_pair(17535);
+    let gaps = Arc::new(Mutex::new(0));
+    let mut subscriber = subscribing
+        .declare_subscriber("test/gap/none/*")
+        .on_gap({
+            let gaps = gaps.clone();
+            move |_| *gaps.lock().unwrap() += 1
+        })
+        .await_routed(TIMEOUT)
+        .res()
+        .unwrap();
+
+    // Interleaved writes on different keys and from different sessions are not gaps
+    for i in 0..10 {
+        publishing.put("test/gap/none/a", i).res().unwrap();
+        publishing.put("test/gap/none/b", i).res().unwrap();
+        subscribing.put("test/gap/none/a", i).res().unwrap();
+    }
+    for _ in 0..30 {
+        subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+    }
+    assert_eq!(*gaps.lock().unwrap(), 0);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn ordered_delivery() {
+    use zenoh::testing::{Delay, Faults};
+
+    let (publishing, subscribing) = open_pair(17540);
+    let subscriber = subscribing
+        .declare_subscriber("test/gap/ordered")
+        .reliable()
+        .ordered(TIMEOUT)
+        .await_routed(TIMEOUT)
+        .res()
+        .unwrap();
+
+    // The first sample received starts the sequence of the key, so it must not be reordered
+    publishing.put("test/gap/ordered", 0).res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert_eq!(sample.value.to_string(), "0");
+
+    // The delayed samples are reordered
+    publishing
+        .fault_injector()
+        .set(Faults::default().delay(Delay::Uniform {
+            min: Duration::ZERO,
+            max: Duration::from_millis(50),
+        }));
+    for i in 1..32 {
+        publishing.put("test/gap/ordered", i).res().unwrap();
+    }
+    for i in 1..32 {
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), i.to_string());
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn ordered_window_elapsed() {
+    use zenoh::testing::Faults;
+
+    let (publishing, subscribing) = open_pair(17541);
+    let gaps = Arc::new(Mutex::new(0));
+    let subscriber = subscribing
+        .declare_subscriber("test/gap/window")
+        .reliable()
+        .ordered(Duration::from_millis(100))
+        .on_gap({
+            let gaps = gaps.clone();
+            move |gap| *gaps.lock().unwrap() += gap.missed()
+        })
+        .await_routed(TIMEOUT)
+        .res()
+        .unwrap();
+
+    publishing.put("test/gap/window", "first").res().unwrap();
+    publishing
+        .fault_injector()
+        .set(Faults::default().drop_rate(1.0));
+    publishing.put("test/gap/window", "lost").res().unwrap();
+    publishing.fault_injector().clear();
+    publishing.put("test/gap/window", "last").res().unwrap();
+
+    // The sample following the lost one is delivered once the window elapsed
+    for value in ["first", "last"] {
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), value);
+    }
+    assert_eq!(*gaps.lock().unwrap(), 1);
+}
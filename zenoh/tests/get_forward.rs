@@ -0,0 +1,109 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod get_forward {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+    use zenoh::query::ConsolidationMode;
+    use zenoh::queryable::Queryable;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    // Replies with three samples and an error
+    fn storage<'a>(session: &'a Session, prefix: &'static str) -> Queryable<'a, ()> {
+        use zenoh_core::SyncResolve;
+
+        session
+            .declare_queryable(format!("{prefix}/**"))
+            .callback(move |query| {
+                for i in 0..3 {
+                    let key_expr = KeyExpr::try_from(format!("{prefix}/{i}")).unwrap();
+                    let sample = Sample::new(key_expr, i as u64);
+                    query.reply(Ok(sample)).res_sync().unwrap();
+                }
+                query
+                    .reply(Err(Value::from("unavailable")))
+                    .res_sync()
+                    .unwrap();
+            })
+            .res_sync()
+            .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn forward_into_vec() {
+        let session = zenoh::open(config::peer()).res().await.unwrap();
+        let _storage = storage(&session, "test/forward/vec");
+
+        let errors = Arc::new(Mutex::new(vec![]));
+        let mut samples: Vec<Sample> = vec![];
+        let on_error = {
+            let errors = errors.clone();
+            move |error: Value| errors.lock().unwrap().push(error.to_string())
+        };
+        tokio::time::timeout(
+            TIMEOUT,
+            session
+                .get("test/forward/vec/**")
+                .consolidation(ConsolidationMode::None)
+                .forward(&mut samples)
+                .on_error(on_error)
+                .res(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let mut values: Vec<_> = samples.iter().map(|s| s.value.to_string()).collect();
+        values.sort();
+        assert_eq!(values, ["0", "1", "2"]);
+        assert_eq!(*errors.lock().unwrap(), ["unavailable"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn forward_into_publisher() {
+        let session = zenoh::open(config::peer()).res().await.unwrap();
+        let _storage = storage(&session, "test/forward/remote");
+        let subscriber = session
+            .declare_subscriber("test/forward/local")
+            .res()
+            .await
+            .unwrap();
+        let publisher = session
+            .declare_publisher("test/forward/local")
+            .res()
+            .await
+            .unwrap();
+
+        session
+            .get("test/forward/remote/**")
+            .consolidation(ConsolidationMode::None)
+            .forward(publisher)
+            .res()
+            .await
+            .unwrap();
+
+        let mut values = vec![];
+        for _ in 0..3 {
+            let sample = tokio::time::timeout(TIMEOUT, subscriber.recv_async())
+                .await
+                .unwrap()
+                .unwrap();
+            values.push(sample.value.to_string());
+        }
+        values.sort();
+        assert_eq!(values, ["0", "1", "2"]);
+    }
+}
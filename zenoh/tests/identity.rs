@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+const SLEEP: Duration = Duration::from_secs(1);
+
+fn config() -> Config {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+}
+
+#[test]
+fn configured_zid_is_stable() {
+    let zid: ZenohId = "a1b2c3d4".parse().unwrap();
+    let mut config = config();
+    config.set_id(zid).unwrap();
+
+    for _ in 0..2 {
+        let session = zenoh::open(config.clone()).res().unwrap();
+        assert_eq!(session.zid(), zid);
+        session.close().res().unwrap();
+    }
+}
+
+#[cfg(feature = "keystore")]
+#[test]
+fn keystore_zid_is_stable() {
+    let path = std::env::temp_dir().join(format!("zenoh-keystore-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut config = config();
+    config
+        .set_keystore(Some(path.to_string_lossy().into_owned()))
+        .unwrap();
+
+    // The keystore is created on first run...
+    let session = zenoh::open(config.clone()).res().unwrap();
+    let zid = session.zid();
+    // ...and can't be used by another session while the first one is running
+    assert!(zenoh::open(config.clone()).res().is_err());
+    session.close().res().unwrap();
+
+    // The keystore is reused afterwards, whatever the configured id
+    config.set_id(ZenohId::rand()).unwrap();
+    for _ in 0..2 {
+        let session = zenoh::open(config.clone()).res().unwrap();
+        assert_eq!(session.zid(), zid);
+        session.close().res().unwrap();
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn zid_collision_is_refused() {
+    let endpoint = "tcp/127.0.0.1:17485";
+    let zid = ZenohId::rand();
+
+    let mut config01 = config();
+    config01.set_id(zid).unwrap();
+    config01.listen.endpoints = vec![endpoint.parse().unwrap()];
+    let peer01 = zenoh::open(config01).res().unwrap();
+
+    let mut config02 = config();
+    config02.set_id(zid).unwrap();
+    config02.connect.endpoints = vec![endpoint.parse().unwrap()];
+    let peer02 = zenoh::open(config02).res().unwrap();
+    std::thread::sleep(SLEEP);
+
+    assert_eq!(peer01.info().peers_zid().res().count(), 0);
+    assert_eq!(peer02.info().peers_zid().res().count(), 0);
+
+    peer02.close().res().unwrap();
+    peer01.close().res().unwrap();
+}
@@ -0,0 +1,45 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh::key_expr::{KeyExprError, KeyExprErrorKind};
+use zenoh::prelude::sync::*;
+
+fn key_expr_error(error: zenoh::Error) -> KeyExprError {
+    *KeyExprError::find(&*error).unwrap()
+}
+
+#[test]
+fn invalid_key_expr_errors() {
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let expected = KeyExprError {
+        offset: 7,
+        kind: KeyExprErrorKind::WildcardAdjacent,
+    };
+
+    let error = session
+        .declare_subscriber("foo/**/**bar")
+        .res()
+        .err()
+        .unwrap();
+    assert_eq!(key_expr_error(error), expected);
+    let error = session
+        .declare_publisher("foo/**/**bar")
+        .res()
+        .err()
+        .unwrap();
+    assert_eq!(key_expr_error(error), expected);
+    let error = session.put("foo/**/**bar", "value").res().err().unwrap();
+    assert_eq!(key_expr_error(error), expected);
+    let error = session.get("foo/**/**bar").res().err().unwrap();
+    assert_eq!(key_expr_error(error), expected);
+}
@@ -76,3 +76,44 @@ async fn zenoh_liveliness() {
 
     assert!(replies.try_recv().is_err());
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_liveliness_session_closed() {
+    let mut c1 = config::peer();
+    c1.listen
+        .set_endpoints(vec!["tcp/localhost:47448".parse().unwrap()])
+        .unwrap();
+    c1.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session1 = ztimeout!(zenoh::open(c1).res_async()).unwrap();
+    let mut c2 = config::peer();
+    c2.connect
+        .set_endpoints(vec!["tcp/localhost:47448".parse().unwrap()])
+        .unwrap();
+    c2.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session2 = ztimeout!(zenoh::open(c2).res_async()).unwrap();
+
+    let sub = ztimeout!(session2
+        .liveliness()
+        .declare_subscriber("zenoh_liveliness_test/closed/**")
+        .res_async())
+    .unwrap();
+
+    let token = ztimeout!(session1
+        .liveliness()
+        .declare_token("zenoh_liveliness_test/closed/a")
+        .res_async())
+    .unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Put);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_test/closed/a");
+
+    // The token is never undeclared: it disappears with the transport of its session
+    std::mem::forget(token);
+    ztimeout!(session1.close().res_async()).unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Delete);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_test/closed/a");
+}
@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use zenoh::prelude::sync::*;
+
+const ITERATIONS: usize = 1_000;
+
+#[test]
+fn local_put_after_subscribe_is_delivered() {
+    let session = zenoh::open(Config::default()).res().unwrap().into_arc();
+    let running = Arc::new(AtomicBool::new(true));
+
+    // Concurrently churn subscribers and publications on the same key expressions.
+    let churn = std::thread::spawn({
+        let session = session.clone();
+        let running = running.clone();
+        move || {
+            let publisher = session.declare_publisher("test/local/churn").res().unwrap();
+            while running.load(Ordering::Relaxed) {
+                let subscriber = session
+                    .declare_subscriber("test/local/**")
+                    .callback(|_| {})
+                    .res()
+                    .unwrap();
+                publisher.put("churn").res().unwrap();
+                subscriber.undeclare().res().unwrap();
+            }
+        }
+    });
+
+    let publisher = session.declare_publisher("test/local/key").res().unwrap();
+    for i in 0..ITERATIONS {
+        let received = Arc::new(AtomicUsize::new(0));
+        let subscriber = session
+            .declare_subscriber("test/local/key")
+            .callback({
+                let received = received.clone();
+                move |_| {
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .res()
+            .unwrap();
+        if i % 2 == 0 {
+            publisher.put("value").res().unwrap();
+        } else {
+            session.put("test/local/key", "value").res().unwrap();
+        }
+        assert_eq!(
+            received.load(Ordering::SeqCst),
+            1,
+            "Missed local publication at iteration {i}"
+        );
+        subscriber.undeclare().res().unwrap();
+    }
+
+    running.store(false, Ordering::Relaxed);
+    churn.join().unwrap();
+}
@@ -0,0 +1,132 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::EnvFilter;
+use zenoh::prelude::sync::*;
+use zenoh::query::Reply;
+use zenoh::Session;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+const TARGET: &str = "logging_test";
+
+// Counts the events of the test that pass the log filter
+struct EventCounter(Arc<AtomicUsize>);
+
+impl<S: Subscriber> Layer<S> for EventCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() == TARGET {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+fn open(listen: Option<&str>, connect: Option<&str>, logging: Option<&str>) -> Session {
+    let mut config = config::peer();
+    if let Some(endpoint) = listen {
+        config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    }
+    if let Some(endpoint) = connect {
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.permissions.set_write(true).unwrap();
+    if let Some(logging) = logging {
+        config.insert_json5("logging", logging).unwrap();
+    }
+    zenoh::open(config).res().unwrap()
+}
+
+fn emitted(counter: &AtomicUsize) -> bool {
+    let before = counter.load(Ordering::SeqCst);
+    tracing::debug!(target: TARGET, "debug event");
+    counter.load(Ordering::SeqCst) > before
+}
+
+#[test]
+fn runtime_log_filter() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let subscriber = tracing_subscriber::registry()
+        .with(zenoh_util::reloadable_log_filter(EnvFilter::new(format!(
+            "{TARGET}=info"
+        ))))
+        .with(EventCounter(counter.clone()));
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+
+    let session = open(Some("tcp/127.0.0.1:17509"), None, None);
+    let operator = open(None, Some("tcp/127.0.0.1:17509"), None);
+    std::thread::sleep(SLEEP);
+    let logging_key = format!("@/session/{}/config/logging", session.zid());
+    assert!(!emitted(&counter));
+
+    // An operator enables the debug logs of the remote session
+    operator
+        .put(&logging_key, format!("{TARGET}=debug"))
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+    assert!(emitted(&counter));
+
+    // An invalid filter is rejected and the previous filter is kept
+    let replies: Vec<Reply> = operator
+        .get(&logging_key)
+        .with_value(format!("{TARGET}=verbose"))
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(replies.len(), 1);
+    let error = replies[0].sample.as_ref().unwrap_err().to_string();
+    assert!(error.contains("Invalid log filter"), "{error}");
+    assert!(emitted(&counter));
+
+    let reply = operator
+        .get(&logging_key)
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap()
+        .recv_timeout(TIMEOUT)
+        .unwrap();
+    assert_eq!(
+        reply.sample.unwrap().value.to_string(),
+        format!("{TARGET}=debug")
+    );
+
+    // The debug logs are disabled again
+    let reply = operator
+        .get(&logging_key)
+        .with_value(format!("{TARGET}=info"))
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap()
+        .recv_timeout(TIMEOUT)
+        .unwrap();
+    assert!(reply.sample.is_ok());
+    assert!(!emitted(&counter));
+
+    // The logging configuration is applied when a session is opened
+    let _configured = open(
+        None,
+        None,
+        Some(&format!(
+            r#"{{ level: "info", modules: {{ "{TARGET}": "debug" }} }}"#
+        )),
+    );
+    assert!(emitted(&counter));
+}
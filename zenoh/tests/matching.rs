@@ -226,3 +226,49 @@ async fn zenoh_matching_status_local() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_matching_publishers() -> Result<()> {
+    use flume::RecvTimeoutError;
+
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:18002").await;
+
+    // Announced before the subscriber is declared
+    let publisher1 = ztimeout!(session1
+        .declare_publisher("zenoh_matching_publishers_test/a")
+        .announce()
+        .res_async())
+    .unwrap();
+
+    let (sender, matching_listener) = flume::unbounded();
+    let _sub = ztimeout!(session2
+        .declare_subscriber("zenoh_matching_publishers_test/**")
+        .matching_listener(move |status| sender.send(status).unwrap())
+        .res_async())
+    .unwrap();
+
+    let received_status = matching_listener.recv_timeout(TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_publishers()) == Some(true));
+
+    // Only the first publisher appearing and the last one disappearing are notified
+    let publisher2 = ztimeout!(session1
+        .declare_publisher("zenoh_matching_publishers_test/b")
+        .announce()
+        .res_async())
+    .unwrap();
+    let _unannounced = ztimeout!(session1
+        .declare_publisher("zenoh_matching_publishers_test/c")
+        .res_async())
+    .unwrap();
+    ztimeout!(publisher1.undeclare().res_async()).unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.err() == Some(RecvTimeoutError::Timeout));
+
+    drop(publisher2);
+
+    let received_status = matching_listener.recv_timeout(TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_publishers()) == Some(false));
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "metrics")]
+use prometheus_parse::{Scrape, Value as MetricValue};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+fn parse(text: &str) -> Scrape {
+    assert!(text.ends_with("# EOF\n"));
+    Scrape::parse(text.lines().map(|line| Ok(line.to_string()))).unwrap()
+}
+
+fn value(scrape: &Scrape, metric: &str, labels: &[(&str, &str)]) -> Option<f64> {
+    scrape
+        .samples
+        .iter()
+        .find(|sample| {
+            sample.metric == metric
+                && labels
+                    .iter()
+                    .all(|(name, value)| sample.labels.get(name) == Some(value))
+        })
+        .map(|sample| match sample.value {
+            MetricValue::Counter(v) | MetricValue::Gauge(v) | MetricValue::Untyped(v) => v,
+            _ => panic!("Unexpected value for {metric}"),
+        })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn metrics_exposition() {
+    zenoh_util::try_init_log_from_env();
+    let endpoint: EndPoint = "tcp/127.0.0.1:17502".parse().unwrap();
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.clone()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session01 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session02 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let long_key_expr = format!("test/metrics/{}", "a".repeat(100));
+    let sub01 = ztimeout!(session01.declare_subscriber("test/metrics/*").res_async()).unwrap();
+    let sub02 = ztimeout!(session01.declare_subscriber("test/metrics/*").res_async()).unwrap();
+    let sub03 = ztimeout!(session01
+        .declare_subscriber(long_key_expr.as_str())
+        .res_async())
+    .unwrap();
+    let metrics_key_expr = format!("@/metrics/{}", session01.zid());
+    let queryable = ztimeout!(session01
+        .metrics()
+        .serve(metrics_key_expr.as_str())
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(session02.put("test/metrics/key", "value").res_async()).unwrap();
+    ztimeout!(sub01.recv_async()).unwrap();
+
+    let scrape = parse(&session01.metrics().encode_prometheus());
+    assert_eq!(value(&scrape, "zenoh_build_info", &[]), Some(1.0));
+    assert_eq!(
+        value(
+            &scrape,
+            "zenoh_session_subscribers",
+            &[("key_expr", "test/metrics/*")]
+        ),
+        Some(2.0)
+    );
+    let truncated = format!("{}...", &long_key_expr[..64]);
+    assert_eq!(
+        value(
+            &scrape,
+            "zenoh_session_subscribers",
+            &[("key_expr", truncated.as_str())]
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        value(
+            &scrape,
+            "zenoh_session_queryables",
+            &[("key_expr", metrics_key_expr.as_str())]
+        ),
+        Some(1.0)
+    );
+    let peer = session02.zid().to_string();
+    let labels = [("peer", peer.as_str()), ("protocol", "tcp")];
+    assert!(value(&scrape, "zenoh_transport_rx_bytes_total", &labels).unwrap() > 0.0);
+    assert!(
+        value(
+            &scrape,
+            "zenoh_transport_rx_put_messages_total",
+            &[("peer", peer.as_str()), ("space", "user")]
+        )
+        .unwrap()
+            >= 1.0
+    );
+
+    // The metrics are also served over zenoh
+    let replies = ztimeout!(session02.get(&metrics_key_expr).res_async()).unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    let sample = reply.sample.unwrap();
+    assert_eq!(sample.key_expr.as_str(), metrics_key_expr);
+    let scrape = parse(&sample.value.to_string());
+    assert_eq!(
+        value(&scrape, "zenoh_session_pending_queries", &[]),
+        Some(0.0)
+    );
+
+    ztimeout!(queryable.undeclare().res_async()).unwrap();
+    ztimeout!(sub03.undeclare().res_async()).unwrap();
+    ztimeout!(sub02.undeclare().res_async()).unwrap();
+    ztimeout!(sub01.undeclare().res_async()).unwrap();
+    ztimeout!(session02.close().res_async()).unwrap();
+    ztimeout!(session01.close().res_async()).unwrap();
+}
@@ -0,0 +1,141 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod multi_key_expr {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::EntityKind;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn subscribers(session: &Session) -> Vec<String> {
+        session
+            .entities()
+            .into_iter()
+            .filter(|entity| {
+                entity.kind == EntityKind::Subscriber
+                    && entity.key_expr.as_str().starts_with("test/")
+            })
+            .map(|entity| entity.key_expr.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn single_handler() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/multi/pose/*")
+            .also("test/multi/status/*")
+            .res()
+            .unwrap();
+        assert_eq!(
+            subscribers(&session),
+            ["test/multi/pose/*", "test/multi/status/*"]
+        );
+
+        for key_expr in [
+            "test/multi/pose/a",
+            "test/multi/other/a",
+            "test/multi/status/a",
+        ] {
+            session.put(key_expr, "value").res().unwrap();
+        }
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.key_expr.as_str(), "test/multi/pose/a");
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.key_expr.as_str(), "test/multi/status/a");
+        assert!(subscriber.is_empty());
+
+        // All the key expressions are undeclared together
+        subscriber.undeclare().res().unwrap();
+        assert!(subscribers(&session).is_empty());
+    }
+
+    #[test]
+    fn dropped_together() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/drop/a")
+            .also("test/drop/b")
+            .also("test/drop/c")
+            .callback(|_| {})
+            .res()
+            .unwrap();
+        assert_eq!(subscribers(&session).len(), 3);
+
+        // Even if one of them was undeclared already
+        let id = session
+            .entities()
+            .into_iter()
+            .find(|entity| entity.key_expr.as_str() == "test/drop/b")
+            .unwrap()
+            .id;
+        session.undeclare(id).res().unwrap();
+        drop(subscriber);
+        assert!(subscribers(&session).is_empty());
+    }
+
+    #[test]
+    fn invalid_key_expr() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        assert!(session
+            .declare_subscriber("test/invalid/a")
+            .also("test/invalid/*b")
+            .res()
+            .is_err());
+        assert!(subscribers(&session).is_empty());
+    }
+
+    #[test]
+    fn pull_all() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17529".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+
+        let mut subscriber = subscribing
+            .declare_subscriber("test/pull/a")
+            .also("test/pull/b")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        publishing.put("test/pull/a", "a").res().unwrap();
+        publishing.put("test/pull/b", "b").res().unwrap();
+        // The puts are buffered by the subscribing session once it answered
+        assert_eq!(publishing.routed(TIMEOUT).res().unwrap(), 1);
+        subscriber.pull().res().unwrap();
+        let mut values = vec![
+            subscriber
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .unwrap()
+                .value
+                .to_string(),
+            subscriber
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .unwrap()
+                .value
+                .to_string(),
+        ];
+        values.sort();
+        assert_eq!(values, ["a", "b"]);
+    }
+}
@@ -0,0 +1,142 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod no_route {
+    use std::time::Duration;
+    use zenoh::no_route::{NoRoute, NoRoutePolicy};
+    use zenoh::prelude::sync::*;
+    use zenoh::Session;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    // A peer that connects to the router in the background, once it is started
+    // (clients wait on open until they are connected)
+    fn peer(router: &str, no_route: &str) -> Session {
+        let mut config = config::peer();
+        config.connect.endpoints = vec![router.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .insert_json5("connect/exit_on_failure", "false")
+            .unwrap();
+        config.insert_json5("connect/timeout_ms", "-1").unwrap();
+        config
+            .insert_json5(
+                "connect/retry",
+                "{ period_init_ms: 100, period_max_ms: 100 }",
+            )
+            .unwrap();
+        config.insert_json5("no_route", no_route).unwrap();
+        zenoh::open(config).res().unwrap()
+    }
+
+    fn router(listen: &str) -> Session {
+        let mut config = config::default();
+        config.set_mode(Some(WhatAmI::Router)).unwrap();
+        config.listen.endpoints = vec![listen.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        zenoh::open(config).res().unwrap()
+    }
+
+    #[test]
+    fn buffered_until_connected() {
+        let session = peer(
+            "tcp/127.0.0.1:17510",
+            r#"{ policy: "buffer", buffer_size: 16 }"#,
+        );
+        for i in 0..10 {
+            session.put("test/no_route/buffer", i as u64).res().unwrap();
+        }
+
+        let router = router("tcp/127.0.0.1:17510");
+        let mut subscriber = router
+            .declare_subscriber("test/no_route/buffer")
+            .res()
+            .unwrap();
+        for i in 0..10 {
+            let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+            assert_eq!(sample.value.to_string(), i.to_string());
+        }
+
+        // Once connected, the puts are sent right away
+        session
+            .put("test/no_route/buffer", "connected")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "connected");
+    }
+
+    #[test]
+    fn buffer_full() {
+        let session = peer(
+            "tcp/127.0.0.1:17511",
+            r#"{ policy: "buffer", buffer_size: 2 }"#,
+        );
+        session.put("test/no_route/full", "first").res().unwrap();
+        session.put("test/no_route/full", "second").res().unwrap();
+        let error = session
+            .put("test/no_route/full", "third")
+            .res()
+            .unwrap_err();
+        assert!(error.downcast_ref::<NoRoute>().is_some());
+    }
+
+    #[test]
+    fn fail_until_connected() {
+        let session = peer("tcp/127.0.0.1:17512", r#"{ policy: "fail" }"#);
+        let error = session
+            .put("test/no_route/fail", "value")
+            .res()
+            .unwrap_err();
+        assert!(error.downcast_ref::<NoRoute>().is_some());
+        let error = session.get("test/no_route/fail").res().unwrap_err();
+        assert!(error.downcast_ref::<NoRoute>().is_some());
+
+        // The policy of the session is overridden by the publishers
+        let publisher = session
+            .declare_publisher("test/no_route/fail")
+            .no_route(NoRoutePolicy::Drop)
+            .res()
+            .unwrap();
+        publisher.put("dropped").res().unwrap();
+
+        let router = router("tcp/127.0.0.1:17512");
+        let mut subscriber = router
+            .declare_subscriber("test/no_route/fail")
+            .res()
+            .unwrap();
+        let _queryable = router
+            .declare_queryable("test/no_route/fail")
+            .callback(|query| {
+                let sample = Sample::new(query.key_expr().clone(), "reply");
+                query.reply(Ok(sample)).res().unwrap();
+            })
+            .res()
+            .unwrap();
+        std::thread::sleep(5 * SLEEP);
+
+        session.put("test/no_route/fail", "value").res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+        let reply = session
+            .get("test/no_route/fail")
+            .timeout(TIMEOUT)
+            .res()
+            .unwrap()
+            .recv_timeout(TIMEOUT)
+            .unwrap();
+        assert_eq!(reply.sample.unwrap().value.to_string(), "reply");
+    }
+}
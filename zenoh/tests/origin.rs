@@ -0,0 +1,78 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod origin {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const KEY_EXPR: &str = "test/origin/key";
+
+    /// Subscribe with `origin` on a session that also publishes on the subscribed key,
+    /// and return the values it received from itself and from a connected session.
+    fn received(origin: Locality, port: u16) -> Vec<String> {
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let session = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let remote = zenoh::open(config).res().unwrap();
+
+        let subscriber = session
+            .declare_subscriber(KEY_EXPR)
+            .allowed_origin(origin)
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        session.put(KEY_EXPR, "local").res().unwrap();
+        remote.put(KEY_EXPR, "remote").res().unwrap();
+        // The put of `remote` is delivered once the subscribing session answered
+        assert_eq!(remote.routed(TIMEOUT).res().unwrap(), 1);
+
+        let mut values = Vec::new();
+        while let Ok(sample) = subscriber.try_recv() {
+            values.push(sample.value.to_string());
+        }
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn any() {
+        assert_eq!(received(Locality::Any, 17530), ["local", "remote"]);
+    }
+
+    #[test]
+    fn session_local() {
+        assert_eq!(received(Locality::SessionLocal, 17531), ["local"]);
+    }
+
+    #[test]
+    fn remote() {
+        // Echo suppression: the session ignores its own publications
+        assert_eq!(received(Locality::Remote, 17532), ["remote"]);
+    }
+
+    #[test]
+    fn local_shorthand() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session.declare_subscriber(KEY_EXPR).local().res().unwrap();
+        session.put(KEY_EXPR, "local").res().unwrap();
+        let sample = subscriber.try_recv().unwrap();
+        assert_eq!(sample.value.to_string(), "local");
+    }
+}
@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod overflow_policy {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use zenoh::handlers::OverflowPolicy;
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::FlumeSubscriber;
+    use zenoh::Session;
+
+    const SLEEP: Duration = Duration::from_secs(1);
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn received(subscriber: &FlumeSubscriber) -> Vec<String> {
+        subscriber
+            .drain(usize::MAX)
+            .into_iter()
+            .map(|sample| sample.value.to_string())
+            .collect()
+    }
+
+    fn burst(session: &Session, key_expr: &str) {
+        for i in 0..10 {
+            session.put(key_expr, i as u64).res().unwrap();
+        }
+    }
+
+    #[test]
+    fn drop_newest() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/overflow/newest")
+            .channel_size(4)
+            .on_full(OverflowPolicy::DropNewest)
+            .res()
+            .unwrap();
+
+        burst(&session, "test/overflow/newest");
+        assert_eq!(received(&subscriber), ["0", "1", "2", "3"]);
+        assert_eq!(subscriber.dropped_samples(), 6);
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/overflow/oldest")
+            .on_full(OverflowPolicy::DropOldest)
+            .channel_size(4)
+            .res()
+            .unwrap();
+
+        burst(&session, "test/overflow/oldest");
+        assert_eq!(received(&subscriber), ["6", "7", "8", "9"]);
+        assert_eq!(subscriber.dropped_samples(), 6);
+
+        // Nothing is dropped while the channel has room
+        burst(&session, "test/overflow/oldest/none");
+        session.put("test/overflow/oldest", "last").res().unwrap();
+        assert_eq!(received(&subscriber), ["last"]);
+        assert_eq!(subscriber.dropped_samples(), 6);
+    }
+
+    #[test]
+    fn dropped_samples_handed_to_hook() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let on_drop = {
+            let dropped = dropped.clone();
+            move |sample: Sample| dropped.lock().unwrap().push(sample.value.to_string())
+        };
+        let subscriber = session
+            .declare_subscriber("test/overflow/hook")
+            .channel_size(4)
+            .on_full(OverflowPolicy::DropOldest)
+            .on_drop(on_drop.clone())
+            .res()
+            .unwrap();
+
+        burst(&session, "test/overflow/hook");
+        assert_eq!(received(&subscriber), ["6", "7", "8", "9"]);
+        assert_eq!(*dropped.lock().unwrap(), ["0", "1", "2", "3", "4", "5"]);
+        drop(subscriber);
+
+        dropped.lock().unwrap().clear();
+        let subscriber = session
+            .declare_subscriber("test/overflow/hook")
+            .on_drop(on_drop)
+            .channel_size(4)
+            .on_full(OverflowPolicy::DropNewest)
+            .res()
+            .unwrap();
+        burst(&session, "test/overflow/hook");
+        assert_eq!(received(&subscriber), ["0", "1", "2", "3"]);
+        assert_eq!(*dropped.lock().unwrap(), ["4", "5", "6", "7", "8", "9"]);
+    }
+
+    #[test]
+    fn block() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let mut subscriber = session
+            .declare_subscriber("test/overflow/block")
+            .channel_size(4)
+            .on_full(OverflowPolicy::Block)
+            .res()
+            .unwrap();
+
+        let publisher = {
+            let session = session.clone();
+            std::thread::spawn(move || burst(&session, "test/overflow/block"))
+        };
+        std::thread::sleep(SLEEP);
+        assert_eq!(subscriber.len(), 4);
+        assert!(!publisher.is_finished());
+
+        for i in 0..10 {
+            let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+            assert_eq!(sample.value.to_string(), i.to_string());
+        }
+        publisher.join().unwrap();
+        assert_eq!(subscriber.dropped_samples(), 0);
+    }
+
+    #[test]
+    fn unbounded_never_drops() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/overflow/unbounded")
+            .on_full(OverflowPolicy::DropNewest)
+            .unbounded()
+            .res()
+            .unwrap();
+
+        burst(&session, "test/overflow/unbounded");
+        assert_eq!(received(&subscriber).len(), 10);
+        assert_eq!(subscriber.dropped_samples(), 0);
+    }
+}
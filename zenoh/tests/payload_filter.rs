@@ -0,0 +1,162 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn payload_prefix_filters() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/payload/filter";
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:17484".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let sub_session = zenoh::open(config).res().unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec!["tcp/127.0.0.1:17484".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let pub_session = zenoh::open(config).res().unwrap();
+
+    let sub01 = sub_session
+        .declare_subscriber(KEY_EXPR)
+        .payload_prefix(0, [0x01])
+        .res()
+        .unwrap();
+    let sub02 = sub_session
+        .declare_subscriber(KEY_EXPR)
+        .payload_prefix(1, [0x02, 0x03])
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    let payloads: [&[u8]; 5] = [
+        &[0x01, 0x00],
+        &[0x00, 0x02, 0x03],
+        &[0x01, 0x02, 0x03],
+        &[0x00, 0x02],
+        &[],
+    ];
+    for payload in payloads {
+        pub_session.put(KEY_EXPR, payload.to_vec()).res().unwrap();
+        // Local publications are filtered the same way
+        sub_session.put(KEY_EXPR, payload.to_vec()).res().unwrap();
+    }
+    std::thread::sleep(SLEEP);
+
+    let received = |receiver: &flume::Receiver<Sample>| {
+        receiver
+            .drain()
+            .map(|sample| sample.value.payload.contiguous().to_vec())
+            .collect::<Vec<_>>()
+    };
+    let mut received01 = received(&sub01);
+    received01.sort();
+    assert_eq!(
+        received01,
+        vec![
+            vec![0x01, 0x00],
+            vec![0x01, 0x00],
+            vec![0x01, 0x02, 0x03],
+            vec![0x01, 0x02, 0x03]
+        ]
+    );
+    let mut received02 = received(&sub02);
+    received02.sort();
+    assert_eq!(
+        received02,
+        vec![
+            vec![0x00, 0x02, 0x03],
+            vec![0x00, 0x02, 0x03],
+            vec![0x01, 0x02, 0x03],
+            vec![0x01, 0x02, 0x03]
+        ]
+    );
+
+    sub01.undeclare().res().unwrap();
+    sub02.undeclare().res().unwrap();
+    pub_session.close().res().unwrap();
+    sub_session.close().res().unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn payload_prefix_filters_at_publisher() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/payload/filter/remote";
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:17486".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let sub_session = zenoh::open(config).res().unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec!["tcp/127.0.0.1:17486".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let pub_session = zenoh::open(config).res().unwrap();
+
+    let sub01 = sub_session
+        .declare_subscriber(KEY_EXPR)
+        .payload_prefix(0, [0x01])
+        .res()
+        .unwrap();
+    let sub02 = sub_session
+        .declare_subscriber(KEY_EXPR)
+        .payload_prefix(1, [0x02, 0x03])
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    let payloads: [&[u8]; 5] = [
+        &[0x01, 0x00],
+        &[0x00, 0x02, 0x03],
+        &[0x01, 0x02, 0x03],
+        &[0x00, 0x02],
+        &[],
+    ];
+    for payload in payloads {
+        pub_session.put(KEY_EXPR, payload.to_vec()).res().unwrap();
+    }
+    std::thread::sleep(SLEEP);
+
+    let received = |receiver: &flume::Receiver<Sample>| {
+        let mut received = receiver
+            .drain()
+            .map(|sample| sample.value.payload.contiguous().to_vec())
+            .collect::<Vec<_>>();
+        received.sort();
+        received
+    };
+    assert_eq!(
+        received(&sub01),
+        vec![vec![0x01, 0x00], vec![0x01, 0x02, 0x03]]
+    );
+    assert_eq!(
+        received(&sub02),
+        vec![vec![0x00, 0x02, 0x03], vec![0x01, 0x02, 0x03]]
+    );
+    // The subscriptions are declared with the union of their filters: the publisher doesn't send
+    // the payloads matching neither, so each subscriber only rejects the payload of the other
+    assert_eq!(sub01.filtered(), 1);
+    assert_eq!(sub02.filtered(), 1);
+
+    sub01.undeclare().res().unwrap();
+    sub02.undeclare().res().unwrap();
+    pub_session.close().res().unwrap();
+    sub_session.close().res().unwrap();
+}
@@ -0,0 +1,108 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod payload_limits {
+    use std::time::Duration;
+    use zenoh::events::SessionEvent;
+    use zenoh::prelude::sync::*;
+    use zenoh::publication::PayloadTooLarge;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    // Two connected sessions, with the given max_payload_size
+    fn open_pair(port: u16, listening: usize, connecting: usize) -> (Session, Session) {
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.set_max_payload_size(Some(listening)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let listening = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.set_max_payload_size(Some(connecting)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let connecting = zenoh::open(config).res().unwrap();
+        (listening, connecting)
+    }
+
+    fn payload(size: usize) -> Vec<u8> {
+        vec![0; size]
+    }
+
+    #[test]
+    fn put_limit() {
+        let (large, small) = open_pair(17525, 1024, 512);
+        assert_eq!(large.info().max_payload_size(), Some(1024));
+        assert_eq!(small.info().max_payload_size(), Some(512));
+
+        for (session, limit) in [(&large, 1024), (&small, 512)] {
+            session
+                .put("test/limits/put", payload(limit))
+                .res()
+                .unwrap();
+            let error = session
+                .put("test/limits/put", payload(limit + 1))
+                .res()
+                .unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<PayloadTooLarge>(),
+                Some(&PayloadTooLarge {
+                    size: limit + 1,
+                    limit
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn reception_limit() {
+        let (large, small) = open_pair(17526, 1024, 512);
+        let events = small.event_listener().res().unwrap();
+        let mut on_small = small
+            .declare_subscriber("test/limits/recv")
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        let mut on_large = large
+            .declare_subscriber("test/limits/recv")
+            .allowed_origin(Locality::Remote)
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+
+        // Large to small: the payloads over the limit of the receiver are dropped
+        large.put("test/limits/recv", payload(512)).res().unwrap();
+        large.put("test/limits/recv", payload(513)).res().unwrap();
+        large.put("test/limits/recv", "last").res().unwrap();
+        let sample = on_small.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.payload.len(), 512);
+        let sample = on_small.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "last");
+        assert_eq!(small.info().oversized_payloads(), 1);
+        assert_eq!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            SessionEvent::PayloadTooLarge {
+                key_expr: "test/limits/recv".to_string(),
+                size: 513,
+                limit: 512,
+            }
+        );
+
+        // Small to large: the payloads are bounded by the limit of the sender
+        small.put("test/limits/recv", payload(512)).res().unwrap();
+        let sample = on_large.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.payload.len(), 512);
+        assert_eq!(large.info().oversized_payloads(), 0);
+    }
+}
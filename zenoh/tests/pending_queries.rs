@@ -0,0 +1,67 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn cancel_pending_query() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::query::QUERY_CANCELLED;
+
+    const KEY_EXPR: &str = "test/pending/silent";
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+
+    // A queryable that never replies nor finalizes the queries it receives
+    let held = Arc::new(Mutex::new(vec![]));
+    let _queryable = session
+        .declare_queryable(KEY_EXPR)
+        .callback({
+            let held = held.clone();
+            move |query| held.lock().unwrap().push(query)
+        })
+        .res()
+        .unwrap();
+
+    let replies = session.get(KEY_EXPR).timeout(TIMEOUT).res().unwrap();
+    let pending = session.pending_queries();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].selector.key_expr.as_str(), KEY_EXPR);
+    assert_eq!(pending[0].replies, 0);
+    let qid = pending[0].qid;
+
+    // The pending query is also listed in the admin space
+    let admin = session
+        .get(format!("@/session/{}/query/*", session.zid()))
+        .res()
+        .unwrap();
+    let listed: Vec<String> = admin
+        .iter()
+        .filter_map(|reply| reply.sample.ok())
+        .map(|sample| sample.key_expr.to_string())
+        .collect();
+    assert!(listed.contains(&format!("@/session/{}/query/{}", session.zid(), qid)));
+
+    session.cancel_query(qid).res().unwrap();
+    let reply = replies.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(
+        reply.sample.unwrap_err().to_string(),
+        QUERY_CANCELLED.to_string()
+    );
+    // The receiver is closed once the query is cancelled
+    assert!(replies.recv_timeout(TIMEOUT).is_err());
+    assert!(session.pending_queries().is_empty());
+    assert!(session.cancel_query(qid).res().is_err());
+}
@@ -0,0 +1,140 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod periodic_pull {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::InvalidPullPeriod;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    // The samples published by a remote session are only delivered when pulled
+    fn open_pair(port: u16) -> (Session, Session) {
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+        (publishing, subscribing)
+    }
+
+    #[test]
+    fn pulls_periodically() {
+        let (session, subscribing) = open_pair(17523);
+        let mut subscriber = subscribing
+            .declare_subscriber("test/periodic/auto")
+            .pull_mode_periodic(Duration::from_millis(50))
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+
+        session.put("test/periodic/auto", "first").res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "first");
+
+        // Manual pulls still work in between the ticks
+        subscriber
+            .set_pull_period(Some(Duration::from_secs(3600)))
+            .res()
+            .unwrap();
+        session.put("test/periodic/auto", "second").res().unwrap();
+        std::thread::sleep(SLEEP);
+        assert!(subscriber.is_empty());
+        subscriber.pull().res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "second");
+    }
+
+    #[test]
+    fn set_pull_period() {
+        let (session, subscribing) = open_pair(17524);
+        let mut subscriber = subscribing
+            .declare_subscriber("test/periodic/set")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+
+        session.put("test/periodic/set", "first").res().unwrap();
+        std::thread::sleep(SLEEP);
+        assert!(subscriber.is_empty());
+        subscriber
+            .set_pull_period(Some(Duration::from_millis(50)))
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "first");
+
+        subscriber.set_pull_period(None).res().unwrap();
+        session.put("test/periodic/set", "second").res().unwrap();
+        std::thread::sleep(SLEEP);
+        assert!(subscriber.is_empty());
+
+        assert!(subscriber
+            .set_pull_period(Some(Duration::ZERO))
+            .res()
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_periods() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        for period in [Duration::ZERO, Duration::from_nanos(500)] {
+            let error = session
+                .declare_subscriber("test/periodic/invalid")
+                .pull_mode_periodic(period)
+                .res()
+                .err()
+                .unwrap();
+            assert_eq!(
+                error.downcast_ref::<InvalidPullPeriod>(),
+                Some(&InvalidPullPeriod { period })
+            );
+        }
+
+        // Periods too long to ever elapse are valid
+        let subscriber = session
+            .declare_subscriber("test/periodic/invalid")
+            .pull_mode_periodic(Duration::from_secs(u32::MAX as u64 + 1))
+            .res()
+            .unwrap();
+        subscriber
+            .set_pull_period(Some(Duration::MAX))
+            .res()
+            .unwrap();
+        subscriber.undeclare().res().unwrap();
+    }
+
+    #[test]
+    fn stops_when_undeclared() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/periodic/undeclare")
+            .pull_mode_periodic(Duration::from_millis(10))
+            .res()
+            .unwrap();
+        std::thread::sleep(SLEEP / 10);
+        subscriber.undeclare().res().unwrap();
+
+        // The session closes without waiting for a leftover pulling task
+        let start = std::time::Instant::now();
+        session.close().res().unwrap();
+        assert!(start.elapsed() < 5 * SLEEP);
+    }
+}
@@ -0,0 +1,82 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn loop_guard_breaks_bridge_loop() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::sample::ProvenanceHop;
+
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    type Received = Arc<Mutex<Vec<Vec<ProvenanceHop>>>>;
+
+    // Re-publish everything received on `from/**` on `to/key`
+    fn bridge(
+        session: &Arc<Session>,
+        from: &str,
+        to: &str,
+    ) -> (zenoh::subscriber::Subscriber<'static, ()>, Received) {
+        let received = Received::default();
+        let publisher = session
+            .declare_publisher(to.to_string())
+            .res()
+            .unwrap()
+            .loop_guard(true);
+        let subscriber = session
+            .declare_subscriber(format!("{from}/**"))
+            .callback({
+                let received = received.clone();
+                move |sample| {
+                    received.lock().unwrap().push(sample.provenance());
+                    publisher.forward(&sample).res().unwrap();
+                }
+            })
+            .res()
+            .unwrap();
+        (subscriber, received)
+    }
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:17487".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session01 = zenoh::open(config).res().unwrap().into_arc();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec!["tcp/127.0.0.1:17487".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session02 = zenoh::open(config).res().unwrap().into_arc();
+
+    let (bridge01, received01) = bridge(&session01, "a", "b/key");
+    let (bridge02, received02) = bridge(&session02, "b", "a/key");
+    std::thread::sleep(SLEEP);
+
+    session02.put("a/key", "value").res().unwrap();
+    std::thread::sleep(SLEEP);
+
+    // The original sample and its looped-back copy reach the first bridge,
+    // which then drops the copy as it already went through it.
+    let received01 = received01.lock().unwrap().clone();
+    let received02 = received02.lock().unwrap().clone();
+    assert_eq!(received01.len(), 2);
+    assert_eq!(received02.len(), 1);
+    assert!(received01[0].is_empty());
+    let zids = |hops: &Vec<ProvenanceHop>| hops.iter().map(|h| h.zid).collect::<Vec<_>>();
+    assert_eq!(zids(&received02[0]), vec![session01.zid()]);
+    assert_eq!(zids(&received01[1]), vec![session01.zid(), session02.zid()]);
+
+    bridge01.undeclare().res().unwrap();
+    bridge02.undeclare().res().unwrap();
+}
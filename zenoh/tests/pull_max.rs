@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod pull_max {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn bounded_pulls() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17538".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+        let mut subscriber = subscribing
+            .declare_subscriber("test/pullmax/*")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+
+        // The routers keep the last sample of each key
+        for key in ["a", "b", "c"] {
+            publishing
+                .put(format!("test/pullmax/{key}"), key)
+                .res()
+                .unwrap();
+        }
+        std::thread::sleep(SLEEP);
+
+        assert_eq!(subscriber.pull_max(0).res().unwrap(), 0);
+        std::thread::sleep(SLEEP);
+        assert!(subscriber.is_empty());
+
+        // The oldest samples are delivered first
+        assert_eq!(subscriber.pull_max(1).res().unwrap(), 1);
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "a");
+        std::thread::sleep(SLEEP);
+        assert!(subscriber.is_empty());
+
+        // The others are left for the next pull
+        assert_eq!(subscriber.pull_max(usize::MAX).res().unwrap(), 2);
+        for key in ["b", "c"] {
+            let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+            assert_eq!(sample.value.to_string(), key);
+        }
+        std::thread::sleep(SLEEP);
+        assert!(subscriber.is_empty());
+
+        publishing.put("test/pullmax/d", "d").res().unwrap();
+        std::thread::sleep(SLEEP);
+        subscriber.pull().res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "d");
+    }
+
+    #[test]
+    fn shared_budget() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17539".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+        let mut subscriber = subscribing
+            .declare_subscriber("test/pullshare/a/*")
+            .also("test/pullshare/b/*")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+
+        for key in ["a/1", "a/2", "b/1", "b/2"] {
+            publishing
+                .put(format!("test/pullshare/{key}"), key)
+                .res()
+                .unwrap();
+        }
+        std::thread::sleep(SLEEP);
+
+        // The key expressions share the bound, in their order
+        assert_eq!(subscriber.pull_max(3).res().unwrap(), 3);
+        for key in ["a/1", "a/2", "b/1"] {
+            let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+            assert_eq!(sample.value.to_string(), key);
+        }
+        assert!(subscriber.is_empty());
+
+        assert_eq!(subscriber.pull_max(3).res().unwrap(), 1);
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "b/2");
+        assert!(subscriber.is_empty());
+    }
+}
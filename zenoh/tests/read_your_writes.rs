@@ -0,0 +1,127 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod read_your_writes {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::query::ConsolidationMode;
+    use zenoh::queryable::Queryable;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    // A storage lagging behind the writes: it always replies with its initial values,
+    // timestamped when replying if `fresh`
+    fn storage(session: &Session, fresh: bool) -> Queryable<'_, ()> {
+        session
+            .declare_queryable("test/ryw/**")
+            .callback(move |query| {
+                for key_expr in ["test/ryw/a", "test/ryw/b"] {
+                    let key_expr = KeyExpr::try_from(key_expr).unwrap();
+                    let mut sample = Sample::new(key_expr, "stored");
+                    if fresh {
+                        sample = sample.with_timestamp(zenoh::time::new_reception_timestamp());
+                    }
+                    query.reply(Ok(sample)).res().unwrap();
+                }
+            })
+            .res()
+            .unwrap()
+    }
+
+    fn get(session: &Session, read_your_writes: bool) -> BTreeMap<String, String> {
+        let replies = session
+            .get("test/ryw/**")
+            .consolidation(ConsolidationMode::None)
+            .timeout(TIMEOUT)
+            .read_your_writes(read_your_writes)
+            .res()
+            .unwrap();
+        replies
+            .iter()
+            .map(|reply| {
+                let sample = reply.sample.unwrap();
+                (sample.key_expr.to_string(), sample.value.to_string())
+            })
+            .collect()
+    }
+
+    fn open(journal_size: usize, journal_ttl_ms: u64) -> Session {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.insert_json5("timestamping/enabled", "true").unwrap();
+        config
+            .read_your_writes
+            .set_journal_size(Some(journal_size))
+            .unwrap();
+        config
+            .read_your_writes
+            .set_journal_ttl_ms(Some(journal_ttl_ms))
+            .unwrap();
+        zenoh::open(config).res().unwrap()
+    }
+
+    #[test]
+    fn writes_overlay_replies() {
+        let session = open(16, 60_000);
+        let _storage = storage(&session, false);
+
+        session.put("test/ryw/a", "written").res().unwrap();
+        session.delete("test/ryw/b").res().unwrap();
+        session.put("test/ryw/c", "written").res().unwrap();
+        assert_eq!(
+            get(&session, true),
+            BTreeMap::from([
+                ("test/ryw/a".to_string(), "written".to_string()),
+                ("test/ryw/c".to_string(), "written".to_string()),
+            ])
+        );
+        assert_eq!(
+            get(&session, false),
+            BTreeMap::from([
+                ("test/ryw/a".to_string(), "stored".to_string()),
+                ("test/ryw/b".to_string(), "stored".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn newer_replies_win() {
+        let session = open(16, 60_000);
+        let _storage = storage(&session, true);
+
+        session.put("test/ryw/a", "written").res().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        let values = get(&session, true);
+        assert_eq!(values["test/ryw/a"], "stored");
+    }
+
+    #[test]
+    fn journal_bounds() {
+        let session = open(1, 500);
+        let _storage = storage(&session, false);
+
+        // Only the last write is kept
+        session.put("test/ryw/a", "written").res().unwrap();
+        session.put("test/ryw/b", "written").res().unwrap();
+        let values = get(&session, true);
+        assert_eq!(values["test/ryw/a"], "stored");
+        assert_eq!(values["test/ryw/b"], "written");
+
+        // Until it expires
+        std::thread::sleep(Duration::from_secs(1));
+        let values = get(&session, true);
+        assert_eq!(values["test/ryw/b"], "stored");
+    }
+}
@@ -0,0 +1,137 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod recv_timeout {
+    use std::time::{Duration, Instant};
+    use zenoh::handlers::{ChannelDisconnected, SampleReceiver};
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SHORT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn flume_handler() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/recv_timeout/flume")
+            .res()
+            .unwrap();
+
+        assert!(subscriber.recv_timeout(SHORT).unwrap().is_none());
+        session
+            .put("test/recv_timeout/flume", "value")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+        assert!(subscriber.recv_deadline(Instant::now()).unwrap().is_none());
+    }
+
+    #[test]
+    fn std_handler() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/recv_timeout/std")
+            .with(std::sync::mpsc::channel())
+            .res()
+            .unwrap();
+
+        assert!(subscriber.recv_timeout(SHORT).unwrap().is_none());
+        session.put("test/recv_timeout/std", "value").res().unwrap();
+        let sample = subscriber
+            .recv_deadline(Instant::now() + TIMEOUT)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    #[test]
+    fn tokio_handler() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/recv_timeout/tokio")
+            .with(tokio::sync::mpsc::channel(16))
+            .res()
+            .unwrap();
+
+        assert!(subscriber.recv_timeout(SHORT).unwrap().is_none());
+        session
+            .put("test/recv_timeout/tokio", "value")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    #[test]
+    fn tokio_broadcast_handler() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/recv_timeout/broadcast")
+            .with(tokio::sync::broadcast::channel(16))
+            .res()
+            .unwrap();
+
+        assert!(subscriber.recv_timeout(SHORT).unwrap().is_none());
+        session
+            .put("test/recv_timeout/broadcast", "value")
+            .res()
+            .unwrap();
+        let sample = subscriber
+            .recv_deadline(Instant::now() + TIMEOUT)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    fn is_disconnected<T>(result: zenoh::Result<Option<T>>) -> bool {
+        matches!(result, Err(e) if e.downcast_ref::<ChannelDisconnected>().is_some())
+    }
+
+    #[test]
+    fn disconnected() {
+        let (sender, mut receiver) = flume::bounded::<Sample>(1);
+        drop(sender);
+        assert!(is_disconnected(SampleReceiver::recv_timeout(
+            &mut receiver,
+            TIMEOUT
+        )));
+        // Timeouts too long to compute a deadline don't overflow
+        assert!(is_disconnected(SampleReceiver::recv_timeout(
+            &mut receiver,
+            Duration::MAX
+        )));
+
+        let (sender, mut receiver) = std::sync::mpsc::channel::<Sample>();
+        drop(sender);
+        assert!(is_disconnected(SampleReceiver::recv_timeout(
+            &mut receiver,
+            TIMEOUT
+        )));
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel::<Sample>(1);
+        drop(sender);
+        assert!(is_disconnected(SampleReceiver::recv_timeout(
+            &mut receiver,
+            TIMEOUT
+        )));
+
+        let (sender, mut receiver) = tokio::sync::broadcast::channel::<Sample>(1);
+        drop(sender);
+        assert!(is_disconnected(SampleReceiver::recv_timeout(
+            &mut receiver,
+            TIMEOUT
+        )));
+    }
+}
@@ -0,0 +1,119 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod reply_cache {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::queryable::{Queryable, NOCACHE_PARAMETER};
+    use zenoh::Session;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    /// Declares a queryable replying twice with the number of queries it handled.
+    fn declare<'a>(
+        session: &'a Session,
+        key_expr: &'static str,
+        ttl: Duration,
+        max_entries: usize,
+    ) -> (Queryable<'a, ()>, Arc<AtomicUsize>) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let queryable = {
+            let count = count.clone();
+            session
+                .declare_queryable(key_expr)
+                .callback(move |query| {
+                    let n = count.fetch_add(1, Ordering::SeqCst) + 1;
+                    for i in 0..2 {
+                        let reply = Sample::new(query.key_expr().clone(), format!("{n}/{i}"));
+                        query.reply(Ok(reply)).res().unwrap();
+                    }
+                })
+                .cache(ttl, max_entries)
+                .res()
+                .unwrap()
+        };
+        (queryable, count)
+    }
+
+    fn get(session: &Session, selector: &str) -> Vec<String> {
+        session
+            .get(selector)
+            .consolidation(ConsolidationMode::None)
+            .timeout(TIMEOUT)
+            .res()
+            .unwrap()
+            .into_iter()
+            .map(|reply| reply.sample.unwrap().value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn replies_cached_per_selector() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let (queryable, count) = declare(&session, "test/cache/**", TIMEOUT, 16);
+
+        assert_eq!(get(&session, "test/cache/a?x=1&y=2"), ["1/0", "1/1"]);
+        // Identical selectors, whatever the order of their parameters, are replayed
+        assert_eq!(get(&session, "test/cache/a?x=1&y=2"), ["1/0", "1/1"]);
+        assert_eq!(get(&session, "test/cache/a?y=2&x=1"), ["1/0", "1/1"]);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // Other key expressions or parameters are cached separately
+        assert_eq!(get(&session, "test/cache/b?x=1&y=2"), ["2/0", "2/1"]);
+        assert_eq!(get(&session, "test/cache/a?x=2"), ["3/0", "3/1"]);
+        assert_eq!(get(&session, "test/cache/a"), ["4/0", "4/1"]);
+        assert_eq!(get(&session, "test/cache/b?x=1&y=2"), ["2/0", "2/1"]);
+        assert_eq!(get(&session, "test/cache/a"), ["4/0", "4/1"]);
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+
+        // The cache is bypassed, and left untouched, by the queries asking for it
+        let nocache = format!("test/cache/a?x=2&{NOCACHE_PARAMETER}");
+        assert_eq!(get(&session, &nocache), ["5/0", "5/1"]);
+        assert_eq!(get(&session, "test/cache/a?x=2"), ["3/0", "3/1"]);
+
+        queryable.invalidate("test/cache/a");
+        assert_eq!(get(&session, "test/cache/a?x=2"), ["6/0", "6/1"]);
+        assert_eq!(get(&session, "test/cache/b?x=1&y=2"), ["2/0", "2/1"]);
+        assert_eq!(count.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn cached_replies_expire() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let (_queryable, count) = declare(&session, "test/cache/ttl", SLEEP, 16);
+
+        assert_eq!(get(&session, "test/cache/ttl"), ["1/0", "1/1"]);
+        assert_eq!(get(&session, "test/cache/ttl"), ["1/0", "1/1"]);
+        std::thread::sleep(2 * SLEEP);
+        assert_eq!(get(&session, "test/cache/ttl"), ["2/0", "2/1"]);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn oldest_entry_evicted() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let (_queryable, count) = declare(&session, "test/cache/evict/*", TIMEOUT, 2);
+
+        get(&session, "test/cache/evict/a");
+        get(&session, "test/cache/evict/b");
+        get(&session, "test/cache/evict/c");
+        assert_eq!(get(&session, "test/cache/evict/c"), ["3/0", "3/1"]);
+        assert_eq!(get(&session, "test/cache/evict/b"), ["2/0", "2/1"]);
+        assert_eq!(get(&session, "test/cache/evict/a"), ["4/0", "4/1"]);
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+    }
+}
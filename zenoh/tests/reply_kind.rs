@@ -0,0 +1,115 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::query::ConsolidationMode;
+
+const SLEEP: Duration = Duration::from_secs(1);
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+fn sample(key_expr: &str, kind: SampleKind) -> Sample {
+    let mut sample = Sample::new(KeyExpr::try_from(key_expr).unwrap().into_owned(), "");
+    sample.kind = kind;
+    sample
+}
+
+#[test]
+fn delete_replies() {
+    let mut config = config::peer();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:17504".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let qbl_session = zenoh::open(config).res().unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec!["tcp/127.0.0.1:17504".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let get_session = zenoh::open(config).res().unwrap();
+
+    let _queryable = qbl_session
+        .declare_queryable("test/reply_kind/**")
+        .callback(|query| {
+            let mut put = sample("test/reply_kind/put", SampleKind::Put);
+            put.value = "value".into();
+            query.reply(Ok(put)).res().unwrap();
+            let delete = sample("test/reply_kind/delete", SampleKind::Delete);
+            query.reply(Ok(delete)).res().unwrap();
+        })
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    let replies = get_session
+        .get("test/reply_kind/**")
+        .consolidation(ConsolidationMode::None)
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap();
+    let mut samples: Vec<Sample> = replies
+        .into_iter()
+        .map(|reply| reply.sample.unwrap())
+        .collect();
+    samples.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0].key_expr.as_str(), "test/reply_kind/delete");
+    assert_eq!(samples[0].kind, SampleKind::Delete);
+    assert_eq!(samples[1].key_expr.as_str(), "test/reply_kind/put");
+    assert_eq!(samples[1].kind, SampleKind::Put);
+    assert_eq!(samples[1].value.to_string(), "value");
+}
+
+#[test]
+fn latest_consolidation_with_delete_replies() {
+    let session = zenoh::open(config::peer()).res().unwrap();
+
+    let _queryable = session
+        .declare_queryable("test/reply_kind/latest/**")
+        .callback(|query| {
+            let older = zenoh::time::new_reception_timestamp();
+            std::thread::sleep(Duration::from_millis(10));
+            let newer = zenoh::time::new_reception_timestamp();
+            // A newer Delete supersedes an older Put
+            for (kind, timestamp) in [(SampleKind::Put, older), (SampleKind::Delete, newer)] {
+                let sample =
+                    sample("test/reply_kind/latest/deleted", kind).with_timestamp(timestamp);
+                query.reply(Ok(sample)).res().unwrap();
+            }
+            // A newer Put supersedes an older Delete
+            for (kind, timestamp) in [(SampleKind::Put, newer), (SampleKind::Delete, older)] {
+                let sample = sample("test/reply_kind/latest/put", kind).with_timestamp(timestamp);
+                query.reply(Ok(sample)).res().unwrap();
+            }
+        })
+        .res()
+        .unwrap();
+
+    let replies = session
+        .get("test/reply_kind/latest/**")
+        .consolidation(ConsolidationMode::Latest)
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap();
+    let mut samples: Vec<Sample> = replies
+        .into_iter()
+        .map(|reply| reply.sample.unwrap())
+        .collect();
+    samples.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    assert_eq!(samples.len(), 2);
+    assert_eq!(
+        samples[0].key_expr.as_str(),
+        "test/reply_kind/latest/deleted"
+    );
+    assert_eq!(samples[0].kind, SampleKind::Delete);
+    assert_eq!(samples[1].key_expr.as_str(), "test/reply_kind/latest/put");
+    assert_eq!(samples[1].kind, SampleKind::Put);
+}
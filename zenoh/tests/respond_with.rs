@@ -0,0 +1,120 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn respond_with_async_function() {
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+    use zenoh_core::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    let session = ztimeout!(zenoh::open(config::peer()).res_async()).unwrap();
+    let queryable = ztimeout!(session
+        .declare_queryable("test/respond_with/*")
+        .respond_with(|query| async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            match query.key_expr().as_str() {
+                "test/respond_with/ok" => Ok(Value::from("value")),
+                "test/respond_with/panic" => panic!("responder failure"),
+                _ => Err("unknown key"),
+            }
+        })
+        .res_async())
+    .unwrap();
+
+    let get = |key_expr: &'static str| {
+        let session = &session;
+        async move {
+            let replies = ztimeout!(session.get(key_expr).res_async()).unwrap();
+            let mut results = vec![];
+            // The receiver is closed once the query is finalized
+            while let Ok(reply) = ztimeout!(replies.recv_async()) {
+                results.push(reply.sample.map_err(|e| e.to_string()));
+            }
+            results
+        }
+    };
+
+    let results = get("test/respond_with/ok").await;
+    assert_eq!(results.len(), 1);
+    let sample = results[0].as_ref().unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/respond_with/ok");
+    assert_eq!(sample.value.to_string(), "value");
+
+    let results = get("test/respond_with/err").await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].as_ref().unwrap_err(), "unknown key");
+
+    let results = get("test/respond_with/panic").await;
+    assert_eq!(results.len(), 1);
+    assert!(results[0]
+        .as_ref()
+        .unwrap_err()
+        .contains("responder failure"));
+
+    ztimeout!(queryable.undeclare().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn respond_with_concurrency() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+    use zenoh_core::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const QUERIES: usize = 8;
+
+    let session = ztimeout!(zenoh::open(config::peer()).res_async()).unwrap();
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+    let queryable = ztimeout!(session
+        .declare_queryable("test/respond_with/concurrency")
+        .respond_with({
+            let running = running.clone();
+            let max_running = max_running.clone();
+            move |_query| {
+                let running = running.clone();
+                let max_running = max_running.clone();
+                async move {
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, Value>(Value::from("value"))
+                }
+            }
+        })
+        .concurrency(2)
+        .res_async())
+    .unwrap();
+
+    let mut receivers = vec![];
+    for _ in 0..QUERIES {
+        receivers
+            .push(ztimeout!(session.get("test/respond_with/concurrency").res_async()).unwrap());
+    }
+    for replies in receivers {
+        let reply = ztimeout!(replies.recv_async()).unwrap();
+        assert!(reply.sample.is_ok());
+    }
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+
+    ztimeout!(queryable.undeclare().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -0,0 +1,134 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod routed {
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn pair_configs(port: u16) -> (Config, Config) {
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+        let mut listening = config::peer();
+        listening
+            .scouting
+            .multicast
+            .set_enabled(Some(false))
+            .unwrap();
+        listening.listen.endpoints = vec![endpoint.clone()];
+        let mut connecting = config::peer();
+        connecting
+            .scouting
+            .multicast
+            .set_enabled(Some(false))
+            .unwrap();
+        connecting.connect.endpoints = vec![endpoint];
+        (listening, connecting)
+    }
+
+    fn open_pair(port: u16) -> (Session, Session) {
+        let (listening, connecting) = pair_configs(port);
+        (
+            zenoh::open(listening).res().unwrap(),
+            zenoh::open(connecting).res().unwrap(),
+        )
+    }
+
+    #[test]
+    fn without_peers() {
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let session = zenoh::open(config).res().unwrap();
+        let start = Instant::now();
+        let subscriber = session
+            .declare_subscriber("test/routed/alone")
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.routed(TIMEOUT).res().unwrap(), 0);
+        assert!(start.elapsed() < TIMEOUT);
+    }
+
+    #[test]
+    fn subscriber_routed() {
+        let (publishing, subscribing) = open_pair(17527);
+        let mut subscriber = subscribing
+            .declare_subscriber("test/routed/sub")
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.routed(TIMEOUT).res().unwrap(), 1);
+
+        // No need to wait before publishing
+        publishing.put("test/routed/sub", "value").res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    #[test]
+    fn queryable_routed() {
+        let (querying, replying) = open_pair(17528);
+        let queryable = replying
+            .declare_queryable("test/routed/qable")
+            .callback(|query| {
+                let key_expr = query.key_expr().clone();
+                query
+                    .reply(Ok(Sample::new(key_expr, "value")))
+                    .res()
+                    .unwrap();
+            })
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        assert_eq!(queryable.routed(TIMEOUT).res().unwrap(), 1);
+
+        let replies = querying
+            .get("test/routed/qable")
+            .timeout(TIMEOUT)
+            .res()
+            .unwrap();
+        let sample = replies.recv().unwrap().sample.unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn routed_async() {
+        use zenoh::prelude::r#async::AsyncResolve;
+
+        let (listening, connecting) = pair_configs(17529);
+        let publishing = zenoh::open(listening).res_async().await.unwrap();
+        let subscribing = zenoh::open(connecting).res_async().await.unwrap();
+        let subscriber = subscribing
+            .declare_subscriber("test/routed/async")
+            .await_routed(TIMEOUT)
+            .res_async()
+            .await
+            .unwrap();
+
+        publishing
+            .put("test/routed/async", "value")
+            .res_async()
+            .await
+            .unwrap();
+        let sample = tokio::time::timeout(TIMEOUT, subscriber.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+
+        subscriber.undeclare().res_async().await.unwrap();
+        subscribing.close().res_async().await.unwrap();
+        publishing.close().res_async().await.unwrap();
+    }
+}
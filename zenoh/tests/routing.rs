@@ -28,6 +28,10 @@ use zenoh_protocol::core::{WhatAmI, WhatAmIMatcher};
 use zenoh_result::bail;
 
 const TIMEOUT: Duration = Duration::from_secs(10);
+// The routing of a declaration is awaited for at most this long: in the recipes, some of the
+// nodes it's routed to may not answer, having not started yet or being closed.
+#[cfg(feature = "unstable")]
+const ROUTED_TIMEOUT: Duration = Duration::from_secs(1);
 const MSG_COUNT: usize = 50;
 const MSG_SIZE: [usize; 2] = [1_024, 131_072];
 // Maximal recipes to run at once
@@ -54,7 +58,10 @@ impl Task {
         match self {
             // The Sub task checks if the incoming message matches the expected size until it receives enough counts.
             Self::Sub(ke, expected_size) => {
-                let sub = ztimeout!(session.declare_subscriber(ke).res_async())?;
+                let sub = session.declare_subscriber(ke);
+                #[cfg(feature = "unstable")]
+                let sub = sub.await_routed(ROUTED_TIMEOUT);
+                let sub = ztimeout!(sub.res_async())?;
                 let mut counter = 0;
                 loop {
                     tokio::select! {
@@ -133,7 +140,10 @@ impl Task {
 
             // The Queryable task keeps replying to requested messages until all checkpoints are finished.
             Self::Queryable(ke, payload_size) => {
-                let queryable = session.declare_queryable(ke).res_async().await?;
+                let queryable = session.declare_queryable(ke);
+                #[cfg(feature = "unstable")]
+                let queryable = queryable.await_routed(ROUTED_TIMEOUT);
+                let queryable = ztimeout!(queryable.res_async())?;
                 let sample = Sample::try_from(ke.clone(), vec![0u8; *payload_size])?;
 
                 loop {
@@ -371,6 +381,8 @@ async fn gossip() -> Result<()> {
     let ke = String::from("testKeyExprGossip");
     let msg_size = 8;
 
+    // node1 and node2 start their tasks once node3 is gone: these sleeps don't wait for the
+    // routing of declarations, the subscriber and queryable awaiting theirs when declared.
     // node1 in peer mode playing pub and queryable
     let node1 = Node {
         name: format!("Pub & Queryable {}", WhatAmI::Peer),
@@ -0,0 +1,113 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod scope {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use zenoh::prelude::sync::*;
+
+    fn counter() -> (Arc<AtomicUsize>, impl Fn(Sample) + Send + Sync + 'static) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let c = count.clone();
+        (count, move |_| {
+            c.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+
+    fn queryables(session: &Session, key_expr: &str) -> usize {
+        session
+            .get(key_expr)
+            .res()
+            .unwrap()
+            .into_iter()
+            .filter(|reply| reply.sample.is_ok())
+            .count()
+    }
+
+    #[test]
+    fn scope_undeclares_leaked_entities() {
+        let session = zenoh::open(Config::default()).res().unwrap();
+        let (direct, callback) = counter();
+        let direct_sub = session
+            .declare_subscriber("test/scope/**")
+            .callback(callback)
+            .res()
+            .unwrap();
+
+        let scope = session.scope();
+        let (scoped, callback) = counter();
+        let sub = scope
+            .declare_subscriber("test/scope/**")
+            .callback(callback)
+            .res()
+            .unwrap();
+        std::mem::forget(sub);
+        let qabl = scope
+            .declare_queryable("test/scope/qabl")
+            .callback(|query| {
+                query
+                    .reply(Ok(Sample::new(query.key_expr().clone(), "reply")))
+                    .res()
+                    .unwrap()
+            })
+            .res()
+            .unwrap();
+        std::mem::forget(qabl);
+        let publisher = scope.declare_publisher("test/scope/pub").res().unwrap();
+
+        publisher.put("value").res().unwrap();
+        assert_eq!(scoped.load(Ordering::SeqCst), 1);
+        assert_eq!(direct.load(Ordering::SeqCst), 1);
+        assert_eq!(queryables(&session, "test/scope/qabl"), 1);
+
+        drop(publisher);
+        scope.close().res().unwrap();
+
+        session.put("test/scope/pub", "value").res().unwrap();
+        assert_eq!(scoped.load(Ordering::SeqCst), 1);
+        assert_eq!(direct.load(Ordering::SeqCst), 2);
+        assert_eq!(queryables(&session, "test/scope/qabl"), 0);
+
+        direct_sub.undeclare().res().unwrap();
+        session.close().res().unwrap();
+    }
+
+    #[test]
+    fn nested_scopes_are_closed_with_their_parent() {
+        let session = zenoh::open(Config::default()).res().unwrap();
+        let parent = session.scope();
+        let child = parent.scope();
+
+        let (count, callback) = counter();
+        std::mem::forget(
+            child
+                .declare_subscriber("test/nested/key")
+                .callback(callback)
+                .res()
+                .unwrap(),
+        );
+        session.put("test/nested/key", "value").res().unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        parent.close().res().unwrap();
+        session.put("test/nested/key", "value").res().unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // Nothing can be declared anymore in a scope closed along with its parent
+        assert!(child.declare_subscriber("test/nested/key").res().is_err());
+
+        drop(child);
+        session.close().res().unwrap();
+    }
+}
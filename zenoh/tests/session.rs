@@ -71,6 +71,11 @@ async fn close_session(peer01: Session, peer02: Session) {
     ztimeout!(peer02.close().res_async()).unwrap();
 }
 
+// Wait for the declarations of the session to propagate, within TIMEOUT
+async fn routed(session: &Session) {
+    session.routed(TIMEOUT).res_async().await.unwrap();
+}
+
 async fn test_session_pubsub(peer01: &Session, peer02: &Session, reliability: Reliability) {
     let key_expr = "test/session";
     let msg_count = match reliability {
@@ -95,7 +100,7 @@ async fn test_session_pubsub(peer01: &Session, peer02: &Session, reliability: Re
         .unwrap();
 
         // Wait for the declaration to propagate
-        tokio::time::sleep(SLEEP).await;
+        routed(peer01).await;
 
         // Put data
         println!("[PS][02b] Putting on peer02 session. {MSG_COUNT} msgs of {size} bytes.");
@@ -119,14 +124,11 @@ async fn test_session_pubsub(peer01: &Session, peer02: &Session, reliability: Re
             }
         });
 
-        // Wait for the messages to arrive
-        tokio::time::sleep(SLEEP).await;
-
         println!("[PS][03b] Unsubscribing on peer01 session");
         ztimeout!(sub.undeclare().res_async()).unwrap();
 
         // Wait for the declaration to propagate
-        tokio::time::sleep(SLEEP).await;
+        routed(peer01).await;
     }
 }
 
@@ -158,7 +160,7 @@ async fn test_session_qryrep(peer01: &Session, peer02: &Session, reliability: Re
         .unwrap();
 
         // Wait for the declaration to propagate
-        tokio::time::sleep(SLEEP).await;
+        routed(peer01).await;
 
         // Get data
         println!("[QR][02c] Getting on peer02 session. {msg_count} msgs.");
@@ -178,7 +180,7 @@ async fn test_session_qryrep(peer01: &Session, peer02: &Session, reliability: Re
         ztimeout!(qbl.undeclare().res_async()).unwrap();
 
         // Wait for the declaration to propagate
-        tokio::time::sleep(SLEEP).await;
+        routed(peer01).await;
     }
 }
 
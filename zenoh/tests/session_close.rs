@@ -0,0 +1,104 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn close_with_many_subscribers() {
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::sync::*;
+
+    const SUBSCRIBERS: usize = 1_000;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const SLEEP: Duration = Duration::from_millis(50);
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:17493".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let listener = zenoh::open(config).res().unwrap();
+    let publisher = listener.declare_publisher("test/close/0").res().unwrap();
+    let wait_matching = |expected: bool| {
+        let start = Instant::now();
+        while publisher
+            .matching_status()
+            .res()
+            .unwrap()
+            .matching_subscribers()
+            != expected
+        {
+            assert!(
+                start.elapsed() < TIMEOUT,
+                "matching_subscribers() did not become {expected} within {TIMEOUT:?}"
+            );
+            std::thread::sleep(SLEEP);
+        }
+    };
+
+    for undeclare_on_close in [false, true] {
+        let mut config = config::peer();
+        config.connect.endpoints = vec!["tcp/127.0.0.1:17493".parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .set_undeclare_on_close(Some(undeclare_on_close))
+            .unwrap();
+        let session = zenoh::open(config).res().unwrap();
+        for i in 0..SUBSCRIBERS {
+            let subscriber = session
+                .declare_subscriber(format!("test/close/{i}"))
+                .callback(|_| {})
+                .res()
+                .unwrap();
+            // Leave the subscriber declared until the session is closed
+            std::mem::forget(subscriber);
+        }
+        wait_matching(true);
+
+        session.close().res().unwrap();
+        wait_matching(false);
+    }
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn close_notifies_matching_listeners() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = zenoh::open(config).res().unwrap();
+    let publisher = session
+        .declare_publisher("test/close/matching")
+        .res()
+        .unwrap();
+    let (tx, rx) = flume::unbounded();
+    let listener = publisher
+        .matching_listener()
+        .callback(move |status| tx.send(status.matching_subscribers()).unwrap())
+        .res()
+        .unwrap();
+    let subscriber = session
+        .declare_subscriber("test/close/matching")
+        .callback(|_| {})
+        .res()
+        .unwrap();
+    assert_eq!(rx.recv_timeout(TIMEOUT), Ok(true));
+
+    // Leave the publisher, its listener and the subscriber declared until the session is closed
+    std::mem::forget(subscriber);
+    std::mem::forget(listener);
+    std::mem::forget(publisher);
+    session.close().res().unwrap();
+    assert_eq!(rx.recv_timeout(TIMEOUT), Ok(false));
+}
@@ -0,0 +1,88 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod set_callback {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use zenoh::prelude::sync::*;
+
+    const MSG_COUNT: usize = 10_000;
+
+    fn counting(counter: &Arc<AtomicUsize>) -> impl Fn(Sample) + Send + Sync + 'static {
+        let counter = counter.clone();
+        move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn swap_while_flooding() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let counters = [Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0))];
+        let subscriber = session
+            .declare_subscriber("test/set_callback/flood")
+            .callback(counting(&counters[0]))
+            .res()
+            .unwrap();
+
+        let publisher = {
+            let session = session.clone();
+            std::thread::spawn(move || {
+                for i in 0..MSG_COUNT {
+                    session.put("test/set_callback/flood", i).res().unwrap();
+                }
+            })
+        };
+        let mut swaps = 0;
+        while !publisher.is_finished() {
+            swaps += 1;
+            subscriber.set_callback(counting(&counters[swaps % 2]));
+        }
+        publisher.join().unwrap();
+
+        // Every sample was delivered exactly once, to either callback
+        let delivered: usize = counters.iter().map(|c| c.load(Ordering::Relaxed)).sum();
+        assert_eq!(delivered, MSG_COUNT);
+
+        // The samples received after a swap go to the new callback only
+        let last = Arc::new(AtomicUsize::new(0));
+        subscriber.set_callback(counting(&last));
+        session
+            .put("test/set_callback/flood", "last")
+            .res()
+            .unwrap();
+        let delivered: usize = counters.iter().map(|c| c.load(Ordering::Relaxed)).sum();
+        assert_eq!(delivered, MSG_COUNT);
+        assert_eq!(last.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn set_callback_mut() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/set_callback/mut")
+            .callback(|_| {})
+            .res()
+            .unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut count = 0;
+        subscriber.set_callback_mut(move |_| {
+            count += 1;
+            sender.send(count).unwrap();
+        });
+        session.put("test/set_callback/mut", "a").res().unwrap();
+        session.put("test/set_callback/mut", "b").res().unwrap();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+    }
+}
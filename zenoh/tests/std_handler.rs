@@ -0,0 +1,43 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SAMPLES: usize = 16;
+
+#[test]
+fn std_channels() {
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let sync_subscriber = session
+        .declare_subscriber("test/std/channel")
+        .with(std::sync::mpsc::sync_channel(SAMPLES))
+        .res()
+        .unwrap();
+    let subscriber = session
+        .declare_subscriber("test/std/channel")
+        .with(std::sync::mpsc::channel())
+        .res()
+        .unwrap();
+
+    for i in 0..SAMPLES {
+        session.put("test/std/channel", i as u64).res().unwrap();
+    }
+    for receiver in [&*sync_subscriber, &*subscriber] {
+        for i in 0..SAMPLES {
+            let sample = receiver.recv_timeout(TIMEOUT).unwrap();
+            assert_eq!(sample.value.to_string(), i.to_string());
+        }
+    }
+}
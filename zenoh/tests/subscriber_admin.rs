@@ -0,0 +1,109 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_admin {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::Session;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    fn open(listen: Option<&str>, connect: Option<&str>, adminspace: bool) -> Session {
+        let mut config = config::peer();
+        if let Some(endpoint) = listen {
+            config.listen.endpoints = vec![endpoint.parse().unwrap()];
+        }
+        if let Some(endpoint) = connect {
+            config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        }
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.adminspace.set_enabled(adminspace).unwrap();
+        zenoh::open(config).res().unwrap()
+    }
+
+    fn query_subscribers(operator: &Session, zid: ZenohId) -> Vec<serde_json::Value> {
+        operator
+            .get(format!("@/session/{zid}/subscriber/*"))
+            .timeout(TIMEOUT)
+            .res()
+            .unwrap()
+            .into_iter()
+            .map(|reply| {
+                let sample = reply.sample.unwrap();
+                serde_json::from_slice(&sample.payload.contiguous()).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn remote_subscriber_metrics() {
+        let endpoint = "tcp/127.0.0.1:18004";
+        let session = open(Some(endpoint), None, true);
+        let operator = open(None, Some(endpoint), false);
+        let subscriber = session
+            .declare_subscriber("test/admin/subscriber")
+            .reliable()
+            .channel_size(8)
+            .res()
+            .unwrap();
+        std::thread::sleep(SLEEP);
+
+        for i in 0..3 {
+            operator
+                .put("test/admin/subscriber", i as u64)
+                .res()
+                .unwrap();
+        }
+        std::thread::sleep(SLEEP);
+
+        let subscribers = query_subscribers(&operator, session.zid());
+        let value = subscribers
+            .iter()
+            .find(|value| value["key_expr"] == "test/admin/subscriber")
+            .unwrap();
+        assert_eq!(value["mode"], "Push");
+        assert_eq!(value["reliability"], "Reliable");
+        assert_eq!(value["samples_delivered"], 3);
+        assert_eq!(value["samples_dropped"], 0);
+        assert_eq!(value["queued"], 3);
+
+        // The occupancy follows the reception of the samples
+        assert_eq!(subscriber.drain(usize::MAX).len(), 3);
+        let subscribers = query_subscribers(&operator, session.zid());
+        let value = subscribers
+            .iter()
+            .find(|value| value["key_expr"] == "test/admin/subscriber")
+            .unwrap();
+        assert_eq!(value["queued"], 0);
+    }
+
+    #[test]
+    fn disabled_adminspace() {
+        let endpoint = "tcp/127.0.0.1:18005";
+        let session = open(Some(endpoint), None, false);
+        let operator = open(None, Some(endpoint), false);
+        let _subscriber = session
+            .declare_subscriber("test/admin/disabled")
+            .res()
+            .unwrap();
+        std::thread::sleep(SLEEP);
+
+        assert!(query_subscribers(&operator, session.zid()).is_empty());
+        // The subscribers are still listed to the session itself
+        assert!(query_subscribers(&session, session.zid())
+            .iter()
+            .any(|value| value["key_expr"] == "test/admin/disabled"));
+    }
+}
@@ -0,0 +1,58 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn cache_keeps_latest_value_per_key() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use zenoh::prelude::sync::*;
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let forwarded = Arc::new(AtomicUsize::new(0));
+    let subscriber = session
+        .declare_subscriber("test/cache/**/temp")
+        .with_cache()
+        .callback({
+            let forwarded = forwarded.clone();
+            move |_| {
+                forwarded.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .res()
+        .unwrap();
+    assert!(subscriber.is_empty());
+
+    session.put("test/cache/a/temp", 1).res().unwrap();
+    session.put("test/cache/b/temp", 2).res().unwrap();
+    session.put("test/cache/a/temp", 3).res().unwrap();
+    session.put("test/cache/c/humidity", 4).res().unwrap();
+    assert_eq!(subscriber.len(), 2);
+    let get = |key| {
+        subscriber
+            .get(keyexpr::new(key).unwrap())
+            .map(|sample| sample.value.to_string())
+    };
+    assert_eq!(get("test/cache/a/temp").as_deref(), Some("3"));
+    assert_eq!(get("test/cache/b/temp").as_deref(), Some("2"));
+
+    session.delete("test/cache/a/temp").res().unwrap();
+    assert_eq!(get("test/cache/a/temp"), None);
+    let snapshot: Vec<_> = subscriber
+        .snapshot()
+        .into_iter()
+        .map(|sample| sample.key_expr.to_string())
+        .collect();
+    assert_eq!(snapshot, ["test/cache/b/temp"]);
+    assert_eq!(forwarded.load(Ordering::Relaxed), 4);
+}
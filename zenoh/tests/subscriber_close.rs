@@ -0,0 +1,114 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_close {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::EntityId;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn is_declared(session: &Session, id: EntityId) -> bool {
+        session.subscribers().iter().any(|sub| sub.id == id)
+    }
+
+    #[test]
+    fn close_timeout() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/close/timeout")
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+        subscriber.close_timeout(TIMEOUT).res().unwrap();
+        assert!(!is_declared(&session, id));
+
+        let subscriber = session
+            .declare_subscriber("test/close/timeout")
+            .pull_mode()
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+        subscriber.close_timeout(TIMEOUT).res().unwrap();
+        assert!(!is_declared(&session, id));
+    }
+
+    #[test]
+    fn force_close() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let received = Arc::new(AtomicUsize::new(0));
+        let subscriber = session
+            .declare_subscriber("test/close/force")
+            .callback({
+                let received = received.clone();
+                move |_| {
+                    received.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+        subscriber.force_close().res().unwrap();
+        assert!(!is_declared(&session, id));
+
+        session.put("test/close/force", "value").res().unwrap();
+        assert_eq!(received.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn drop_timeout() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/close/drop")
+            .drop_timeout(TIMEOUT)
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+        drop(subscriber);
+        assert!(!is_declared(&session, id));
+    }
+
+    #[test]
+    fn default_drop_timeout() {
+        let mut config = config::peer();
+        config
+            .set_subscribers_drop_timeout(Some(TIMEOUT.as_millis() as u64))
+            .unwrap();
+        let session = zenoh::open(config).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/close/default_drop")
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+        drop(subscriber);
+        assert!(!is_declared(&session, id));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn close_timeout_async() {
+        use zenoh::prelude::r#async::AsyncResolve;
+
+        let session = zenoh::open(config::peer()).res_async().await.unwrap();
+        let subscriber = session
+            .declare_subscriber("test/close/async")
+            .res_async()
+            .await
+            .unwrap();
+        let id = subscriber.id();
+        subscriber.close_timeout(TIMEOUT).res_async().await.unwrap();
+        assert!(!is_declared(&session, id));
+    }
+}
@@ -0,0 +1,127 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_drain {
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SHORT: Duration = Duration::from_millis(100);
+
+    fn values(samples: &[Sample]) -> Vec<String> {
+        samples
+            .iter()
+            .map(|sample| sample.value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn drain_in_batches() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/drain/batch")
+            .res()
+            .unwrap();
+        assert!(subscriber.drain(8).is_empty());
+
+        for i in 0..5 {
+            session.put("test/drain/batch", i).res().unwrap();
+        }
+        assert_eq!(values(&subscriber.drain(3)), ["0", "1", "2"]);
+
+        let mut samples = subscriber.drain(1);
+        assert_eq!(subscriber.drain_into(&mut samples, 8), 1);
+        assert_eq!(values(&samples), ["3", "4"]);
+        assert_eq!(subscriber.drain_into(&mut samples, 8), 0);
+    }
+
+    #[test]
+    fn drain_timeout() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let subscriber = session
+            .declare_subscriber("test/drain/timeout")
+            .res()
+            .unwrap();
+        assert!(subscriber.drain_timeout(8, SHORT).unwrap().is_empty());
+
+        let publisher = {
+            let session = session.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(SHORT);
+                for i in 0..3 {
+                    session.put("test/drain/timeout", i).res().unwrap();
+                }
+            })
+        };
+        let mut samples = subscriber.drain_timeout(8, TIMEOUT).unwrap();
+        publisher.join().unwrap();
+        subscriber.drain_into(&mut samples, 8);
+        assert_eq!(values(&samples), ["0", "1", "2"]);
+    }
+
+    #[test]
+    fn close_and_drain() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/drain/close")
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+        for i in 0..5 {
+            session.put("test/drain/close", i).res().unwrap();
+        }
+        let samples = subscriber.close_and_drain().res().unwrap();
+        assert_eq!(values(&samples), ["0", "1", "2", "3", "4"]);
+        assert!(session.entities().iter().all(|entity| entity.id != id));
+    }
+
+    #[test]
+    fn pull_close_and_drain() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17533".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+
+        let subscriber = subscribing
+            .declare_subscriber("test/drain/pull/*")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        for i in 0..3 {
+            publishing
+                .put(format!("test/drain/pull/{i}"), i)
+                .res()
+                .unwrap();
+        }
+        assert_eq!(publishing.routed(TIMEOUT).res().unwrap(), 1);
+        subscriber.pull().res().unwrap();
+        let deadline = Instant::now() + TIMEOUT;
+        while subscriber.len() < 3 && Instant::now() < deadline {
+            std::thread::sleep(SHORT);
+        }
+
+        // Published after the last pull: not returned, there is no implicit final pull
+        publishing.put("test/drain/pull/3", 3).res().unwrap();
+        assert_eq!(publishing.routed(TIMEOUT).res().unwrap(), 1);
+        let mut samples = values(&subscriber.close_and_drain().res().unwrap());
+        samples.sort();
+        assert_eq!(samples, ["0", "1", "2"]);
+    }
+}
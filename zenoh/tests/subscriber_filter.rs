@@ -0,0 +1,81 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn filter_with_channel() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/filter/channel";
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    // Rejected samples must not take room in the channel, or the puts would block
+    let mut subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .with(flume::bounded(2))
+        .filter(|sample| sample.value.payload.len() > 3)
+        .res()
+        .unwrap();
+
+    for _ in 0..10 {
+        session.put(KEY_EXPR, "low").res().unwrap();
+    }
+    session.put(KEY_EXPR, "high").res().unwrap();
+    session.put(KEY_EXPR, "higher").res().unwrap();
+
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert_eq!(sample.value.to_string(), "high");
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert_eq!(sample.value.to_string(), "higher");
+    assert!(subscriber.try_recv().is_err());
+    assert_eq!(subscriber.filtered(), 10);
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn filter_with_callback() {
+    use std::sync::{Arc, Mutex};
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/filter/callback";
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let received = Arc::new(Mutex::new(vec![]));
+    let subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .callback({
+            let received = received.clone();
+            move |sample| received.lock().unwrap().push(sample.value.to_string())
+        })
+        .filter(|sample| sample.kind == SampleKind::Put)
+        .res()
+        .unwrap();
+
+    session.put(KEY_EXPR, "value").res().unwrap();
+    session.delete(KEY_EXPR).res().unwrap();
+    session.put(KEY_EXPR, "other").res().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), ["value", "other"]);
+    assert_eq!(subscriber.filtered(), 1);
+
+    // Without filter, no sample is rejected
+    let subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .callback(|_| {})
+        .res()
+        .unwrap();
+    session.delete(KEY_EXPR).res().unwrap();
+    assert_eq!(subscriber.filtered(), 0);
+}
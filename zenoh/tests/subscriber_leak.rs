@@ -0,0 +1,158 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::Session;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SUBSCRIBERS: usize = 100_000;
+// The memory that may remain allocated after creating and dropping all the subscribers
+const MAX_GROWTH: isize = 1024 * 1024;
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size() as isize, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size() as isize, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(
+            new_size as isize - layout.size() as isize,
+            Ordering::Relaxed,
+        );
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// The tests measure the allocations of the whole process, so they must not run concurrently
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn subscribe_and_drop(session: &Session, range: std::ops::Range<usize>) {
+    for i in range {
+        let key_expr = format!("test/leak/{i}");
+        let subscriber = session.declare_subscriber(&key_expr).res().unwrap();
+        // Leave a sample in the channel: it must be freed with the subscriber
+        session.put(&key_expr, vec![0u8; 64]).res().unwrap();
+        assert_eq!(subscriber.len(), 1);
+        if i % 2 == 0 {
+            subscriber.undeclare().res().unwrap();
+        }
+    }
+}
+
+fn open(listen: Option<&str>, connect: Option<&str>) -> Session {
+    let mut config = config::peer();
+    if let Some(endpoint) = listen {
+        config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    }
+    if let Some(endpoint) = connect {
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.set_enabled(true).unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+// The suffixes of the subscriptions to test/leak/** known by the session
+fn subscriptions(session: &Session) -> Vec<String> {
+    let prefix = format!("@/peer/{}/subscriber/test/leak/", session.zid());
+    let mut subscriptions: Vec<String> = session
+        .get(format!("{prefix}**"))
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap()
+        .into_iter()
+        .map(|reply| reply.sample.unwrap().key_expr.as_str()[prefix.len()..].to_string())
+        .collect();
+    subscriptions.sort();
+    subscriptions
+}
+
+fn wait_subscriptions(session: &Session, expected: &[&str]) {
+    let deadline = std::time::Instant::now() + TIMEOUT;
+    while subscriptions(session) != expected && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert_eq!(subscriptions(session), expected);
+}
+
+// Wait for the remote peer to process the declarations and undeclarations of the session:
+// they are received in order, so once a last subscription is seen all of them were
+fn sync(session: &Session, remote: &Session) {
+    let subscriber = session.declare_subscriber("test/leak/sync").res().unwrap();
+    wait_subscriptions(remote, &["alive", "sync"]);
+    subscriber.undeclare().res().unwrap();
+    wait_subscriptions(remote, &["alive"]);
+}
+
+#[test]
+fn short_lived_subscribers_are_reclaimed() {
+    let _serial = SERIAL.lock().unwrap();
+    let session = open(None, None);
+
+    // Let the allocations made once (e.g. the growth of the tables) happen
+    subscribe_and_drop(&session, 0..1000);
+    let _subscriber = session.declare_subscriber("test/leak/alive").res().unwrap();
+    assert_eq!(subscriptions(&session), ["alive"]);
+    let before = ALLOCATED.load(Ordering::Relaxed);
+
+    subscribe_and_drop(&session, 0..SUBSCRIBERS);
+
+    assert_eq!(subscriptions(&session), ["alive"]);
+    let growth = ALLOCATED.load(Ordering::Relaxed) - before;
+    assert!(
+        growth < MAX_GROWTH,
+        "{growth} bytes remain allocated after dropping {SUBSCRIBERS} subscribers"
+    );
+}
+
+#[test]
+fn short_lived_remote_subscriptions_are_reclaimed() {
+    let _serial = SERIAL.lock().unwrap();
+    let session = open(Some("tcp/127.0.0.1:17507"), None);
+    let remote = open(None, Some("tcp/127.0.0.1:17507"));
+    while session.info().peers_zid().res().count() == 0 {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    subscribe_and_drop(&session, 0..1000);
+    let _subscriber = session.declare_subscriber("test/leak/alive").res().unwrap();
+    sync(&session, &remote);
+    let before = ALLOCATED.load(Ordering::Relaxed);
+
+    subscribe_and_drop(&session, 0..SUBSCRIBERS / 10);
+    // The subscriptions and key expressions are also reclaimed by the remote peer
+    sync(&session, &remote);
+    let growth = ALLOCATED.load(Ordering::Relaxed) - before;
+    assert!(
+        growth < MAX_GROWTH,
+        "{growth} bytes remain allocated after dropping {} subscribers",
+        SUBSCRIBERS / 10
+    );
+}
@@ -0,0 +1,68 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_map {
+    use zenoh::prelude::sync::*;
+
+    #[test]
+    fn mapped_values() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/map/*")
+            .map(|sample| (sample.key_expr.to_string(), sample.value.payload.len()))
+            .with(flume::bounded(8))
+            .res()
+            .unwrap();
+
+        session.put("test/map/a", "value").res().unwrap();
+        session.put("test/map/b", "").res().unwrap();
+        assert_eq!(
+            subscriber.drain().collect::<Vec<_>>(),
+            [("test/map/a".to_string(), 5), ("test/map/b".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn default_channel() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/map/default")
+            .map(|sample| sample.value.to_string())
+            .res()
+            .unwrap();
+
+        session.put("test/map/default", "value").res().unwrap();
+        assert_eq!(subscriber.try_recv().unwrap(), "value");
+    }
+
+    #[test]
+    fn panicking_map() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/map/panic")
+            .map(|sample| {
+                let value = sample.value.to_string();
+                assert_ne!(value, "poison");
+                value
+            })
+            .res()
+            .unwrap();
+
+        for value in ["poison", "good"] {
+            session.put("test/map/panic", value).res().unwrap();
+        }
+        assert_eq!(subscriber.drain().collect::<Vec<_>>(), ["good"]);
+        assert_eq!(subscriber.metrics().panics, 1);
+    }
+}
@@ -0,0 +1,66 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_metrics {
+    use std::time::SystemTime;
+    use zenoh::handlers::OverflowPolicy;
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::SubscriberMetrics;
+
+    const SAMPLES: u64 = 100;
+    const PAYLOAD: &str = "value";
+
+    #[test]
+    fn full_channel_drops() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/metrics/channel")
+            .channel_size(1)
+            .on_full(OverflowPolicy::DropNewest)
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.metrics(), SubscriberMetrics::default());
+
+        // The subscriber doesn't consume the samples until the publication is over
+        let start = SystemTime::now();
+        for _ in 0..SAMPLES {
+            session.put("test/metrics/channel", PAYLOAD).res().unwrap();
+        }
+        let metrics = subscriber.metrics();
+        assert_eq!(metrics.samples_delivered, SAMPLES);
+        assert_eq!(metrics.bytes_delivered, SAMPLES * PAYLOAD.len() as u64);
+        assert_eq!(metrics.samples_dropped, SAMPLES - 1);
+        assert!(metrics.last_received.unwrap() >= start);
+        assert_eq!(subscriber.len(), 1);
+    }
+
+    #[test]
+    fn filtered_samples_not_delivered() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/metrics/filter/*")
+            .callback(|_| {})
+            .filter(|sample| sample.key_expr.as_str().ends_with('a'))
+            .res()
+            .unwrap();
+
+        session.put("test/metrics/filter/a", PAYLOAD).res().unwrap();
+        session.put("test/metrics/filter/b", PAYLOAD).res().unwrap();
+        let metrics = subscriber.metrics();
+        assert_eq!(metrics.samples_delivered, 1);
+        assert_eq!(metrics.bytes_delivered, PAYLOAD.len() as u64);
+        assert_eq!(metrics.samples_dropped, 0);
+        assert_eq!(subscriber.filtered(), 1);
+    }
+}
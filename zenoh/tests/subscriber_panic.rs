@@ -0,0 +1,69 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_panic {
+    use std::sync::{Arc, Mutex};
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::{PanicPolicy, Subscriber};
+
+    fn declare(
+        session: &Arc<Session>,
+        key_expr: &'static str,
+        policy: PanicPolicy,
+    ) -> (Subscriber<'static, ()>, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = session
+            .declare_subscriber(key_expr)
+            .callback({
+                let received = received.clone();
+                move |sample| {
+                    let value = sample.value.to_string();
+                    if value == "poison" {
+                        panic!("poisoned sample");
+                    }
+                    received.lock().unwrap().push(value);
+                }
+            })
+            .on_panic(policy)
+            .res()
+            .unwrap();
+        (subscriber, received)
+    }
+
+    #[test]
+    fn continue_after_panic() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let (subscriber, received) =
+            declare(&session, "test/panic/continue", PanicPolicy::Continue);
+
+        for value in ["poison", "good"] {
+            session.put("test/panic/continue", value).res().unwrap();
+        }
+        assert_eq!(*received.lock().unwrap(), ["good"]);
+        assert_eq!(subscriber.metrics().panics, 1);
+    }
+
+    #[test]
+    fn close_after_panic() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let (subscriber, received) =
+            declare(&session, "test/panic/close", PanicPolicy::CloseSubscriber);
+
+        for value in ["good", "poison", "good"] {
+            session.put("test/panic/close", value).res().unwrap();
+        }
+        assert_eq!(*received.lock().unwrap(), ["good"]);
+        assert_eq!(subscriber.metrics().panics, 1);
+    }
+}
@@ -0,0 +1,101 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_pause {
+    use std::sync::{Arc, Mutex};
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::PausePolicy;
+
+    fn put(session: &Session, key_expr: &str, values: std::ops::Range<usize>) {
+        for i in values {
+            session.put(key_expr, i as u64).res().unwrap();
+        }
+    }
+
+    fn values(subscriber: &zenoh::subscriber::FlumeSubscriber) -> Vec<String> {
+        subscriber
+            .drain(usize::MAX)
+            .into_iter()
+            .map(|sample| sample.value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn pause_drops_samples() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session.declare_subscriber("test/pause/drop").res().unwrap();
+
+        put(&session, "test/pause/drop", 0..2);
+        subscriber.pause().res().unwrap();
+        assert!(subscriber.is_paused());
+        put(&session, "test/pause/drop", 2..4);
+        subscriber.resume().res().unwrap();
+        assert!(!subscriber.is_paused());
+        put(&session, "test/pause/drop", 4..6);
+        assert_eq!(values(&subscriber), ["0", "1", "4", "5"]);
+    }
+
+    #[test]
+    fn pause_buffers_samples() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/pause/buffer")
+            .res()
+            .unwrap();
+
+        subscriber.pause_with(PausePolicy::Buffer(3)).res().unwrap();
+        put(&session, "test/pause/buffer", 0..5);
+        assert!(subscriber.is_empty());
+        // The buffered samples are delivered first, in order
+        subscriber.resume().res().unwrap();
+        put(&session, "test/pause/buffer", 5..6);
+        assert_eq!(values(&subscriber), ["0", "1", "2", "5"]);
+    }
+
+    #[test]
+    fn pause_concurrently_with_delivery() {
+        const SAMPLES: usize = 10_000;
+
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let received = Arc::new(Mutex::new(vec![]));
+        let subscriber = {
+            let received = received.clone();
+            session
+                .declare_subscriber("test/pause/concurrent")
+                .callback(move |sample| {
+                    let value = sample.value.to_string().parse::<usize>().unwrap();
+                    received.lock().unwrap().push(value);
+                })
+                .res()
+                .unwrap()
+        };
+
+        let publisher = {
+            let session = session.clone();
+            std::thread::spawn(move || put(&session, "test/pause/concurrent", 0..SAMPLES))
+        };
+        while !publisher.is_finished() {
+            subscriber
+                .pause_with(PausePolicy::Buffer(SAMPLES))
+                .res()
+                .unwrap();
+            std::thread::yield_now();
+            subscriber.resume().res().unwrap();
+        }
+        publisher.join().unwrap();
+
+        // No sample is lost, duplicated or reordered
+        assert_eq!(*received.lock().unwrap(), (0..SAMPLES).collect::<Vec<_>>());
+    }
+}
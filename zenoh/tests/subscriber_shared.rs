@@ -0,0 +1,125 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_shared {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::SharedSubscriber;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    fn wait_for(received: &AtomicUsize, count: usize) {
+        let deadline = Instant::now() + TIMEOUT;
+        while received.load(Ordering::SeqCst) < count {
+            assert!(Instant::now() < deadline);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn assert_shareable<T: Clone + Send + Sync + 'static>(_: &T) {}
+
+    #[test]
+    fn undeclared_on_last_drop() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let received = Arc::new(AtomicUsize::new(0));
+        let subscriber = session
+            .declare_subscriber("test/shared/push")
+            .callback({
+                let received = received.clone();
+                move |_| {
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .res()
+            .unwrap()
+            .into_shared();
+        assert_shareable(&subscriber);
+        assert_eq!(subscriber.key_expr().as_str(), "test/shared/push");
+
+        let mut handles: Vec<SharedSubscriber> = (0..4).map(|_| subscriber.clone()).collect();
+        assert_eq!(subscriber.handle_count(), 5);
+        let kept = handles.remove(0);
+        drop(subscriber);
+        let workers: Vec<_> = handles
+            .into_iter()
+            .map(|handle| std::thread::spawn(move || drop(handle)))
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // The last handle still keeps the subscription
+        assert_eq!(kept.handle_count(), 1);
+        assert_eq!(session.subscribers().len(), 1);
+        session.put("test/shared/push", "value").res().unwrap();
+        wait_for(&received, 1);
+        drop(kept);
+        assert!(session.subscribers().is_empty());
+    }
+
+    #[test]
+    fn last_handle_dropped() {
+        let session = zenoh::open(config::peer()).res().unwrap().into_arc();
+        let subscriber = session
+            .declare_subscriber("test/shared/dropped")
+            .callback(|_| {})
+            .res()
+            .unwrap()
+            .into_shared();
+        let other = subscriber.clone();
+        drop(subscriber);
+        assert_eq!(session.subscribers().len(), 1);
+        std::thread::spawn(move || drop(other)).join().unwrap();
+        assert!(session.subscribers().is_empty());
+    }
+
+    #[test]
+    fn shared_pull() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:18006".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap().into_arc();
+        let received = Arc::new(AtomicUsize::new(0));
+        let subscriber = subscribing
+            .declare_subscriber("test/shared/pull")
+            .callback({
+                let received = received.clone();
+                move |_| {
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap()
+            .into_shared();
+
+        // Kept by the routers until pulled through any of the handles
+        publishing.put("test/shared/pull", "value").res().unwrap();
+        std::thread::sleep(SLEEP);
+        let handle = subscriber.clone();
+        drop(subscriber);
+        handle.pull().res().unwrap();
+        wait_for(&received, 1);
+        assert_eq!(handle.metrics().samples_delivered, 1);
+    }
+}
@@ -0,0 +1,85 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_split {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn split() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let (handle, receiver) = session
+            .declare_subscriber("test/split/push")
+            .res()
+            .unwrap()
+            .split();
+
+        let worker = std::thread::spawn(move || {
+            // Ends once the subscription is undeclared and the samples are drained
+            receiver
+                .iter()
+                .map(|sample| sample.value.to_string())
+                .collect::<Vec<_>>()
+        });
+        session.put("test/split/push", "value").res().unwrap();
+        drop(handle);
+        assert_eq!(worker.join().unwrap(), ["value"]);
+        assert!(session.subscribers().is_empty());
+    }
+
+    #[test]
+    fn split_pull() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17539".parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+        let (handle, receiver) = subscribing
+            .declare_subscriber("test/split/pull")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap()
+            .split();
+
+        // Kept by the routers until pulled through the handle
+        publishing.put("test/split/pull", "value").res().unwrap();
+        std::thread::sleep(SLEEP);
+        handle.pull().res().unwrap();
+        let sample = receiver.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    #[test]
+    fn into_receiver() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let receiver = session
+            .declare_subscriber("test/split/leaked")
+            .res()
+            .unwrap()
+            .into_receiver();
+
+        assert_eq!(session.subscribers().len(), 1);
+        session.put("test/split/leaked", "value").res().unwrap();
+        let sample = receiver.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+}
@@ -0,0 +1,80 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_stream {
+    use futures::StreamExt;
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    async fn next<S: futures::Stream<Item = Sample> + Unpin>(stream: &mut S) -> Option<String> {
+        tokio::time::timeout(TIMEOUT, stream.next())
+            .await
+            .unwrap()
+            .map(|sample| sample.value.to_string())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn subscriber_is_a_stream() {
+        let session = zenoh::open(config::peer()).res_async().await.unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/stream/push")
+            .res_async()
+            .await
+            .unwrap();
+
+        session
+            .put("test/stream/push", "first")
+            .res_async()
+            .await
+            .unwrap();
+        assert_eq!(next(&mut subscriber).await.as_deref(), Some("first"));
+        // Polling through a reference keeps the subscription declared
+        session
+            .put("test/stream/push", "second")
+            .res_async()
+            .await
+            .unwrap();
+        assert_eq!(next(&mut subscriber).await.as_deref(), Some("second"));
+        assert!(subscriber.recv_timeout(Duration::ZERO).unwrap().is_none());
+
+        let mut stream = subscriber.into_stream();
+        session
+            .put("test/stream/push", "third")
+            .res_async()
+            .await
+            .unwrap();
+        assert_eq!(next(&mut stream).await.as_deref(), Some("third"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn pull_subscriber_is_a_stream() {
+        let session = zenoh::open(config::peer()).res_async().await.unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/stream/pull")
+            .pull_mode()
+            .res_async()
+            .await
+            .unwrap();
+
+        session
+            .put("test/stream/pull", "value")
+            .res_async()
+            .await
+            .unwrap();
+        subscriber.pull().res_async().await.unwrap();
+        assert_eq!(next(&mut subscriber).await.as_deref(), Some("value"));
+    }
+}
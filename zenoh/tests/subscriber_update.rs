@@ -0,0 +1,179 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+mod subscriber_update {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::subscriber::{PullSubscriber, SubMode};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn open_pair(port: u16) -> (Session, Session) {
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{port}").parse().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        let publishing = zenoh::open(config).res().unwrap();
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.connect.endpoints = vec![endpoint];
+        let subscribing = zenoh::open(config).res().unwrap();
+        (publishing, subscribing)
+    }
+
+    #[test]
+    fn reliability() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let mut subscriber = session
+            .declare_subscriber("test/update/reliability")
+            .best_effort()
+            .res()
+            .unwrap();
+        let id = subscriber.id();
+
+        subscriber
+            .update(Reliability::Reliable, SubMode::Push)
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.reliability(), Reliability::Reliable);
+        assert_eq!(subscriber.id(), id);
+        let subscription = session
+            .subscribers()
+            .into_iter()
+            .find(|sub| sub.id == id)
+            .unwrap();
+        assert_eq!(subscription.reliability, Reliability::Reliable);
+
+        session
+            .put("test/update/reliability", "value")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "value");
+    }
+
+    #[test]
+    fn pull_to_push() {
+        let (publishing, subscribing) = open_pair(17536);
+        let mut subscriber = subscribing
+            .declare_subscriber("test/update/mode")
+            .pull_mode()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+
+        // Kept by the routers until pulled
+        publishing.put("test/update/mode", "pulled").res().unwrap();
+        assert!(subscriber
+            .recv_timeout(Duration::from_millis(100))
+            .unwrap()
+            .is_none());
+        subscriber.pull().res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "pulled");
+
+        subscriber
+            .update(Reliability::Reliable, SubMode::Push)
+            .res()
+            .unwrap();
+        subscriber.routed(TIMEOUT).res().unwrap();
+        assert_eq!(subscriber.mode(), SubMode::Push);
+
+        publishing.put("test/update/mode", "pushed").res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "pushed");
+    }
+
+    #[test]
+    fn through_router() {
+        let endpoint: EndPoint = "tcp/127.0.0.1:17537".parse().unwrap();
+        let mut config = config::default();
+        config.set_mode(Some(WhatAmI::Router)).unwrap();
+        config.listen.endpoints = vec![endpoint.clone()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _router = zenoh::open(config).res().unwrap();
+        let publishing = zenoh::open(config::client([endpoint.clone()]))
+            .res()
+            .unwrap();
+        let subscribing = zenoh::open(config::client([endpoint])).res().unwrap();
+        let mut subscriber = subscribing
+            .declare_subscriber("test/update/router")
+            .pull_mode()
+            .best_effort()
+            .await_routed(TIMEOUT)
+            .res()
+            .unwrap();
+        let update = |subscriber: &PullSubscriber<flume::Receiver<Sample>>, reliability, mode| {
+            subscriber.update(reliability, mode).res().unwrap();
+            subscriber.routed(TIMEOUT).res().unwrap();
+        };
+
+        // The router updates the reliability of the subscription back and forth
+        update(&subscriber, Reliability::Reliable, SubMode::Push);
+        publishing
+            .put("test/update/router", "reliable")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "reliable");
+        update(&subscriber, Reliability::BestEffort, SubMode::Push);
+        assert_eq!(subscriber.reliability(), Reliability::BestEffort);
+        publishing
+            .put("test/update/router", "best effort")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "best effort");
+
+        // It keeps the samples until pulled once back in pull mode
+        update(&subscriber, Reliability::BestEffort, SubMode::Pull);
+        publishing
+            .put("test/update/router", "pulled")
+            .res()
+            .unwrap();
+        assert!(subscriber
+            .recv_timeout(Duration::from_millis(100))
+            .unwrap()
+            .is_none());
+        subscriber.pull().res().unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "pulled");
+
+        // And pushes them again once back in push mode
+        update(&subscriber, Reliability::Reliable, SubMode::Push);
+        publishing
+            .put("test/update/router", "pushed")
+            .res()
+            .unwrap();
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), "pushed");
+    }
+
+    #[test]
+    fn update_with_unsync_receiver() {
+        let session = zenoh::open(config::peer()).res().unwrap();
+        let subscriber = session
+            .declare_subscriber("test/update/unsync")
+            .with(std::sync::mpsc::channel())
+            .res()
+            .unwrap();
+
+        // std::sync::mpsc::Receiver isn't Sync, the update must not capture it
+        subscriber
+            .update(Reliability::Reliable, SubMode::Push)
+            .res()
+            .unwrap();
+        assert_eq!(subscriber.reliability(), Reliability::Reliable);
+    }
+}
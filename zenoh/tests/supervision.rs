@@ -0,0 +1,94 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(all(feature = "unstable", feature = "testing"))]
+use std::time::{Duration, Instant};
+use zenoh::events::SessionEvent;
+use zenoh::prelude::sync::*;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn expect_failure(listener: &flume::Receiver<SessionEvent>, expected: &str) {
+    match listener.recv_timeout(TIMEOUT).unwrap() {
+        SessionEvent::InternalTaskFailed { task, .. } => assert_eq!(task, expected),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+}
+
+fn wait_unhealthy(session: &Session) {
+    let deadline = Instant::now() + TIMEOUT;
+    while session.is_healthy() {
+        assert!(Instant::now() < deadline);
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn internal_task_failure() {
+    const TASK: &str = "config_notifier";
+
+    let session = zenoh::open(Config::default()).res().unwrap();
+    let listener = session.event_listener().res().unwrap();
+    assert!(session.is_healthy());
+
+    // The task is restarted after each of its first failures
+    for _ in 0..3 {
+        session.inject_task_failure(TASK).res();
+        expect_failure(&listener, TASK);
+        assert!(session.is_healthy());
+    }
+
+    // Once its restarts are exhausted, the session becomes unhealthy
+    session.inject_task_failure(TASK).res();
+    expect_failure(&listener, TASK);
+    wait_unhealthy(&session);
+
+    listener.undeclare().res().unwrap();
+    session.close().res().unwrap();
+}
+
+#[test]
+fn accept_loop_failure() {
+    const TASK: &str = "accept";
+    let endpoint: EndPoint = "tcp/127.0.0.1:18007".parse().unwrap();
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.clone()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = zenoh::open(config).res().unwrap();
+    let listener = session.event_listener().res().unwrap();
+
+    let connect = || {
+        let mut config = config::client([endpoint.clone()]);
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        zenoh::open(config).res().unwrap().close().res().unwrap();
+    };
+    connect();
+
+    // The listener is reopened after each of the first terminations of its accept loop
+    for _ in 0..3 {
+        session.inject_task_failure(TASK).res();
+        expect_failure(&listener, TASK);
+        std::thread::sleep(Duration::from_millis(100));
+        connect();
+        assert!(session.is_healthy());
+    }
+
+    // Once its restarts are exhausted, the session becomes unhealthy
+    session.inject_task_failure(TASK).res();
+    expect_failure(&listener, TASK);
+    wait_unhealthy(&session);
+
+    listener.undeclare().res().unwrap();
+    session.close().res().unwrap();
+}
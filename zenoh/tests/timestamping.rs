@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::query::ConsolidationMode;
+use zenoh::Session;
+
+const SLEEP: Duration = Duration::from_secs(1);
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+fn open(listen: Option<&str>, connect: Option<&str>, timestamping: bool) -> Session {
+    let mut config = config::peer();
+    if let Some(endpoint) = listen {
+        config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    }
+    if let Some(endpoint) = connect {
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5("timestamping/enabled", &timestamping.to_string())
+        .unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+#[test]
+fn clock_free_session() {
+    let session = open(None, None, false);
+    assert!(session.hlc().is_none());
+
+    let subscriber = session
+        .declare_subscriber("test/timestamping/local/*")
+        .res()
+        .unwrap();
+    let _queryable = session
+        .declare_queryable("test/timestamping/local/*")
+        .callback(|query| {
+            for value in ["first", "second"] {
+                let sample = Sample::new(query.key_expr().clone(), value);
+                query.reply(Ok(sample)).res().unwrap();
+            }
+        })
+        .res()
+        .unwrap();
+
+    session
+        .put("test/timestamping/local/a", "value")
+        .res()
+        .unwrap();
+    let sample = subscriber.receiver.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.value.to_string(), "value");
+    assert!(sample.timestamp.is_none());
+
+    // Without timestamps, the first reply for a key wins
+    for consolidation in [ConsolidationMode::Monotonic, ConsolidationMode::Latest] {
+        let replies: Vec<Sample> = session
+            .get("test/timestamping/local/b")
+            .consolidation(consolidation)
+            .timeout(TIMEOUT)
+            .res()
+            .unwrap()
+            .into_iter()
+            .map(|reply| reply.sample.unwrap())
+            .collect();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].value.to_string(), "first");
+        assert!(replies[0].timestamp.is_none());
+    }
+}
+
+#[test]
+fn clock_free_interop() {
+    let stamping = open(Some("tcp/127.0.0.1:17505"), None, true);
+    let clock_free = open(None, Some("tcp/127.0.0.1:17505"), false);
+    assert!(stamping.hlc().is_some());
+    assert!(clock_free.hlc().is_none());
+
+    let clock_free_sub = clock_free
+        .declare_subscriber("test/timestamping/interop/stamped")
+        .res()
+        .unwrap();
+    let stamping_sub = stamping
+        .declare_subscriber("test/timestamping/interop/clock_free")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    // The timestamps of the timestamping peer are passed through untouched
+    stamping
+        .put("test/timestamping/interop/stamped", "value")
+        .res()
+        .unwrap();
+    let sample = clock_free_sub.receiver.recv_timeout(TIMEOUT).unwrap();
+    let timestamp = sample.timestamp.unwrap();
+    assert_eq!(*timestamp.get_id(), *stamping.hlc().unwrap().get_id());
+
+    // The publications of the clock-free peer are stamped by the timestamping peer
+    clock_free
+        .put("test/timestamping/interop/clock_free", "value")
+        .res()
+        .unwrap();
+    let sample = stamping_sub.receiver.recv_timeout(TIMEOUT).unwrap();
+    let timestamp = sample.timestamp.unwrap();
+    assert_eq!(*timestamp.get_id(), *stamping.hlc().unwrap().get_id());
+}
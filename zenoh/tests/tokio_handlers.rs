@@ -0,0 +1,114 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SAMPLES: usize = 16;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn mpsc_channel() {
+    let session = zenoh::open(config::peer())
+        .res_async()
+        .await
+        .unwrap()
+        .into_arc();
+    let mut subscriber = session
+        .declare_subscriber("test/tokio/mpsc")
+        .with(tokio::sync::mpsc::channel(4))
+        .res_async()
+        .await
+        .unwrap();
+
+    // The puts run on a tokio runtime and wait while the channel is full
+    let publisher = {
+        let session = session.clone();
+        tokio::spawn(async move {
+            for i in 0..SAMPLES {
+                session
+                    .put("test/tokio/mpsc", i as u64)
+                    .res_async()
+                    .await
+                    .unwrap();
+            }
+        })
+    };
+    for i in 0..SAMPLES {
+        let sample = tokio::time::timeout(TIMEOUT, subscriber.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample.value.to_string(), i.to_string());
+    }
+    publisher.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn broadcast_channel() {
+    let session = zenoh::open(config::peer()).res_async().await.unwrap();
+    let mut subscriber = session
+        .declare_subscriber("test/tokio/broadcast")
+        .with(tokio::sync::broadcast::channel(SAMPLES))
+        .res_async()
+        .await
+        .unwrap();
+    let mut other = subscriber.resubscribe();
+
+    for i in 0..SAMPLES {
+        session
+            .put("test/tokio/broadcast", i as u64)
+            .res_async()
+            .await
+            .unwrap();
+    }
+    for receiver in [&mut *subscriber, &mut other] {
+        for i in 0..SAMPLES {
+            let sample = tokio::time::timeout(TIMEOUT, receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(sample.value.to_string(), i.to_string());
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn watch_channel() {
+    let session = zenoh::open(config::peer()).res_async().await.unwrap();
+    let subscriber = session
+        .declare_subscriber("test/tokio/watch/*")
+        .with(tokio::sync::watch::channel(None))
+        .res_async()
+        .await
+        .unwrap();
+    assert!(subscriber.borrow().is_none());
+
+    let mut consumers: Vec<_> = (0..3).map(|_| subscriber.receiver.clone()).collect();
+    // The consumers only observe the latest value
+    for value in ["first", "latest"] {
+        session
+            .put("test/tokio/watch/config", value)
+            .res_async()
+            .await
+            .unwrap();
+    }
+    for consumer in consumers.iter_mut() {
+        tokio::time::timeout(TIMEOUT, consumer.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        let latest = consumer.borrow_and_update();
+        assert_eq!(latest.as_ref().unwrap().value.to_string(), "latest");
+    }
+}
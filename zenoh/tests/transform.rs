@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(all(feature = "unstable", feature = "transform_deflate"))]
+#[test]
+fn deflate_round_trip() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+    use zenoh::transform::{DeflateCompressor, DeflateDecompressor, TransformChain};
+
+    const KEY_EXPR: &str = "test/transform/deflate";
+    const SLEEP: Duration = Duration::from_secs(1);
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:17489".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let sub_session = zenoh::open(config).res().unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec!["tcp/127.0.0.1:17489".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let pub_session = zenoh::open(config).res().unwrap();
+
+    let raw = sub_session.declare_subscriber(KEY_EXPR).res().unwrap();
+    let chain = TransformChain::new().then(DeflateDecompressor);
+    let subscriber = sub_session
+        .declare_subscriber(KEY_EXPR)
+        .transform(chain.clone())
+        .res()
+        .unwrap();
+    let publisher = pub_session
+        .declare_publisher(KEY_EXPR)
+        .res()
+        .unwrap()
+        .transform_out(TransformChain::new().then(DeflateCompressor::default()));
+    std::thread::sleep(SLEEP);
+
+    let payload = "zenoh ".repeat(100);
+    publisher
+        .put(Value::from(payload.as_str()).encoding(Encoding::TEXT_PLAIN))
+        .res()
+        .unwrap();
+
+    let compressed = raw.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert!(compressed.value.payload.len() < payload.len());
+    assert_eq!(compressed.value.encoding.suffix(), ";deflate");
+
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert_eq!(sample.value.encoding, Encoding::TEXT_PLAIN);
+    assert_eq!(
+        String::from_utf8(sample.value.payload.contiguous().to_vec()).unwrap(),
+        payload
+    );
+    assert_eq!(chain.errors(), 0);
+}
+
+#[cfg(all(feature = "unstable", feature = "transform_deflate"))]
+#[test]
+fn transform_errors_are_reported() {
+    use std::sync::{Arc, Mutex};
+    use zenoh::prelude::sync::*;
+    use zenoh::transform::{DeflateDecompressor, NoopTransformer, TransformChain, TransformError};
+
+    const KEY_EXPR: &str = "test/transform/errors";
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let reported = Arc::new(Mutex::new(vec![]));
+    let chain = TransformChain::new()
+        .then(NoopTransformer)
+        .then(DeflateDecompressor)
+        .then(|sample: Sample| match sample.value.payload.len() {
+            0 => Err(TransformError::new("empty payload")),
+            _ => Ok(sample),
+        })
+        .on_error({
+            let reported = reported.clone();
+            move |e| reported.lock().unwrap().push(e.to_string())
+        });
+    let subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .transform(chain.clone())
+        .res()
+        .unwrap();
+
+    session.put(KEY_EXPR, "value").res().unwrap();
+    session.put(KEY_EXPR, Vec::<u8>::new()).res().unwrap();
+    // Not a valid deflate stream
+    session
+        .put(
+            KEY_EXPR,
+            Value::from("invalid").encoding(Encoding::TEXT_PLAIN.with_suffix(";deflate").unwrap()),
+        )
+        .res()
+        .unwrap();
+
+    assert_eq!(subscriber.drain(usize::MAX).len(), 1);
+    assert_eq!(chain.errors(), 2);
+    let reported = reported.lock().unwrap();
+    assert_eq!(reported[0], "empty payload");
+    assert!(reported[1].starts_with("Unable to decompress sample on test/transform/errors"));
+}
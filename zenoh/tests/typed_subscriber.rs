@@ -0,0 +1,103 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn typed_subscriber_deserializes_samples() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/typed/push";
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let mut subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .reliable()
+        .typed::<(String, u32)>()
+        .res()
+        .unwrap();
+
+    let value = session
+        .serialization()
+        .serialize(&("zenoh".to_string(), 42u32), &Encoding::APP_JSON)
+        .unwrap();
+    session.put(KEY_EXPR, value).res().unwrap();
+    let (key_expr, value) = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap().unwrap();
+    assert_eq!(key_expr.as_str(), KEY_EXPR);
+    assert_eq!(value, ("zenoh".to_string(), 42));
+
+    // Invalid samples are received as errors, without closing the subscriber
+    session
+        .put(
+            KEY_EXPR,
+            Value::from("not json").encoding(Encoding::APP_JSON),
+        )
+        .res()
+        .unwrap();
+    session
+        .put(
+            KEY_EXPR,
+            Value::from("[]").encoding(Encoding::APP_OCTET_STREAM),
+        )
+        .res()
+        .unwrap();
+    assert!(subscriber.recv_timeout(TIMEOUT).unwrap().unwrap().is_err());
+    assert!(subscriber.recv_timeout(TIMEOUT).unwrap().unwrap().is_err());
+
+    session
+        .put(
+            KEY_EXPR,
+            Value::from(r#"["zenoh",43]"#).encoding(Encoding::TEXT_JSON),
+        )
+        .res()
+        .unwrap();
+    let (_, value) = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap().unwrap();
+    assert_eq!(value, ("zenoh".to_string(), 43));
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn typed_pull_subscriber_uses_session_codecs() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/typed/pull";
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    let session = zenoh::open(config::peer()).res().unwrap();
+    let encoding = Encoding::APP_CUSTOM.with_suffix("le").unwrap();
+    session.serialization().register::<u32>(
+        encoding.clone(),
+        |v| Ok(v.to_le_bytes().to_vec().into()),
+        |v| {
+            let bytes: [u8; 4] = v.payload.contiguous()[..]
+                .try_into()
+                .map_err(|_| "Invalid u32")?;
+            Ok(u32::from_le_bytes(bytes))
+        },
+    );
+    let mut subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .typed::<u32>()
+        .pull_mode()
+        .allowed_origin(Locality::SessionLocal)
+        .res()
+        .unwrap();
+
+    let value = session.serialization().serialize(&7u32, &encoding).unwrap();
+    session.put(KEY_EXPR, value).res().unwrap();
+    subscriber.pull().res().unwrap();
+    let (_, value) = subscriber.recv_timeout(TIMEOUT).unwrap().unwrap().unwrap();
+    assert_eq!(value, 7);
+}
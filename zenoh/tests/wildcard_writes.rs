@@ -0,0 +1,129 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::publication::WildcardWriteError;
+use zenoh::Session;
+use zenoh_core::zlock;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+fn open(allow_wildcard_writes: bool) -> Session {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .set_allow_wildcard_writes(Some(allow_wildcard_writes))
+        .unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+fn assert_wildcard_write_error(error: zenoh::Error, key_expr: &str) {
+    let error = error.downcast_ref::<WildcardWriteError>().unwrap();
+    assert_eq!(error.key_expr().as_str(), key_expr);
+}
+
+#[test]
+fn wildcard_writes_are_rejected() {
+    let session = open(false);
+    let subscriber = session.declare_subscriber("test/wild/**").res().unwrap();
+
+    let error = session.put("test/wild/*", "value").res().unwrap_err();
+    assert_wildcard_write_error(error, "test/wild/*");
+    let error = session.delete("test/wild/**").res().unwrap_err();
+    assert_wildcard_write_error(error, "test/wild/**");
+    let error = session.declare_publisher("test/wild/*").res().unwrap_err();
+    assert_wildcard_write_error(error, "test/wild/*");
+    assert!(subscriber.is_empty());
+
+    // The writes on key expressions without wildcards are unaffected
+    session.put("test/wild/a", "value").res().unwrap();
+    assert_eq!(
+        subscriber
+            .receiver
+            .recv_timeout(TIMEOUT)
+            .unwrap()
+            .key_expr
+            .as_str(),
+        "test/wild/a"
+    );
+}
+
+#[test]
+fn wildcard_replies_are_rejected() {
+    let session = open(false);
+    let errors = Arc::new(Mutex::new(vec![]));
+    let _queryable = {
+        let errors = errors.clone();
+        session
+            .declare_queryable("test/wild/reply/**")
+            .callback(move |query| {
+                let reply = Sample::new(KeyExpr::try_from("test/wild/reply/*").unwrap(), "value");
+                if let Err(e) = query.reply(Ok(reply)).res() {
+                    zlock!(errors).push(e);
+                }
+            })
+            .res()
+            .unwrap()
+    };
+
+    let replies = session
+        .get("test/wild/reply/**")
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap();
+    assert_eq!(replies.into_iter().count(), 0);
+    let error = zlock!(errors).pop().unwrap();
+    assert_wildcard_write_error(error, "test/wild/reply/*");
+}
+
+#[test]
+fn wildcard_writes_when_allowed() {
+    let session = open(true);
+    let subscriber = session.declare_subscriber("test/wild/a").res().unwrap();
+    let _queryable = session
+        .declare_queryable("test/wild/reply/**")
+        .callback(|query| {
+            let reply = Sample::new(KeyExpr::try_from("test/wild/reply/*").unwrap(), "value");
+            query.reply(Ok(reply)).res().unwrap();
+        })
+        .res()
+        .unwrap();
+
+    session.delete("test/wild/*").res().unwrap();
+    let sample = subscriber.receiver.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/wild/*");
+    assert_eq!(sample.kind, SampleKind::Delete);
+
+    let publisher = session.declare_publisher("test/wild/*").res().unwrap();
+    publisher.put("value").res().unwrap();
+    assert_eq!(
+        subscriber
+            .receiver
+            .recv_timeout(TIMEOUT)
+            .unwrap()
+            .key_expr
+            .as_str(),
+        "test/wild/*"
+    );
+
+    let reply = session
+        .get("test/wild/reply/**")
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap()
+        .recv()
+        .unwrap();
+    assert_eq!(reply.sample.unwrap().key_expr.as_str(), "test/wild/reply/*");
+}
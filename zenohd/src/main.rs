@@ -287,7 +287,7 @@ fn init_logging() -> Result<()> {
         .with_target(true);
 
     let tracing_sub = tracing_subscriber::registry()
-        .with(env_filter)
+        .with(zenoh_util::reloadable_log_filter(env_filter))
         .with(fmt_layer);
 
     #[cfg(feature = "loki")]